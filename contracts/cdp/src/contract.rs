@@ -4,34 +4,36 @@ use std::env;
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     attr, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Reply, Response, StdError, StdResult, Uint128, WasmMsg,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
 };
 
 use membrane::auction::ExecuteMsg as AuctionExecuteMsg;
 use membrane::helpers::assert_sent_native_token_balance;
 use membrane::liq_queue::ExecuteMsg as LQ_ExecuteMsg;
+use membrane::oracle::ExecuteMsg as OracleExecuteMsg;
 use membrane::cdp::{Config, CallbackMsg, ExecuteMsg, InstantiateMsg, QueryMsg, UpdateConfig, MigrateMsg};
 use membrane::types::{
-    cAsset, Asset, AssetInfo, Basket, UserInfo,
+    cAsset, Asset, AssetInfo, Basket, CollateralDeprecation, CollateralVolatility, DiscountSource, LTVRamp, OperatorAllowance, ParameterRamp, Position, RateModel, RedemptionInfo, StoredPrice, UserInfo, VolatilityConfig,
 };
 use membrane::osmosis_proxy::ExecuteMsg as OP_ExecuteMsg;
 
 use crate::error::ContractError;
-use crate::rates::external_accrue_call;
-use crate::risk_engine::assert_basket_assets;
+use crate::rates::{external_accrue_call, validate_discount_sources, validate_rate_model, validate_volatility_config};
+use crate::risk_engine::{apply_risk_parameters, assert_basket_assets, update_isolated_debt};
 use crate::positions::{
     deposit,
     edit_basket, increase_debt,
     liq_repay, repay, redeem_for_collateral, edit_redemption_info,
-    withdraw, BAD_DEBT_REPLY_ID, WITHDRAW_REPLY_ID,
-    LIQ_QUEUE_REPLY_ID, USER_SP_REPAY_REPLY_ID, create_basket,
+    withdraw, close_position, loop_position, flash_mint, check_flash_mint_repayment, transfer_position, accept_position_transfer, cancel_position_transfer, edit_operator, validate_operator, OperatorAction, BAD_DEBT_REPLY_ID, WITHDRAW_REPLY_ID,
+    LIQ_QUEUE_REPLY_ID, USER_SP_REPAY_REPLY_ID, CLOSE_POSITION_REPLY_ID, LOOP_POSITION_REPLY_ID, PRICE_HISTORY_REPLY_ID, LIQUIDATE_MANY_REPLY_ID, create_basket,
 };
 use crate::query::{
-    query_basket_credit_interest, query_basket_debt_caps, query_basket_positions, query_basket_redeemability, query_collateral_rates, simulate_LTV_mint
+    query_basket_credit_interest, query_basket_debt_caps, query_debt_cap_dashboard, query_collateral_volatility, query_basket_positions, query_basket_redeemability, query_baskets, query_collateral_deprecations, query_collateral_rates, query_operators, query_parameter_ramps, query_position_insolvency, query_position_orders, query_position_rate, query_position_snapshots, simulate_LTV_mint, simulate_redemption
 };
-use crate::liquidations::liquidate;
-use crate::reply::{handle_liq_queue_reply, handle_withdraw_reply};
-use crate::state::{ get_target_position, update_position, ContractVersion, POSITIONS, LIQUIDATION, BASKET, CONFIG, CONTRACT, OWNERSHIP_TRANSFER, VOLATILITY };
+use crate::liquidations::{liquidate, liquidate_many};
+use crate::orders::{cancel_position_order, execute_position_order, set_position_order};
+use crate::reply::{handle_close_position_reply, handle_liq_queue_reply, handle_loop_position_reply, handle_withdraw_reply};
+use crate::state::{ get_target_position, update_position, ContractVersion, POSITIONS, LIQUIDATION, BASKETS, DEFAULT_BASKET_ID, FREEZE_TIMER, REDEMPTION_OPT_IN, LEGACY_BASKET, LEGACY_FREEZE_TIMER, LEGACY_OPERATORS, LEGACY_POSITIONS, LEGACY_REDEMPTION_OPT_IN, LEGACY_STORED_PRICES, LEGACY_VOLATILITY, OPERATORS, STORED_PRICES, RATE_CONTROLLERS, CONFIG, CONTRACT, DEPRECATED_COLLATERAL, DISCOUNT_SOURCES, LTV_RAMPS, OWNERSHIP_TRANSFER, PRICE_RECORDS, VOLATILITY };
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cdp";
@@ -64,6 +66,9 @@ pub fn instantiate(
         collateral_twap_timeframe: msg.collateral_twap_timeframe,
         credit_twap_timeframe: msg.credit_twap_timeframe,
         rate_hike_rate: Some(Decimal::percent(30)),
+        flash_mint_cap: None,
+        flash_mint_fee: None,
+        price_policy: None,
     };
 
     //Set optional config parameters
@@ -123,13 +128,60 @@ pub fn instantiate(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    //Msgs without a Basket ID are for the default Basket
+    let (basket_id, msg) = match msg {
+        ExecuteMsg::WithBasket { basket_id, msg } => {
+            if !BASKETS.has(deps.storage, basket_id.u128()) {
+                return Err(ContractError::CustomError { val: format!("Basket {} doesn't exist", basket_id) })
+            }
+            (basket_id, *msg)
+        },
+        msg => (DEFAULT_BASKET_ID.load(deps.storage)?, msg),
+    };
+
+    let res = execute_basket_msg(deps.branch(), env, info, basket_id, msg)?;
+
+    Ok(send_price_records(deps, res)?)
+}
+
+/// Send prices queried during the msg to the oracle's price history.
+/// Errors are ignored in the reply so the oracle can't block Position msgs.
+fn send_price_records(deps: DepsMut, res: Response) -> StdResult<Response> {
+    let records = PRICE_RECORDS.may_load(deps.storage)?.unwrap_or_default();
+    if records.is_empty() {
+        return Ok(res);
+    }
+    PRICE_RECORDS.remove(deps.storage);
+
+    let oracle_contract = match CONFIG.load(deps.storage)?.oracle_contract {
+        Some(oracle_contract) => oracle_contract,
+        None => return Ok(res),
+    };
+    let msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: oracle_contract.to_string(),
+        msg: to_binary(&OracleExecuteMsg::RecordPrices { prices: records })?,
+        funds: vec![],
+    });
+
+    Ok(res.add_submessage(SubMsg::reply_on_error(msg, PRICE_HISTORY_REPLY_ID)))
+}
+
+/// Execute a msg for the given Basket
+fn execute_basket_msg(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    basket_id: Uint128,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::UpdateConfig (update) => update_config(deps, info, update),
+        ExecuteMsg::EditDiscountSources { sources } => edit_discount_sources(deps, info, sources),
         ExecuteMsg::Deposit { position_owner, position_id} => {
             //Set valid_assets from funds sent
             let valid_assets = info
@@ -146,29 +198,37 @@ pub fn execute(
                 deps.storage,
                 deps.querier,
                 env.clone(),
+                basket_id,
                 valid_assets,
             )?;
 
             //If there is nothing being deposited, error
             if cAssets == vec![] { return Err(ContractError::CustomError { val: String::from("No deposit assets passed") }) }
 
-            deposit(deps, env, info, position_owner, position_id, cAssets)
+            deposit(deps, env, info, basket_id, position_owner, position_id, cAssets)
         }
         ExecuteMsg::Withdraw {
             position_id,
             assets,
             send_to,
+            position_owner,
         } => {
+            let (info, operator) = validate_operator(deps.storage, deps.api, env.clone(), info, basket_id, position_owner, vec![position_id], OperatorAction::Withdraw)?;
+            //Operator withdrawals are sent to the Position owner
+            if operator.is_some() && send_to.is_some() {
+                return Err(ContractError::CustomError { val: String::from("Operators can't set a withdrawal recipient") })
+            }
             duplicate_asset_check(assets.clone())?;
             let cAssets: Vec<cAsset> = assert_basket_assets(
                 deps.storage,
                 deps.querier,
                 env.clone(),
+                basket_id,
                 assets,
             )?;
             //If there is nothing being withdrawn, error
             if cAssets == vec![] { return Err(ContractError::CustomError { val: String::from("No withdrawal assets passed") }) }
-            withdraw(deps, env, info, position_id, cAssets, send_to)
+            withdraw(deps, env, info, basket_id, position_id, cAssets, send_to)
         }
 
         ExecuteMsg::IncreaseDebt {
@@ -176,13 +236,21 @@ pub fn execute(
             amount,
             mint_to_addr,
             LTV,
-        } => increase_debt(deps, env, info, position_id, amount, LTV, mint_to_addr),
+            position_owner,
+        } => {
+            let (info, operator) = validate_operator(deps.storage, deps.api, env.clone(), info, basket_id, position_owner, vec![position_id], OperatorAction::IncreaseDebt)?;
+            //Operator mints are sent to the Position owner
+            if operator.is_some() && mint_to_addr.is_some() {
+                return Err(ContractError::CustomError { val: String::from("Operators can't set a mint recipient") })
+            }
+            increase_debt(deps, env, info, basket_id, position_id, amount, LTV, mint_to_addr, operator)
+        },
         ExecuteMsg::Repay {
             position_id,
             position_owner,
             send_excess_to,
         } => {
-            let basket: Basket = BASKET.load(deps.storage)?;                        
+            let basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;                        
             let credit_asset = assert_sent_native_token_balance(basket.credit_asset.info, &info)?;
 
             repay(
@@ -191,25 +259,46 @@ pub fn execute(
                 deps.api,
                 env,
                 info,
+                basket_id,
                 position_id,
                 position_owner,
                 credit_asset,
                 send_excess_to,
             )
         },
-        ExecuteMsg::Accrue { position_owner, position_ids } => { external_accrue_call(deps.storage, deps.api, deps.querier, info, env, position_owner, position_ids) },
-        ExecuteMsg::RedeemCollateral { max_collateral_premium } => {
+        ExecuteMsg::ClosePosition {
+            position_id,
+            max_spread,
+            send_to,
+        } => close_position(deps, env, info.sender, basket_id, position_id, max_spread, None, send_to),
+        ExecuteMsg::LoopPosition {
+            position_id,
+            target_LTV,
+            max_slippage,
+            num_loops,
+        } => loop_position(deps, env, info, basket_id, position_id, target_LTV, max_slippage, num_loops),
+        ExecuteMsg::SetPositionOrder { position_id, trigger, action } => set_position_order(deps, info, basket_id, position_id, trigger, action),
+        ExecuteMsg::CancelPositionOrder { order_id } => cancel_position_order(deps, info, basket_id, order_id),
+        ExecuteMsg::ExecutePositionOrder { order_id } => execute_position_order(deps, env, info, basket_id, order_id),
+        ExecuteMsg::FlashMint { amount, receiver, msg } => flash_mint(deps, env, basket_id, amount, receiver, msg),
+        ExecuteMsg::Accrue { position_owner, position_ids } => { external_accrue_call(deps.storage, deps.api, deps.querier, info, env, basket_id, position_owner, position_ids) },
+        ExecuteMsg::RedeemCollateral { max_collateral_premium, collateral_filter, min_value_received } => {
             redeem_for_collateral(
                 deps, 
                 env, 
                 info, 
-                max_collateral_premium.unwrap_or(99u128)
+                basket_id,
+                max_collateral_premium.unwrap_or(99u128),
+                collateral_filter,
+                min_value_received,
             )
         },
-        ExecuteMsg::EditRedeemability { position_ids, redeemable, premium, max_loan_repayment, restricted_collateral_assets } => {
+        ExecuteMsg::EditRedeemability { position_ids, redeemable, premium, max_loan_repayment, restricted_collateral_assets, position_owner } => {
+            let (info, _operator) = validate_operator(deps.storage, deps.api, env, info, basket_id, position_owner, position_ids.clone(), OperatorAction::EditRedeemability)?;
             edit_redemption_info(
                 deps.storage,
                 info, 
+                basket_id,
                 position_ids, 
                 redeemable, 
                 premium, 
@@ -218,6 +307,10 @@ pub fn execute(
                 false
             )
         },
+        ExecuteMsg::TransferPosition { position_id, new_owner } => transfer_position(deps, info, basket_id, position_id, new_owner),
+        ExecuteMsg::AcceptPositionTransfer { position_id } => accept_position_transfer(deps, info, basket_id, position_id),
+        ExecuteMsg::CancelPositionTransfer { position_id } => cancel_position_transfer(deps, info, basket_id, position_id),
+        ExecuteMsg::EditOperator { operator, allowance } => edit_operator(deps, info, basket_id, operator, allowance),
         ExecuteMsg::LiqRepay {} => {
             if !info.funds.is_empty() {
                 let credit_asset = Asset {
@@ -235,37 +328,81 @@ pub fn execute(
             asset,
             max_borrow_LTV,
             max_LTV,
-        } => edit_cAsset(deps, info, asset, max_borrow_LTV, max_LTV),
-        ExecuteMsg::EditBasket(edit) => edit_basket(deps, env, info,edit),
+            isolated_debt_ceiling,
+            rate_model,
+            remove_isolation,
+            ramp_duration,
+            volatility_config,
+        } => edit_cAsset(deps, env, info, basket_id, asset, max_borrow_LTV, max_LTV, isolated_debt_ceiling, rate_model, remove_isolation, ramp_duration, volatility_config),
+        ExecuteMsg::DeprecatecAsset {
+            asset,
+            ramp_duration,
+            final_max_LTV,
+            liquidation_incentive,
+        } => deprecate_cAsset(deps, env, info, basket_id, asset, ramp_duration, final_max_LTV, liquidation_incentive),
+        ExecuteMsg::RemovecAsset { asset } => remove_cAsset(deps, info, basket_id, asset),
+        ExecuteMsg::EditBasket(edit) => edit_basket(deps, env, info, basket_id, edit),
+        ExecuteMsg::CreateBasket(create) => create_basket(
+            deps,
+            info,
+            env,
+            create.basket_id,
+            create.collateral_types,
+            create.credit_asset,
+            create.credit_price,
+            create.base_interest_rate,
+            create.credit_pool_infos,
+            create.liq_queue,
+        ),
         ExecuteMsg::Liquidate {
             position_id,
             position_owner,
+            unwrap_lp,
         } => liquidate(
             deps.storage,
             deps.api,
             deps.querier,
             env,
             info,
+            basket_id,
             position_id,
             position_owner,
+            unwrap_lp.unwrap_or(false),
+        ),
+        ExecuteMsg::LiquidateMany { positions } => liquidate_many(
+            deps.storage,
+            deps.api,
+            deps.querier,
+            env,
+            info,
+            basket_id,
+            positions,
         ),
         ExecuteMsg::Callback(msg) => {
             if info.sender == env.contract.address {
-                callback_handler(deps, env, msg)
+                callback_handler(deps, env, basket_id, msg)
             } else {
                 Err(ContractError::Unauthorized { owner: env.contract.address.to_string() })
             }
         }
+        ExecuteMsg::WithBasket { .. } => Err(ContractError::CustomError { val: String::from("WithBasket msgs can't be nested") }),
     }
 }
 
 /// Edit params for a cAsset in the basket
 fn edit_cAsset(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
+    basket_id: Uint128,
     asset: AssetInfo,
     max_borrow_LTV: Option<Decimal>,
     max_LTV: Option<Decimal>,
+    isolated_debt_ceiling: Option<Uint128>,
+    rate_model: Option<RateModel>,
+    remove_isolation: Option<bool>,
+    ramp_duration: Option<u64>,
+    volatility_config: Option<VolatilityConfig>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -274,7 +411,9 @@ fn edit_cAsset(
         return Err(ContractError::Unauthorized { owner: config.owner.to_string() });
     }
 
-    let mut basket: Basket = BASKET.load(deps.storage)?;
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+    //Edit from the current, possibly mid-ramp, LTVs
+    apply_risk_parameters(deps.storage, &env, basket_id, &mut basket.collateral_types)?;
     let mut attrs = vec![
         attr("method", "edit_cAsset"),
     ];
@@ -290,6 +429,7 @@ fn edit_cAsset(
     {
         Some(mut asset) => {
             attrs.push(attr("asset", asset.asset.info.to_string()));
+            let prev_asset = asset.clone();
 
             if let Some(LTV) = max_LTV {
                 //Enforce 1-100% range
@@ -316,6 +456,7 @@ fn edit_cAsset(
                             bid_for: asset.clone().asset.info,
                             max_premium: Some(max_premium),
                             bid_threshold: None,
+                            lp_pool_id: None,
                         })?,
                         funds: vec![],
                     }));
@@ -334,6 +475,71 @@ fn edit_cAsset(
                     })
                 }
             }
+
+            //Isolated assets can't share positions with other collateral
+            //Note: Positions that already mix the asset aren't affected
+            if let Some(debt_ceiling) = isolated_debt_ceiling {
+                asset.isolated_debt_ceiling = Some(debt_ceiling);
+                attrs.push(attr("isolated_debt_ceiling", debt_ceiling.to_string()));
+            } else if let Some(true) = remove_isolation {
+                asset.isolated_debt_ceiling = None;
+                attrs.push(attr("isolated_debt_ceiling", "None"));
+            }
+
+            //Swap the interest rate model, PID controllers restart from scratch
+            if let Some(rate_model) = rate_model {
+                validate_rate_model(&rate_model)?;
+                RATE_CONTROLLERS.remove(deps.storage, (basket_id.u128(), asset.asset.info.to_string()));
+
+                attrs.push(attr("rate_model", rate_model.to_string()));
+                asset.rate_model = Some(rate_model);
+            }
+
+            //Volatility is tracked per Basket & asset
+            if let Some(volatility_config) = volatility_config {
+                validate_volatility_config(&volatility_config)?;
+                let mut volatility = VOLATILITY.may_load(deps.storage, (basket_id.u128(), asset.asset.info.to_string()))?.unwrap_or_default();
+
+                //Trim the list to the new window & bound the index
+                while volatility.volatility_list.len() > volatility_config.window as usize {
+                    volatility.volatility_list.remove(0);
+                }
+                volatility.index = volatility.index
+                    .max(volatility_config.multiplier_floor)
+                    .min(volatility_config.multiplier_ceiling);
+
+                attrs.push(attr("volatility_config", format!("{:?}", volatility_config)));
+                volatility.config = Some(volatility_config);
+                VOLATILITY.save(deps.storage, (basket_id.u128(), asset.asset.info.to_string()), &volatility)?;
+            }
+
+            //Ramp LTV changes, the ramped LTVs are applied in accrue() & get_avg_LTV()
+            if max_LTV.is_some() || max_borrow_LTV.is_some() {
+                match ramp_duration {
+                    Some(ramp_duration) if ramp_duration > 0 => {
+                        LTV_RAMPS.save(deps.storage, (basket_id.u128(), asset.asset.info.to_string()), &LTVRamp {
+                            asset_info: asset.asset.info.clone(),
+                            max_borrow_LTV: ParameterRamp {
+                                start_value: prev_asset.max_borrow_LTV,
+                                end_value: asset.max_borrow_LTV,
+                                start_time: env.block.time.seconds(),
+                                end_time: env.block.time.seconds() + ramp_duration,
+                            },
+                            max_LTV: ParameterRamp {
+                                start_value: prev_asset.max_LTV,
+                                end_value: asset.max_LTV,
+                                start_time: env.block.time.seconds(),
+                                end_time: env.block.time.seconds() + ramp_duration,
+                            },
+                        })?;
+                        attrs.push(attr("ramp_end_time", (env.block.time.seconds() + ramp_duration).to_string()));
+
+                        asset.max_borrow_LTV = prev_asset.max_borrow_LTV;
+                        asset.max_LTV = prev_asset.max_LTV;
+                    },
+                    _ => LTV_RAMPS.remove(deps.storage, (basket_id.u128(), asset.asset.info.to_string())),
+                }
+            }
             new_asset = asset;
         }
         None => {
@@ -352,11 +558,163 @@ fn edit_cAsset(
 
     basket.collateral_types.push(new_asset);
 
-    BASKET.save(deps.storage, &basket)?;
+    BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
 
     Ok(Response::new().add_attributes(attrs).add_messages(msgs))
 }
 
+/// Deprecate a cAsset to wind it down.
+/// Deposits are blocked, its borrow LTV is zeroed & its max_LTV ramps down in accrue().
+fn deprecate_cAsset(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    basket_id: Uint128,
+    asset: AssetInfo,
+    ramp_duration: u64,
+    final_max_LTV: Decimal,
+    liquidation_incentive: Decimal,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    //Assert Authority
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized { owner: config.owner.to_string() });
+    }
+
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+    //Deprecate from the current, possibly mid-ramp, LTVs
+    apply_risk_parameters(deps.storage, &env, basket_id, &mut basket.collateral_types)?;
+
+    //Find the cAsset
+    let (index, deprecated_asset) = match basket
+        .clone()
+        .collateral_types
+        .into_iter()
+        .enumerate()
+        .find(|(_i, cAsset)| cAsset.asset.info.equal(&asset))
+    {
+        Some(cAsset) => cAsset,
+        None => {
+            return Err(ContractError::CustomError {
+                val: String::from("Collateral type doesn't exist in basket"),
+            })
+        }
+    };
+
+    if DEPRECATED_COLLATERAL.has(deps.storage, (basket_id.u128(), asset.to_string())) {
+        return Err(ContractError::CustomError {
+            val: String::from("Collateral is already deprecated"),
+        });
+    }
+    //max_LTV can only ramp down & keeps the 1% floor
+    if final_max_LTV > deprecated_asset.max_LTV || final_max_LTV < Decimal::percent(1) {
+        return Err(ContractError::InvalidMaxLTV { max_LTV: final_max_LTV });
+    }
+    if liquidation_incentive >= Decimal::one() {
+        return Err(ContractError::CustomError {
+            val: String::from("Liquidation incentive must be less than 100%"),
+        });
+    }
+
+    let deprecation = CollateralDeprecation {
+        asset_info: asset.clone(),
+        start_time: env.block.time.seconds(),
+        end_time: env.block.time.seconds() + ramp_duration,
+        initial_max_LTV: deprecated_asset.max_LTV,
+        final_max_LTV,
+        liquidation_incentive,
+        removed: false,
+    };
+    DEPRECATED_COLLATERAL.save(deps.storage, (basket_id.u128(), asset.to_string()), &deprecation)?;
+
+    //Borrow LTV is zeroed now, max_LTV is ramped lazily
+    basket.collateral_types[index].max_borrow_LTV = Decimal::zero();
+    BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "deprecate_cAsset"),
+        attr("asset", asset.to_string()),
+        attr("end_time", deprecation.end_time.to_string()),
+        attr("final_max_LTV", final_max_LTV.to_string()),
+        attr("liquidation_incentive", liquidation_incentive.to_string()),
+    ]))
+}
+
+/// Remove a deprecated cAsset from the Basket once its supply is 0.
+/// Positions that still hold it without debt can withdraw it.
+fn remove_cAsset(
+    deps: DepsMut,
+    info: MessageInfo,
+    basket_id: Uint128,
+    asset: AssetInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    //Assert Authority
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized { owner: config.owner.to_string() });
+    }
+
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+
+    let mut deprecation = match DEPRECATED_COLLATERAL.may_load(deps.storage, (basket_id.u128(), asset.to_string()))? {
+        Some(deprecation) if !deprecation.removed => deprecation,
+        _ => {
+            return Err(ContractError::CustomError {
+                val: String::from("Only deprecated collateral can be removed"),
+            })
+        }
+    };
+
+    let index = match basket
+        .collateral_types
+        .iter()
+        .position(|cAsset| cAsset.asset.info.equal(&asset))
+    {
+        Some(index) => index,
+        None => {
+            return Err(ContractError::CustomError {
+                val: String::from("Collateral type doesn't exist in basket"),
+            })
+        }
+    };
+
+    //Supply has to be fully withdrawn, liquidated or redeemed
+    let current_supply = match basket.collateral_supply_caps.iter().find(|cap| cap.asset_info.equal(&asset)) {
+        Some(cap) => cap.current_supply,
+        None => basket.collateral_types[index].asset.amount,
+    };
+    if !current_supply.is_zero() {
+        return Err(ContractError::CustomError {
+            val: format!("Deprecated collateral still has supply: {}", current_supply),
+        });
+    }
+
+    //Remove from the Basket's collateral lists
+    basket.collateral_types.remove(index);
+    if index < basket.lastest_collateral_rates.len() {
+        basket.lastest_collateral_rates.remove(index);
+    }
+    basket.collateral_supply_caps.retain(|cap| !cap.asset_info.equal(&asset));
+    for multi_asset_cap in basket.multi_asset_supply_caps.iter_mut() {
+        multi_asset_cap.assets.retain(|info| !info.equal(&asset));
+    }
+    basket.multi_asset_supply_caps.retain(|cap| !cap.assets.is_empty());
+    BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
+
+    //Keep the schedule so leftover holdings use the final LTVs
+    deprecation.removed = true;
+    DEPRECATED_COLLATERAL.save(deps.storage, (basket_id.u128(), asset.to_string()), &deprecation)?;
+    LTV_RAMPS.remove(deps.storage, (basket_id.u128(), asset.to_string()));
+    RATE_CONTROLLERS.remove(deps.storage, (basket_id.u128(), asset.to_string()));
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "remove_cAsset"),
+        attr("asset", asset.to_string()),
+    ]))
+}
+
 /// Update contract config
 fn update_config(
     deps: DepsMut,
@@ -398,17 +756,56 @@ fn update_config(
     Ok(Response::new().add_attributes(attrs))
 }
 
+/// Replace the interest discount sources
+fn edit_discount_sources(
+    deps: DepsMut,
+    info: MessageInfo,
+    sources: Vec<DiscountSource>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    //Assert Authority
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized { owner: config.owner.to_string() });
+    }
+
+    validate_discount_sources(deps.api, &sources)?;
+    DISCOUNT_SOURCES.save(deps.storage, &sources)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "edit_discount_sources"),
+        attr("sources", format!("{:?}", sources)),
+    ]))
+}
+
 /// Handle CallbackMsgs
 pub fn callback_handler(
     deps: DepsMut,
     env: Env,
+    basket_id: Uint128,
     msg: CallbackMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         CallbackMsg::BadDebtCheck {
             position_owner,
             position_id,
-        } => check_and_fulfill_bad_debt(deps, env, position_id, position_owner),
+        } => check_and_fulfill_bad_debt(deps, env, basket_id, position_id, position_owner),
+        CallbackMsg::LiquidatePosition {
+            position_id,
+            position_owner,
+            fee_recipient,
+        } => liquidate(
+            deps.storage,
+            deps.api,
+            deps.querier,
+            env,
+            MessageInfo { sender: fee_recipient, funds: vec![] },
+            basket_id,
+            position_id,
+            position_owner.to_string(),
+            false,
+        ),
+        CallbackMsg::CheckFlashMintRepayment {} => check_flash_mint_repayment(deps, env),
     }
 }
 
@@ -416,15 +813,16 @@ pub fn callback_handler(
 fn check_and_fulfill_bad_debt(
     deps: DepsMut,
     _env: Env,
+    basket_id: Uint128,
     position_id: Uint128,
     position_owner: Addr,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
-    let mut basket: Basket = BASKET.load(deps.storage)?;
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
 
     //Get target Position
-    let (_i, mut target_position) = get_target_position(deps.storage, position_owner.clone(), position_id)?;
+    let (_i, mut target_position) = get_target_position(deps.storage, basket_id, position_owner.clone(), position_id)?;
 
     //Load Liquidation Prop
     let cAsset_prices = LIQUIDATION.load(deps.storage)?.cAsset_prices;
@@ -478,11 +876,14 @@ fn check_and_fulfill_bad_debt(
             }
         }
 
+        //Update isolated debt total for the debt covered by revenue
+        update_isolated_debt(deps.storage, &basket, &target_position.collateral_assets, target_position.credit_amount - bad_debt_amount, false)?;
+
         //Set target_position.credit_amount to the leftover bad debt
         target_position.credit_amount = bad_debt_amount;
         
         //Save target_position w/ updated debt
-        update_position(deps.storage, position_owner.clone(), target_position)?;
+        update_position(deps.storage, basket_id, position_owner.clone(), target_position)?;
 
         //Send bad debt amount to the auction contract if greater than 0
         if config.debt_auction.is_some() && !bad_debt_amount.is_zero() {
@@ -510,7 +911,7 @@ fn check_and_fulfill_bad_debt(
         }
 
         //Save Basket w/ updated revenue
-        BASKET.save(deps.storage, &basket)?;
+        BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
         
         attrs.push(
             attr("amount_sent_to_auction", bad_debt_amount)
@@ -523,17 +924,39 @@ fn check_and_fulfill_bad_debt(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
-    match msg.id {
-        LIQ_QUEUE_REPLY_ID => handle_liq_queue_reply(deps, msg, env),
-        WITHDRAW_REPLY_ID => handle_withdraw_reply(deps, env, msg),
+pub fn reply(mut deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    let res = match msg.id {
+        LIQ_QUEUE_REPLY_ID => handle_liq_queue_reply(deps.branch(), msg, env),
+        WITHDRAW_REPLY_ID => handle_withdraw_reply(deps.branch(), env, msg),
+        CLOSE_POSITION_REPLY_ID => handle_close_position_reply(deps.branch(), env, msg),
+        LOOP_POSITION_REPLY_ID => handle_loop_position_reply(deps.branch(), env, msg),
         BAD_DEBT_REPLY_ID => Ok(Response::new()),
+        PRICE_HISTORY_REPLY_ID => Ok(Response::new()),
+        //Failed LiquidateMany liquidations are skipped so the rest of the batch goes through
+        LIQUIDATE_MANY_REPLY_ID => match msg.result.into_result() {
+            Err(err) => Ok(Response::new().add_attribute("skipped_liquidation", err)),
+            Ok(_) => Ok(Response::new()),
+        },
         id => Err(StdError::generic_err(format!("invalid reply id: {}", id))),
-    }
+    }?;
+
+    //Prices queried in replies are sent from the reply
+    send_price_records(deps, res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    //Queries without a Basket ID are for the default Basket
+    let (basket_id, msg) = match msg {
+        QueryMsg::WithBasket { basket_id, msg } => (basket_id, *msg),
+        msg => (DEFAULT_BASKET_ID.load(deps.storage)?, msg),
+    };
+
+    query_basket_msg(deps, env, basket_id, msg)
+}
+
+/// Query the given Basket
+fn query_basket_msg(deps: Deps, env: Env, basket_id: Uint128, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
         QueryMsg::GetBasketPositions {
@@ -544,27 +967,65 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         } => to_binary(&query_basket_positions(
             deps,
             env,
+            basket_id,
             start_after,
             limit,
             user_info, 
             user,
         )?),
-        QueryMsg::GetBasket { } => to_binary(&BASKET.load(deps.storage)?),
+        QueryMsg::GetBasket { } => to_binary(&BASKETS.load(deps.storage, basket_id.u128())?),
+        QueryMsg::GetBaskets { start_after, limit } => {
+            to_binary(&query_baskets(deps, start_after, limit)?)
+        }
         QueryMsg::GetBasketRedeemability { position_owner, start_after, limit } => {
-            to_binary(&query_basket_redeemability(deps, position_owner, start_after, limit)?)
+            to_binary(&query_basket_redeemability(deps, basket_id, position_owner, start_after, limit)?)
         }
         QueryMsg::GetBasketDebtCaps { } => {
-            to_binary(&query_basket_debt_caps(deps, env)?)
+            to_binary(&query_basket_debt_caps(deps, env, basket_id)?)
+        }
+        QueryMsg::GetDebtCapDashboard { } => {
+            to_binary(&query_debt_cap_dashboard(deps, env, basket_id)?)
+        }
+        QueryMsg::GetCollateralVolatility { } => {
+            to_binary(&query_collateral_volatility(deps, basket_id)?)
         }
         QueryMsg::GetCreditRate { } => {
-            to_binary(&query_basket_credit_interest(deps, env)?)
+            to_binary(&query_basket_credit_interest(deps, env, basket_id)?)
         }
         QueryMsg::GetCollateralInterest { } => {
-            to_binary(&query_collateral_rates(deps)?)
+            to_binary(&query_collateral_rates(deps, basket_id)?)
+        },
+        QueryMsg::GetDiscountSources { } => {
+            to_binary(&DISCOUNT_SOURCES.may_load(deps.storage)?.unwrap_or_default())
+        },
+        QueryMsg::GetPositionRate { position_owner, position_id } => {
+            to_binary(&query_position_rate(deps, env, basket_id, position_owner, position_id)?)
+        },
+        QueryMsg::GetOperators { position_owner } => {
+            to_binary(&query_operators(deps, basket_id, position_owner)?)
         },
         QueryMsg::SimulateMint { position_info, LTV } => {
-            to_binary(&simulate_LTV_mint(deps, env, position_info, LTV)?)
+            to_binary(&simulate_LTV_mint(deps, env, basket_id, position_info, LTV)?)
+        },
+        QueryMsg::GetPositionInsolvency { user_info, start_after, limit, min_LTV, collateral_asset } => {
+            to_binary(&query_position_insolvency(deps, env, basket_id, user_info, start_after, limit, min_LTV, collateral_asset)?)
+        }
+        QueryMsg::GetPositionOrders { position_owner, start_after, limit } => {
+            to_binary(&query_position_orders(deps, basket_id, position_owner, start_after, limit)?)
+        },
+        QueryMsg::GetPositionSnapshots { position_owner, position_id, start_after, limit } => {
+            to_binary(&query_position_snapshots(deps, basket_id, position_owner, position_id, start_after, limit)?)
+        },
+        QueryMsg::GetCollateralDeprecations { } => {
+            to_binary(&query_collateral_deprecations(deps, basket_id)?)
+        }
+        QueryMsg::GetParameterRamps { } => {
+            to_binary(&query_parameter_ramps(deps, basket_id)?)
+        }
+        QueryMsg::SimulateRedemption { credit_amount, max_collateral_premium, collateral_filter } => {
+            to_binary(&simulate_redemption(deps, env, basket_id, credit_amount, max_collateral_premium.unwrap_or(99u128), collateral_filter)?)
         }
+        QueryMsg::WithBasket { .. } => Err(StdError::GenericErr { msg: String::from("WithBasket queries can't be nested") }),
     }
 }
 
@@ -588,5 +1049,65 @@ fn duplicate_asset_check(assets: Vec<Asset>) -> Result<(), ContractError> {
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    //Move single Basket state to Basket keyed state
+    if let Some(basket) = LEGACY_BASKET.may_load(deps.storage)? {
+        let basket_id = basket.basket_id.u128();
+
+        BASKETS.save(deps.storage, basket_id, &basket)?;
+        DEFAULT_BASKET_ID.save(deps.storage, &basket.basket_id)?;
+        LEGACY_BASKET.remove(deps.storage);
+
+        //Positions
+        let positions = LEGACY_POSITIONS
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(Addr, Vec<Position>)>>>()?;
+        for (owner, user_positions) in positions {
+            POSITIONS.save(deps.storage, (basket_id, owner.clone()), &user_positions)?;
+            LEGACY_POSITIONS.remove(deps.storage, owner);
+        }
+
+        //Redemption opt-ins
+        let opt_ins = LEGACY_REDEMPTION_OPT_IN
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(u128, Vec<RedemptionInfo>)>>>()?;
+        for (premium, users) in opt_ins {
+            REDEMPTION_OPT_IN.save(deps.storage, (basket_id, premium), &users)?;
+            LEGACY_REDEMPTION_OPT_IN.remove(deps.storage, premium);
+        }
+
+        //Freeze timer
+        if let Some(timer) = LEGACY_FREEZE_TIMER.may_load(deps.storage)? {
+            FREEZE_TIMER.save(deps.storage, basket_id, &timer)?;
+            LEGACY_FREEZE_TIMER.remove(deps.storage);
+        }
+    }
+
+    //Move asset keyed volatility, stored prices & operators to the default Basket
+    let basket_id = DEFAULT_BASKET_ID.load(deps.storage)?.u128();
+
+    let volatility = LEGACY_VOLATILITY
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, CollateralVolatility)>>>()?;
+    for (asset, asset_volatility) in volatility {
+        VOLATILITY.save(deps.storage, (basket_id, asset.clone()), &asset_volatility)?;
+        LEGACY_VOLATILITY.remove(deps.storage, asset);
+    }
+
+    let stored_prices = LEGACY_STORED_PRICES
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, StoredPrice)>>>()?;
+    for (asset, stored_price) in stored_prices {
+        STORED_PRICES.save(deps.storage, (basket_id, asset.clone()), &stored_price)?;
+        LEGACY_STORED_PRICES.remove(deps.storage, asset);
+    }
+
+    let operators = LEGACY_OPERATORS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<((Addr, Addr), OperatorAllowance)>>>()?;
+    for ((owner, operator), allowance) in operators {
+        OPERATORS.save(deps.storage, (basket_id, owner.clone(), operator.clone()), &allowance)?;
+        LEGACY_OPERATORS.remove(deps.storage, (owner, operator));
+    }
+
     Ok(Response::default())
 }
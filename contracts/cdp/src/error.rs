@@ -72,6 +72,9 @@ pub enum ContractError {
     #[error("Maximum position number reached")]
     MaxPositionsReached {},
 
+    #[error("Oracle price for {asset} is degraded, it can't back debt increases or withdrawals")]
+    DegradedPrice { asset: String },
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
@@ -8,6 +8,7 @@ pub mod contract;
 mod error;
 pub mod helpers;
 pub mod liquidations;
+pub mod orders;
 pub mod positions;
 pub mod query;
 pub mod state;
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use cosmwasm_std::{Storage, Api, QuerierWrapper, Env, MessageInfo, Uint128, Response, Decimal, CosmosMsg, attr, SubMsg, Addr, StdResult, StdError, to_binary, WasmMsg, QueryRequest, WasmQuery, BankMsg, Coin, ReplyOn};
+use cosmwasm_std::{Storage, Api, QuerierWrapper, Env, Event, MessageInfo, Uint128, Response, Decimal, CosmosMsg, attr, SubMsg, Addr, StdResult, StdError, to_binary, WasmMsg, QueryRequest, WasmQuery, BankMsg, Coin, ReplyOn};
 use osmosis_std::shim::Duration;
 use osmosis_std::types::osmosis::downtimedetector::v1beta1::DowntimedetectorQuerier;
 
@@ -12,19 +12,21 @@ use membrane::osmosis_proxy::QueryMsg as OsmoQueryMsg;
 use membrane::stability_pool::{LiquidatibleResponse as SP_LiquidatibleResponse, ExecuteMsg as SP_ExecuteMsg, QueryMsg as SP_QueryMsg};
 use membrane::liq_queue::{ExecuteMsg as LQ_ExecuteMsg, QueryMsg as LQ_QueryMsg, LiquidatibleResponse as LQ_LiquidatibleResponse};
 use membrane::staking::ExecuteMsg as StakingExecuteMsg;
-use membrane::types::{Basket, Position, AssetInfo, UserInfo, Asset, cAsset, PoolStateResponse, AssetPool};
+use membrane::types::{Basket, Position, AssetInfo, UserInfo, Asset, cAsset, PoolStateResponse, AssetPool, SoftLiquidation};
 
 use crate::error::ContractError; 
-use crate::positions::{BAD_DEBT_REPLY_ID, USER_SP_REPAY_REPLY_ID, LIQ_QUEUE_REPLY_ID};
-use crate::query::{insolvency_check, get_cAsset_ratios};
-use crate::risk_engine::update_basket_tally;
-use crate::state::{get_target_position, update_position, LiquidationPropagation, Timer, BASKET, CONFIG, FREEZE_TIMER, LIQUIDATION};
+use crate::positions::{record_position_health, BAD_DEBT_REPLY_ID, USER_SP_REPAY_REPLY_ID, LIQ_QUEUE_REPLY_ID, LIQUIDATE_MANY_REPLY_ID};
+use crate::query::{insolvency_check, insolvency_check_with_prices, get_cAsset_ratios};
+use crate::risk_engine::{apply_risk_parameters, get_deprecation_incentive, update_basket_tally};
+use crate::state::{get_target_position, update_position, LiquidationPropagation, Timer, BASKETS, CONFIG, FREEZE_TIMER, LIQUIDATION};
 
 pub const SECONDS_PER_DAY: u64 = 86400;
+pub const MAX_LIQUIDATIONS_PER_MSG: usize = 10;
 
 /// Confirms insolvency and calculates repayment amount,
 /// then sends liquidation messages to the modules if they have funds.
 /// If not, sell wall.
+/// LP share collateral can be unwrapped into its underlying assets for the caller fee & SP.
 #[allow(unused_variables)]
 pub fn liquidate(
     storage: &mut dyn Storage,
@@ -32,43 +34,14 @@ pub fn liquidate(
     querier: QuerierWrapper,
     env: Env,
     info: MessageInfo,
+    basket_id: Uint128,
     position_id: Uint128,
     position_owner: String,
+    unwrap_lp: bool,
 ) -> Result<Response, ContractError> {
-    //Check for Osmosis downtime 
-    match DowntimedetectorQuerier::new(&querier)
-        .recovered_since_downtime_of_length(
-            10 * 60 * 8, //8 hours from 6 second blocks
-            Some(Duration {
-                seconds: 60 * 60 * 1, //1 hour
-                nanos: 0,
-            })
-    ){
-        Ok(resp) => {            
-            if !resp.succesfully_recovered {
-                return Err(ContractError::CustomError { val: String::from("Downtime recovery window hasn't elapsed yet ") })
-            }
-        },
-        Err(_) => (),
-    };
-
-    let mut basket: Basket = BASKET.load(storage)?;
-    //Check if frozen
-    if basket.frozen {
-        return Err(ContractError::Frozen {});
-    }
-
-    //Check contract downtime
-    let freeze_timer = match FREEZE_TIMER.load(storage){
-        Ok(timer) => timer,
-        Err(_) => Timer {
-            start_time: 0,
-            end_time: 0,
-        },
-    };
-    if (env.block.time.seconds().checked_sub(freeze_timer.end_time).unwrap_or_else(|| SECONDS_PER_DAY/24)) < (SECONDS_PER_DAY/24){ //1 hour grace
-        return Err(ContractError::Std(StdError::GenericErr { msg: format!("You can liquidate in {} seconds, there is a post-freeze grace period", (SECONDS_PER_DAY/24) - (env.block.time.seconds() - freeze_timer.end_time)) }));
-    }
+    let mut basket: Basket = BASKETS.load(storage, basket_id.u128())?;
+    //Check that liquidations are enabled
+    assert_liquidation_window(storage, querier, env.clone(), &basket)?;
 
     //Load state
     let config: Config = CONFIG.load(storage)?;
@@ -77,6 +50,7 @@ pub fn liquidate(
 
     let (_i, mut target_position) = get_target_position(
         storage,
+        basket_id,
         valid_position_owner.clone(),
         position_id,
     )?;
@@ -97,7 +71,9 @@ pub fn liquidate(
         config.clone(),
     )?;
     
-    if !insolvent {
+    //Positions between their borrow & max LTVs can be soft liquidated
+    let soft_liquidation = get_soft_liquidation(&basket, insolvent, current_LTV, avg_borrow_LTV);
+    if !insolvent && soft_liquidation.is_none() {
         return Err(ContractError::PositionSolvent {});
     }
 
@@ -112,13 +88,14 @@ pub fn liquidate(
         current_LTV,
         avg_borrow_LTV,
         total_value,
+        soft_liquidation.clone(),
     )?;
 
     // Don't send any funds here, only send UserInfo and repayment amounts.
     // We want to act on the reply status but since SubMsg state won't revert if we catch the error,
     // assets we send prematurely won't come back.
 
-    let res = Response::new();
+    let mut res = Response::new();
     let mut submessages = vec![];
     let mut caller_fee_messages: Vec<CosmosMsg> = vec![];
 
@@ -126,7 +103,13 @@ pub fn liquidate(
     let mut collateral_assets = target_position.clone().collateral_assets;
 
     //Dynamic fee that goes to the caller (info.sender): current_LTV - max_LTV
-    let caller_fee = decimal_subtraction(current_LTV, avg_max_LTV)?;
+    //Soft liquidations use the Basket's fixed fee
+    let mut caller_fee = match soft_liquidation {
+        Some(soft_liquidation) => soft_liquidation.caller_fee,
+        None => decimal_subtraction(current_LTV, avg_max_LTV)?,
+    };
+    //Deprecated collateral adds its liquidation incentive
+    caller_fee += get_deprecation_incentive(storage, basket_id, &collateral_assets, &cAsset_ratios)?;
 
     //Set pre-user repay amount 
     let pre_user_repay_repay_amount = credit_repay_amount;
@@ -151,10 +134,12 @@ pub fn liquidate(
     //and amount to send to the Liquidation Queue.
     let (protocol_fee_msg, leftover_repayment) = per_asset_fulfillments(
         querier, 
+        env.clone(),
         config.clone(), 
         basket.clone(), 
         info.sender.to_string(),
         caller_fee,
+        unwrap_lp,
         &mut collateral_assets, 
         &mut leftover_position_value, 
         leftover_repayment.to_uint_floor(),
@@ -173,6 +158,7 @@ pub fn liquidate(
     target_position.collateral_assets = collateral_assets;
 
     //If the user repaid the whole liquidation from the SP, we need to update the position here
+    let mut health_event: Option<Event> = None;
     if leftover_repayment.is_zero() && user_repay_amount == pre_user_repay_repay_amount {
         //Update the credit
         target_position.credit_amount -= pre_user_repay_repay_amount.to_uint_floor();
@@ -213,10 +199,13 @@ pub fn liquidate(
             };
         }            
         //Update Basket
-        BASKET.save(storage, &basket)?;
+        BASKETS.save(storage, basket_id.u128(), &basket)?;
 
         //Update position w/ new credit amount
-        update_position(storage, valid_position_owner.clone(), target_position.clone())?;      
+        update_position(storage, basket_id, valid_position_owner.clone(), target_position.clone())?;      
+
+        //Record post-liquidation health
+        health_event = Some(record_position_health(storage, querier, env.clone(), basket_id, valid_position_owner.clone(), position_id, "liquidation"));
     }
 
 
@@ -239,15 +228,19 @@ pub fn liquidate(
         cAsset_ratios,
         cAsset_prices_res,
         caller_fee_value_paid,
+        unwrap_lp,
     )?;
 
     //Create the Bad debt callback message to be added as the last SubMsg
     let msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: env.contract.address.to_string(),
-        msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::BadDebtCheck {
-            position_id,
-            position_owner: valid_position_owner.clone(),
-        }))?,
+        msg: to_binary(&ExecuteMsg::WithBasket {
+            basket_id,
+            msg: Box::new(ExecuteMsg::Callback(CallbackMsg::BadDebtCheck {
+                position_id,
+                position_owner: valid_position_owner.clone(),
+            })),
+        })?,
         funds: vec![],
     });
     //The logic for this will be handled in the callback
@@ -258,6 +251,10 @@ pub fn liquidate(
     let mut liquidation_propagation: Option<String> = None;
     if let Ok(repay) = LIQUIDATION.load(storage) { liquidation_propagation = Some(format!("{:?}", repay)) }
     
+    if let Some(health_event) = health_event {
+        res = res.add_event(health_event);
+    }
+
     Ok(res
         .add_submessages(submessages) //LQ & SP msgs
         .add_submessage(call_back)
@@ -274,6 +271,191 @@ pub fn liquidate(
     
 }
 
+/// Filters a list of Positions down to the liquidatable ones using a single price query per collateral asset,
+/// then liquidates each one in its own callback so the caller fees go to the sender.
+/// Solvent, debtless, duplicate or nonexistent Positions are skipped instead of erroring,
+/// as are Positions whose health check or liquidation callback fails.
+pub fn liquidate_many(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    querier: QuerierWrapper,
+    env: Env,
+    info: MessageInfo,
+    basket_id: Uint128,
+    positions: Vec<UserInfo>,
+) -> Result<Response, ContractError> {
+    //Enforce Vec max size
+    if positions.is_empty() || positions.len() > MAX_LIQUIDATIONS_PER_MSG {
+        return Err(ContractError::CustomError { val: format!("Must liquidate between 1 and {} positions", MAX_LIQUIDATIONS_PER_MSG) });
+    }
+
+    let basket: Basket = BASKETS.load(storage, basket_id.u128())?;
+    //Check that liquidations are enabled
+    assert_liquidation_window(storage, querier, env.clone(), &basket)?;
+
+    //Load state
+    let config: Config = CONFIG.load(storage)?;
+
+    //Load target Positions
+    let mut targets: Vec<(Addr, Position)> = vec![];
+    let mut skipped: Vec<String> = vec![];
+    for user in positions {
+        let position_owner = match api.addr_validate(&user.position_owner) {
+            Ok(owner) => owner,
+            Err(_) => {
+                skipped.push(format!("{}:{}", user.position_owner, user.position_id));
+                continue;
+            }
+        };
+        //Skip duplicates
+        if targets.iter().any(|(owner, position)| owner == &position_owner && position.position_id == user.position_id) {
+            continue;
+        }
+
+        match get_target_position(storage, basket_id, position_owner.clone(), user.position_id) {
+            Ok((_i, position)) if !position.credit_amount.is_zero() => targets.push((position_owner, position)),
+            _ => skipped.push(format!("{}:{}", position_owner, user.position_id)),
+        }
+    }
+
+    //Price each collateral asset in the batch once.
+    //This saves the prices so each liquidation in this block uses the stored price.
+    let mut batch_assets: Vec<cAsset> = vec![];
+    for (_owner, position) in targets.iter() {
+        for cAsset in position.collateral_assets.iter() {
+            match batch_assets.iter_mut().find(|asset| asset.asset.info.equal(&cAsset.asset.info)) {
+                Some(asset) => asset.asset.amount += cAsset.asset.amount,
+                None => batch_assets.push(cAsset.clone()),
+            }
+        }
+    }
+    let (_ratios, batch_prices) = get_cAsset_ratios(
+        storage,
+        env.clone(),
+        querier,
+        batch_assets.clone(),
+        config.clone(),
+        Some(basket.clone()),
+    )?;
+    let batch_asset_infos: Vec<AssetInfo> = batch_assets.into_iter().map(|cAsset| cAsset.asset.info).collect();
+
+    //Check each Position's health with the batch prices
+    let mut messages: Vec<SubMsg> = vec![];
+    let mut liquidated: Vec<String> = vec![];
+    for (position_owner, position) in targets {
+        //Use ramped & deprecated LTVs
+        let mut collateral_assets = position.clone().collateral_assets;
+        let health = apply_risk_parameters(storage, &env, basket_id, &mut collateral_assets)
+            .and_then(|_| insolvency_check_with_prices(
+                collateral_assets,
+                position.credit_amount,
+                basket.clone().credit_price,
+                &batch_asset_infos,
+                &batch_prices,
+            ));
+        //A failed health check skips the Position instead of failing the batch
+        let ((insolvent, current_LTV, _available_fee), (avg_borrow_LTV, _avg_max_LTV, _total_value, _prices, _ratios)) = match health {
+            Ok(health) => health,
+            Err(_) => {
+                skipped.push(format!("{}:{}", position_owner, position.position_id));
+                continue;
+            }
+        };
+
+        if !insolvent && get_soft_liquidation(&basket, insolvent, current_LTV, avg_borrow_LTV).is_none() {
+            skipped.push(format!("{}:{}", position_owner, position.position_id));
+            continue;
+        }
+
+        //Liquidations run sequentially so each one's LQ & SP replies use its own LIQUIDATION propagation.
+        //A failed liquidation is reverted on its own & skipped in the reply.
+        messages.push(SubMsg::reply_on_error(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::WithBasket {
+                basket_id,
+                msg: Box::new(ExecuteMsg::Callback(CallbackMsg::LiquidatePosition {
+                    position_id: position.position_id,
+                    position_owner: position_owner.clone(),
+                    fee_recipient: info.sender.clone(),
+                })),
+            })?,
+            funds: vec![],
+        }), LIQUIDATE_MANY_REPLY_ID));
+        liquidated.push(format!("{}:{}", position_owner, position.position_id));
+    }
+
+    if messages.is_empty() {
+        return Err(ContractError::CustomError { val: String::from("No insolvent positions to liquidate") });
+    }
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(vec![
+            attr("method", "liquidate_many"),
+            attr("liquidated_positions", liquidated.join(", ")),
+            attr("skipped_positions", skipped.join(", ")),
+        ]))
+}
+
+/// Asserts that liquidations aren't paused by chain downtime,
+/// a frozen basket or the post-freeze grace period
+fn assert_liquidation_window(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    env: Env,
+    basket: &Basket,
+) -> Result<(), ContractError> {
+    //Check for Osmosis downtime 
+    match DowntimedetectorQuerier::new(&querier)
+        .recovered_since_downtime_of_length(
+            10 * 60 * 8, //8 hours from 6 second blocks
+            Some(Duration {
+                seconds: 60 * 60 * 1, //1 hour
+                nanos: 0,
+            })
+    ){
+        Ok(resp) => {            
+            if !resp.succesfully_recovered {
+                return Err(ContractError::CustomError { val: String::from("Downtime recovery window hasn't elapsed yet ") })
+            }
+        },
+        Err(_) => (),
+    };
+
+    //Check if frozen
+    if basket.frozen {
+        return Err(ContractError::Frozen {});
+    }
+
+    //Check contract downtime
+    let freeze_timer = match FREEZE_TIMER.load(storage, basket.basket_id.u128()){
+        Ok(timer) => timer,
+        Err(_) => Timer {
+            start_time: 0,
+            end_time: 0,
+        },
+    };
+    if (env.block.time.seconds().checked_sub(freeze_timer.end_time).unwrap_or_else(|| SECONDS_PER_DAY/24)) < (SECONDS_PER_DAY/24){ //1 hour grace
+        return Err(ContractError::Std(StdError::GenericErr { msg: format!("You can liquidate in {} seconds, there is a post-freeze grace period", (SECONDS_PER_DAY/24) - (env.block.time.seconds() - freeze_timer.end_time)) }));
+    }
+
+    Ok(())
+}
+
+/// Returns the Basket's soft liquidation settings if the Position is between its borrow & max LTVs
+pub fn get_soft_liquidation(
+    basket: &Basket,
+    insolvent: bool,
+    current_LTV: Decimal,
+    avg_borrow_LTV: Decimal,
+) -> Option<SoftLiquidation> {
+    if insolvent || current_LTV <= avg_borrow_LTV {
+        return None;
+    }
+
+    basket.soft_liquidation.clone()
+}
+
 /// Calculate the amount & value of debt to repay 
 fn get_repay_quantities(
     config: Config,
@@ -282,18 +464,31 @@ fn get_repay_quantities(
     current_LTV: Decimal,
     borrow_LTV: Decimal,
     total_value: Decimal,
+    soft_liquidation: Option<SoftLiquidation>,
 ) -> Result<(Decimal, Decimal), ContractError>{
     
     // max_borrow_LTV/ current_LTV, * current_loan_value, current_loan_value - __ = value of loan amount
     let loan_value = basket.credit_price.get_value(target_position.credit_amount)?;
 
+    //Partial liquidations leave a remainder of debt in the Position
+    let partial_liquidation = current_LTV < Decimal::one() && (soft_liquidation.is_some() || basket.close_factor.is_some());
+
     //repay value = the % of the loan insolvent. Insolvent is anything between current and max borrow LTV.
     //IE, repay what to get the position down to borrow LTV
     //If the position LTV is above 100%, repay using all the collateral 
-    let mut repay_value = if current_LTV >= Decimal::one() {
+    let mut repay_value = if let Some(soft_liquidation) = soft_liquidation {
+        //Soft liquidations only repay a slice of the loan
+        decimal_multiplication(loan_value, soft_liquidation.repay_ratio)?
+    } else if current_LTV >= Decimal::one() {
         total_value
     } else {
-        decimal_multiplication( decimal_division( decimal_subtraction(current_LTV, borrow_LTV)?, current_LTV)?, loan_value)?
+        let repay_value = decimal_multiplication( decimal_division( decimal_subtraction(current_LTV, borrow_LTV)?, current_LTV)?, loan_value)?;
+
+        //Cap the repay value at the Basket's close factor
+        match basket.close_factor {
+            Some(close_factor) => repay_value.min(decimal_multiplication(loan_value, close_factor)?),
+            None => repay_value,
+        }
     };
 
     //Assert repay_value is above the minimum, if not repay at least the minimum
@@ -310,6 +505,9 @@ fn get_repay_quantities(
             //Else liquidate it all
             repay_value = loan_value;
         }
+    } else if partial_liquidation && decimal_subtraction(loan_value, repay_value)? < decimal_debt_minimum {
+        //Repay the full loan if a partial liquidation would leave dust, i.e. less than the minimum
+        repay_value = loan_value;
     }
 
     let credit_repay_amount = match basket.credit_price.get_amount(repay_value)?{
@@ -407,10 +605,12 @@ fn get_user_repay_amount(
 /// Send liquidatible amount to Liquidation Queue.
 fn per_asset_fulfillments(
     querier: QuerierWrapper,
+    env: Env,
     config: Config,
     basket: Basket,
     fee_recipient: String,
     caller_fee: Decimal,
+    unwrap_lp: bool,
     collateral_assets: &mut Vec<cAsset>,
     leftover_position_value: &mut Decimal,
     mut leftover_repayment: Uint128,
@@ -486,12 +686,21 @@ fn per_asset_fulfillments(
         match cAsset.clone().asset.info {
             AssetInfo::Token { address: _ } => { return Err(StdError::GenericErr { msg: String::from("Cw20 assets aren't allowed") }) },
             AssetInfo::NativeToken { denom: _ } => {
-                let asset = Asset {
-                    amount: caller_fee_in_collateral_amount,
-                    ..cAsset.clone().asset
-                };
-
-                caller_coins.push(asset_to_coin(asset)?);
+                //Caller fee is paid in the LP's underlying assets if unwrapping
+                let (exit_msg, fee_assets) = unwrap_lp_collateral(
+                    querier,
+                    env.clone(),
+                    &config,
+                    cAsset,
+                    caller_fee_in_collateral_amount,
+                    unwrap_lp,
+                )?;
+                if let Some(exit_msg) = exit_msg {
+                    caller_fee_messages.push(exit_msg);
+                }
+                for (asset, _value_ratio) in fee_assets {
+                    add_coin(&mut caller_coins, asset_to_coin(asset)?);
+                }
 
                 let asset = Asset {
                     amount: protocol_fee_in_collateral_amount,
@@ -620,6 +829,7 @@ pub fn build_sp_submsgs(
     cAsset_ratios: Vec<Decimal>,
     cAsset_prices: Vec<PriceResponse>,
     caller_fee_value_paid: Decimal,
+    unwrap_lp: bool,
 ) -> Result<(Decimal), ContractError>{
 
     //Starts at what LQ is supposed to pay
@@ -676,6 +886,7 @@ pub fn build_sp_submsgs(
             cAsset_prices,
             basket,
             config: config.clone(),
+            unwrap_lp,
         };
 
         LIQUIDATION.save(storage, &liquidation_propagation)?;
@@ -724,6 +935,7 @@ pub fn build_sp_submsgs(
             cAsset_prices,
             basket,
             config,
+            unwrap_lp,
         };
 
         LIQUIDATION.save(storage, &liquidation_propagation)?;
@@ -732,6 +944,59 @@ pub fn build_sp_submsgs(
     Ok((leftover_repayment))
 }
 
+/// Returns the assets to pay out for an amount of collateral, each w/ its ratio of the collateral's value.
+/// If unwrapping, LP shares are exited into the pool's underlying assets,
+/// otherwise the collateral is returned as is.
+pub fn unwrap_lp_collateral(
+    querier: QuerierWrapper,
+    env: Env,
+    config: &Config,
+    collateral: &cAsset,
+    amount: Uint128,
+    unwrap_lp: bool,
+) -> StdResult<(Option<CosmosMsg>, Vec<(Asset, Decimal)>)> {
+    let pool_info = match collateral.clone().pool_info {
+        Some(pool_info) if unwrap_lp && !amount.is_zero() => pool_info,
+        _ => return Ok((None, vec![(Asset { amount, ..collateral.clone().asset }, Decimal::one())])),
+    };
+
+    let (exit_msg, share_assets) = pool_query_and_exit(
+        querier,
+        env,
+        config.clone().osmosis_proxy.unwrap_or_else(|| Addr::unchecked("")).to_string(),
+        pool_info.pool_id,
+        amount,
+    )?;
+
+    let mut underlying_assets = vec![];
+    for share_asset in share_assets.clone() {
+        let info = AssetInfo::NativeToken { denom: share_asset.denom };
+        //Split the LP's value by the pool's asset ratios
+        let value_ratio = match pool_info.asset_infos.iter().find(|lp_asset| lp_asset.info.equal(&info)) {
+            Some(lp_asset) => lp_asset.ratio,
+            None => Decimal::from_ratio(1u128, share_assets.len() as u128),
+        };
+
+        underlying_assets.push((
+            Asset {
+                info,
+                amount: Uint128::from_str(&share_asset.amount)?,
+            },
+            value_ratio,
+        ));
+    }
+
+    Ok((Some(exit_msg), underlying_assets))
+}
+
+/// Add a Coin to a list, merging it with an existing Coin of the same denom
+pub fn add_coin(coins: &mut Vec<Coin>, coin: Coin) {
+    match coins.iter_mut().find(|listed| listed.denom == coin.denom) {
+        Some(listed) => listed.amount += coin.amount,
+        None => coins.push(coin),
+    }
+}
+
 /// Returns leftover liquidatible amount from the stability pool
 pub fn query_stability_pool_liquidatible(
     querier: QuerierWrapper,
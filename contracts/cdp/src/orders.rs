@@ -0,0 +1,280 @@
+use cosmwasm_std::{attr, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
+
+use membrane::cdp::Config;
+use membrane::helpers::withdrawal_msg;
+use membrane::types::{cAsset, Asset, AssetInfo, Basket, OrderAction, OrderTrigger, PositionOrder};
+
+use crate::error::ContractError;
+use crate::positions::{close_position, withdraw};
+use crate::query::insolvency_check;
+use crate::rates::accrue;
+use crate::risk_engine::assert_basket_assets;
+use crate::state::{get_target_position, update_position, BASKETS, CONFIG, ORDER_ID, POSITION_ORDERS, POSITION_ORDER_IDS};
+
+//Constants
+const MAX_ORDERS_PER_POSITION: usize = 10;
+
+/// Set a conditional order on one of the sender's Positions.
+/// Funds sent are escrowed as the executor's tip,
+/// tips have to be the Basket's credit asset or one of its collateral types.
+pub fn set_position_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    basket_id: Uint128,
+    position_id: Uint128,
+    trigger: OrderTrigger,
+    action: OrderAction,
+) -> Result<Response, ContractError> {
+    //This forces orders to be set by the Position owner
+    let (_i, target_position) = get_target_position(deps.storage, basket_id, info.clone().sender, position_id)?;
+    let holds_asset = |asset: &AssetInfo| target_position.collateral_assets.iter().any(|cAsset| cAsset.asset.info.equal(asset));
+
+    //Validate trigger
+    match trigger.clone() {
+        OrderTrigger::LTVAbove { LTV } => {
+            if LTV.is_zero() || LTV > Decimal::one() {
+                return Err(ContractError::CustomError { val: String::from("Trigger LTV must be between 0 & 100%") });
+            }
+        },
+        OrderTrigger::PriceBelow { asset, price } | OrderTrigger::PriceAbove { asset, price } => {
+            if price.is_zero() {
+                return Err(ContractError::CustomError { val: String::from("Trigger price must be greater than 0") });
+            }
+            if !holds_asset(&asset) {
+                return Err(ContractError::CustomError { val: format!("Position doesn't hold trigger asset: {}", asset) });
+            }
+        },
+    };
+
+    //Validate action
+    match action.clone() {
+        OrderAction::Close { max_spread } | OrderAction::Deleverage { max_spread, .. } => {
+            if max_spread >= Decimal::one() {
+                return Err(ContractError::CustomError { val: String::from("Max spread must be less than 100%") });
+            }
+            if let OrderAction::Deleverage { repay_ratio, .. } = action {
+                if repay_ratio.is_zero() || repay_ratio >= Decimal::one() {
+                    return Err(ContractError::CustomError { val: String::from("Repay ratio must be between 0 & 100%") });
+                }
+            }
+        },
+        OrderAction::Withdraw { assets } => {
+            if assets.is_empty() || assets.iter().any(|asset| asset.amount.is_zero() || !holds_asset(&asset.info)) {
+                return Err(ContractError::CustomError { val: String::from("Withdrawal assets must be held by the Position") });
+            }
+        },
+    };
+
+    //Escrow the tip
+    let tip = match info.funds.len() {
+        0 => None,
+        1 => Some(Asset {
+            info: AssetInfo::NativeToken { denom: info.funds[0].denom.clone() },
+            amount: info.funds[0].amount,
+        }),
+        _ => return Err(ContractError::CustomError { val: String::from("Tip must be a single asset") }),
+    };
+    //Executors are paid in assets they can value
+    if let Some(tip) = tip.clone() {
+        let basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+        let valid_denom = basket.credit_asset.info.equal(&tip.info) || basket.collateral_types.iter().any(|cAsset| cAsset.asset.info.equal(&tip.info));
+        if tip.amount.is_zero() || !valid_denom {
+            return Err(ContractError::CustomError { val: String::from("Tip must be the Basket's credit asset or a collateral type") });
+        }
+    }
+
+    let order_id = ORDER_ID.may_load(deps.storage)?.unwrap_or(0) + 1;
+    ORDER_ID.save(deps.storage, &order_id)?;
+
+    //Add to the Position's orders.
+    //The cap keeps clearing them on transfers bounded.
+    let mut order_ids = POSITION_ORDER_IDS.may_load(deps.storage, (basket_id.u128(), position_id.u128()))?.unwrap_or_default();
+    if order_ids.len() >= MAX_ORDERS_PER_POSITION {
+        return Err(ContractError::CustomError { val: format!("Positions can have at most {} orders", MAX_ORDERS_PER_POSITION) });
+    }
+    order_ids.push(order_id);
+    POSITION_ORDER_IDS.save(deps.storage, (basket_id.u128(), position_id.u128()), &order_ids)?;
+
+    let order = PositionOrder {
+        order_id,
+        position_owner: info.sender,
+        position_id,
+        trigger,
+        action,
+        tip,
+    };
+    POSITION_ORDERS.save(deps.storage, (basket_id.u128(), order_id), &order)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "set_position_order"),
+            attr("order_id", order_id.to_string()),
+            attr("position_id", position_id),
+            attr("trigger", format!("{:?}", order.trigger)),
+            attr("action", format!("{:?}", order.action)),
+        ]))
+}
+
+/// Cancel an order & refund its tip to the Position owner
+pub fn cancel_position_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    basket_id: Uint128,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    let order = load_order(deps.storage, basket_id, order_id)?;
+
+    if info.sender != order.position_owner {
+        return Err(ContractError::Unauthorized { owner: order.position_owner.to_string() });
+    }
+    remove_order(deps.storage, basket_id, &order)?;
+
+    let mut res = Response::new();
+    if let Some(tip) = order.tip {
+        res = res.add_message(withdrawal_msg(tip, order.position_owner)?);
+    }
+
+    Ok(res.add_attributes(vec![
+        attr("method", "cancel_position_order"),
+        attr("order_id", order_id.to_string()),
+    ]))
+}
+
+/// Execute a triggered order.
+/// Triggers are checked against the Position's current LTV & oracle prices.
+/// The caller receives the tip once the action is complete.
+pub fn execute_position_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    basket_id: Uint128,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+    let order = load_order(deps.storage, basket_id, order_id)?;
+
+    let (_i, mut target_position) = get_target_position(deps.storage, basket_id, order.position_owner.clone(), order.position_id)?;
+
+    //Accrue interest so the LTV is current
+    accrue(
+        deps.storage,
+        deps.querier,
+        env.clone(),
+        config.clone(),
+        &mut target_position,
+        &mut basket,
+        order.position_owner.to_string(),
+        false,
+    )?;
+    BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
+    update_position(deps.storage, basket_id, order.position_owner.clone(), target_position.clone())?;
+
+    //Get the Position's LTV & collateral prices
+    let ((_insolvent, current_LTV, _available_fee), (_borrow_LTV, _max_LTV, _total_value, cAsset_prices, _cAsset_ratios)) = insolvency_check(
+        deps.storage,
+        env.clone(),
+        deps.querier,
+        Some(basket.clone()),
+        target_position.clone().collateral_assets,
+        target_position.credit_amount,
+        basket.clone().credit_price,
+        false,
+        config,
+    )?;
+    let asset_price = |asset: &AssetInfo| -> Option<Decimal> {
+        target_position.collateral_assets
+            .iter()
+            .position(|cAsset| cAsset.asset.info.equal(asset))
+            .map(|index| cAsset_prices[index].price)
+    };
+
+    let triggered = match order.trigger.clone() {
+        OrderTrigger::LTVAbove { LTV } => current_LTV >= LTV,
+        OrderTrigger::PriceBelow { asset, price } => asset_price(&asset).map_or(false, |current| current <= price),
+        OrderTrigger::PriceAbove { asset, price } => asset_price(&asset).map_or(false, |current| current >= price),
+    };
+    if !triggered {
+        return Err(ContractError::CustomError { val: format!("Order {} hasn't been triggered", order_id) });
+    }
+
+    //Orders execute once
+    remove_order(deps.storage, basket_id, &order)?;
+
+    let contract = env.contract.address.clone();
+    let mut res = match order.action.clone() {
+        OrderAction::Close { max_spread } => close_position(deps, env, order.position_owner.clone(), basket_id, order.position_id, max_spread, None, None)?,
+        OrderAction::Deleverage { repay_ratio, max_spread } => close_position(deps, env, order.position_owner.clone(), basket_id, order.position_id, max_spread, Some(repay_ratio), None)?,
+        OrderAction::Withdraw { assets } => {
+            let cAssets: Vec<cAsset> = assert_basket_assets(deps.storage, deps.querier, env.clone(), basket_id, assets)?;
+            //Withdrawing as the contract sends the assets to the Position owner
+            withdraw(
+                deps,
+                env,
+                MessageInfo { sender: contract, funds: vec![] },
+                basket_id,
+                order.position_id,
+                cAssets,
+                Some(order.position_owner.to_string()),
+            )?
+        },
+    };
+
+    //Pay the executor after the action's msgs
+    if let Some(tip) = order.tip {
+        res = res.add_message(withdrawal_msg(tip, info.sender.clone())?);
+    }
+
+    Ok(res.add_attributes(vec![
+        attr("method", "execute_position_order"),
+        attr("order_id", order_id.to_string()),
+        attr("executor", info.sender),
+    ]))
+}
+
+/// Remove all of a Position's orders.
+/// Returns msgs refunding their tips to the owner that set them.
+pub fn clear_position_orders(
+    storage: &mut dyn Storage,
+    basket_id: Uint128,
+    position_id: Uint128,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let order_ids = POSITION_ORDER_IDS.may_load(storage, (basket_id.u128(), position_id.u128()))?.unwrap_or_default();
+    POSITION_ORDER_IDS.remove(storage, (basket_id.u128(), position_id.u128()));
+
+    let mut refunds: Vec<CosmosMsg> = vec![];
+    for order_id in order_ids {
+        if let Some(order) = POSITION_ORDERS.may_load(storage, (basket_id.u128(), order_id))? {
+            POSITION_ORDERS.remove(storage, (basket_id.u128(), order_id));
+
+            if let Some(tip) = order.tip {
+                refunds.push(withdrawal_msg(tip, order.position_owner)?);
+            }
+        }
+    }
+
+    Ok(refunds)
+}
+
+/// Remove an order & its ID from the Position's orders
+fn remove_order(storage: &mut dyn Storage, basket_id: Uint128, order: &PositionOrder) -> Result<(), ContractError> {
+    POSITION_ORDERS.remove(storage, (basket_id.u128(), order.order_id));
+
+    let key = (basket_id.u128(), order.position_id.u128());
+    let mut order_ids = POSITION_ORDER_IDS.may_load(storage, key)?.unwrap_or_default();
+    order_ids.retain(|order_id| *order_id != order.order_id);
+    if order_ids.is_empty() {
+        POSITION_ORDER_IDS.remove(storage, key);
+    } else {
+        POSITION_ORDER_IDS.save(storage, key, &order_ids)?;
+    }
+
+    Ok(())
+}
+
+fn load_order(storage: &dyn Storage, basket_id: Uint128, order_id: u64) -> Result<PositionOrder, ContractError> {
+    match POSITION_ORDERS.may_load(storage, (basket_id.u128(), order_id))? {
+        Some(order) => Ok(order),
+        None => Err(ContractError::CustomError { val: format!("Order {} doesn't exist", order_id) }),
+    }
+}
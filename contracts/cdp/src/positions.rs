@@ -2,13 +2,13 @@ use std::str::FromStr;
 use std::vec;
 
 use cosmwasm_std::{
-    attr, to_binary, Addr, Api, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
+    attr, to_binary, Addr, Api, BankMsg, Binary, Coin, CosmosMsg, Decimal, DepsMut, Env, Event, MessageInfo,
     QuerierWrapper, QueryRequest, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
     WasmQuery,
 };
 
 use membrane::helpers::{validate_position_owner, asset_to_coin, withdrawal_msg, get_contract_balances};
-use membrane::cdp::{Config, EditBasket};
+use membrane::cdp::{CallbackMsg, Config, EditBasket, ExecuteMsg, PositionSnapshot};
 use membrane::oracle::{AssetResponse, PriceResponse};
 use membrane::liq_queue::ExecuteMsg as LQ_ExecuteMsg;
 use membrane::liquidity_check::ExecuteMsg as LiquidityExecuteMsg;
@@ -19,16 +19,18 @@ use membrane::stability_pool::ExecuteMsg as SP_ExecuteMsg;
 use membrane::math::{decimal_division, decimal_multiplication, Uint256, decimal_subtraction};
 use membrane::types::{
     cAsset, Asset, AssetInfo, AssetOracleInfo, Basket, LiquidityInfo, Position, PoolStateResponse,
-    SupplyCap, UserInfo, PoolType, RedemptionInfo, PositionRedemption, PoolInfo, LPAssetInfo
+    SupplyCap, UserInfo, PoolType, RedemptionInfo, RedemptionCollateralFilter, PositionRedemption, PoolInfo, LPAssetInfo, OperatorAllowance, ParameterRamp, CollateralVolatility
 };
 
-use crate::query::{get_cAsset_ratios, get_avg_LTV, insolvency_check};
+use crate::liquidations::unwrap_lp_collateral;
+use crate::orders::clear_position_orders;
+use crate::query::{assert_price_policy, get_cAsset_ratios, get_avg_LTV, insolvency_check};
 use crate::rates::accrue;
-use crate::risk_engine::update_basket_tally;
-use crate::state::{get_target_position, update_position, update_position_claims, ClosePositionPropagation, CollateralVolatility, Timer, BASKET, CLOSE_POSITION, FREEZE_TIMER, REDEMPTION_OPT_IN, STORED_PRICES, VOLATILITY};
+use crate::risk_engine::{assert_isolation, update_basket_tally, update_isolated_debt};
+use crate::state::{get_target_position, update_position, update_position_claims, get_basket_by_credit_asset, ClosePositionPropagation, FlashMintPropagation, LoopPositionPropagation, PendingTransfer, Timer, BASKETS, BASE_RATE_RAMP, CLOSE_POSITION, DEFAULT_BASKET_ID, DEPRECATED_COLLATERAL, FLASH_MINT, FREEZE_TIMER, LOOP_POSITION, LTV_RAMPS, PENDING_TRANSFERS, POSITION_SNAPSHOTS, REDEMPTION_OPT_IN, STORED_PRICES, VOLATILITY};
 use crate::{
     state::{
-        WithdrawPropagation, CONFIG, POSITIONS, LIQUIDATION, WITHDRAW,
+        WithdrawPropagation, CONFIG, POSITIONS, LIQUIDATION, OPERATORS, WITHDRAW,
     },
     ContractError,
 };
@@ -39,11 +41,18 @@ pub const SP_REPLY_ID: u64 = 2u64;
 pub const USER_SP_REPAY_REPLY_ID: u64 = 3u64;
 
 pub const WITHDRAW_REPLY_ID: u64 = 4u64;
+pub const CLOSE_POSITION_REPLY_ID: u64 = 5u64;
+pub const LOOP_POSITION_REPLY_ID: u64 = 6u64;
+pub const PRICE_HISTORY_REPLY_ID: u64 = 7u64;
+pub const LIQUIDATE_MANY_REPLY_ID: u64 = 8u64;
 pub const BAD_DEBT_REPLY_ID: u64 = 999999u64;
 
 
 //Constants
 const MAX_POSITIONS_AMOUNT: u32 = 9;
+const MAX_LOOPS: u64 = 10;
+/// position_health event schema version
+pub const POSITION_EVENT_VERSION: &str = "1";
 
 
 /// Deposit collateral to existing position. New or existing collateral.
@@ -52,18 +61,26 @@ pub fn deposit(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    basket_id: Uint128,
     position_owner: Option<String>,
     position_id: Option<Uint128>,
     cAssets: Vec<cAsset>,
 ) -> Result<Response, ContractError> {    
     let config = CONFIG.load(deps.storage)?;
     let valid_owner_addr = validate_position_owner(deps.api, info.clone(), position_owner)?;
-    let mut basket: Basket = BASKET.load(deps.storage)?;
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
     let mut set_redemption = false;
     
     //Check if frozen
     if basket.frozen { return Err(ContractError::Frozen {  }) }
 
+    //Deprecated collateral can't be deposited
+    for cAsset in cAssets.iter() {
+        if DEPRECATED_COLLATERAL.has(deps.storage, (basket_id.u128(), cAsset.asset.info.to_string())) {
+            return Err(ContractError::CustomError { val: format!("{} is deprecated, deposits are disabled", cAsset.asset.info) });
+        }
+    }
+
     //Set deposit_amounts to double check state storage 
     let deposit_amounts: Vec<Uint128> = cAssets.clone()
         .into_iter()
@@ -82,7 +99,7 @@ pub fn deposit(
     let mut positions_prev_collateral = vec![];
     let position_info: UserInfo;
 
-    if let Ok(mut positions) = POSITIONS.load(deps.storage, valid_owner_addr.clone()){
+    if let Ok(mut positions) = POSITIONS.load(deps.storage, (basket_id.u128(), valid_owner_addr.clone())){
 
         //Add collateral to the position_id or Create a new position 
         if let Some(position_id) = position_id {
@@ -91,6 +108,7 @@ pub fn deposit(
                 edit_redemption_info(
                     deps.storage,
                     info.clone(),
+                    basket_id,
                     vec![position_id],
                     Some(true),
                     Some(1),
@@ -135,6 +153,9 @@ pub fn deposit(
 
                     }
                 }
+                //Isolated collateral can't share a position
+                assert_isolation(&basket, &position.collateral_assets)?;
+
                 //Set updated position
                 positions[position_index] = position.clone();
                 
@@ -150,7 +171,7 @@ pub fn deposit(
                     true,
                 )?;
                 //Save Updated Vec<Positions> for the user
-                POSITIONS.save(deps.storage, valid_owner_addr, &positions)?;
+                POSITIONS.save(deps.storage, (basket_id.u128(), valid_owner_addr), &positions)?;
 
                 if !position.credit_amount.is_zero() {
                     //Update Supply caps
@@ -167,7 +188,7 @@ pub fn deposit(
                     )?;
                 }
                 //Save Basket
-                BASKET.save(deps.storage, &basket)?;
+                BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
 
             } else {
                 //If position_ID is passed but no position is found, Error. 
@@ -185,7 +206,7 @@ pub fn deposit(
             let (new_position_info, new_position) = create_position_in_deposit(
                 deps.storage,
                 deps.querier,
-                env,
+                env.clone(),
                 config.clone(),
                 valid_owner_addr.clone(),
                 cAssets.clone(),
@@ -198,7 +219,7 @@ pub fn deposit(
             //Add new position to the user's Vec<Positions>
             POSITIONS.update(
                 deps.storage,
-                valid_owner_addr,
+                (basket_id.u128(), valid_owner_addr),
                 |positions| -> StdResult<_> {
                     let mut positions = positions.unwrap_or_default();
                     positions.push(new_position);
@@ -212,6 +233,7 @@ pub fn deposit(
                 edit_redemption_info(
                     deps.storage,
                     info.clone(),
+                    basket_id,
                     vec![position_info.position_id],
                     Some(true),
                     Some(1),
@@ -225,7 +247,7 @@ pub fn deposit(
         let (new_position_info, new_position) = create_position_in_deposit(
             deps.storage,
             deps.querier,
-            env,
+            env.clone(),
             config.clone(),
             valid_owner_addr.clone(),
             cAssets.clone(),
@@ -238,7 +260,7 @@ pub fn deposit(
         //Add new Vec of Positions to state under the user
         POSITIONS.save(
             deps.storage,
-            valid_owner_addr,
+            (basket_id.u128(), valid_owner_addr),
             &vec![new_position],
         )?;
                 
@@ -247,6 +269,7 @@ pub fn deposit(
             edit_redemption_info(
                 deps.storage,
                 info.clone(),
+                basket_id,
                 vec![position_info.position_id],
                 Some(true),
                 Some(1),
@@ -258,9 +281,21 @@ pub fn deposit(
     }
 
     //Double check State storage
-    check_deposit_state(deps.storage, deps.api, positions_prev_collateral, deposit_amounts, position_info.clone())?;    
+    check_deposit_state(deps.storage, deps.api, basket_id, positions_prev_collateral, deposit_amounts, position_info.clone())?;    
+
+    //Record post-deposit health
+    let health_event = record_position_health(
+        deps.storage,
+        deps.querier,
+        env,
+        basket_id,
+        deps.api.addr_validate(&position_info.position_owner)?,
+        position_info.position_id,
+        "deposit",
+    );
 
     Ok(Response::new()
+    .add_event(health_event)
     .add_attributes(vec![
         attr("method", "deposit"),
         attr("position_owner", position_info.position_owner),
@@ -280,6 +315,9 @@ fn create_position_in_deposit(
     cAssets: Vec<cAsset>,
     basket: &mut Basket,
 ) -> Result<(UserInfo, Position), ContractError> {
+    //Isolated collateral can't share a position
+    assert_isolation(basket, &cAssets)?;
+
     let mut new_position = create_position(cAssets, basket)?;
 
     //Store position_info for reply
@@ -300,7 +338,7 @@ fn create_position_in_deposit(
         true,
     )?;
     //Save Basket
-    BASKET.save(storage, basket)?;
+    BASKETS.save(storage, basket.basket_id.u128(), basket)?;
 
     Ok((position_info, new_position))
 }
@@ -309,12 +347,14 @@ fn create_position_in_deposit(
 fn check_deposit_state(
     storage: &mut dyn Storage,  
     api: &dyn Api,   
+    basket_id: Uint128,
     positions_prev_collateral: Vec<Asset>, //Amount of collateral in the position before the deposit
     deposit_amounts: Vec<Uint128>,
     position_info: UserInfo,
 ) -> Result<(), ContractError>{
     let (_i, target_position) = get_target_position(
         storage, 
+        basket_id,
         api.addr_validate(&position_info.position_owner)?, 
         position_info.position_id
     )?;
@@ -350,12 +390,13 @@ pub fn withdraw(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    basket_id: Uint128,
     position_id: Uint128,
     cAssets: Vec<cAsset>,
     send_to: Option<String>,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
-    let mut basket: Basket = BASKET.load(deps.storage)?;
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
     let mut msgs = vec![];
 
     //Check if frozen
@@ -376,7 +417,7 @@ pub fn withdraw(
     }
 
     //This forces withdrawals to be done by the info.sender
-    let (position_index, mut target_position) = get_target_position(deps.storage, valid_position_owner.clone(), position_id)?;
+    let (position_index, mut target_position) = get_target_position(deps.storage, basket_id, valid_position_owner.clone(), position_id)?;
     //Accrue interest
     accrue(
         deps.storage,
@@ -438,7 +479,7 @@ pub fn withdraw(
 
                 //If resulting LTV makes the position insolvent, error. If not construct withdrawal_msg
                 //This is taking max_borrow_LTV so users can't max borrow and then withdraw to get a higher initial LTV
-                let (insolvency_res, _) = insolvency_check(
+                let (insolvency_res, (_borrow_LTV, _max_LTV, _total_value, cAsset_prices, _cAsset_ratios)) = insolvency_check(
                     deps.storage,
                     env.clone(),
                     deps.querier,
@@ -449,11 +490,20 @@ pub fn withdraw(
                     true,
                     config.clone(),
                 )?;
+                //Degraded, stale or uncertain oracle prices can't back the debt left after a withdrawal
+                if !target_position.credit_amount.is_zero() {
+                    assert_price_policy(config.clone().price_policy, env.block.time.seconds(), &target_position.collateral_assets, &cAsset_prices)?;
+                    for (i, cAsset) in target_position.collateral_assets.iter().enumerate() {
+                        if !cAsset.asset.amount.is_zero() && cAsset_prices[i].degraded {
+                            return Err(ContractError::DegradedPrice { asset: cAsset.asset.info.to_string() });
+                        }
+                    }
+                }
                 if insolvency_res.0 {
                     return Err(ContractError::PositionInsolvent { insolvency_res });
                 } else {
                     //Update Position list
-                    POSITIONS.update(deps.storage, valid_position_owner.clone(), |positions: Option<Vec<Position>>| -> Result<Vec<Position>, ContractError>{
+                    POSITIONS.update(deps.storage, (basket_id.u128(), valid_position_owner.clone()), |positions: Option<Vec<Position>>| -> Result<Vec<Position>, ContractError>{
 
                         let mut updating_positions = positions.unwrap_or_else(|| vec![]);
 
@@ -467,10 +517,10 @@ pub fn withdraw(
                         Ok( updating_positions )                    
                     })?;
                     //load to check if positions list is fully empty
-                    let positions = POSITIONS.load(deps.storage, valid_position_owner.clone())?;
+                    let positions = POSITIONS.load(deps.storage, (basket_id.u128(), valid_position_owner.clone()))?;
                     //Delete if empty
                     if positions.is_empty(){
-                        POSITIONS.remove(deps.storage, valid_position_owner.clone());
+                        POSITIONS.remove(deps.storage, (basket_id.u128(), valid_position_owner.clone()));
                     }
 
                 }
@@ -496,6 +546,13 @@ pub fn withdraw(
         msgs.push(SubMsg::reply_on_success(message, WITHDRAW_REPLY_ID));
     }
 
+    //Refund the order tips of a fully withdrawn Position
+    if check_for_empty_position(target_position.clone().collateral_assets) {
+        for refund_msg in clear_position_orders(deps.storage, basket_id, position_id)? {
+            msgs.push(SubMsg::new(refund_msg));
+        }
+    }
+
     //Update supply cap tallies
     if !target_position.clone().credit_amount.is_zero(){        
         //Update basket supply cap tallies after the full withdrawal to improve UX by smoothing debt_cap restrictions
@@ -512,7 +569,7 @@ pub fn withdraw(
         )?;
     } 
     //Save updated repayment price and asset tallies
-    BASKET.save(deps.storage, &basket)?;
+    BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
     
     //Set Withdrawal_Prop
     let prop_assets_info: Vec<AssetInfo> = prop_assets
@@ -526,17 +583,22 @@ pub fn withdraw(
         withdraw_amounts,
         contracts_prev_collateral_amount: get_contract_balances(
             deps.querier,
-            env,
+            env.clone(),
             prop_assets_info,
         )?,
         position_info: UserInfo {
             position_id,
             position_owner: info.sender.to_string(),
         },
+        basket_id,
     };
     WITHDRAW.save(deps.storage, &withdrawal_prop)?;
 
+    //Record post-withdrawal health
+    let health_event = record_position_health(deps.storage, deps.querier, env, basket_id, valid_position_owner, position_id, "withdraw");
+
     Ok(Response::new()
+        .add_event(health_event)
         .add_attributes(vec![
             attr("method", "withdraw"),
             attr("position_id", position_id),
@@ -554,13 +616,14 @@ pub fn repay(
     api: &dyn Api,
     env: Env,
     info: MessageInfo,
+    basket_id: Uint128,
     position_id: Uint128,
     position_owner: Option<String>,
     credit_asset: Asset,
     send_excess_to: Option<String>,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(storage)?;
-    let mut basket: Basket = BASKET.load(storage)?;
+    let mut basket: Basket = BASKETS.load(storage, basket_id.u128())?;
 
     //Check if frozen
     if basket.frozen { return Err(ContractError::Frozen {  }) }
@@ -569,7 +632,7 @@ pub fn repay(
     let valid_owner_addr = validate_position_owner(api, info.clone(), position_owner)?;
     
     //Get target_position
-    let (position_index, mut target_position) = get_target_position(storage, valid_owner_addr.clone(), position_id)?;
+    let (position_index, mut target_position) = get_target_position(storage, basket_id, valid_owner_addr.clone(), position_id)?;
 
     //SP accrues external before calling repay, so we only accrue if the sender isn't the SP
     if info.sender != config.clone().stability_pool.unwrap_or(Addr::unchecked("")){   
@@ -603,6 +666,9 @@ pub fn repay(
         },
     };
 
+    //Update isolated debt total
+    update_isolated_debt(storage, &basket, &target_position.collateral_assets, credit_asset.amount - excess_repayment, false)?;
+
     //Update Supply caps if this clears all debt
     if target_position.credit_amount.is_zero(){
         update_basket_tally(
@@ -644,7 +710,7 @@ pub fn repay(
     //To indicate removed positions during ClosePosition
     let mut removed = false;
     //Update Position
-    POSITIONS.update(storage, valid_owner_addr.clone(), |positions: Option<Vec<Position>>| -> Result<Vec<Position>, ContractError> {
+    POSITIONS.update(storage, (basket_id.u128(), valid_owner_addr.clone()), |positions: Option<Vec<Position>>| -> Result<Vec<Position>, ContractError> {
         let mut updating_positions = positions.unwrap_or_else(|| vec![]);
 
         //If new position isn't empty, update
@@ -698,20 +764,28 @@ pub fn repay(
     };
 
     //Save updated repayment price and debts
-    BASKET.save(storage, &basket)?;
+    BASKETS.save(storage, basket_id.u128(), &basket)?;
 
     if !removed {
         //Check that state was saved correctly
         check_repay_state(
             storage,
+            basket_id,
             credit_asset.amount - excess_repayment, 
             prev_credit_amount, 
             position_id, 
-            valid_owner_addr
+            valid_owner_addr.clone()
         )?;
+    } else {
+        //Refund the closed Position's order tips
+        messages.extend(clear_position_orders(storage, basket_id, position_id)?);
     }
+
+    //Record post-repayment health
+    let health_event = record_position_health(storage, querier, env, basket_id, valid_owner_addr, position_id, "repay");
     
     Ok(Response::new()
+        .add_event(health_event)
         .add_messages(messages)
         .add_attributes(vec![
             attr("method", "repay"),
@@ -723,6 +797,7 @@ pub fn repay(
 /// Asserts valid state after repay()
 fn check_repay_state(
     storage: &mut dyn Storage,
+    basket_id: Uint128,
     repay_amount: Uint128,
     prev_credit_amount: Uint128,
     position_id: Uint128,
@@ -730,7 +805,7 @@ fn check_repay_state(
 ) -> Result<(), ContractError>{
 
     //Get target_position
-    let (_i, target_position) = get_target_position(storage, position_owner, position_id)?;
+    let (_i, target_position) = get_target_position(storage, basket_id, position_owner, position_id)?;
 
     //If repay amount should've 0'd the position's debt and it didn't error
     if repay_amount >= prev_credit_amount && target_position.credit_amount != Uint128::zero(){ 
@@ -745,6 +820,727 @@ fn check_repay_state(
     Ok(())
 }
 
+/// Close a Position by selling collateral to repay its debt.
+/// Sells collateral through the Osmosis Proxy's swap routes, the repayment & withdrawal happen in the reply.
+/// A repay_ratio only sells enough to repay that % of the debt & leaves the Position open.
+/// Note: The leftover collateral defaults to the Position owner.
+pub fn close_position(
+    deps: DepsMut,
+    env: Env,
+    position_owner: Addr,
+    basket_id: Uint128,
+    position_id: Uint128,
+    max_spread: Decimal,
+    repay_ratio: Option<Decimal>,
+    send_to: Option<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+
+    //Check if frozen
+    if basket.frozen { return Err(ContractError::Frozen {  }) }
+
+    //Spread can't be 100% or more
+    if max_spread >= Decimal::one() {
+        return Err(ContractError::CustomError { val: String::from("Max spread must be less than 100%") });
+    }
+
+    //Validate send_to
+    if let Some(addr) = send_to.clone() {
+        deps.api.addr_validate(&addr)?;
+    }
+
+    //Get Osmosis Proxy to route the sale
+    let osmosis_proxy = match config.clone().osmosis_proxy {
+        Some(addr) => addr,
+        None => return Err(ContractError::CustomError { val: String::from("Osmosis Proxy contract not added to config") }),
+    };
+
+    //Partial repayments have to leave debt to repay
+    if let Some(ratio) = repay_ratio {
+        if ratio.is_zero() || ratio >= Decimal::one() {
+            return Err(ContractError::CustomError { val: String::from("Repay ratio must be between 0 & 100%") });
+        }
+    }
+
+    let (_i, mut target_position) = get_target_position(deps.storage, basket_id, position_owner.clone(), position_id)?;
+
+    //Accrue interest
+    accrue(
+        deps.storage,
+        deps.querier,
+        env.clone(),
+        config.clone(),
+        &mut target_position,
+        &mut basket,
+        position_owner.to_string(),
+        false,
+    )?;
+    //Save updated repayment price
+    BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
+    update_position(deps.storage, basket_id, position_owner.clone(), target_position.clone())?;
+
+    //Positions without debt don't need to sell collateral
+    if target_position.credit_amount.is_zero() {
+        return Err(ContractError::CustomError { val: String::from("Position has no debt to repay, use Withdraw instead") });
+    }
+
+    //Get collateral ratios & prices
+    let (cAsset_ratios, cAsset_prices) = get_cAsset_ratios(
+        deps.storage,
+        env.clone(),
+        deps.querier,
+        target_position.clone().collateral_assets,
+        config.clone(),
+        Some(basket.clone()),
+    )?;
+
+    //Value of the debt, increased by the spread to ensure the sale covers the full repayment
+    let mut credit_value = basket.clone().credit_price.get_value(target_position.credit_amount)?;
+    if let Some(ratio) = repay_ratio {
+        credit_value = decimal_multiplication(credit_value, ratio)?;
+    }
+    let sale_value = decimal_multiplication(credit_value, Decimal::one() + max_spread)?;
+
+    //Sell collateral in proportion to the Position's collateral ratios
+    let mut withdrawn_assets: Vec<Asset> = vec![];
+    let mut sale_coins: Vec<Coin> = vec![];
+    for (i, cAsset) in target_position.clone().collateral_assets.into_iter().enumerate() {
+        let asset_sale_value = decimal_multiplication(sale_value, cAsset_ratios[i])?;
+        let mut sale_amount = cAsset_prices[i].get_amount(asset_sale_value)?;
+
+        //Can't sell more than the Position holds
+        if sale_amount > cAsset.asset.amount {
+            sale_amount = cAsset.asset.amount;
+        }
+        if sale_amount.is_zero() { continue }
+
+        let sale_asset = Asset {
+            amount: sale_amount,
+            info: cAsset.asset.info,
+        };
+        sale_coins.push(asset_to_coin(sale_asset.clone())?);
+        withdrawn_assets.push(sale_asset);
+    }
+    sale_coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    //Swap the collateral for the credit asset
+    let swap_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: osmosis_proxy.to_string(),
+        msg: to_binary(&OsmoExecuteMsg::ExecuteSwaps {
+            token_out: basket.clone().credit_asset.info.to_string(),
+            max_slippage: max_spread,
+        })?,
+        funds: sale_coins,
+    });
+
+    //Set ClosePosition Prop
+    CLOSE_POSITION.save(deps.storage, &ClosePositionPropagation {
+        withdrawn_assets: withdrawn_assets.clone(),
+        contracts_prev_credit_amount: get_contract_balances(
+            deps.querier,
+            env,
+            vec![basket.clone().credit_asset.info],
+        )?[0],
+        position_info: UserInfo {
+            position_id,
+            position_owner: position_owner.to_string(),
+        },
+        send_to,
+        basket_id,
+        repay_ratio,
+    })?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "close_position"),
+            attr("position_id", position_id),
+            attr("credit_amount", target_position.credit_amount),
+            attr("sold_assets", format!("{:?}", withdrawn_assets)),
+        ])
+        .add_submessage(SubMsg::reply_on_success(swap_msg, CLOSE_POSITION_REPLY_ID)))
+}
+
+/// Loop a Position's debt into its collateral to lever up to a target LTV.
+/// Each loop mints credit, swaps it into the Position's collateral ratios & deposits the swapped collateral.
+pub fn loop_position(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    basket_id: Uint128,
+    position_id: Uint128,
+    target_LTV: Decimal,
+    max_slippage: Decimal,
+    num_loops: Option<u64>,
+) -> Result<Response, ContractError> {
+    //Slippage can't be 100% or more
+    if max_slippage >= Decimal::one() {
+        return Err(ContractError::CustomError { val: String::from("Max slippage must be less than 100%") });
+    }
+
+    //Validate loop count
+    let num_loops = num_loops.unwrap_or(MAX_LOOPS);
+    if num_loops == 0 || num_loops > MAX_LOOPS {
+        return Err(ContractError::CustomError { val: format!("Number of loops must be between 1 and {}", MAX_LOOPS) });
+    }
+
+    //This forces loops to be done by the info.sender
+    loop_position_step(deps, env, basket_id, info.sender, position_id, target_LTV, max_slippage, num_loops)
+}
+
+/// Mint credit to reach the target LTV & swap it into the Position's collateral.
+/// The swapped collateral is deposited in the LOOP_POSITION_REPLY_ID reply.
+pub fn loop_position_step(
+    mut deps: DepsMut,
+    env: Env,
+    basket_id: Uint128,
+    position_owner: Addr,
+    position_id: Uint128,
+    target_LTV: Decimal,
+    max_slippage: Decimal,
+    loops_left: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+
+    //Get Osmosis Proxy to route the swaps
+    let osmosis_proxy = match config.clone().osmosis_proxy {
+        Some(addr) => addr,
+        None => return Err(ContractError::CustomError { val: String::from("Osmosis Proxy contract not added to config") }),
+    };
+
+    let (_i, mut target_position) = get_target_position(deps.storage, basket_id, position_owner.clone(), position_id)?;
+
+    //LPs can't be swapped into
+    if target_position.collateral_assets.iter().any(|cAsset| cAsset.pool_info.is_some()) {
+        return Err(ContractError::CustomError { val: String::from("Positions with LP collateral can't be looped") });
+    }
+
+    //Accrue interest
+    accrue(
+        deps.storage,
+        deps.querier,
+        env.clone(),
+        config.clone(),
+        &mut target_position,
+        &mut basket,
+        position_owner.to_string(),
+        false,
+    )?;
+    //Save updated repayment price
+    BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
+    update_position(deps.storage, basket_id, position_owner.clone(), target_position.clone())?;
+
+    //Get amount of credit to mint to reach the target LTV
+    let mint_amount = get_amount_from_LTV(
+        deps.storage,
+        deps.querier,
+        env.clone(),
+        config.clone(),
+        target_position.clone(),
+        basket.clone(),
+        target_LTV,
+    )?;
+
+    //Mint the credit to the contract
+    let mint_res = increase_debt(
+        deps.branch(),
+        env.clone(),
+        MessageInfo {
+            sender: position_owner.clone(),
+            funds: vec![],
+        },
+        basket_id,
+        position_id,
+        Some(mint_amount),
+        None,
+        Some(env.contract.address.to_string()),
+        None,
+    )?;
+
+    //Get collateral ratios
+    let (cAsset_ratios, _cAsset_prices) = get_cAsset_ratios(
+        deps.storage,
+        env.clone(),
+        deps.querier,
+        target_position.clone().collateral_assets,
+        config.clone(),
+        Some(basket.clone()),
+    )?;
+
+    //Swap the minted credit in proportion to the Position's collateral ratios
+    let mut swap_msgs: Vec<CosmosMsg> = vec![];
+    let mut swapped_amount = Uint128::zero();
+    for (i, cAsset) in target_position.collateral_assets.iter().enumerate() {
+        //The last asset gets the remainder
+        let swap_amount = if i == target_position.collateral_assets.len() - 1 {
+            mint_amount - swapped_amount
+        } else {
+            mint_amount * cAsset_ratios[i]
+        };
+        if swap_amount.is_zero() { continue }
+        swapped_amount += swap_amount;
+
+        swap_msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: osmosis_proxy.to_string(),
+            msg: to_binary(&OsmoExecuteMsg::ExecuteSwaps {
+                token_out: cAsset.asset.info.to_string(),
+                max_slippage,
+            })?,
+            funds: vec![asset_to_coin(Asset {
+                amount: swap_amount,
+                info: basket.clone().credit_asset.info,
+            })?],
+        }));
+    }
+
+    //Reply to the last swap to deposit the swapped collateral
+    let last_swap = match swap_msgs.pop() {
+        Some(msg) => msg,
+        None => return Err(ContractError::CustomError { val: String::from("No credit to loop") }),
+    };
+
+    //Set LoopPosition Prop
+    let collateral_infos: Vec<AssetInfo> = target_position.collateral_assets.iter().map(|cAsset| cAsset.asset.info.clone()).collect();
+    let prev_balances = get_contract_balances(deps.querier, env, collateral_infos.clone())?;
+    LOOP_POSITION.save(deps.storage, &LoopPositionPropagation {
+        position_info: UserInfo {
+            position_id,
+            position_owner: position_owner.to_string(),
+        },
+        target_LTV,
+        max_slippage,
+        loops_left: loops_left - 1,
+        contracts_prev_collateral: collateral_infos
+            .into_iter()
+            .zip(prev_balances.into_iter())
+            .map(|(info, amount)| Asset { info, amount })
+            .collect(),
+        basket_id,
+    })?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "loop_position"),
+            attr("position_id", position_id),
+            attr("minted_amount", mint_amount),
+            attr("loops_left", (loops_left - 1).to_string()),
+        ])
+        .add_submessages(mint_res.messages)
+        .add_messages(swap_msgs)
+        .add_submessage(SubMsg::reply_on_success(last_swap, LOOP_POSITION_REPLY_ID)))
+}
+
+/// Mint credit without collateral & call the receiver with it.
+/// A callback asserts that the amount + fee was returned before the transaction ends.
+pub fn flash_mint(
+    deps: DepsMut,
+    env: Env,
+    basket_id: Uint128,
+    amount: Uint128,
+    receiver: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+
+    //Check if frozen
+    if basket.frozen { return Err(ContractError::Frozen {  }) }
+
+    //Flash mints can't be nested
+    if FLASH_MINT.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::CustomError { val: String::from("Flash mint already in progress") });
+    }
+
+    //Enforce the cap
+    let flash_mint_cap = config.flash_mint_cap.unwrap_or_default();
+    if flash_mint_cap.is_zero() {
+        return Err(ContractError::CustomError { val: String::from("Flash minting is disabled") });
+    }
+    if amount.is_zero() || amount > flash_mint_cap {
+        return Err(ContractError::CustomError { val: format!("Flash mint amount must be between 0 and {}", flash_mint_cap) });
+    }
+
+    let receiver = deps.api.addr_validate(&receiver)?;
+    let fee = amount * config.flash_mint_fee.unwrap_or_default();
+
+    //Save the contract's credit balance to check the repayment against
+    let contracts_prev_credit_amount = get_contract_balances(deps.querier, env.clone(), vec![basket.clone().credit_asset.info])?[0];
+    FLASH_MINT.save(deps.storage, &FlashMintPropagation {
+        amount,
+        fee,
+        receiver: receiver.clone(),
+        contracts_prev_credit_amount,
+        basket_id,
+    })?;
+
+    //Mint to the receiver
+    let mint_msg = credit_mint_msg(
+        config,
+        Asset {
+            amount,
+            info: basket.clone().credit_asset.info,
+        },
+        receiver.clone(),
+    )?;
+
+    //Call the receiver
+    let receiver_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: receiver.to_string(),
+        msg,
+        funds: vec![],
+    });
+
+    //Check the repayment after the receiver is done
+    let callback_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_binary(&ExecuteMsg::WithBasket {
+            basket_id,
+            msg: Box::new(ExecuteMsg::Callback(CallbackMsg::CheckFlashMintRepayment {})),
+        })?,
+        funds: vec![],
+    });
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "flash_mint"),
+            attr("receiver", receiver),
+            attr("amount", amount),
+            attr("fee", fee),
+        ])
+        .add_messages(vec![mint_msg, receiver_msg, callback_msg]))
+}
+
+/// Assert the flash mint was returned with its fee.
+/// The returned credit is burned & the fee is added to pending_revenue.
+pub fn check_flash_mint_repayment(
+    deps: DepsMut,
+    env: Env,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let flash_mint = FLASH_MINT.load(deps.storage)?;
+    FLASH_MINT.remove(deps.storage);
+
+    let mut basket: Basket = BASKETS.load(deps.storage, flash_mint.basket_id.u128())?;
+
+    //Assert the amount + fee was returned
+    let repayment_amount = flash_mint.amount + flash_mint.fee;
+    let credit_amount = get_contract_balances(deps.querier, env.clone(), vec![basket.clone().credit_asset.info])?[0];
+    let returned_amount = credit_amount.checked_sub(flash_mint.contracts_prev_credit_amount).unwrap_or_default();
+    if returned_amount < repayment_amount {
+        return Err(ContractError::CustomError { val: format!("Flash mint wasn't repaid: {} < {}", returned_amount, repayment_amount) });
+    }
+
+    //Burn the repayment, the fee is minted later as revenue
+    let burn_msg = match (config.osmosis_proxy, basket.clone().credit_asset.info) {
+        (Some(osmosis_proxy), AssetInfo::NativeToken { denom }) => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: osmosis_proxy.to_string(),
+            msg: to_binary(&OsmoExecuteMsg::BurnTokens {
+                denom,
+                amount: repayment_amount,
+                burn_from_address: env.contract.address.to_string(),
+            })?,
+            funds: vec![],
+        }),
+        _ => return Err(ContractError::CustomError { val: String::from("No proxy contract setup") }),
+    };
+
+    basket.pending_revenue += flash_mint.fee;
+    BASKETS.save(deps.storage, flash_mint.basket_id.u128(), &basket)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "check_flash_mint_repayment"),
+            attr("receiver", flash_mint.receiver),
+            attr("repaid_amount", returned_amount),
+            attr("fee", flash_mint.fee),
+        ])
+        .add_message(burn_msg))
+}
+
+/// Position actions an operator can be allowed to take
+pub enum OperatorAction {
+    Withdraw,
+    IncreaseDebt,
+    EditRedeemability,
+}
+
+/// Validate the sender's authority over the Position owner's Positions.
+/// Returns MessageInfo w/ the owner as the sender & the operator if the sender is acting as one.
+pub fn validate_operator(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    env: Env,
+    info: MessageInfo,
+    basket_id: Uint128,
+    position_owner: Option<String>,
+    position_ids: Vec<Uint128>,
+    action: OperatorAction,
+) -> Result<(MessageInfo, Option<Addr>), ContractError> {
+    let valid_owner_addr = validate_position_owner(api, info.clone(), position_owner)?;
+
+    //Owners don't need an allowance
+    if valid_owner_addr == info.sender {
+        return Ok((info, None));
+    }
+
+    //Load the allowance for the Basket, Position IDs are per Basket
+    let allowance = match OPERATORS.load(storage, (basket_id.u128(), valid_owner_addr.clone(), info.clone().sender)){
+        Ok(allowance) => allowance,
+        Err(_) => return Err(ContractError::Unauthorized { owner: valid_owner_addr.to_string() }),
+    };
+
+    //Check expiration
+    if let Some(expiration) = allowance.expiration {
+        if env.block.time.seconds() >= expiration {
+            return Err(ContractError::CustomError { val: String::from("Operator allowance has expired") });
+        }
+    }
+
+    //Check Position IDs
+    if let Some(allowed_ids) = allowance.clone().position_ids {
+        for id in position_ids {
+            if !allowed_ids.contains(&id) {
+                return Err(ContractError::CustomError { val: format!("Operator isn't allowed to manage Position {}", id) });
+            }
+        }
+    }
+
+    //Check action
+    let allowed = match action {
+        OperatorAction::Withdraw => allowance.withdraw,
+        OperatorAction::IncreaseDebt => allowance.increase_debt,
+        OperatorAction::EditRedeemability => allowance.edit_redeemability,
+    };
+    if !allowed {
+        return Err(ContractError::Unauthorized { owner: valid_owner_addr.to_string() });
+    }
+
+    Ok((
+        MessageInfo {
+            sender: valid_owner_addr,
+            funds: info.funds,
+        },
+        Some(info.sender),
+    ))
+}
+
+/// Subtract minted debt from an operator's debt limit
+fn use_operator_debt_allowance(
+    storage: &mut dyn Storage,
+    basket_id: Uint128,
+    position_owner: Addr,
+    operator: Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let mut allowance = OPERATORS.load(storage, (basket_id.u128(), position_owner.clone(), operator.clone()))?;
+
+    if let Some(debt_limit) = allowance.debt_limit {
+        allowance.debt_limit = match debt_limit.checked_sub(amount){
+            Ok(remaining) => Some(remaining),
+            Err(_) => return Err(ContractError::CustomError { val: format!("Operator debt limit exceeded: {} > {}", amount, debt_limit) }),
+        };
+
+        OPERATORS.save(storage, (basket_id.u128(), position_owner, operator), &allowance)?;
+    }
+
+    Ok(())
+}
+
+/// Approve, edit or remove an operator for the sender's Positions in the Basket
+pub fn edit_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    basket_id: Uint128,
+    operator: String,
+    allowance: Option<OperatorAllowance>,
+) -> Result<Response, ContractError> {
+    let valid_operator = deps.api.addr_validate(&operator)?;
+
+    if valid_operator == info.sender {
+        return Err(ContractError::CustomError { val: String::from("Position owners can't be their own operator") });
+    }
+
+    match allowance.clone() {
+        Some(allowance) => OPERATORS.save(deps.storage, (basket_id.u128(), info.clone().sender, valid_operator.clone()), &allowance)?,
+        None => OPERATORS.remove(deps.storage, (basket_id.u128(), info.clone().sender, valid_operator.clone())),
+    };
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "edit_operator"),
+            attr("basket_id", basket_id),
+            attr("position_owner", info.sender),
+            attr("operator", valid_operator),
+            attr("allowance", format!("{:?}", allowance)),
+        ]))
+}
+
+/// Offer a Position to a new owner.
+/// The transfer completes once the new owner accepts it, a new offer replaces the pending one.
+pub fn transfer_position(
+    deps: DepsMut,
+    info: MessageInfo,
+    basket_id: Uint128,
+    position_id: Uint128,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+
+    //Check if frozen
+    if basket.frozen { return Err(ContractError::Frozen {  }) }
+
+    let valid_new_owner = deps.api.addr_validate(&new_owner)?;
+    if valid_new_owner == info.sender {
+        return Err(ContractError::CustomError { val: String::from("Position is already owned by the new owner") });
+    }
+
+    //This forces transfers to be offered by the info.sender
+    get_target_position(deps.storage, basket_id, info.clone().sender, position_id)?;
+
+    PENDING_TRANSFERS.save(deps.storage, (basket_id.u128(), position_id.u128()), &PendingTransfer {
+        position_owner: info.clone().sender,
+        new_owner: valid_new_owner.clone(),
+    })?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "transfer_position"),
+            attr("position_id", position_id),
+            attr("position_owner", info.sender),
+            attr("new_owner", valid_new_owner),
+        ]))
+}
+
+/// Cancel a pending Position transfer
+pub fn cancel_position_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    basket_id: Uint128,
+    position_id: Uint128,
+) -> Result<Response, ContractError> {
+    let transfer = load_pending_transfer(deps.storage, basket_id, position_id)?;
+
+    if info.sender != transfer.position_owner {
+        return Err(ContractError::Unauthorized { owner: transfer.position_owner.to_string() });
+    }
+    PENDING_TRANSFERS.remove(deps.storage, (basket_id.u128(), position_id.u128()));
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "cancel_position_transfer"),
+            attr("position_id", position_id),
+            attr("new_owner", transfer.new_owner),
+        ]))
+}
+
+/// Accept a pending Position transfer.
+/// The Position's redemption opt-in moves with it,
+/// its orders are removed & their tips refunded to the previous owner.
+pub fn accept_position_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    basket_id: Uint128,
+    position_id: Uint128,
+) -> Result<Response, ContractError> {
+    let basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+
+    //Check if frozen
+    if basket.frozen { return Err(ContractError::Frozen {  }) }
+
+    let transfer = load_pending_transfer(deps.storage, basket_id, position_id)?;
+    if info.sender != transfer.new_owner {
+        return Err(ContractError::Unauthorized { owner: transfer.new_owner.to_string() });
+    }
+    PENDING_TRANSFERS.remove(deps.storage, (basket_id.u128(), position_id.u128()));
+
+    let previous_owner = transfer.position_owner;
+    let valid_new_owner = transfer.new_owner;
+
+    //Errors if the Position was closed since the offer
+    let (position_index, target_position) = get_target_position(deps.storage, basket_id, previous_owner.clone(), position_id)?;
+
+    //Remove Position from the owner
+    let mut owner_positions = POSITIONS.load(deps.storage, (basket_id.u128(), previous_owner.clone()))?;
+    owner_positions.remove(position_index);
+    if owner_positions.is_empty() {
+        POSITIONS.remove(deps.storage, (basket_id.u128(), previous_owner.clone()));
+    } else {
+        POSITIONS.save(deps.storage, (basket_id.u128(), previous_owner.clone()), &owner_positions)?;
+    }
+
+    //Add Position to the new owner
+    POSITIONS.update(deps.storage, (basket_id.u128(), valid_new_owner.clone()), |positions: Option<Vec<Position>>| -> Result<Vec<Position>, ContractError> {
+        let mut new_owner_positions = positions.unwrap_or_else(|| vec![]);
+
+        //Enforce max positions
+        if new_owner_positions.len() >= MAX_POSITIONS_AMOUNT as usize {
+            return Err(ContractError::MaxPositionsReached {});
+        }
+        new_owner_positions.push(target_position.clone());
+
+        Ok(new_owner_positions)
+    })?;
+
+    //Move the Position's redemption opt-in to the new owner
+    for premium in 0..100u128 {
+        let mut users_of_premium: Vec<RedemptionInfo> = match REDEMPTION_OPT_IN.load(deps.storage, (basket_id.u128(), premium)){
+            Ok(list)=> list,
+            Err(_err) => continue,
+        };
+
+        //Remove the Position from the owner's RedemptionInfo
+        let mut position_redemption: Option<PositionRedemption> = None;
+        if let Some((user_index, mut user_positions)) = users_of_premium.clone().into_iter().enumerate().find(|(_, user)| user.position_owner == previous_owner){
+            if let Some((redemption_index, redemption)) = user_positions.clone().position_infos.into_iter().enumerate().find(|(_, position)| position.position_id == position_id){
+                user_positions.position_infos.remove(redemption_index);
+                position_redemption = Some(redemption);
+
+                if user_positions.position_infos.is_empty() {
+                    users_of_premium.remove(user_index);
+                } else {
+                    users_of_premium[user_index] = user_positions;
+                }
+            }
+        }
+
+        //Add it to the new owner's RedemptionInfo
+        if let Some(redemption) = position_redemption {
+            if let Some((user_index, _)) = users_of_premium.clone().into_iter().enumerate().find(|(_, user)| user.position_owner == valid_new_owner){
+                users_of_premium[user_index].position_infos.push(redemption);
+            } else {
+                users_of_premium.push(RedemptionInfo {
+                    position_owner: valid_new_owner.clone(),
+                    position_infos: vec![redemption],
+                });
+            }
+            REDEMPTION_OPT_IN.save(deps.storage, (basket_id.u128(), premium), &users_of_premium)?;
+
+            //Positions are only opted into one premium
+            break;
+        }
+    }
+
+    //Orders were set by the previous owner
+    let refund_msgs = clear_position_orders(deps.storage, basket_id, position_id)?;
+
+    Ok(Response::new()
+        .add_messages(refund_msgs)
+        .add_attributes(vec![
+            attr("method", "accept_position_transfer"),
+            attr("position_id", position_id),
+            attr("previous_owner", previous_owner),
+            attr("new_owner", valid_new_owner),
+        ]))
+}
+
+fn load_pending_transfer(storage: &dyn Storage, basket_id: Uint128, position_id: Uint128) -> Result<PendingTransfer, ContractError> {
+    match PENDING_TRANSFERS.may_load(storage, (basket_id.u128(), position_id.u128()))? {
+        Some(transfer) => Ok(transfer),
+        None => Err(ContractError::CustomError { val: format!("Position {} has no pending transfer", position_id) }),
+    }
+}
+
 /// This is what the stability pool contract calls to repay for a liquidation and get its collateral distribution
 pub fn liq_repay(
     deps: DepsMut,
@@ -797,6 +1593,9 @@ pub fn liq_repay(
             Uint128::zero()
         },
     };
+
+    //Update isolated debt total
+    update_isolated_debt(deps.storage, &basket, &target_position.collateral_assets, credit_asset.amount, false)?;
     
     //Burn repayment & send revenue to stakers
     let burn_and_rev_msgs = credit_burn_rev_msg(
@@ -833,9 +1632,8 @@ pub fn liq_repay(
 
     //Stability Pool receives pro rata assets
     //Add distribute messages to the message builder, so the contract knows what to do with the received funds
-    let mut distribution_assets = vec![];
-
-    let mut coins: Vec<Coin> = vec![];    
+    let mut distribution_assets: Vec<Asset> = vec![];
+    let mut distribution_asset_ratios: Vec<Decimal> = vec![];
 
     //Get SP liq fee
     let sp_liq_fee = liquidation_propagation.sp_liq_fee;
@@ -864,10 +1662,40 @@ pub fn liq_repay(
             }
         );
 
+        //LP collateral is distributed in its underlying assets if unwrapping
+        let (exit_msg, underlying_assets) = unwrap_lp_collateral(
+            deps.querier,
+            env.clone(),
+            &config,
+            &cAsset,
+            collateral_w_fee,
+            liquidation_propagation.unwrap_lp,
+        )?;
+        if let Some(exit_msg) = exit_msg {
+            messages.push(exit_msg);
+        }
+
         //SP Distribution needs list of cAsset's and is pulling the amount from the Asset object
-        distribution_assets.push(distribution_asset.clone());
-        coins.push(asset_to_coin(distribution_asset)?);
+        for (asset, value_ratio) in underlying_assets {
+            let asset_ratio = decimal_multiplication(cAsset_ratios[num], value_ratio)?;
+
+            match distribution_assets.iter().position(|listed| listed.info.equal(&asset.info)) {
+                Some(index) => {
+                    distribution_assets[index].amount += asset.amount;
+                    distribution_asset_ratios[index] += asset_ratio;
+                },
+                None => {
+                    distribution_assets.push(asset);
+                    distribution_asset_ratios.push(asset_ratio);
+                },
+            }
+        }
     }
+    let coins: Vec<Coin> = distribution_assets
+        .clone()
+        .into_iter()
+        .map(asset_to_coin)
+        .collect::<StdResult<Vec<Coin>>>()?;
 
     if target_position.credit_amount.is_zero(){                
         //Remove position's assets from Supply caps 
@@ -898,14 +1726,25 @@ pub fn liq_repay(
     }
 
     //Update position
-    update_position(deps.storage, liquidation_propagation.position_owner, target_position)?;
+    update_position(deps.storage, basket.basket_id, liquidation_propagation.position_owner.clone(), target_position.clone())?;
     //Update Basket
-    BASKET.save(deps.storage, &basket)?;
+    BASKETS.save(deps.storage, basket.basket_id.u128(), &basket)?;
+
+    //Record post-liquidation health
+    let health_event = record_position_health(
+        deps.storage,
+        deps.querier,
+        env,
+        basket.basket_id,
+        liquidation_propagation.position_owner,
+        target_position.position_id,
+        "liquidation",
+    );
 
     //Adds Native token distribution msg to messages
     let distribution_msg = SP_ExecuteMsg::Distribute {
         distribution_assets: distribution_assets.clone(),
-        distribution_asset_ratios: cAsset_ratios, //The distributions are based off cAsset_ratios so they shouldn't change
+        distribution_asset_ratios, //The distributions are based off cAsset_ratios so they shouldn't change
         distribute_for: credit_asset.amount,
     };
     //Build the Execute msg w/ the full list of native tokens
@@ -917,6 +1756,7 @@ pub fn liq_repay(
     messages.push(msg);
     
     Ok(Response::new()
+        .add_event(health_event)
         .add_messages(messages)
         .add_attribute("method", "liq_repay")
         .add_attribute("distribution_assets", format!("{:?}", distribution_assets))
@@ -931,20 +1771,29 @@ pub fn increase_debt(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    basket_id: Uint128,
     position_id: Uint128,
     amount: Option<Uint128>,
     LTV: Option<Decimal>,
     mint_to_addr: Option<String>,
+    operator: Option<Addr>,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
-    let mut basket: Basket = BASKET.load(deps.storage)?;
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
     let mut set_redemption = false;
 
     //Check if frozen
     if basket.frozen { return Err(ContractError::Frozen {  }) }
 
     //Get Target position
-    let (position_index, mut target_position) = get_target_position(deps.storage, info.clone().sender, position_id)?;
+    let (position_index, mut target_position) = get_target_position(deps.storage, basket_id, info.clone().sender, position_id)?;
+
+    //Removed collateral isn't in the Basket's supply caps, so it can't back new debt
+    for cAsset in target_position.collateral_assets.iter() {
+        if !cAsset.asset.amount.is_zero() && !basket.collateral_types.iter().any(|basket_asset| basket_asset.asset.info.equal(&cAsset.asset.info)) {
+            return Err(ContractError::CustomError { val: format!("{} was removed from the Basket, withdraw it before taking new debt", cAsset.asset.info) });
+        }
+    }
     
     //If any cAsset is a rate_hike asset, force a redemption 
     for cAsset in target_position.collateral_assets.clone(){
@@ -996,6 +1845,14 @@ pub fn increase_debt(
         }
     };
 
+    //Operators mint within their debt limit
+    if let Some(operator) = operator {
+        use_operator_debt_allowance(deps.storage, basket_id, info.clone().sender, operator, amount)?;
+    }
+
+    //Isolated collateral mints within its own debt ceiling
+    update_isolated_debt(deps.storage, &basket, &target_position.collateral_assets, amount, true)?;
+
     //Add new credit_amount
     target_position.credit_amount += amount;
 
@@ -1010,7 +1867,7 @@ pub fn increase_debt(
     //Can't take credit before an oracle is set
     if basket.oracle_set {
         //If resulting LTV makes the position insolvent, error. If not construct mint msg
-        let (insolvency_res, _) = insolvency_check(
+        let (insolvency_res, (_borrow_LTV, _max_LTV, _total_value, cAsset_prices, _cAsset_ratios)) = insolvency_check(
             deps.storage,
             env.clone(),
             deps.querier,
@@ -1022,6 +1879,16 @@ pub fn increase_debt(
             config.clone(),
         )?;
 
+        //Stale or uncertain oracle prices block new debt against the collateral
+        assert_price_policy(config.clone().price_policy, env.block.time.seconds(), &target_position.collateral_assets, &cAsset_prices)?;
+
+        //Degraded oracle prices block new debt against the collateral
+        for (i, cAsset) in target_position.collateral_assets.iter().enumerate() {
+            if !cAsset.asset.amount.is_zero() && cAsset_prices[i].degraded {
+                return Err(ContractError::DegradedPrice { asset: cAsset.asset.info.to_string() });
+            }
+        }
+
         if insolvency_res.0 {
             return Err(ContractError::PositionInsolvent { insolvency_res });
         } else {
@@ -1044,7 +1911,7 @@ pub fn increase_debt(
 
             //Add credit amount to the position
             //Update Position
-            POSITIONS.update(deps.storage, info.clone().sender, |positions: Option<Vec<Position>>| -> Result<Vec<Position>, ContractError> {
+            POSITIONS.update(deps.storage, (basket_id.u128(), info.clone().sender), |positions: Option<Vec<Position>>| -> Result<Vec<Position>, ContractError> {
                 let mut updating_positions = positions.unwrap_or_else(|| vec![]);
                 updating_positions[position_index] = target_position.clone();
 
@@ -1055,7 +1922,7 @@ pub fn increase_debt(
             basket.credit_asset.amount += amount;
             
             //Save updated repayment price and debts
-            BASKET.save(deps.storage, &basket)?;
+            BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
         }
     } else {
         return Err(ContractError::NoRepaymentPrice {});
@@ -1064,6 +1931,7 @@ pub fn increase_debt(
     //Check state changes
     check_debt_increase_state(
         deps.storage, 
+        basket_id,
         amount, 
         prev_credit_amount,
         prev_basket_credit,
@@ -1076,6 +1944,7 @@ pub fn increase_debt(
         edit_redemption_info(
             deps.storage,
             info.clone(),
+            basket_id,
             vec![position_id.clone()],
             Some(true),
             Some(1),
@@ -1085,7 +1954,11 @@ pub fn increase_debt(
         )?;
     }
 
+    //Record post-mint health
+    let health_event = record_position_health(deps.storage, deps.querier, env, basket_id, info.sender, position_id, "increase_debt");
+
     let response = Response::new()
+        .add_event(health_event)
         .add_message(message)
         .add_attribute("method", "increase_debt")
         .add_attribute("position_id", position_id.to_string())
@@ -1098,6 +1971,7 @@ pub fn increase_debt(
 /// Asserts valid state after increase_debt()
 fn check_debt_increase_state(
     storage: &mut dyn Storage,
+    basket_id: Uint128,
     increase_amount: Uint128,
     prev_credit_amount: Uint128,
     prev_basket_credit: Uint128,
@@ -1106,8 +1980,8 @@ fn check_debt_increase_state(
 ) -> Result<(), ContractError>{
     
     //Get target_position & Basket
-    let (_i, target_position) = get_target_position(storage, position_owner, position_id)?;
-    let basket = BASKET.load(storage)?;
+    let (_i, target_position) = get_target_position(storage, basket_id, position_owner, position_id)?;
+    let basket = BASKETS.load(storage, basket_id.u128())?;
 
     //Assert that credit_amount is equal to the origin + what was added
     if target_position.credit_amount != prev_credit_amount + increase_amount {
@@ -1126,6 +2000,7 @@ fn check_debt_increase_state(
 pub fn edit_redemption_info(
     storage: &mut dyn Storage, 
     info: MessageInfo,
+    basket_id: Uint128,
     // Position IDs to edit
     mut position_ids: Vec<Uint128>,
     // Add or remove redeemability
@@ -1168,7 +2043,7 @@ pub fn edit_redemption_info(
 
     //If a rate hike asset is in the position, USER CAN"T REMOVE REEDMPTIONS
     for id in position_ids.clone() {
-        let (_i, target_position) = get_target_position(storage, info.sender.clone(), id)?;
+        let (_i, target_position) = get_target_position(storage, basket_id, info.sender.clone(), id)?;
         for cAsset in target_position.collateral_assets.clone(){
             if !called_by_contract && cAsset.hike_rates.is_some() && cAsset.hike_rates.unwrap() {
                 return Err(ContractError::CustomError { val: format!("Can't edit redemption for a position with a rate hike asset: {:?}", cAsset.asset.info) })
@@ -1181,7 +2056,7 @@ pub fn edit_redemption_info(
     if (redeemable.is_some() && redeemable.unwrap_or_else(|| false)) || redeemable.is_none(){
         if let Some(updated_premium) = updated_premium {                
             //Load premium we are adding to 
-            match REDEMPTION_OPT_IN.load(storage, updated_premium){
+            match REDEMPTION_OPT_IN.load(storage, (basket_id.u128(), updated_premium)){
                 Ok(mut users_of_premium)=> {
                     //If the user already has a PositionRedemption, add the Position to the list
                     if let Some ((user_index, mut user_positions)) = users_of_premium.clone().into_iter().enumerate().find(|(_, user)| user.position_owner == info.sender){
@@ -1191,7 +2066,7 @@ pub fn edit_redemption_info(
                             if !user_positions.position_infos.iter().any(|position| position.position_id == id){
 
                                 //Get target_position
-                                let target_position = match get_target_position(storage, info.sender.clone(), id){
+                                let target_position = match get_target_position(storage, basket_id, info.sender.clone(), id){
                                     Ok((_, pos)) => pos,
                                     Err(_e) => return Err(ContractError::CustomError { val: String::from("User does not own this position id") })
                                 };
@@ -1211,12 +2086,13 @@ pub fn edit_redemption_info(
                         users_of_premium[user_index] = user_positions;
 
                         //Save the updated list
-                        REDEMPTION_OPT_IN.save(storage, updated_premium, &users_of_premium)?;
+                        REDEMPTION_OPT_IN.save(storage, (basket_id.u128(), updated_premium), &users_of_premium)?;
                     } //Add user to the premium state
                     else {                            
                         //Create new RedemptionInfo
                         let new_redemption_info = create_redemption_info(
                             storage,
+                            basket_id,
                             position_ids.clone(), 
                             max_loan_repayment.clone(), 
                             info.clone().sender,
@@ -1227,7 +2103,7 @@ pub fn edit_redemption_info(
                         users_of_premium.push(new_redemption_info);
 
                         //Save the updated list
-                        REDEMPTION_OPT_IN.save(storage, updated_premium, &users_of_premium)?;
+                        REDEMPTION_OPT_IN.save(storage, (basket_id.u128(), updated_premium), &users_of_premium)?;
                     }
                 },
                 //If no users, create a new list
@@ -1235,6 +2111,7 @@ pub fn edit_redemption_info(
                     //Create new RedemptionInfo
                     let new_redemption_info = create_redemption_info(
                         storage,
+                        basket_id,
                         position_ids.clone(), 
                         max_loan_repayment.clone(), 
                         info.clone().sender,
@@ -1242,7 +2119,7 @@ pub fn edit_redemption_info(
                     )?;
 
                     //Save the new RedemptionInfo
-                    REDEMPTION_OPT_IN.save(storage, updated_premium, &vec![new_redemption_info])?;
+                    REDEMPTION_OPT_IN.save(storage, (basket_id.u128(), updated_premium), &vec![new_redemption_info])?;
                 },
             };
         } else if (redeemable.is_some() && redeemable.unwrap_or_else(|| false)) && updated_premium.is_none(){
@@ -1254,7 +2131,7 @@ pub fn edit_redemption_info(
     //Parse through premium range to look for the Position IDs
     for premium in 0..100u128 {
         //Load premium we are editing
-        let mut users_of_premium: Vec<RedemptionInfo> = match REDEMPTION_OPT_IN.load(storage, premium){
+        let mut users_of_premium: Vec<RedemptionInfo> = match REDEMPTION_OPT_IN.load(storage, (basket_id.u128(), premium)){
             Ok(list)=> list,
             Err(_err) => vec![], //If no users, return empty vec
         };
@@ -1278,7 +2155,7 @@ pub fn edit_redemption_info(
                                     users_of_premium.remove(user_index);
                                     
                                     //Save the updated list
-                                    REDEMPTION_OPT_IN.save(storage, premium, &users_of_premium)?;
+                                    REDEMPTION_OPT_IN.save(storage, (basket_id.u128(), premium), &users_of_premium)?;
                                     break;
                                 }
                             }
@@ -1287,7 +2164,7 @@ pub fn edit_redemption_info(
                         //Update maximum loan repayment
                         if let Some(max_loan_repayment) = max_loan_repayment {
                             //Get target_position
-                            let target_position = match get_target_position(storage, info.sender.clone(), id){
+                            let target_position = match get_target_position(storage, basket_id, info.sender.clone(), id){
                                 Ok((_, pos)) => pos,
                                 Err(_e) => return Err(ContractError::CustomError { val: String::from("User does not own this position id") })
                             };
@@ -1305,7 +2182,7 @@ pub fn edit_redemption_info(
                                     users_of_premium.remove(user_index);
                                     
                                     //Save the updated list
-                                    REDEMPTION_OPT_IN.save(storage, premium, &users_of_premium)?;
+                                    REDEMPTION_OPT_IN.save(storage, (basket_id.u128(), premium), &users_of_premium)?;
                                     break;
                                 }
                             }   
@@ -1314,7 +2191,7 @@ pub fn edit_redemption_info(
                         //Update restricted collateral assets
                         if let Some(restricted_assets) = restricted_collateral_assets.clone() {
                             //Map collateral assets to String
-                            let basket = BASKET.load(storage)?;
+                            let basket = BASKETS.load(storage, basket_id.u128())?;
                             let collateral = basket.collateral_types.iter().map(|asset| asset.asset.info.to_string()).collect::<Vec<String>>();
 
                             //If all restricted assets are valid, swap objects
@@ -1329,7 +2206,7 @@ pub fn edit_redemption_info(
                         users_of_premium[user_index] = user_positions.clone();
 
                         //Save the updated list
-                        REDEMPTION_OPT_IN.save(storage, premium, &users_of_premium)?;
+                        REDEMPTION_OPT_IN.save(storage, (basket_id.u128(), premium), &users_of_premium)?;
 
                         //Remove the Position ID from the list
                         position_ids = position_ids
@@ -1352,6 +2229,7 @@ pub fn edit_redemption_info(
 
 fn create_redemption_info(
     storage: &dyn Storage,
+    basket_id: Uint128,
     position_ids: Vec<Uint128>,
     max_loan_repayment: Option<Decimal>,
     position_owner: Addr,
@@ -1362,7 +2240,7 @@ fn create_redemption_info(
     
     for id in position_ids.clone(){
         //Get target_position
-        let target_position = match get_target_position(storage, position_owner.clone(), id){
+        let target_position = match get_target_position(storage, basket_id, position_owner.clone(), id){
             Ok((_, pos)) => pos,
             Err(_e) => return Err(StdError::GenericErr { msg: String::from("User does not own this position id") })
         };
@@ -1387,15 +2265,20 @@ pub fn redeem_for_collateral(
     deps: DepsMut, 
     env: Env,
     info: MessageInfo,
+    basket_id: Uint128,
     max_collateral_premium: u128,
+    collateral_filter: Option<RedemptionCollateralFilter>,
+    min_value_received: Option<Decimal>,
 ) -> Result<Response, ContractError>{
     //Load State
     let config: Config = CONFIG.load(deps.storage)?;
-    let basket: Basket = BASKET.load(deps.storage)?;
+    let basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
 
     let mut credit_amount;
     let mut redeemable_credit = Decimal::zero();
     let mut collateral_sends: Vec<Asset> = vec![];
+    let mut value_received = Decimal::zero();
+    let mut health_events: Vec<Event> = vec![];
     
     //Validate asset 
     if info.clone().funds.len() != 1 || info.clone().funds[0].denom != basket.credit_asset.info.to_string(){
@@ -1419,7 +2302,7 @@ pub fn redeem_for_collateral(
         if !credit_amount.is_zero(){
             
             //Query for Users in the premium 
-            let mut users_of_premium: Vec<RedemptionInfo> = match REDEMPTION_OPT_IN.load(deps.storage, premium){
+            let mut users_of_premium: Vec<RedemptionInfo> = match REDEMPTION_OPT_IN.load(deps.storage, (basket_id.u128(), premium)){
                 Ok(list)=> list,
                 Err(_err) => vec![], //If no users, return empty vec
             };
@@ -1431,6 +2314,7 @@ pub fn redeem_for_collateral(
                     //Query for user Positions in the premium
                     let (_i, mut target_position) = get_target_position(
                         deps.storage, 
+                        basket_id,
                         user.clone().position_owner, 
                         position_redemption_info.position_id
                     )?;                    
@@ -1451,6 +2335,13 @@ pub fn redeem_for_collateral(
                         }
                         continue;
                     }
+                    //Skip collateral the redeemer doesn't accept, the Position stays opted in
+                    if let Some(filter) = &collateral_filter {
+                        target_position.collateral_assets.retain(|asset| filter.accepts(&asset.asset.info));
+                        if target_position.collateral_assets.is_empty() {
+                            continue;
+                        }
+                    }
 
                     //Get cAsset ratios
                     let (cAsset_ratios, cAsset_prices) = get_cAsset_ratios(
@@ -1490,7 +2381,7 @@ pub fn redeem_for_collateral(
                         users_of_premium[user_index] = user.clone();
                     }
                     
-                    REDEMPTION_OPT_IN.save(deps.storage, premium, &users_of_premium)?;
+                    REDEMPTION_OPT_IN.save(deps.storage, (basket_id.u128(), premium), &users_of_premium)?;
 
                     // Calc credit_value
                     //redeemable_credit * credit_price
@@ -1510,6 +2401,7 @@ pub fn redeem_for_collateral(
                             cAsset_ratios[i]
                         )?;
                         let collateral_to_send = cAsset_prices[i].get_amount(value_to_send)?;
+                        value_received += cAsset_prices[i].get_value(collateral_to_send)?;
 
                         //Add to send list
                         if let Some(asset) = collateral_sends.iter_mut().find(|a| a.info == cAsset.asset.info) {
@@ -1527,6 +2419,7 @@ pub fn redeem_for_collateral(
                             deps.querier, 
                             env.clone(), 
                             config.clone(),
+                            basket_id,
                             position_redemption_info.position_id, 
                             user.clone().position_owner, 
                             cAsset.asset.info.clone(), 
@@ -1537,6 +2430,7 @@ pub fn redeem_for_collateral(
                     //Reload target_position
                     let (_i, mut target_position) = get_target_position(
                         deps.storage, 
+                        basket_id,
                         user.clone().position_owner, 
                         position_redemption_info.position_id
                     )?;
@@ -1544,6 +2438,9 @@ pub fn redeem_for_collateral(
                     //Set position.credit_amount
                     target_position.credit_amount -= redeemable_credit.to_uint_floor();
 
+                    //Update isolated debt total
+                    update_isolated_debt(deps.storage, &basket, &target_position.collateral_assets, redeemable_credit.to_uint_floor(), false)?;
+
                     //Remove from redemption_info if credit_amount is zero
                     if target_position.credit_amount.is_zero() {
                         //Remove PositionRedemption from user
@@ -1557,9 +2454,21 @@ pub fn redeem_for_collateral(
                     //Update position.credit_amount
                     update_position(
                         deps.storage, 
+                        basket_id,
                         user.clone().position_owner, 
                         target_position.clone()
                     )?;
+
+                    //Record post-redemption health
+                    health_events.push(record_position_health(
+                        deps.storage,
+                        deps.querier,
+                        env.clone(),
+                        basket_id,
+                        user.clone().position_owner,
+                        position_redemption_info.position_id,
+                        "redemption",
+                    ));
                 }
             }
         }
@@ -1568,6 +2477,12 @@ pub fn redeem_for_collateral(
     if credit_amount == initial_credit_amount {
         return Err(ContractError::CustomError { val: String::from("No collateral to redeem with at this max premium") })
     }
+    //Revert if the collateral is worth less than the redeemer's minimum
+    if let Some(min_value_received) = min_value_received {
+        if value_received < min_value_received {
+            return Err(ContractError::CustomError { val: format!("Redeemed collateral value {} is below the minimum {}", value_received, min_value_received) })
+        }
+    }
 
     //Convert collateral_sends to coins
     let mut coins: Vec<Coin> = vec![];
@@ -1612,6 +2527,7 @@ pub fn redeem_for_collateral(
         messages.push(credit_msg.into());
 
         return Ok(Response::new()
+            .add_events(health_events)
             .add_messages(messages)
             .add_attributes(vec![
                 attr("action", "redeem_for_collateral"),
@@ -1624,6 +2540,7 @@ pub fn redeem_for_collateral(
 
     //Response
     Ok(Response::new()
+        .add_events(health_events)
         .add_messages(messages)
         .add_attributes(vec![
         attr("action", "redeem_for_collateral"),
@@ -1633,7 +2550,7 @@ pub fn redeem_for_collateral(
     )
 }
 
-/// Create the contract's Basket.
+/// Create a Basket.
 /// Validates params.
  pub fn create_basket(
     deps: DepsMut,
@@ -1653,9 +2570,12 @@ pub fn redeem_for_collateral(
     if info.sender != config.owner {
         return Err(ContractError::NotContractOwner {});
     }
-    //One basket per contract
-    if let Ok(_basket) = BASKET.load(deps.storage){
-        return Err(ContractError::CustomError { val: String::from("Only one basket per contract") })
+    //Basket IDs & credit assets are unique
+    if BASKETS.has(deps.storage, basket_id.u128()){
+        return Err(ContractError::CustomError { val: format!("Basket {} already exists", basket_id) })
+    }
+    if get_basket_by_credit_asset(deps.storage, &credit_asset.info).is_ok(){
+        return Err(ContractError::CustomError { val: format!("{} is already a Basket's credit asset", credit_asset.info) })
     }
 
     let mut new_assets = collateral_types.clone();
@@ -1728,6 +2648,8 @@ pub fn redeem_for_collateral(
                         //Bid total before bids go to the waiting queue. 
                         // The cumulative threshold of frequented slots should be larger than the largest single liquidation amount to prevent waiting bids from causing InsufficientBids errors.
                         bid_threshold: Uint256::from(1_000_000_000_000u128), //1 million
+                        //LP bids are settled in the pool's underlying assets
+                        lp_pool_id: asset.clone().pool_info.map(|pool_info| pool_info.pool_id),
                     })?,
                     funds: vec![],
                 }));
@@ -1764,6 +2686,8 @@ pub fn redeem_for_collateral(
             price: credit_price,
             prices: vec![],
             decimals: 6,
+            degraded: false,
+            metadata: None,
         },
         base_interest_rate,
         pending_revenue: Uint128::zero(),
@@ -1775,6 +2699,8 @@ pub fn redeem_for_collateral(
         oracle_set: false,
         frozen: false,
         rev_to_stakers: true,
+        close_factor: None,
+        soft_liquidation: None,
     };
 
     //Denom check
@@ -1800,7 +2726,11 @@ pub fn redeem_for_collateral(
     }
 
     //Save Basket
-    BASKET.save( deps.storage, &new_basket )?;
+    BASKETS.save( deps.storage, basket_id.u128(), &new_basket )?;
+    //The first Basket is the default
+    if DEFAULT_BASKET_ID.may_load(deps.storage)?.is_none(){
+        DEFAULT_BASKET_ID.save(deps.storage, &basket_id)?;
+    }
 
     //Response Building
     let response = Response::new();
@@ -1819,7 +2749,7 @@ pub fn redeem_for_collateral(
         .add_messages(msgs))
 } 
 
-/// Edit the contract's Basket.
+/// Edit a Basket.
 /// Can't edit basket id, current_position_id or credit_asset.
 /// Credit price can only be changed thru the accrue function.
 /// Validates parameters and updates the basket.
@@ -1827,6 +2757,7 @@ pub fn edit_basket(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    basket_id: Uint128,
     editable_parameters: EditBasket,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
@@ -1854,12 +2785,14 @@ pub fn edit_basket(
         pool_info: None,
         rate_index: Decimal::one(),
         hike_rates: Some(false),
+        isolated_debt_ceiling: None,
+        rate_model: None,
     };
 
     let mut msgs: Vec<CosmosMsg> = vec![];    
     let mut attrs = vec![attr("method", "edit_basket")];
 
-    let mut basket = BASKET.load(deps.storage)?;
+    let mut basket = BASKETS.load(deps.storage, basket_id.u128())?;
     //cAsset check
     if let Some(added_cAsset) = editable_parameters.clone().added_cAsset {
         let mut check = true;
@@ -1899,6 +2832,9 @@ pub fn edit_basket(
                 val: String::from("Attempting to add duplicate asset"),
             });
         }
+        //Relisting removed collateral clears its deprecation & old LTV ramps
+        DEPRECATED_COLLATERAL.remove(deps.storage, (basket_id.u128(), new_cAsset.asset.info.to_string()));
+        LTV_RAMPS.remove(deps.storage, (basket_id.u128(), new_cAsset.asset.info.to_string()));
 
         if let Some(mut pool_info) = added_cAsset.pool_info {
 
@@ -1960,7 +2896,7 @@ pub fn edit_basket(
                 msg: to_binary(&OracleExecuteMsg::AddAsset { 
                     asset_info: new_cAsset.clone().asset.info,
                     oracle_info: AssetOracleInfo { 
-                        basket_id: Uint128::one(), 
+                        basket_id, 
                         pools_for_osmo_twap: vec![],
                         is_usd_par: false,
                         lp_pool_info: Some(
@@ -1983,6 +2919,8 @@ pub fn edit_basket(
                         decimals: 18,
                         pyth_price_feed_id: None,
                         vault_info: None,
+                        max_source_deviation: None,
+                        sources: vec![],
                     },
                 })?,
                 funds: vec![],
@@ -2027,6 +2965,8 @@ pub fn edit_basket(
                     //Bid total before bids go to the waiting queue. 
                     //Threshold should be larger than the largest single liquidation amount to prevent waiting bids from causing InsufficientBids errors.
                     bid_threshold: Uint256::from(1_000_000_000_000u128), //1 million
+                    //LP bids are settled in the pool's underlying assets
+                    lp_pool_id: new_cAsset.clone().pool_info.map(|pool_info| pool_info.pool_id),
                 })?,
                 funds: vec![],
             }));
@@ -2049,6 +2989,8 @@ pub fn edit_basket(
                     //Bid total before bids go to the waiting queue. 
                     //Threshold should be larger than the largest single liquidation amount to prevent waiting bids from causing InsufficientBids errors.
                     bid_threshold: Uint256::from(1_000_000_000_000u128), //1 million
+                    //LP bids are settled in the pool's underlying assets
+                    lp_pool_id: new_cAsset.clone().pool_info.map(|pool_info| pool_info.pool_id),
                 })?,
                 funds: vec![],
             }));
@@ -2084,14 +3026,11 @@ pub fn edit_basket(
         });
 
         //Create Volatility Index for the asset
-        VOLATILITY.save(deps.storage, new_cAsset.clone().asset.info.to_string(), &CollateralVolatility {
-            index: Decimal::one(),
-            volatility_list: vec![],
-        })?;
+        VOLATILITY.save(deps.storage, (basket_id.u128(), new_cAsset.clone().asset.info.to_string()), &CollateralVolatility::default())?;
     }
     
     //Save basket's new collateral_supply_caps
-    BASKET.save(deps.storage, &basket)?;
+    BASKETS.save(deps.storage, basket_id.u128(), &basket)?;
 
     //Send credit_asset TWAP info to Oracle Contract
     let mut oracle_set = basket.oracle_set;
@@ -2111,6 +3050,8 @@ pub fn edit_basket(
                         decimals: 6,
                         pyth_price_feed_id: None,
                         vault_info: None,
+                        max_source_deviation: None,
+                        sources: vec![],
                     }),
                     remove: false,
                 })?,
@@ -2141,7 +3082,7 @@ pub fn edit_basket(
 
     //If updating frozen, set timer
     if let Some(frozen) = editable_parameters.clone().frozen {
-        let mut timer = match FREEZE_TIMER.load(deps.storage){
+        let mut timer = match FREEZE_TIMER.load(deps.storage, basket_id.u128()){
             Ok(timer) => timer,
             Err(_err) => Timer {
                 start_time: 0,
@@ -2154,29 +3095,26 @@ pub fn edit_basket(
             timer.start_time = env.block.time.seconds();
             
             //Save timer
-            FREEZE_TIMER.save(deps.storage, &timer)?;
+            FREEZE_TIMER.save(deps.storage, basket_id.u128(), &timer)?;
         } else  if !frozen && basket.frozen {
             //If we are unfreezing, set end timer
             timer.end_time = env.block.time.seconds();
 
             //Save timer
-            FREEZE_TIMER.save(deps.storage, &timer)?;
+            FREEZE_TIMER.save(deps.storage, basket_id.u128(), &timer)?;
         }
     }
     //Reset the Volatility Index for any edited supply caps
     if let Some(caps) = editable_parameters.clone().collateral_supply_caps {
         for cap in caps {
-            VOLATILITY.update(deps.storage, cap.asset_info.to_string(), |mut vol| -> StdResult<CollateralVolatility> {
+            VOLATILITY.update(deps.storage, (basket_id.u128(), cap.asset_info.to_string()), |mut vol| -> StdResult<CollateralVolatility> {
                 match vol {
                     Some(mut vol) => {
-                        vol.index = Decimal::one();
+                        vol.index = vol.get_config().multiplier_ceiling;
                         Ok(vol)
                     },
                     None => {
-                        let mut vol = CollateralVolatility {
-                            index: Decimal::one(),
-                            volatility_list: vec![],
-                        };
+                        let mut vol = CollateralVolatility::default();
                         Ok(vol)
                     }
                 }
@@ -2186,17 +3124,14 @@ pub fn edit_basket(
     if let Some(caps) = editable_parameters.clone().multi_asset_supply_caps {
         for cap in caps {
             for asset in cap.assets {
-                VOLATILITY.update(deps.storage, asset.to_string(), |mut vol| -> StdResult<CollateralVolatility> {
+                VOLATILITY.update(deps.storage, (basket_id.u128(), asset.to_string()), |mut vol| -> StdResult<CollateralVolatility> {
                     match vol {
                         Some(mut vol) => {
-                            vol.index = Decimal::one();
+                            vol.index = vol.get_config().multiplier_ceiling;
                             Ok(vol)
                         },
                         None => {
-                            let mut vol = CollateralVolatility {
-                                index: Decimal::one(),
-                                volatility_list: vec![],
-                            };
+                            let mut vol = CollateralVolatility::default();
                             Ok(vol)
                         }
                     }
@@ -2205,8 +3140,45 @@ pub fn edit_basket(
         }
     }
 
+    //Validate liquidation settings
+    if let Some(close_factor) = editable_parameters.close_factor {
+        if close_factor.is_zero() || close_factor > Decimal::one() {
+            return Err(ContractError::CustomError { val: String::from("Close factor must be between 0% & 100%") });
+        }
+    }
+    if let Some(soft_liquidation) = editable_parameters.clone().soft_liquidation {
+        if soft_liquidation.repay_ratio > Decimal::one() || soft_liquidation.caller_fee >= Decimal::one() {
+            return Err(ContractError::CustomError { val: String::from("Soft liquidation repay ratio can't exceed 100% & the caller fee must be less than 100%") });
+        }
+    }
+
+    //Ramp base rate changes, the ramped rate is set in accrue()
+    let mut editable_parameters = editable_parameters;
+    if let Some(base_interest_rate) = editable_parameters.base_interest_rate {
+        match editable_parameters.rate_ramp_duration {
+            Some(ramp_duration) if ramp_duration > 0 => {
+                //Start from the current rate, which may be mid-ramp
+                let start_value = match BASE_RATE_RAMP.may_load(deps.storage, basket_id.u128())? {
+                    Some(ramp) => ramp.get_value(env.block.time.seconds()),
+                    None => basket.base_interest_rate,
+                };
+                BASE_RATE_RAMP.save(deps.storage, basket_id.u128(), &ParameterRamp {
+                    start_value,
+                    end_value: base_interest_rate,
+                    start_time: env.block.time.seconds(),
+                    end_time: env.block.time.seconds() + ramp_duration,
+                })?;
+                attrs.push(attr("base_rate_ramp_end_time", (env.block.time.seconds() + ramp_duration).to_string()));
+
+                editable_parameters.base_interest_rate = None;
+            },
+            _ => BASE_RATE_RAMP.remove(deps.storage, basket_id.u128()),
+        }
+    }
+
     //Update Basket
-    BASKET.update(deps.storage, |mut basket| -> Result<Basket, ContractError> {
+    BASKETS.update(deps.storage, basket_id.u128(), |basket| -> Result<Basket, ContractError> {
+        let mut basket = basket.ok_or_else(|| ContractError::CustomError { val: format!("Basket {} doesn't exist", basket_id) })?;
         //Set all optional parameters
         editable_parameters.edit_basket(&mut basket, new_cAsset, new_queue, oracle_set)?;        
 
@@ -2459,6 +3431,84 @@ pub fn credit_burn_rev_msg(
     } else { Err(StdError::GenericErr { msg: String::from("Cw20 assets aren't allowed") }) }
 }
 
+/// Snapshot a Position's post-action state & return a versioned position_health event.
+/// Closed Positions are recorded with zeroed values.
+/// Recording is best-effort so it never fails the action, collateral that can't be priced leaves the value & LTV empty.
+pub fn record_position_health(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    env: Env,
+    basket_id: Uint128,
+    position_owner: Addr,
+    position_id: Uint128,
+    action: &str,
+) -> Event {
+    let mut event = Event::new("position_health").add_attributes(vec![
+        attr("version", POSITION_EVENT_VERSION),
+        attr("action", action),
+        attr("basket_id", basket_id),
+        attr("position_owner", position_owner.clone()),
+        attr("position_id", position_id),
+    ]);
+    let (config, basket) = match (CONFIG.load(storage), BASKETS.load(storage, basket_id.u128())) {
+        (Ok(config), Ok(basket)) => (config, basket),
+        _ => return event,
+    };
+
+    let (collateral_assets, credit_amount) = match get_target_position(storage, basket_id, position_owner.clone(), position_id) {
+        Ok((_i, position)) => (position.collateral_assets, position.credit_amount),
+        Err(_) => (vec![], Uint128::zero()),
+    };
+
+    //Get post-action values, skipping the collateral value if its prices are unavailable
+    let collateral_value = if check_for_empty_position(collateral_assets.clone()) {
+        Some(Decimal::zero())
+    } else {
+        get_avg_LTV(
+            storage,
+            env.clone(),
+            querier,
+            config,
+            Some(basket.clone()),
+            collateral_assets.clone(),
+            false,
+        )
+        .map(|(_borrow_LTV, _max_LTV, total_value, _prices, _ratios)| total_value)
+        .ok()
+    };
+    let debt_value = basket.credit_price.get_value(credit_amount).ok();
+    let LTV = match (collateral_value, debt_value) {
+        (Some(collateral_value), _) if collateral_value.is_zero() => Some(Decimal::zero()),
+        (Some(collateral_value), Some(debt_value)) => decimal_division(debt_value, collateral_value).ok(),
+        _ => None,
+    };
+
+    //One snapshot per block, the latest action in the block overwrites
+    let snapshot = PositionSnapshot {
+        basket_id,
+        time: env.block.time.seconds(),
+        credit_amount,
+        collateral_assets: collateral_assets.into_iter().map(|cAsset| cAsset.asset).collect(),
+        collateral_value,
+        debt_value,
+        LTV,
+    };
+    let _ = POSITION_SNAPSHOTS.save(storage, ((basket_id.u128(), position_owner), position_id.u128(), env.block.time.seconds()), &snapshot);
+
+    event = event.add_attribute("credit_amount", credit_amount);
+    if let Some(collateral_value) = collateral_value {
+        event = event.add_attribute("collateral_value", collateral_value.to_string());
+    }
+    if let Some(debt_value) = debt_value {
+        event = event.add_attribute("debt_value", debt_value.to_string());
+    }
+    if let Some(LTV) = LTV {
+        event = event.add_attribute("LTV", LTV.to_string());
+    }
+
+    event
+}
+
 /// Checks if any cAsset amount is zero or if asset list is empty
 pub fn check_for_empty_position( collateral_assets: Vec<cAsset> )-> bool {
     
@@ -8,28 +8,30 @@ use cosmwasm_std::{
 
 use cw_storage_plus::Bound;
 
-use membrane::oracle::{PriceResponse, QueryMsg as OracleQueryMsg};
+use membrane::oracle::{PricePolicy, PriceRecord, PriceResponse, QueryMsg as OracleQueryMsg};
 use membrane::cdp::{
     Config, CollateralInterestResponse,
-    InterestResponse, PositionResponse, BasketPositionsResponse, RedeemabilityResponse,
+    InterestResponse, PositionResponse, BasketPositionsResponse, RedeemabilityResponse, OperatorResponse, InsolvencyResponse, ParameterRampsResponse,
+    RedeemedPosition, PremiumRedemption, RedemptionSimulationResponse, PositionSnapshot, DebtCapDashboardResponse, CollateralCapInfo, MultiAssetCapInfo, CollateralVolatilityResponse, PositionRateResponse,
 };
 
 use membrane::types::{
-    cAsset, AssetInfo, Basket, DebtCap, Position, PremiumInfo, RedemptionInfo, StoredPrice, UserInfo
+    cAsset, Asset, AssetInfo, Basket, CollateralDeprecation, DebtCap, InsolventPosition, LTVRamp, Position, PositionOrder, PremiumInfo, RateModel, RedemptionCollateralFilter, RedemptionInfo, StoredPrice, UserInfo, VolatilityConfig, VolatilitySmoothing
 };
 use membrane::math::{decimal_division, decimal_multiplication, decimal_subtraction};
 
 use crate::positions::get_amount_from_LTV;
-use crate::risk_engine::get_basket_debt_caps;
-use crate::state::{get_target_position, CollateralVolatility, BASKET, CONFIG, POSITIONS, REDEMPTION_OPT_IN, STORED_PRICES, VOLATILITY};
+use crate::rates::{get_user_discounts, transform_caps_based_on_volatility};
+use crate::risk_engine::{apply_risk_parameters, get_basket_debt_caps};
+use crate::state::{get_target_position, BASKETS, BASE_RATE_RAMP, DEFAULT_BASKET_ID, CONFIG, DEPRECATED_COLLATERAL, LTV_RAMPS, OPERATORS, POSITIONS, POSITION_ORDERS, POSITION_SNAPSHOTS, PRICE_RECORDS, REDEMPTION_OPT_IN, STORED_PRICES, VOLATILITY};
 
 const MAX_LIMIT: u32 = 31;
-pub const VOLATILITY_LIST_LIMIT: u32 = 48;
 
 /// Returns Positions in a Basket
 pub fn query_basket_positions(
     deps: Deps,
     env: Env,
+    basket_id: Uint128,
     start_after: Option<String>,
     limit: Option<u32>,
     // Single position
@@ -37,7 +39,7 @@ pub fn query_basket_positions(
     // Single user
     user: Option<String>,
 ) -> StdResult<Vec<BasketPositionsResponse>> {
-    let basket = BASKET.load(deps.storage)?;
+    let basket = BASKETS.load(deps.storage, basket_id.u128())?;
     let config = CONFIG.load(deps.storage)?;
     /////Check single user and single position first/////
     /// User, default limit is 10 anyway
@@ -45,7 +47,7 @@ pub fn query_basket_positions(
         
         let user = deps.api.addr_validate(&user)?;
 
-        let positions: Vec<Position> = match POSITIONS.load(deps.storage,user.clone()){
+        let positions: Vec<Position> = match POSITIONS.load(deps.storage, (basket_id.u128(), user.clone())){
             Err(_) => return Err(StdError::GenericErr{msg: String::from("No User Positions")}),
             Ok(positions) => positions,
         };
@@ -70,7 +72,7 @@ pub fn query_basket_positions(
     } else if let Some(user_info) = user_info {
         let user = deps.api.addr_validate(&user_info.position_owner)?;
 
-        let (_i, position) = match get_target_position(deps.storage, user.clone(), user_info.position_id){
+        let (_i, position) = match get_target_position(deps.storage, basket_id, user.clone(), user_info.position_id){
             Ok(position) => position,
             Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
         };
@@ -99,6 +101,7 @@ pub fn query_basket_positions(
     };
 
     POSITIONS
+        .prefix(basket_id.u128())
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|item| {
@@ -123,9 +126,128 @@ pub fn query_basket_positions(
         .collect()
 }
 
+/// Returns the insolvency status of Positions.
+/// Prices are queried once for all collateral assets in the page.
+pub fn query_position_insolvency(
+    deps: Deps,
+    env: Env,
+    basket_id: Uint128,
+    user_info: Option<UserInfo>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    min_LTV: Option<Decimal>,
+    collateral_asset: Option<AssetInfo>,
+) -> StdResult<InsolvencyResponse> {
+    let basket = BASKETS.load(deps.storage, basket_id.u128())?;
+    let config = CONFIG.load(deps.storage)?;
+
+    //Load Positions to check
+    let user_positions: Vec<(Addr, Vec<Position>)> = if let Some(user_info) = user_info {
+        let user = deps.api.addr_validate(&user_info.position_owner)?;
+
+        let (_i, position) = match get_target_position(deps.storage, basket_id, user.clone(), user_info.position_id){
+            Ok(position) => position,
+            Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+        };
+
+        vec![(user, vec![position])]
+    } else {
+        let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+
+        let start = if let Some(start) = start_after {
+            let start_after_addr = deps.api.addr_validate(&start)?;
+            Some(Bound::exclusive(start_after_addr))
+        } else {
+            None
+        };
+
+        POSITIONS
+            .prefix(basket_id.u128())
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<(Addr, Vec<Position>)>>>()?
+    };
+    let last_position_owner = user_positions.last().map(|(user, _positions)| user.to_string());
+
+    //Get unique collateral assets
+    let mut asset_infos: Vec<AssetInfo> = vec![];
+    for (_user, positions) in user_positions.iter() {
+        for position in positions.iter() {
+            for cAsset in position.collateral_assets.iter() {
+                if !asset_infos.iter().any(|info| info.equal(&cAsset.asset.info)) {
+                    asset_infos.push(cAsset.asset.info.clone());
+                }
+            }
+        }
+    }
+
+    //Query prices once for the page
+    let prices = if asset_infos.is_empty() {
+        vec![]
+    } else {
+        query_prices(
+            deps.storage,
+            deps.querier,
+            env.clone(),
+            config,
+            asset_infos.clone(),
+            Some(basket.clone()),
+            false,
+        )?
+    };
+
+    let mut insolvent_positions: Vec<InsolventPosition> = vec![];
+    for (user, positions) in user_positions {
+        for position in positions {
+            //Filter by collateral asset
+            if let Some(ref asset_info) = collateral_asset {
+                if !position.collateral_assets.iter().any(|cAsset| cAsset.asset.info.equal(asset_info)) {
+                    continue;
+                }
+            }
+
+            //Use ramped & deprecated LTVs
+            let mut collateral_assets = position.clone().collateral_assets;
+            apply_risk_parameters(deps.storage, &env, basket_id, &mut collateral_assets)?;
+
+            let ((insolvent, current_LTV, available_fee), (_avg_borrow_LTV, avg_max_LTV, _total_value, _prices, _ratios)) = insolvency_check_with_prices(
+                collateral_assets,
+                position.credit_amount,
+                basket.clone().credit_price,
+                &asset_infos,
+                &prices,
+            )?;
+
+            //Filter by LTV
+            if let Some(min_LTV) = min_LTV {
+                if current_LTV < min_LTV {
+                    continue;
+                }
+            }
+
+            insolvent_positions.push(InsolventPosition {
+                insolvent,
+                position_info: UserInfo {
+                    position_id: position.position_id,
+                    position_owner: user.to_string(),
+                },
+                current_LTV,
+                avg_max_LTV,
+                LTV_buffer: avg_max_LTV.checked_sub(current_LTV).unwrap_or_else(|_| Decimal::zero()),
+                available_fee,
+            });
+        }
+    }
+
+    Ok(InsolvencyResponse {
+        insolvent_positions,
+        last_position_owner,
+    })
+}
+
 //Calculate debt caps
-pub fn query_basket_debt_caps(deps: Deps, env: Env) -> StdResult<Vec<DebtCap>> {    
-    let mut basket: Basket = BASKET.load(deps.storage)?;
+pub fn query_basket_debt_caps(deps: Deps, env: Env, basket_id: Uint128) -> StdResult<Vec<DebtCap>> {    
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
 
     let asset_caps = get_basket_debt_caps(deps.storage, deps.querier, env, &mut basket, &mut vec![], None)?;
 
@@ -144,26 +266,171 @@ pub fn query_basket_debt_caps(deps: Deps, env: Env) -> StdResult<Vec<DebtCap>> {
     Ok( res )
 }
 
+/// Returns debt & supply cap metrics for each collateral & multi-asset cap.
+/// Volatility adjusted cap ratios are the ratios enforced on deposits.
+pub fn query_debt_cap_dashboard(deps: Deps, env: Env, basket_id: Uint128) -> StdResult<DebtCapDashboardResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+
+    //This also splits the Basket's debt into each SupplyCap's debt_total
+    let debt_caps = get_basket_debt_caps(deps.storage, deps.querier, env.clone(), &mut basket, &mut vec![], None)?;
+
+    //Get current TVL ratios
+    let (cAsset_ratios, _) = get_cAsset_ratios_imut(
+        deps.storage,
+        env,
+        deps.querier,
+        basket.clone().collateral_types,
+        config,
+        Some(basket.clone()),
+    )?;
+    let vol_caps = transform_caps_based_on_volatility(deps.storage, basket.clone())?;
+
+    let mut collateral_caps = vec![];
+    for (index, cap) in basket.clone().collateral_supply_caps.into_iter().enumerate() {
+        //The index is only applied once the volatility list is full
+        let volatility_index = match VOLATILITY.may_load(deps.storage, (basket_id.u128(), cap.asset_info.to_string()))? {
+            Some(vol_store) if vol_store.volatility_list.len() >= vol_store.get_config().window as usize => Some(vol_store.index),
+            _ => None,
+        };
+
+        collateral_caps.push(CollateralCapInfo {
+            collateral: cap.clone().asset_info,
+            debt_total: cap.debt_total,
+            debt_cap: debt_caps[index],
+            remaining_mintable: debt_caps[index].saturating_sub(cap.debt_total),
+            supply_ratio: cAsset_ratios[index],
+            supply_cap_ratio: cap.supply_cap_ratio,
+            volatility_adjusted_cap_ratio: vol_caps[index].supply_cap_ratio,
+            volatility_index,
+            stability_pool_ratio: cap.stability_pool_ratio_for_debt_cap,
+            stability_pool_cap: cap.stability_pool_ratio_for_debt_cap.map(|_| debt_caps[index]),
+        });
+    }
+
+    let mut multi_asset_caps = vec![];
+    for multi_asset_cap in basket.clone().multi_asset_supply_caps {
+        //Sum the ratios of the cap's assets
+        let supply_ratio: Decimal = basket.collateral_types
+            .iter()
+            .enumerate()
+            .filter(|(_i, cAsset)| multi_asset_cap.assets.iter().any(|asset| asset.equal(&cAsset.asset.info)))
+            .map(|(i, _cAsset)| cAsset_ratios[i])
+            .sum();
+
+        let utilization = if multi_asset_cap.supply_cap_ratio.is_zero() {
+            None
+        } else {
+            Some(decimal_division(supply_ratio, multi_asset_cap.supply_cap_ratio)?)
+        };
+
+        multi_asset_caps.push(MultiAssetCapInfo {
+            assets: multi_asset_cap.assets,
+            supply_ratio,
+            supply_cap_ratio: multi_asset_cap.supply_cap_ratio,
+            utilization,
+        });
+    }
+
+    Ok(DebtCapDashboardResponse {
+        collateral_caps,
+        multi_asset_caps,
+    })
+}
+
 /// Returns cAsset interest rates for the Basket
 pub fn query_collateral_rates(
     deps: Deps,
+    basket_id: Uint128,
 ) -> StdResult<CollateralInterestResponse> {
-    let basket = BASKET.load(deps.storage)?;
+    let basket = BASKETS.load(deps.storage, basket_id.u128())?;
 
     let rates = basket.lastest_collateral_rates.into_iter().map(|rate| rate.rate).collect::<Vec<Decimal>>();
+    let rate_models = basket.collateral_types
+        .into_iter()
+        .map(|asset| asset.rate_model.unwrap_or(RateModel::TwoSlope {}))
+        .collect::<Vec<RateModel>>();
 
-    Ok(CollateralInterestResponse { rates })
+    Ok(CollateralInterestResponse { rates, rate_models })
     
 }
 
+/// Returns a Position's undiscounted rate, each applied discount & its effective rate
+pub fn query_position_rate(
+    deps: Deps,
+    env: Env,
+    basket_id: Uint128,
+    position_owner: String,
+    position_id: Uint128,
+) -> StdResult<PositionRateResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let basket = BASKETS.load(deps.storage, basket_id.u128())?;
+    let user = deps.api.addr_validate(&position_owner)?;
+
+    let (_i, position) = match get_target_position(deps.storage, basket_id, user.clone(), position_id){
+        Ok(position) => position,
+        Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+    };
+
+    let (ratios, _) = get_cAsset_ratios_imut(
+        deps.storage,
+        env.clone(),
+        deps.querier,
+        position.clone().collateral_assets,
+        config.clone(),
+        Some(basket.clone()),
+    )?;
+
+    //Weight the Basket's latest collateral rates by the Position's collateral ratios
+    let mut undiscounted_rate = Decimal::zero();
+    for (i, cAsset) in position.collateral_assets.iter().enumerate() {
+        //Removed collateral no longer accrues
+        if let Some(index) = basket.collateral_types.iter().position(|basket_asset| basket_asset.asset.info.equal(&cAsset.asset.info)) {
+            if let Some(rate) = basket.lastest_collateral_rates.get(index) {
+                undiscounted_rate += decimal_multiplication(ratios[i], rate.rate)?;
+            }
+        }
+    }
+
+    let (discounts, total_discount) = get_user_discounts(deps.storage, deps.querier, &env, &config, user.to_string())?;
+    let effective_rate = decimal_multiplication(undiscounted_rate, decimal_subtraction(Decimal::one(), total_discount)?)?;
+
+    Ok(PositionRateResponse {
+        undiscounted_rate,
+        discounts,
+        total_discount,
+        effective_rate,
+    })
+}
+
+/// Returns the volatility tracker of each Basket collateral
+pub fn query_collateral_volatility(
+    deps: Deps,
+    basket_id: Uint128,
+) -> StdResult<Vec<CollateralVolatilityResponse>> {
+    let basket = BASKETS.load(deps.storage, basket_id.u128())?;
+
+    basket.collateral_types
+        .into_iter()
+        .map(|cAsset| {
+            let mut volatility = VOLATILITY.may_load(deps.storage, (basket_id.u128(), cAsset.asset.info.to_string()))?.unwrap_or_default();
+            //Return the effective config
+            volatility.config = Some(volatility.get_config());
+
+            Ok(CollateralVolatilityResponse { asset: cAsset.asset.info, volatility })
+        })
+        .collect::<StdResult<Vec<CollateralVolatilityResponse>>>()
+}
+
 /// Returns Basket credit redemption interest rate
 pub fn query_basket_credit_interest(
     deps: Deps,
     env: Env,
+    basket_id: Uint128,
 ) -> StdResult<InterestResponse> {
     let config = CONFIG.load(deps.storage)?;
 
-    let basket = BASKET.load(deps.storage)?;
+    let basket = BASKETS.load(deps.storage, basket_id.u128())?;
 
     let time_elapsed = env.block.time.seconds() - basket.credit_last_accrued;
     let mut price_difference = Decimal::zero();
@@ -178,6 +445,8 @@ pub fn query_basket_credit_interest(
             pool_info: None,
             rate_index: Decimal::one(),
             hike_rates: Some(false),
+            isolated_debt_ceiling: None,
+            rate_model: None,
         };
 
         let credit_TWAP_price = match  get_asset_values(
@@ -259,30 +528,36 @@ pub fn get_cAsset_ratios(
         basket.clone(),
         false
     )?;
+    let basket_id = match basket {
+        Some(basket) => basket.basket_id,
+        None => DEFAULT_BASKET_ID.load(storage)?,
+    };
 
     //Loop through collateral assets to save prices & volatility
     for (i, cAsset) in collateral_assets.iter().enumerate() {
         //Check if the querier used the stored price by asserting equality
         //This also skips any equal prices which should be fairly rare anyway
-        let stored_price_res = STORED_PRICES.load(storage, cAsset.asset.info.to_string()); 
+        let stored_price_res = STORED_PRICES.load(storage, (basket_id.u128(), cAsset.asset.info.to_string())); 
         if let Ok(ref stored_price) = stored_price_res {
             if stored_price.price.price != cAsset_prices[i].price.clone() {
                 
                 //Save new Stored price
-                STORED_PRICES.save(storage, cAsset.asset.info.to_string(),
+                STORED_PRICES.save(storage, (basket_id.u128(), cAsset.asset.info.to_string()),
                 &StoredPrice {
                     price: cAsset_prices[i].clone(),
                     last_time_updated: env.block.time.seconds(),
                 })?;
+                queue_price_record(storage, cAsset.asset.info.clone(), basket_id, cAsset_prices[i].clone(), env.block.time.seconds())?;
 
                 //Bc the prices aren't equal we need to update the volatility list
-                let mut volatility_store = match VOLATILITY.load(storage, cAsset.asset.info.to_string()){
-                    Ok(volatility) => volatility,
-                    Err(_) => CollateralVolatility {
-                        index: Decimal::one(),
-                        volatility_list: vec![],
-                    },
-                };
+                let mut volatility_store = VOLATILITY.may_load(storage, (basket_id.u128(), cAsset.asset.info.to_string()))?.unwrap_or_default();
+                let volatility_config = volatility_store.get_config();
+                //Skip samples taken before the sampling interval has passed
+                if let Some(last_sample_time) = volatility_store.last_sample_time {
+                    if env.block.time.seconds() < last_sample_time + volatility_config.sampling_interval {
+                        continue;
+                    }
+                }
                 //Get new volatility %
                 let new_volatility = decimal_division(cAsset_prices[i].price.abs_diff(stored_price.price.price), stored_price.price.price)?;
                 //Get speed of price change by dividing by the time elapsed
@@ -294,13 +569,13 @@ pub fn get_cAsset_ratios(
                 };
                 //Add new volatility to the list
                 volatility_store.volatility_list.push(speed_of_volatility);
-                //If the list is at the limit, remove the first element
-                if volatility_store.volatility_list.len() > VOLATILITY_LIST_LIMIT as usize {
+                volatility_store.last_sample_time = Some(env.block.time.seconds());
+                //If the list is over the window, remove the oldest elements
+                while volatility_store.volatility_list.len() > volatility_config.window as usize {
                     volatility_store.volatility_list.remove(0);
                 }
                 //Find the current average volatility
-                let mut avg_volatility: Decimal = volatility_store.volatility_list.iter().sum();
-                avg_volatility = decimal_division(avg_volatility, Decimal::from_str(&volatility_store.volatility_list.len().to_string())?)?;
+                let avg_volatility = get_average_volatility(&volatility_store.volatility_list, &volatility_config)?;
 
                 //With volatility btwn any time points standardized to the same units (vol/time)
                 // we can now calculate the change in index based on the % difference btwn the avg volatility & the newest speed of volatility
@@ -314,26 +589,27 @@ pub fn get_cAsset_ratios(
                     };
                 }
 
-                //Index can't hit 0
                 volatility_store.index = decimal_multiplication(volatility_store.index, change_in_index)?;
-                //Index can't go above 1
-                volatility_store.index = Decimal::one().min(volatility_store.index);
+                //Keep the index within the config's floor & ceiling
+                volatility_store.index = volatility_store.index
+                    .max(volatility_config.multiplier_floor)
+                    .min(volatility_config.multiplier_ceiling);
                 
                 // println!("Avg: {:?} --- New: {:?}-- Index: {}", avg_volatility, speed_of_volatility, volatility_store.index);
                 //Save the new volatility store
-                VOLATILITY.save(storage, cAsset.asset.info.to_string(), &volatility_store)?;
+                VOLATILITY.save(storage, (basket_id.u128(), cAsset.asset.info.to_string()), &volatility_store)?;
                 
                 //This index will be used to lower the Basket's supply caps on rate calculations & supply tallies
             }
         } 
         //Save new Stored price & skip volatility calcs
         else {
-            STORED_PRICES.save(storage, cAsset.asset.info.to_string(),
+            STORED_PRICES.save(storage, (basket_id.u128(), cAsset.asset.info.to_string()),
             &StoredPrice {
                 price: cAsset_prices[i].clone(),
                 last_time_updated: env.block.time.seconds(),
             })?;
-
+            queue_price_record(storage, cAsset.asset.info.clone(), basket_id, cAsset_prices[i].clone(), env.block.time.seconds())?;
         }
     }
     
@@ -352,6 +628,43 @@ pub fn get_cAsset_ratios(
     Ok((cAsset_ratios, cAsset_prices))
 }
 
+/// Queue a newly queried price for the oracle's price history.
+/// Only the latest price per asset is kept.
+fn queue_price_record(storage: &mut dyn Storage, asset_info: AssetInfo, basket_id: Uint128, price: PriceResponse, time: u64) -> StdResult<()> {
+    let mut records = PRICE_RECORDS.may_load(storage)?.unwrap_or_default();
+    records.retain(|record| !(record.asset_info.equal(&asset_info) && record.basket_id == basket_id));
+    records.push(PriceRecord { asset_info, basket_id, price, time });
+
+    PRICE_RECORDS.save(storage, &records)
+}
+
+/// Returns the average of a volatility list using the config's smoothing method
+fn get_average_volatility(
+    volatility_list: &[Decimal],
+    volatility_config: &VolatilityConfig,
+) -> StdResult<Decimal> {
+    if volatility_list.is_empty() {
+        return Ok(Decimal::zero());
+    }
+
+    match volatility_config.smoothing {
+        VolatilitySmoothing::Simple {} => {
+            let total: Decimal = volatility_list.iter().sum();
+            decimal_division(total, Decimal::from_ratio(volatility_list.len() as u128, 1u128))
+        },
+        VolatilitySmoothing::EMA {} => {
+            //alpha = 2 / (window + 1)
+            let alpha = Decimal::from_ratio(2u128, volatility_config.window as u128 + 1);
+            let mut ema = volatility_list[0];
+            for volatility in volatility_list.iter().skip(1) {
+                //ema = alpha * new + (1 - alpha) * ema
+                ema = decimal_multiplication(alpha, *volatility)? + decimal_multiplication(Decimal::one() - alpha, ema)?;
+            }
+            Ok(ema)
+        },
+    }
+}
+
 //For debt_cap_queries
 pub fn get_cAsset_ratios_imut(
     storage: &dyn Storage,
@@ -404,7 +717,7 @@ pub fn query_prices(
     let basket = if let Some(basket) = basket {
         basket
     } else {
-        BASKET.load(storage)?
+        BASKETS.load(storage, DEFAULT_BASKET_ID.load(storage)?.u128())?
     };
 
     //if AssetInfo is the basket.credit_asset, change twap timeframe
@@ -417,7 +730,7 @@ pub fn query_prices(
     let mut bulk_asset_query = asset_infos.clone();
     for asset_info in asset_infos.clone() {
         //Try to use a stored price
-        let stored_price_res = STORED_PRICES.load(storage, asset_info.to_string()); 
+        let stored_price_res = STORED_PRICES.load(storage, (basket.basket_id.u128(), asset_info.to_string())); 
         //Set the old_price if the stored price is within the oracle_time_limit
         let mut old_price: Option<PriceResponse> = None;
         if let Ok(ref stored_price) = stored_price_res {
@@ -450,6 +763,7 @@ pub fn query_prices(
                 asset_infos: bulk_asset_query.clone(),
                 twap_timeframe,
                 oracle_time_limit: config.oracle_time_limit,
+                basket_id: Some(basket.basket_id),
             })?,
         })) {
             Ok(res) => {
@@ -478,9 +792,139 @@ pub fn query_prices(
     Ok(sorted_prices)
 }
 
+/// Returns Baskets hosted by the contract
+pub fn query_baskets(
+    deps: Deps,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Basket>> {
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|id| Bound::exclusive(id.u128()));
+
+    BASKETS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_id, basket) = item?;
+            Ok(basket)
+        })
+        .collect()
+}
+
+/// Returns operators approved by a Position owner in the Basket
+pub fn query_operators(
+    deps: Deps,
+    basket_id: Uint128,
+    position_owner: String,
+) -> StdResult<Vec<OperatorResponse>> {
+    let position_owner = deps.api.addr_validate(&position_owner)?;
+
+    OPERATORS
+        .prefix((basket_id.u128(), position_owner))
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (operator, allowance) = item?;
+            Ok(OperatorResponse {
+                operator: operator.to_string(),
+                allowance,
+            })
+        })
+        .collect()
+}
+
+/// Returns Position orders, optionally for a single owner
+pub fn query_position_orders(
+    deps: Deps,
+    basket_id: Uint128,
+    position_owner: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PositionOrder>> {
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let position_owner = match position_owner {
+        Some(owner) => Some(deps.api.addr_validate(&owner)?),
+        None => None,
+    };
+
+    POSITION_ORDERS
+        .prefix(basket_id.u128())
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match (item, &position_owner) {
+            (Ok((_id, order)), Some(owner)) => order.position_owner == *owner,
+            _ => true,
+        })
+        .take(limit)
+        .map(|item| {
+            let (_id, order) = item?;
+            Ok(order)
+        })
+        .collect()
+}
+
+/// Returns a Position's snapshots in the Basket
+pub fn query_position_snapshots(
+    deps: Deps,
+    basket_id: Uint128,
+    position_owner: String,
+    position_id: Uint128,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PositionSnapshot>> {
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let position_owner = deps.api.addr_validate(&position_owner)?;
+
+    POSITION_SNAPSHOTS
+        .prefix(((basket_id.u128(), position_owner), position_id.u128()))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_time, snapshot) = item?;
+            Ok(snapshot)
+        })
+        .collect()
+}
+
+/// Returns deprecated collateral & their wind-down schedules
+pub fn query_collateral_deprecations(
+    deps: Deps,
+    basket_id: Uint128,
+) -> StdResult<Vec<CollateralDeprecation>> {
+    DEPRECATED_COLLATERAL
+        .prefix(basket_id.u128())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (_asset, deprecation) = item?;
+            Ok(deprecation)
+        })
+        .collect()
+}
+
+/// Returns pending base rate & cAsset LTV ramps
+pub fn query_parameter_ramps(
+    deps: Deps,
+    basket_id: Uint128,
+) -> StdResult<ParameterRampsResponse> {
+    let cAsset_LTVs = LTV_RAMPS
+        .prefix(basket_id.u128())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (_asset, ramp) = item?;
+            Ok(ramp)
+        })
+        .collect::<StdResult<Vec<LTVRamp>>>()?;
+
+    Ok(ParameterRampsResponse {
+        base_interest_rate: BASE_RATE_RAMP.may_load(deps.storage, basket_id.u128())?,
+        cAsset_LTVs,
+    })
+}
+
 /// Get Basket Redeemability
 pub fn query_basket_redeemability(
     deps: Deps,
+    basket_id: Uint128,
     position_owner: Option<String>,
     start_after: Option<u128>,
     limit: Option<u32>,
@@ -501,7 +945,7 @@ pub fn query_basket_redeemability(
 
     //Query by premium
     for premium in start..100u128 {
-        let users_of_premium: Vec<RedemptionInfo> = match REDEMPTION_OPT_IN.load(deps.storage, premium){
+        let users_of_premium: Vec<RedemptionInfo> = match REDEMPTION_OPT_IN.load(deps.storage, (basket_id.u128(), premium)){
             Ok(list)=> list,
             Err(_err) => vec![], //If no users, return empty vec
         };
@@ -553,14 +997,151 @@ pub fn query_basket_redeemability(
     )
 }
 
+/// Simulate RedeemCollateral.
+/// Walks the premium tiers the same way redemptions do without editing state.
+pub fn simulate_redemption(
+    deps: Deps,
+    env: Env,
+    basket_id: Uint128,
+    credit_amount: Uint128,
+    max_collateral_premium: u128,
+    collateral_filter: Option<RedemptionCollateralFilter>,
+) -> StdResult<RedemptionSimulationResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let basket: Basket = BASKETS.load(deps.storage, basket_id.u128())?;
+
+    let mut credit_amount = Decimal::from_ratio(credit_amount, Uint128::one());
+    let mut premium_tiers: Vec<PremiumRedemption> = vec![];
+    let mut collateral_received: Vec<Asset> = vec![];
+
+    for premium in 0..=max_collateral_premium {
+        if credit_amount.is_zero() {
+            break;
+        }
+        //(100%-premium)
+        let discount_ratio = decimal_subtraction(
+            Decimal::one(),
+            Decimal::percent(premium as u64)
+        )?;
+
+        let users_of_premium: Vec<RedemptionInfo> = REDEMPTION_OPT_IN
+            .may_load(deps.storage, (basket_id.u128(), premium))?
+            .unwrap_or_default();
+
+        let mut tier = PremiumRedemption {
+            premium,
+            positions: vec![],
+            collateral_received: vec![],
+        };
+
+        for user in users_of_premium {
+            for position_redemption_info in user.position_infos {
+                if credit_amount.is_zero() {
+                    break;
+                }
+
+                let (_i, mut target_position) = match get_target_position(
+                    deps.storage,
+                    basket_id,
+                    user.position_owner.clone(),
+                    position_redemption_info.position_id
+                ){
+                    Ok(position) => position,
+                    Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+                };
+
+                //Remove restricted & unaccepted collateral assets
+                target_position.collateral_assets.retain(|asset| {
+                    !position_redemption_info.restricted_collateral_assets.contains(&asset.asset.info.to_string())
+                        && collateral_filter.as_ref().map_or(true, |filter| filter.accepts(&asset.asset.info))
+                });
+                if target_position.collateral_assets.is_empty() {
+                    continue;
+                }
+
+                let (cAsset_ratios, cAsset_prices) = get_cAsset_ratios_imut(
+                    deps.storage,
+                    env.clone(),
+                    deps.querier,
+                    target_position.clone().collateral_assets,
+                    config.clone(),
+                    Some(basket.clone()),
+                )?;
+
+                //Max we can redeem is the target_position's credit_amount
+                let redeemable_credit = Decimal::min(
+                    Decimal::min(
+                        Decimal::from_ratio(position_redemption_info.remaining_loan_repayment, Uint128::one()),
+                        Decimal::from_ratio(target_position.credit_amount, Uint128::one())
+                    ),
+                    credit_amount
+                );
+                if redeemable_credit.to_uint_floor().is_zero() {
+                    continue;
+                }
+                credit_amount = decimal_subtraction(credit_amount, redeemable_credit)?;
+
+                //credit_value * discount_ratio
+                let redeemable_value = decimal_multiplication(
+                    basket.credit_price.get_value(redeemable_credit.to_uint_floor())?,
+                    discount_ratio
+                )?;
+
+                let mut position_collateral: Vec<Asset> = vec![];
+                for (i, cAsset) in target_position.collateral_assets.iter().enumerate() {
+                    let value_to_send = decimal_multiplication(redeemable_value, cAsset_ratios[i])?;
+                    let collateral_to_send = Asset {
+                        info: cAsset.asset.info.clone(),
+                        amount: cAsset_prices[i].get_amount(value_to_send)?,
+                    };
+
+                    add_asset(&mut tier.collateral_received, collateral_to_send.clone());
+                    add_asset(&mut collateral_received, collateral_to_send.clone());
+                    position_collateral.push(collateral_to_send);
+                }
+
+                tier.positions.push(RedeemedPosition {
+                    position_info: UserInfo {
+                        position_id: position_redemption_info.position_id,
+                        position_owner: user.position_owner.to_string(),
+                    },
+                    credit_redeemed: redeemable_credit.to_uint_floor(),
+                    collateral_received: position_collateral,
+                });
+            }
+        }
+
+        if !tier.positions.is_empty() {
+            premium_tiers.push(tier);
+        }
+    }
+
+    Ok(RedemptionSimulationResponse {
+        premium_tiers,
+        collateral_received,
+        unredeemed_credit: credit_amount.to_uint_floor(),
+    })
+}
+
+/// Add an Asset to a list, summing amounts of the same asset
+fn add_asset(assets: &mut Vec<Asset>, new_asset: Asset) {
+    if let Some(asset) = assets.iter_mut().find(|asset| asset.info.equal(&new_asset.info)) {
+        asset.amount += new_asset.amount;
+    } else {
+        assets.push(new_asset);
+    }
+}
+
 pub fn simulate_LTV_mint(
     deps: Deps,
     env: Env,
+    basket_id: Uint128,
     user_info: UserInfo,
     LTV: Decimal,
 ) -> StdResult<Uint128> {
     let (_, target_position) = match get_target_position(
         deps.storage,
+        basket_id,
         deps.api.addr_validate(&user_info.position_owner)?, 
         user_info.position_id){
             Ok(position) => position,
@@ -573,7 +1154,7 @@ pub fn simulate_LTV_mint(
         env.clone(), 
         CONFIG.load(deps.storage)?,
         target_position,
-        BASKET.load(deps.storage)?,
+        BASKETS.load(deps.storage, basket_id.u128())?,
         LTV
     ){
         Ok(amount) => amount,
@@ -647,9 +1228,13 @@ pub fn get_avg_LTV(
     let basket = if let Some(basket) = basket {
         basket
     } else {
-        BASKET.load(storage)?
+        BASKETS.load(storage, DEFAULT_BASKET_ID.load(storage)?.u128())?
     };
 
+    //Use ramped & deprecated LTVs
+    let mut collateral_assets = collateral_assets;
+    apply_risk_parameters(storage, &env, basket.basket_id, &mut collateral_assets)?;
+
     //Calc total value of collateral
     let (cAsset_values, cAsset_price_res) = get_asset_values(
         storage,
@@ -747,6 +1332,55 @@ pub fn insolvency_check(
     Ok((insolvency_check_calc(avg_LTVs.clone(), collateral_assets, credit_amount, credit_price, max_borrow)?, avg_LTVs))
 }
 
+/// Assert collateral prices are within the price policy.
+/// Only checked for actions that add risk, i.e. borrows & withdrawals w/ debt,
+/// so stale prices never block liquidations or repayments.
+/// Prices of empty collateral aren't checked.
+pub fn assert_price_policy(
+    price_policy: Option<PricePolicy>,
+    current_time: u64,
+    collateral_assets: &[cAsset],
+    prices: &[PriceResponse],
+) -> StdResult<()> {
+    if let Some(policy) = price_policy {
+        for (cAsset, price) in collateral_assets.iter().zip(prices.iter()) {
+            if !cAsset.asset.amount.is_zero() {
+                policy.check(&cAsset.asset.info.to_string(), price, current_time)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Insolvency check using prices queried beforehand.
+/// Used to check multiple Positions with a single price query.
+pub fn insolvency_check_with_prices(
+    collateral_assets: Vec<cAsset>,
+    credit_amount: Uint128,
+    credit_price: PriceResponse,
+    asset_infos: &[AssetInfo],
+    prices: &[PriceResponse],
+) -> StdResult<((bool, Decimal, Uint128), (Decimal, Decimal, Decimal, Vec<PriceResponse>, Vec<Decimal>))> {
+    //Match prices to the Position's collateral
+    let mut cAsset_values: Vec<Decimal> = vec![];
+    let mut cAsset_prices: Vec<PriceResponse> = vec![];
+    for cAsset in collateral_assets.iter() {
+        let price = match asset_infos.iter().position(|info| info.equal(&cAsset.asset.info)) {
+            Some(index) => prices[index].clone(),
+            None => return Err(StdError::GenericErr { msg: format!("No price passed for {}", cAsset.asset.info) }),
+        };
+        cAsset_values.push(price.get_value(cAsset.asset.amount)?);
+        cAsset_prices.push(price);
+    }
+
+    //Get avg LTVs
+    let avg_LTVs = calculate_avg_LTV(cAsset_values, cAsset_prices, collateral_assets.clone())?;
+
+    //Insolvency check
+    Ok((insolvency_check_calc(avg_LTVs.clone(), collateral_assets, credit_amount, credit_price, false)?, avg_LTVs))
+}
+
 /// Function handles calculations for the insolvency check
 pub fn insolvency_check_calc(
     //BorrowLTV, MaxLTV, TotalAssetValue, cAssetPrices
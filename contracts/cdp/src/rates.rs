@@ -3,19 +3,26 @@ use std::str::FromStr;
 
 use cosmwasm_std::{attr, Addr, Api, Decimal, DepsMut, Env, MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Storage, Uint128};
 
-use membrane::cdp::Config;
-use membrane::system_discounts::QueryMsg as DiscountQueryMsg;
-use membrane::types::{cAsset, Basket, Position, Rate, SupplyCap};
+use membrane::cdp::{AppliedDiscount, Config};
+use membrane::discount_vault::{QueryMsg as DiscountVault_QueryMsg, UserResponse as DiscountVault_UserResponse};
+use membrane::points_system::{QueryMsg as Points_QueryMsg, UserStatsResponse};
+use membrane::staking::{QueryMsg as Staking_QueryMsg, StakerResponse};
+use membrane::system_discounts::{QueryMsg as DiscountQueryMsg, UserDiscountResponse};
+use membrane::types::{cAsset, Basket, DiscountSource, DiscountSourceType, DiscountTier, Position, Rate, RateModel, SupplyCap, VolatilityConfig};
 use membrane::helpers::get_asset_liquidity;
 use membrane::math::{decimal_multiplication, decimal_division, decimal_subtraction};
 
 use crate::ContractError;
-use crate::query::{get_asset_values, get_cAsset_ratios, VOLATILITY_LIST_LIMIT};
-use crate::risk_engine::get_basket_debt_caps;
-use crate::state::{get_target_position, update_position, BASKET, CONFIG, VOLATILITY};
+use crate::query::{get_asset_values, get_cAsset_ratios};
+use crate::risk_engine::{apply_risk_parameters, get_basket_debt_caps};
+use crate::state::{get_target_position, update_position, RateController, BASKETS, BASE_RATE_RAMP, CONFIG, DISCOUNT_SOURCES, RATE_CONTROLLERS, VOLATILITY};
+
+//Max samples in a volatility list
+const MAX_VOLATILITY_WINDOW: u32 = 168;
 
 //Constants
 pub const SECONDS_PER_YEAR: u64 = 31_536_000u64;
+const SECONDS_PER_DAY: u64 = 86_400u64;
 const MINIMUM_LIQUIDITY: Uint128 = Uint128::new(2_000_000_000_000u128);
 
 /// Accrue interest for a list of Positions
@@ -25,10 +32,11 @@ pub fn external_accrue_call(
     querier: QuerierWrapper,
     info: MessageInfo,
     env: Env,
+    basket_id: Uint128,
     position_owner: Option<String>,
     position_ids: Vec<Uint128>,
 ) -> Result<Response, ContractError>{
-    let mut basket = BASKET.load(storage)?;
+    let mut basket = BASKETS.load(storage, basket_id.u128())?;
     let config = CONFIG.load(storage)?;
 
     //Validate position owner
@@ -48,6 +56,7 @@ pub fn external_accrue_call(
     for position_id in position_ids.clone() {
         let mut position = get_target_position(
             storage,
+            basket_id,
             valid_position_owner.clone(),
             position_id,
         )?.1;
@@ -67,10 +76,10 @@ pub fn external_accrue_call(
 
         accrued_interest += position.clone().credit_amount - prev_loan;
 
-        update_position(storage, valid_position_owner.clone(), position)?;
+        update_position(storage, basket_id, valid_position_owner.clone(), position)?;
     }
     //Save updated Basket
-    BASKET.save(storage, &basket)?;
+    BASKETS.save(storage, basket_id.u128(), &basket)?;
 
     Ok(Response::new()
         .add_attributes(vec![
@@ -99,10 +108,11 @@ pub fn update_rate_indices(
     supply_caps: &mut Vec<SupplyCap>,
     negative_rate: bool,
     credit_price_rate: Decimal,
+    credit_deviation: Decimal,
     cdt_liquidity: Option<Uint128>,
 ) -> StdResult<()>{
     //Get basket rates
-    let mut interest_rates = match get_interest_rates(storage, querier, env.clone(), basket, supply_caps, cdt_liquidity){
+    let mut interest_rates = match get_interest_rates(storage, querier, env.clone(), basket, supply_caps, negative_rate, credit_deviation, cdt_liquidity){
         Ok(rates) => rates,
         Err(err) => {
             return Err(StdError::GenericErr {
@@ -185,6 +195,8 @@ pub fn get_interest_rates(
     env: Env,
     basket: &mut Basket,
     supply_caps: &mut Vec<SupplyCap>,
+    negative_rate: bool,
+    credit_deviation: Decimal,
     cdt_liquidity: Option<Uint128>,
 ) -> StdResult<Vec<Decimal>> {
     let config = CONFIG.load(storage)?;
@@ -353,13 +365,202 @@ pub fn get_interest_rates(
             }
         }
     }
+
+    //Swap in the rates of cAssets that use other models
+    for (i, asset) in basket.clone().collateral_types.into_iter().enumerate() {
+        match asset.rate_model {
+            None | Some(RateModel::TwoSlope {}) => {},
+            Some(RateModel::Kinked { base_rate, kink, slope_1, slope_2 }) => {
+                two_slope_pro_rata_rates[i] = min(
+                    get_kinked_rate(debt_proportions[i], base_rate, kink, slope_1, slope_2)?,
+                    Decimal::one()
+                );
+            },
+            Some(RateModel::Fixed { rate }) => {
+                two_slope_pro_rata_rates[i] = min(rate, Decimal::one());
+            },
+            Some(RateModel::PID { target_utilization, kp, ki, kd, min_rate, max_rate }) => {
+                two_slope_pro_rata_rates[i] = get_pid_rate(
+                    storage,
+                    &env,
+                    (basket.basket_id.u128(), asset.asset.info.to_string()),
+                    debt_proportions[i],
+                    target_utilization,
+                    (kp, ki, kd),
+                    min_rate,
+                    max_rate,
+                )?;
+            },
+            Some(RateModel::CreditDeviation { base_rate, multiplier, max_rate }) => {
+                let adjustment = decimal_multiplication(credit_deviation, multiplier)?;
+                //Rates fall while credit is above the redemption price & rise while it's below
+                let rate = if negative_rate {
+                    base_rate.checked_sub(adjustment).unwrap_or_else(|_| Decimal::zero())
+                } else {
+                    base_rate + adjustment
+                };
+
+                two_slope_pro_rata_rates[i] = min(rate, min(max_rate, Decimal::one()));
+            },
+        }
+    }
         
     Ok(two_slope_pro_rata_rates)
 }
+
+/// Rate along a kinked utilization curve
+fn get_kinked_rate(
+    utilization: Decimal,
+    base_rate: Decimal,
+    kink: Decimal,
+    slope_1: Decimal,
+    slope_2: Decimal,
+) -> StdResult<Decimal> {
+    if utilization <= kink {
+        Ok(base_rate + decimal_multiplication(slope_1, decimal_division(utilization, kink)?)?)
+    } else {
+        //Utilization over 100% keeps climbing slope 2
+        let excess_utilization = decimal_division(
+            decimal_subtraction(utilization, kink)?,
+            decimal_subtraction(Decimal::one(), kink)?,
+        )?;
+
+        Ok(base_rate + slope_1 + decimal_multiplication(slope_2, excess_utilization)?)
+    }
+}
+
+/// Rate from a PID controller targeting a utilization.
+/// Controller state only updates once per block.
+fn get_pid_rate(
+    storage: &mut dyn Storage,
+    env: &Env,
+    controller_key: (u128, String),
+    utilization: Decimal,
+    target_utilization: Decimal,
+    (kp, ki, kd): (Decimal, Decimal, Decimal),
+    min_rate: Decimal,
+    max_rate: Decimal,
+) -> StdResult<Decimal> {
+    let current_time = env.block.time.seconds();
+
+    let controller = match RATE_CONTROLLERS.may_load(storage, controller_key.clone())? {
+        Some(controller) => {
+            if controller.last_updated == current_time {
+                return Ok(controller.rate);
+            }
+            controller
+        },
+        None => RateController {
+            rate: min_rate,
+            integral: Decimal::zero(),
+            integral_negative: false,
+            last_error: Decimal::zero(),
+            last_error_negative: false,
+            last_updated: current_time,
+        },
+    };
+    //Error is positive when utilization is over the target
+    let error = signed_sub((utilization, false), (target_utilization, false))?;
+
+    //Integrate the error per day
+    let days_elapsed = Decimal::from_ratio(current_time - controller.last_updated, SECONDS_PER_DAY);
+    let (mut integral, integral_negative) = signed_sub(
+        (controller.integral, controller.integral_negative),
+        (decimal_multiplication(error.0, days_elapsed)?, !error.1),
+    )?;
+    //Cap the integral so it can't wind up past the max rate
+    if !ki.is_zero() {
+        integral = min(integral, decimal_division(max_rate, ki)?);
+    }
+
+    //Change in error since the last update
+    let derivative = signed_sub(error, (controller.last_error, controller.last_error_negative))?;
+
+    let mut output = (decimal_multiplication(kp, error.0)?, error.1);
+    output = signed_sub(output, (decimal_multiplication(ki, integral)?, !integral_negative))?;
+    output = signed_sub(output, (decimal_multiplication(kd, derivative.0)?, !derivative.1))?;
+
+    let rate = if output.1 {
+        min_rate
+    } else {
+        min(output.0, max_rate).max(min_rate)
+    };
+
+    RATE_CONTROLLERS.save(storage, controller_key, &RateController {
+        rate,
+        integral,
+        integral_negative,
+        last_error: error.0,
+        last_error_negative: error.1,
+        last_updated: current_time,
+    })?;
+
+    Ok(rate)
+}
+
+/// Subtracts signed decimals, (value, is_negative)
+fn signed_sub(
+    a: (Decimal, bool),
+    b: (Decimal, bool),
+) -> StdResult<(Decimal, bool)> {
+    //a - (-b) = a + b
+    let (value, negative) = match (a.1, b.1) {
+        (false, true) => (a.0 + b.0, false),
+        (true, false) => (a.0 + b.0, true),
+        //Same sign, the larger magnitude sets the sign
+        (a_negative, _) => {
+            if a.0 >= b.0 {
+                (decimal_subtraction(a.0, b.0)?, a_negative)
+            } else {
+                (decimal_subtraction(b.0, a.0)?, !a_negative)
+            }
+        }
+    };
+
+    Ok((value, negative && !value.is_zero()))
+}
+
+/// Validate a cAsset rate model's parameters
+pub fn validate_rate_model(
+    rate_model: &RateModel,
+) -> Result<(), ContractError> {
+    let valid = match rate_model {
+        RateModel::TwoSlope {} => true,
+        RateModel::Kinked { kink, .. } => !kink.is_zero() && *kink < Decimal::one(),
+        RateModel::Fixed { rate } => *rate <= Decimal::one(),
+        RateModel::PID { target_utilization, min_rate, max_rate, .. } => {
+            !target_utilization.is_zero() && min_rate <= max_rate && *max_rate <= Decimal::one()
+        },
+        RateModel::CreditDeviation { base_rate, max_rate, .. } => {
+            base_rate <= max_rate && *max_rate <= Decimal::one()
+        },
+    };
+
+    if !valid {
+        return Err(ContractError::CustomError { val: format!("Invalid {} rate model parameters", rate_model) })
+    }
+
+    Ok(())
+}
+
+/// Validate a volatility tracker config
+pub fn validate_volatility_config(
+    volatility_config: &VolatilityConfig,
+) -> Result<(), ContractError> {
+    if volatility_config.window == 0 || volatility_config.window > MAX_VOLATILITY_WINDOW {
+        return Err(ContractError::CustomError { val: format!("Volatility window must be between 1 & {}", MAX_VOLATILITY_WINDOW) })
+    }
+    if volatility_config.multiplier_floor > volatility_config.multiplier_ceiling || volatility_config.multiplier_ceiling > Decimal::one() {
+        return Err(ContractError::CustomError { val: String::from("Volatility multiplier floor must be <= ceiling <= 100%") })
+    }
+
+    Ok(())
+}
+
 //Used for accrual & update_basket_tally()
 //This doesn't alter multi-asset caps
 pub fn transform_caps_based_on_volatility(
-    storage: &mut dyn Storage,
+    storage: &dyn Storage,
     basket: Basket,
 ) -> StdResult<Vec<SupplyCap>> {
 
@@ -367,9 +568,9 @@ pub fn transform_caps_based_on_volatility(
     let vol_caps: Vec<SupplyCap> = basket.clone().collateral_supply_caps.into_iter()
         .map(|cap| {        
             //Load volatility store
-            if let Ok(vol_store) = VOLATILITY.load(storage, cap.asset_info.to_string()){
+            if let Ok(vol_store) = VOLATILITY.load(storage, (basket.basket_id.u128(), cap.asset_info.to_string())){
                 
-                if vol_store.volatility_list.len() == VOLATILITY_LIST_LIMIT as usize {
+                if vol_store.volatility_list.len() >= vol_store.get_config().window as usize {
                     //Transform supply ap based on asset volatility
                     let new_supply_cap = match decimal_multiplication(
                         cap.supply_cap_ratio,
@@ -405,6 +606,7 @@ fn get_credit_rate_of_change(
     position: &mut Position,
     negative_rate: bool,
     credit_price_rate: Decimal,
+    credit_deviation: Decimal,
     cdt_liquidity: Option<Uint128>,
 ) -> StdResult<(Decimal, Vec<Decimal>)> {
     let (ratios, _) = match get_cAsset_ratios(storage, env.clone(), querier, position.clone().collateral_assets, config, Some(basket.clone())){
@@ -423,7 +625,7 @@ fn get_credit_rate_of_change(
         Err(_err) => basket.clone().collateral_supply_caps
     };
     
-    match update_rate_indices(storage, querier, env, basket, &mut supply_caps, negative_rate, credit_price_rate, cdt_liquidity){
+    match update_rate_indices(storage, querier, env, basket, &mut supply_caps, negative_rate, credit_price_rate, credit_deviation, cdt_liquidity){
         Ok(_ok) => {},
         Err(err) => {
             return Err(StdError::GenericErr {
@@ -453,6 +655,9 @@ fn get_credit_rate_of_change(
             
             /////Update cAsset rate_index
             position.collateral_assets[i].rate_index = basket_asset.rate_index;        
+        } else {
+            //Removed collateral no longer accrues, so its index doesn't change
+            avg_change_in_index += ratios[i];
         }
     }    
     //The change in index represents the rate accrued to the cAsset's index in the time since last accrual
@@ -471,12 +676,18 @@ pub fn accrue(
     user: String,
     is_deposit_function: bool,
 ) -> StdResult<Vec<Decimal>> {
+    //Apply ramped parameters
+    apply_risk_parameters(storage, &env, basket.basket_id, &mut basket.collateral_types)?;
+    if let Some(ramp) = BASE_RATE_RAMP.may_load(storage, basket.basket_id.u128())? {
+        basket.base_interest_rate = ramp.get_value(env.block.time.seconds());
+    }
+
     /////Accrue Interest to the Repayment Price///
     //Calc Time-elapsed and update last_Accrued
     let time_elapsed = env.block.time.seconds() - basket.credit_last_accrued;
 
     let mut negative_rate: bool = false;
-    let price_difference: Decimal;
+    let mut price_difference: Decimal = Decimal::zero();
     let mut credit_price_rate: Decimal = Decimal::zero();
 
     ////Credit Price Controller barriers to reduce risk of manipulation
@@ -516,6 +727,8 @@ pub fn accrue(
         pool_info: None,
         rate_index: Decimal::one(),
         hike_rates: Some(false),
+        isolated_debt_ceiling: None,
+        rate_model: None,
     };
 
     let credit_TWAP_price = match get_asset_values(
@@ -618,6 +831,7 @@ pub fn accrue(
         position,
         negative_rate,
         credit_price_rate,
+        price_difference,
         Some(liquidity),
     ){
         Ok(rate) => rate,
@@ -638,12 +852,10 @@ pub fn accrue(
         //Calc accrued interest
         let mut accrued_interest = new_credit_amount - position.credit_amount;
 
-        if let Some(contract) = config.clone().discounts_contract {
-            //Get User's discounted interest
-            accrued_interest = match get_discounted_interest(querier, contract.to_string(), user, accrued_interest){
-                Ok(discounted_interest) => discounted_interest,
-                Err(_) => accrued_interest,
-            };
+        //Get User's discounted interest
+        let (_discounts, total_discount) = get_user_discounts(storage, querier, &env, &config, user)?;
+        if !total_discount.is_zero() {
+            accrued_interest = get_discounted_interest(accrued_interest, total_discount)?;
         }
 
         //Add accrued interest to the basket's pending revenue
@@ -659,16 +871,141 @@ pub fn accrue(
     Ok(ratios)
 }
 
-/// Calculate the discounted interest for a user
-fn get_discounted_interest(
+/// Get the user's discount from each source & the total discount.
+/// Discounts are capped per source & stacked up to 100%.
+/// Sources that fail to return a discount apply none.
+pub fn get_user_discounts(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    env: &Env,
+    config: &Config,
+    user: String,
+) -> StdResult<(Vec<AppliedDiscount>, Decimal)> {
+    let mut discounts: Vec<AppliedDiscount> = vec![];
+
+    //The config's discounts contract is uncapped
+    if let Some(contract) = config.clone().discounts_contract {
+        let discount = query_user_discount(querier, contract.to_string(), user.clone()).unwrap_or_else(|_| Decimal::zero());
+        discounts.push(AppliedDiscount {
+            source: String::from("system_discounts"),
+            contract: contract.to_string(),
+            discount,
+            applied: discount.min(Decimal::one()),
+        });
+    }
+
+    for source in DISCOUNT_SOURCES.may_load(storage)?.unwrap_or_default() {
+        let discount = get_source_discount(querier, env, &source, user.clone()).unwrap_or_else(|_| Decimal::zero());
+        discounts.push(AppliedDiscount {
+            source: source.source_type.to_string(),
+            contract: source.contract,
+            discount,
+            applied: discount.min(source.cap),
+        });
+    }
+
+    let total_discount: Decimal = discounts.iter().map(|discount| discount.applied).sum();
+
+    Ok((discounts, total_discount.min(Decimal::one())))
+}
+
+/// Get the user's uncapped discount from a source
+fn get_source_discount(
+    querier: QuerierWrapper,
+    env: &Env,
+    source: &DiscountSource,
+    user: String,
+) -> StdResult<Decimal> {
+    match source.source_type.clone() {
+        DiscountSourceType::Staking { tiers } => {
+            let staked = querier.query_wasm_smart::<StakerResponse>(
+                source.contract.clone(),
+                &Staking_QueryMsg::UserStake { staker: user },
+            )?.total_staked;
+
+            Ok(get_tier_discount(&tiers, staked))
+        },
+        DiscountSourceType::Points { tiers } => {
+            let points = querier.query_wasm_smart::<Vec<UserStatsResponse>>(
+                source.contract.clone(),
+                &Points_QueryMsg::UserStats { user: Some(user), limit: None, start_after: None },
+            )?
+            .first()
+            .map(|user_stats| user_stats.stats.total_points * Uint128::one())
+            .unwrap_or_default();
+
+            Ok(get_tier_discount(&tiers, points))
+        },
+        DiscountSourceType::Partner {} => query_user_discount(querier, source.contract.clone(), user),
+        DiscountSourceType::VaultTenure { tiers } => {
+            let deposits = querier.query_wasm_smart::<DiscountVault_UserResponse>(
+                source.contract.clone(),
+                &DiscountVault_QueryMsg::User { user, minimum_deposit_time: None },
+            )?.deposits;
+            //Days since the oldest deposit
+            let tenure = deposits
+                .into_iter()
+                .filter(|deposit| !deposit.amount.is_zero())
+                .map(|deposit| env.block.time.seconds().saturating_sub(deposit.deposit_time) / SECONDS_PER_DAY)
+                .max()
+                .unwrap_or(0);
+
+            Ok(get_tier_discount(&tiers, Uint128::from(tenure)))
+        },
+    }
+}
+
+/// Returns the discount of the highest tier reached
+fn get_tier_discount(tiers: &[DiscountTier], amount: Uint128) -> Decimal {
+    tiers
+        .iter()
+        .filter(|tier| amount >= tier.threshold)
+        .map(|tier| tier.discount)
+        .max()
+        .unwrap_or_default()
+}
+
+/// Query a contract that returns a UserDiscountResponse
+fn query_user_discount(
     querier: QuerierWrapper,
-    discounts_contract: String,
+    contract: String,
     user: String,
+) -> StdResult<Decimal> {
+    let res: UserDiscountResponse = querier.query_wasm_smart(contract, &DiscountQueryMsg::UserDiscount { user })?;
+
+    Ok(res.discount)
+}
+
+/// Validate discount sources
+pub fn validate_discount_sources(
+    api: &dyn Api,
+    sources: &[DiscountSource],
+) -> Result<(), ContractError> {
+    for source in sources {
+        api.addr_validate(&source.contract)?;
+
+        if source.cap > Decimal::one() {
+            return Err(ContractError::CustomError { val: format!("The {} discount cap must be at most 100%", source.source_type) })
+        }
+
+        let tiers = match &source.source_type {
+            DiscountSourceType::Staking { tiers } | DiscountSourceType::Points { tiers } | DiscountSourceType::VaultTenure { tiers } => tiers.clone(),
+            DiscountSourceType::Partner {} => vec![],
+        };
+        //A 0 threshold would discount every user
+        if tiers.iter().any(|tier| tier.threshold.is_zero() || tier.discount > Decimal::one()) {
+            return Err(ContractError::CustomError { val: format!("Invalid {} discount tiers", source.source_type) })
+        }
+    }
+
+    Ok(())
+}
+
+/// Calculate the discounted interest
+fn get_discounted_interest(
     undiscounted_interest: Uint128,
+    discount: Decimal,
 ) -> StdResult<Uint128>{
-    //Get discount
-    let discount: Decimal = querier.query_wasm_smart(discounts_contract, &DiscountQueryMsg::UserDiscount { user })?;
-    
     let discounted_interest = {
         let percent_of_interest = decimal_subtraction(Decimal::one(), discount)?;
         decimal_multiplication(Decimal::from_ratio(undiscounted_interest, Uint128::one()), percent_of_interest)?
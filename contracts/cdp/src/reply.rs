@@ -1,12 +1,16 @@
 use std::str::FromStr;
 
-use cosmwasm_std::{DepsMut, Env, Reply, StdResult, Response,  Decimal, Uint128, StdError, attr};
+use cosmwasm_std::{DepsMut, Env, Event, Reply, StdResult, Response,  Decimal, Uint128, StdError, attr, MessageInfo, Coin, CosmosMsg};
 
-use membrane::types::{AssetInfo, Asset, cAsset};
-use membrane::helpers::{withdrawal_msg, get_contract_balances};
+use membrane::cdp::Config;
+use membrane::types::{AssetInfo, Asset, cAsset, Basket, Position};
+use membrane::helpers::{withdrawal_msg, get_contract_balances, multi_native_withdrawal_msg};
 
-use crate::risk_engine::update_basket_tally;
-use crate::state::{LiquidationPropagation, LIQUIDATION, WITHDRAW, BASKET, get_target_position, update_position};
+use crate::ContractError;
+use crate::orders::clear_position_orders;
+use crate::positions::{deposit, loop_position_step, record_position_health, repay};
+use crate::risk_engine::{assert_basket_assets, update_basket_tally, update_isolated_debt};
+use crate::state::{ClosePositionPropagation, LiquidationPropagation, LoopPositionPropagation, CLOSE_POSITION, LOOP_POSITION, CONFIG, LIQUIDATION, POSITIONS, WITHDRAW, BASKETS, get_target_position, update_position};
 
 /// On error of a user's Stability Pool repayment, leave leftover to the SP within the LQ reply.
 // #[allow(unused_variables)]
@@ -76,6 +80,7 @@ pub fn handle_withdraw_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<R
 
                 match get_target_position(
                     deps.storage,
+                    withdraw_prop.basket_id,
                     deps.api.addr_validate(&withdraw_prop.position_info.position_owner)?,
                     withdraw_prop.position_info.position_id,
                 ){
@@ -125,6 +130,260 @@ pub fn handle_withdraw_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<R
     Ok(Response::new().add_attributes(attrs))
 }
 
+/// Repay the Position's debt with the proceeds of the ClosePosition collateral sale.
+/// Reconcile supply caps & send the leftover collateral to the Position owner or send_to.
+pub fn handle_close_position_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.result.into_result() {
+        Ok(_result) => {
+            let config: Config = CONFIG.load(deps.storage)?;
+            let prop: ClosePositionPropagation = CLOSE_POSITION.load(deps.storage)?;
+            let mut basket: Basket = BASKETS.load(deps.storage, prop.basket_id.u128())?;
+
+            let position_id = prop.position_info.position_id;
+            let position_owner = deps.api.addr_validate(&prop.position_info.position_owner)?;
+            let send_to = match prop.clone().send_to {
+                Some(addr) => deps.api.addr_validate(&addr)?,
+                None => position_owner.clone(),
+            };
+
+            //Calc credit received from the sale
+            let current_credit_balance = get_contract_balances(
+                deps.querier,
+                env.clone(),
+                vec![basket.clone().credit_asset.info],
+            )?[0];
+            let credit_received = match current_credit_balance.checked_sub(prop.contracts_prev_credit_amount){
+                Ok(difference) => difference,
+                Err(_) => Uint128::zero(),
+            };
+
+            let (_i, mut target_position) = match get_target_position(deps.storage, prop.basket_id, position_owner.clone(), position_id){
+                Ok(position) => position,
+                Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+            };
+
+            //The sale has to cover the full debt, or the repay_ratio of it for deleverage orders
+            let repay_amount = match prop.repay_ratio {
+                Some(ratio) => target_position.credit_amount * ratio,
+                None => target_position.credit_amount,
+            };
+            if credit_received < repay_amount {
+                return Err(StdError::GenericErr {
+                    msg: format!(
+                        "Collateral sale returned {} of the {} debt, increase the max_spread",
+                        credit_received,
+                        repay_amount
+                    ),
+                });
+            }
+
+            //Remove sold collateral from the Position
+            let mut sold_cAssets: Vec<cAsset> = vec![];
+            for sold_asset in prop.clone().withdrawn_assets {
+                if let Some((collateral_index, position_collateral)) = target_position.clone().collateral_assets
+                    .into_iter()
+                    .enumerate()
+                    .find(|(_i, cAsset)| cAsset.asset.info.equal(&sold_asset.info)){
+
+                    sold_cAssets.push(cAsset {
+                        asset: sold_asset.clone(),
+                        ..position_collateral.clone()
+                    });
+
+                    let leftover_amount = position_collateral.asset.amount - sold_asset.amount;
+                    if leftover_amount.is_zero() {
+                        target_position.collateral_assets.remove(collateral_index);
+                    } else {
+                        target_position.collateral_assets[collateral_index].asset.amount = leftover_amount;
+                    }
+                }
+            }
+
+            //Remove sold collateral from Supply caps
+            match update_basket_tally(
+                deps.storage,
+                deps.querier,
+                env.clone(),
+                &mut basket,
+                sold_cAssets,
+                target_position.clone().collateral_assets,
+                false,
+                config.clone(),
+                true,
+            ){
+                Ok(_) => {},
+                Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+            };
+            BASKETS.save(deps.storage, prop.basket_id.u128(), &basket)?;
+            update_position(deps.storage, prop.basket_id, position_owner.clone(), target_position.clone())?;
+
+            //Repay the debt as the contract, excess goes to send_to.
+            //Supply caps for the remaining collateral are updated in repay() once the debt is cleared.
+            let repay_response = match repay(
+                deps.storage,
+                deps.querier,
+                deps.api,
+                env.clone(),
+                MessageInfo {
+                    sender: env.contract.address.clone(),
+                    funds: vec![Coin {
+                        denom: basket.clone().credit_asset.info.to_string(),
+                        amount: credit_received,
+                    }],
+                },
+                prop.basket_id,
+                position_id,
+                Some(position_owner.to_string()),
+                Asset {
+                    info: basket.clone().credit_asset.info,
+                    amount: credit_received,
+                },
+                Some(send_to.to_string()),
+            ){
+                Ok(res) => res,
+                Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+            };
+
+            //Withdraw leftover collateral & remove the Position.
+            //If all collateral was sold, the Position was removed in repay().
+            //Deleveraged Positions stay open.
+            let mut msgs: Vec<CosmosMsg> = vec![];
+            let mut leftover_assets: Vec<Asset> = vec![];
+            if let (None, Ok((_i, closed_position))) = (prop.repay_ratio, get_target_position(deps.storage, prop.basket_id, position_owner.clone(), position_id)) {
+                leftover_assets = closed_position.collateral_assets
+                    .into_iter()
+                    .map(|cAsset| cAsset.asset)
+                    .filter(|asset| !asset.amount.is_zero())
+                    .collect::<Vec<Asset>>();
+
+                if !leftover_assets.is_empty() {
+                    msgs.push(multi_native_withdrawal_msg(leftover_assets.clone(), send_to.clone())?);
+                }
+
+                POSITIONS.update(deps.storage, (prop.basket_id.u128(), position_owner.clone()), |positions: Option<Vec<Position>>| -> StdResult<Vec<Position>> {
+                    Ok(positions
+                        .unwrap_or_else(|| vec![])
+                        .into_iter()
+                        .filter(|position| position.position_id != position_id)
+                        .collect::<Vec<Position>>())
+                })?;
+
+                //Refund the closed Position's order tips
+                match clear_position_orders(deps.storage, prop.basket_id, position_id) {
+                    Ok(refund_msgs) => msgs.extend(refund_msgs),
+                    Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+                };
+            }
+            //Delete the owner's list if empty
+            if let Ok(positions) = POSITIONS.load(deps.storage, (prop.basket_id.u128(), position_owner.clone())) {
+                if positions.is_empty() {
+                    POSITIONS.remove(deps.storage, (prop.basket_id.u128(), position_owner.clone()));
+                }
+            }
+
+            Ok(repay_response
+                .add_messages(msgs)
+                .add_attributes(vec![
+                    attr("method", "close_position_reply"),
+                    attr("position_id", position_id),
+                    attr("credit_received", credit_received),
+                    attr("sold_assets", format!("{:?}", prop.withdrawn_assets)),
+                    attr("withdrawn_assets", format!("{:?}", leftover_assets)),
+                ]))
+        } //We only reply on success
+        Err(err) => Err(StdError::GenericErr { msg: err }),
+    }
+}
+
+/// Deposit the collateral swapped from a loop's minted credit.
+/// Start the next loop if there are loops left & the Position is under its target LTV.
+pub fn handle_loop_position_reply(mut deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.result.into_result() {
+        Ok(_result) => {
+            let prop: LoopPositionPropagation = LOOP_POSITION.load(deps.storage)?;
+            let position_id = prop.position_info.position_id;
+            let position_owner = deps.api.addr_validate(&prop.position_info.position_owner)?;
+
+            //Calc collateral received from the swaps
+            let current_balances = get_contract_balances(
+                deps.querier,
+                env.clone(),
+                prop.contracts_prev_collateral.iter().map(|asset| asset.info.clone()).collect::<Vec<AssetInfo>>(),
+            )?;
+            let swapped_assets: Vec<Asset> = prop.clone().contracts_prev_collateral
+                .into_iter()
+                .zip(current_balances.into_iter())
+                .map(|(prev_asset, balance)| Asset {
+                    amount: balance.checked_sub(prev_asset.amount).unwrap_or_else(|_| Uint128::zero()),
+                    info: prev_asset.info,
+                })
+                .filter(|asset| !asset.amount.is_zero())
+                .collect();
+
+            let cAssets: Vec<cAsset> = match assert_basket_assets(deps.storage, deps.querier, env.clone(), prop.basket_id, swapped_assets.clone()){
+                Ok(cAssets) => cAssets,
+                Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+            };
+            if cAssets.is_empty() {
+                return Err(StdError::GenericErr { msg: String::from("No collateral received from the loop's swaps") });
+            }
+
+            //Deposit the swapped collateral
+            let deposit_response = match deposit(
+                deps.branch(),
+                env.clone(),
+                MessageInfo {
+                    sender: position_owner.clone(),
+                    funds: vec![],
+                },
+                prop.basket_id,
+                Some(position_owner.to_string()),
+                Some(position_id),
+                cAssets,
+            ){
+                Ok(res) => res,
+                Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+            };
+
+            //Start the next loop
+            let mut response = deposit_response.add_attributes(vec![
+                attr("method", "loop_position_reply"),
+                attr("position_id", position_id),
+                attr("deposited_assets", format!("{:?}", swapped_assets)),
+            ]);
+            let mut looping = false;
+            if prop.loops_left > 0 {
+                match loop_position_step(
+                    deps.branch(),
+                    env,
+                    prop.basket_id,
+                    position_owner,
+                    position_id,
+                    prop.target_LTV,
+                    prop.max_slippage,
+                    prop.loops_left,
+                ){
+                    Ok(res) => {
+                        looping = true;
+                        response = response
+                            .add_submessages(res.messages)
+                            .add_attributes(res.attributes);
+                    },
+                    //Stop looping once the target LTV is reached
+                    Err(ContractError::InvalidLTV { .. }) => {},
+                    Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+                };
+            }
+            if !looping {
+                LOOP_POSITION.remove(deps.storage);
+            }
+
+            Ok(response)
+        } //We only reply on success
+        Err(err) => Err(StdError::GenericErr { msg: err }),
+    }
+}
+
 /// Send the liquidation queue its collateral reward.
 /// Send leftovers to the SP.
 /// Note: We send collateral here bc the LQ queries have returned less debt than the executed msg before so we want to give the LQ exactly what its expecting.
@@ -202,6 +461,12 @@ pub fn handle_liq_queue_reply(deps: DepsMut, msg: Reply, env: Env) -> StdResult<
 
                 //Update credit amount based on liquidation's total repaid amount
                 prop.target_position.credit_amount -= repay_amount;
+
+                //Update isolated debt total
+                match update_isolated_debt(deps.storage, &basket, &prop.target_position.collateral_assets, repay_amount, false){
+                    Ok(_) => {},
+                    Err(err) => return Err(StdError::GenericErr { msg: err.to_string() }),
+                };
                 
                 //Update position claims in prop.target_position
                 prop.target_position.collateral_assets
@@ -223,6 +488,8 @@ pub fn handle_liq_queue_reply(deps: DepsMut, msg: Reply, env: Env) -> StdResult<
                         pool_info: None,
                         rate_index: Decimal::one(),
                         hike_rates: Some(false),
+                        isolated_debt_ceiling: None,
+                        rate_model: None,
                     }
                 );
 
@@ -232,6 +499,7 @@ pub fn handle_liq_queue_reply(deps: DepsMut, msg: Reply, env: Env) -> StdResult<
         
             //If this is the last asset left to send and nothing was sent to the SP, update the position here instead of in liq_repay
             //We use 1 as our 0 to account for LQ rounding errors
+            let mut health_event: Option<Event> = None;
             if prop.per_asset_repayment.len() == 1 && prop.stability_pool <= Decimal::one() {
 
                 //Update supply caps
@@ -269,10 +537,21 @@ pub fn handle_liq_queue_reply(deps: DepsMut, msg: Reply, env: Env) -> StdResult<
                     };
                 }            
                 //Update Basket
-                BASKET.save(deps.storage, &basket)?;
+                BASKETS.save(deps.storage, basket.basket_id.u128(), &basket)?;
 
                 //Update position w/ new credit amount
-                update_position(deps.storage, prop.clone().position_owner, prop.clone().target_position)?;                
+                update_position(deps.storage, basket.basket_id, prop.clone().position_owner, prop.clone().target_position)?;                
+
+                //Record post-liquidation health
+                health_event = Some(record_position_health(
+                    deps.storage,
+                    deps.querier,
+                    env.clone(),
+                    basket.basket_id,
+                    prop.clone().position_owner,
+                    prop.target_position.position_id,
+                    "liquidation",
+                ));
             }
 
             //Remove Asset
@@ -285,10 +564,14 @@ pub fn handle_liq_queue_reply(deps: DepsMut, msg: Reply, env: Env) -> StdResult<
                 attr("reward_info", token_info.to_string()),
             ]);
 
-            Ok(Response::new()
+            let mut res = Response::new()
                 .add_message(msg)
-                .add_attributes(attrs)
-            )
+                .add_attributes(attrs);
+            if let Some(health_event) = health_event {
+                res = res.add_event(health_event);
+            }
+
+            Ok(res)
         }
         Err(string) => {
             //Only reply on success
@@ -2,12 +2,12 @@ use cosmwasm_std::{Decimal, Uint128, Env, QuerierWrapper, Storage, StdResult, St
 
 use membrane::cdp::Config;
 use membrane::stability_pool::QueryMsg as SP_QueryMsg;
-use membrane::types::{Basket, Asset, cAsset, SupplyCap, AssetPool};
+use membrane::types::{Basket, Asset, cAsset, CollateralDeprecation, ParameterRamp, SupplyCap, AssetPool};
 use membrane::helpers::{get_asset_liquidity, get_owner_liquidity_multiplier, get_stability_pool_liquidity};
 use membrane::math::decimal_multiplication; 
 
 use crate::rates::transform_caps_based_on_volatility;
-use crate::state::{CONFIG, BASKET};
+use crate::state::{CONFIG, BASKETS, DEPRECATED_COLLATERAL, ISOLATED_DEBT, LTV_RAMPS};
 use crate::query::{get_cAsset_ratios, get_cAsset_ratios_imut};
 use crate::error::ContractError;
 
@@ -16,27 +16,193 @@ pub fn assert_basket_assets(
     storage: &mut dyn Storage,
     _querier: QuerierWrapper,
     _env: Env,
+    basket_id: Uint128,
     assets: Vec<Asset>,
 ) -> Result<Vec<cAsset>, ContractError> {
-    let basket: Basket = BASKET.load(storage)?;
+    let basket: Basket = BASKETS.load(storage, basket_id.u128())?;
 
     //Checking if Assets for the position are available collateral assets in the basket
-    let collateral_assets = assets
-        .into_iter()
-        .map(|asset| {
-            let cAsset = basket
+    let mut collateral_assets: Vec<cAsset> = vec![];
+    for asset in assets {
+        let cAsset = match basket
+            .collateral_types
+            .iter()
+            .find(|cAsset| cAsset.asset.info.equal(&asset.info))
+        {
+            Some(cAsset) => cAsset.clone(),
+            //Removed collateral is still accepted so Positions can withdraw it
+            None => match DEPRECATED_COLLATERAL.may_load(storage, (basket_id.u128(), asset.info.to_string()))? {
+                Some(deprecation) if deprecation.removed => cAsset {
+                    asset: asset.clone(),
+                    max_borrow_LTV: Decimal::zero(),
+                    max_LTV: deprecation.final_max_LTV,
+                    rate_index: Decimal::one(),
+                    pool_info: None,
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                },
+                _ => return Err(ContractError::InvalidCollateral {}),
+            },
+        };
+        collateral_assets.push(cAsset {
+            asset: asset.clone(),
+            ..cAsset
+        });
+    }
+
+    Ok(collateral_assets)
+}
+
+/// Returns a deprecated cAsset's max_LTV at the given time.
+/// Ramps linearly from the initial to the final max_LTV.
+pub fn get_deprecated_max_LTV(
+    deprecation: &CollateralDeprecation,
+    time: u64,
+) -> Decimal {
+    ParameterRamp {
+        start_value: deprecation.initial_max_LTV,
+        end_value: deprecation.final_max_LTV,
+        start_time: deprecation.start_time,
+        end_time: deprecation.end_time,
+    }.get_value(time)
+}
+
+/// Applies deprecation schedules to a list of cAssets.
+/// Deprecated assets can't be borrowed against & their max_LTV is ramped down.
+pub fn apply_collateral_deprecations(
+    storage: &dyn Storage,
+    env: &Env,
+    basket_id: Uint128,
+    collateral_assets: &mut Vec<cAsset>,
+) -> StdResult<()> {
+    for cAsset in collateral_assets.iter_mut() {
+        if let Some(deprecation) = DEPRECATED_COLLATERAL.may_load(storage, (basket_id.u128(), cAsset.asset.info.to_string()))? {
+            cAsset.max_borrow_LTV = Decimal::zero();
+            cAsset.max_LTV = get_deprecated_max_LTV(&deprecation, env.block.time.seconds());
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies pending LTV ramps & deprecation schedules to a list of cAssets.
+/// Position cAssets are stale copies so this is used anywhere their LTVs are read.
+pub fn apply_risk_parameters(
+    storage: &dyn Storage,
+    env: &Env,
+    basket_id: Uint128,
+    collateral_assets: &mut Vec<cAsset>,
+) -> StdResult<()> {
+    for cAsset in collateral_assets.iter_mut() {
+        if let Some(ramp) = LTV_RAMPS.may_load(storage, (basket_id.u128(), cAsset.asset.info.to_string()))? {
+            cAsset.max_borrow_LTV = ramp.max_borrow_LTV.get_value(env.block.time.seconds());
+            cAsset.max_LTV = ramp.max_LTV.get_value(env.block.time.seconds());
+        }
+    }
+    //Deprecations take precedence over LTV ramps
+    apply_collateral_deprecations(storage, env, basket_id, collateral_assets)
+}
+
+/// Returns the extra caller fee for liquidating a Position holding deprecated collateral.
+/// Each asset's incentive is weighted by its ratio in the Position.
+pub fn get_deprecation_incentive(
+    storage: &dyn Storage,
+    basket_id: Uint128,
+    collateral_assets: &[cAsset],
+    cAsset_ratios: &[Decimal],
+) -> StdResult<Decimal> {
+    let mut incentive = Decimal::zero();
+
+    for (i, cAsset) in collateral_assets.iter().enumerate() {
+        if let Some(deprecation) = DEPRECATED_COLLATERAL.may_load(storage, (basket_id.u128(), cAsset.asset.info.to_string()))? {
+            let ratio = cAsset_ratios.get(i).cloned().unwrap_or_default();
+            incentive += decimal_multiplication(ratio, deprecation.liquidation_incentive)?;
+        }
+    }
+
+    Ok(incentive)
+}
+
+/// Returns the isolated cAsset in a position's collateral, if any.
+/// Isolation parameters are read from the Basket so stale position copies aren't used.
+pub fn get_isolated_asset(
+    basket: &Basket,
+    collateral_assets: &[cAsset],
+) -> Option<cAsset> {
+    collateral_assets
+        .iter()
+        .find_map(|position_asset| {
+            basket
                 .collateral_types
                 .iter()
-                .find(|cAsset| cAsset.asset.info.equal(&asset.info))
-                .ok_or(ContractError::InvalidCollateral {})?;
-            Ok(cAsset {
-                asset: asset.clone(),
-                ..cAsset.clone()
-            })
+                .find(|cAsset| cAsset.asset.info.equal(&position_asset.asset.info) && cAsset.isolated_debt_ceiling.is_some())
+                .cloned()
         })
-        .collect::<Result<Vec<cAsset>, ContractError>>()?;   
+}
 
-    Ok(collateral_assets)
+/// Asserts that isolated collateral isn't mixed with other assets in a position
+pub fn assert_isolation(
+    basket: &Basket,
+    collateral_assets: &[cAsset],
+) -> Result<(), ContractError> {
+    if collateral_assets.len() > 1 {
+        if let Some(isolated_asset) = get_isolated_asset(basket, collateral_assets) {
+            return Err(ContractError::CustomError {
+                val: format!(
+                    "Isolated collateral ({}) can't be mixed with other assets",
+                    isolated_asset.asset.info
+                ),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Update the debt total of a position's isolated asset.
+/// Errors if an increase pushes the total past the asset's debt ceiling.
+pub fn update_isolated_debt(
+    storage: &mut dyn Storage,
+    basket: &Basket,
+    collateral_assets: &[cAsset],
+    amount: Uint128,
+    add_to_debt: bool,
+) -> Result<(), ContractError> {
+    //Only positions holding an isolated asset are tracked
+    let isolated_asset = match get_isolated_asset(basket, collateral_assets) {
+        Some(cAsset) => cAsset,
+        None => return Ok(()),
+    };
+    let debt_ceiling = isolated_asset.isolated_debt_ceiling.unwrap_or_default();
+    let key = (basket.basket_id.u128(), isolated_asset.asset.info.to_string());
+
+    let debt_total = ISOLATED_DEBT.may_load(storage, key.clone())?.unwrap_or_default();
+    let new_debt_total = if add_to_debt {
+        let new_debt_total = debt_total + amount;
+
+        if new_debt_total > debt_ceiling {
+            return Err(ContractError::CustomError {
+                val: format!(
+                    "Isolated debt ceiling for {} is over the limit ({} > {})",
+                    isolated_asset.asset.info,
+                    new_debt_total,
+                    debt_ceiling
+                ),
+            });
+        }
+        new_debt_total
+    } else {
+        //Interest isn't tallied so repayments can exceed the total
+        match debt_total.checked_sub(amount){
+            Ok(diff) => diff,
+            Err(_) => Uint128::zero(),
+        }
+    };
+
+    ISOLATED_DEBT.save(storage, key, &new_debt_total)?;
+
+    Ok(())
 }
 
 /// Update SupplyCap objects in Basket 
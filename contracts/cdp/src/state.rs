@@ -1,11 +1,11 @@
-use membrane::oracle::PriceResponse;
+use membrane::oracle::{PriceRecord, PriceResponse};
 
-use cosmwasm_std::{Addr, Decimal, Uint128, Storage, QuerierWrapper, Env, StdResult, StdError};
+use cosmwasm_std::{Addr, Decimal, Uint128, Storage, QuerierWrapper, Env, Order, StdResult, StdError};
 use cosmwasm_schema::cw_serde;
 use cw_storage_plus::{Item, Map};
 
-use membrane::types::{cAsset, Asset, AssetInfo, Basket, Position, RedemptionInfo, StoredPrice, UserInfo};
-use membrane::cdp::Config;
+use membrane::types::{cAsset, Asset, AssetInfo, Basket, CollateralDeprecation, CollateralVolatility, DiscountSource, LTVRamp, OperatorAllowance, ParameterRamp, Position, PositionOrder, RedemptionInfo, StoredPrice, UserInfo};
+use membrane::cdp::{Config, PositionSnapshot};
 
 use crate::ContractError;
 use crate::risk_engine::update_basket_tally;
@@ -41,6 +41,7 @@ pub struct LiquidationPropagation {
     pub position_owner: Addr,
     pub basket: Basket,
     pub config: Config,
+    pub unwrap_lp: bool, //Exit LP collateral into its underlying assets for the SP distribution
 }
 
 #[cw_serde]
@@ -49,36 +50,102 @@ pub struct WithdrawPropagation {
     pub withdraw_amounts: Vec<Uint128>,
     pub contracts_prev_collateral_amount: Vec<Uint128>,
     pub position_info: UserInfo,
+    pub basket_id: Uint128,
 }
 #[cw_serde]
 pub struct ClosePositionPropagation {
-    pub withdrawn_assets: Vec<Asset>,
+    pub withdrawn_assets: Vec<Asset>, //Collateral sold to repay the Position's debt
+    pub contracts_prev_credit_amount: Uint128, //Contract's credit balance before the sale
     pub position_info: UserInfo,
     pub send_to: Option<String>,
+    pub basket_id: Uint128,
+    pub repay_ratio: Option<Decimal>, //Partial repayment for deleverage orders, full close if None
+}
+#[cw_serde]
+pub struct LoopPositionPropagation {
+    pub position_info: UserInfo,
+    pub target_LTV: Decimal,
+    pub max_slippage: Decimal,
+    pub loops_left: u64,
+    pub contracts_prev_collateral: Vec<Asset>, //Contract's collateral balances before the swaps
+    pub basket_id: Uint128,
+}
+#[cw_serde]
+pub struct FlashMintPropagation {
+    pub amount: Uint128,
+    pub fee: Uint128,
+    pub receiver: Addr,
+    pub contracts_prev_credit_amount: Uint128, //Contract's credit balance before the mint
+    pub basket_id: Uint128,
+}
+#[cw_serde]
+pub struct PendingTransfer {
+    pub position_owner: Addr,
+    pub new_owner: Addr, //Only the new owner can accept the transfer
 }
 #[cw_serde]
 pub struct Timer {
     pub start_time: u64,
     pub end_time: u64,
 }
+//PID rate model state
 #[cw_serde]
-pub struct CollateralVolatility {
-    pub index: Decimal,
-    pub volatility_list: Vec<Decimal>,
+pub struct RateController {
+    pub rate: Decimal,
+    pub integral: Decimal,
+    pub integral_negative: bool,
+    pub last_error: Decimal,
+    pub last_error_negative: bool,
+    pub last_updated: u64,
 }
 
 pub const CONTRACT: Item<ContractVersion> = Item::new("contract_info");
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const BASKET: Item<Basket> = Item::new("basket"); 
-pub const POSITIONS: Map<Addr, Vec<Position>> = Map::new("positions"); //owner, list of positions
-//Volatility Tracker
-pub const VOLATILITY: Map<String, CollateralVolatility> = Map::new("volatility");
-pub const STORED_PRICES: Map<String, StoredPrice> = Map::new("stored_prices");
+//Baskets, (basket_id, basket)
+pub const BASKETS: Map<u128, Basket> = Map::new("baskets");
+//Basket used by msgs that aren't routed through WithBasket
+pub const DEFAULT_BASKET_ID: Item<Uint128> = Item::new("default_basket_id");
+pub const POSITIONS: Map<(u128, Addr), Vec<Position>> = Map::new("basket_positions"); //(basket_id, owner), list of positions
+//Interest discount sources, stacked in accrue()
+pub const DISCOUNT_SOURCES: Item<Vec<DiscountSource>> = Item::new("discount_sources");
+//Volatility Tracker, ((basket_id, asset), volatility)
+pub const VOLATILITY: Map<(u128, String), CollateralVolatility> = Map::new("basket_volatility");
+//Oracle prices are per Basket, ((basket_id, asset), price)
+pub const STORED_PRICES: Map<(u128, String), StoredPrice> = Map::new("basket_stored_prices");
+//Newly queried prices, sent to the oracle's price history at the end of the msg
+pub const PRICE_RECORDS: Item<Vec<PriceRecord>> = Item::new("price_records");
+//Isolated collateral debt totals, ((basket_id, asset), debt)
+pub const ISOLATED_DEBT: Map<(u128, String), Uint128> = Map::new("isolated_debt");
+//Deprecated collateral wind-down schedules, ((basket_id, asset), deprecation)
+pub const DEPRECATED_COLLATERAL: Map<(u128, String), CollateralDeprecation> = Map::new("deprecated_collateral");
+//Pending cAsset LTV changes, ((basket_id, asset), ramp)
+pub const LTV_RAMPS: Map<(u128, String), LTVRamp> = Map::new("ltv_ramps");
+//Pending base interest rate changes, (basket_id, ramp)
+pub const BASE_RATE_RAMP: Map<u128, ParameterRamp> = Map::new("base_rate_ramp");
+//PID rate model controllers, ((basket_id, asset), controller)
+pub const RATE_CONTROLLERS: Map<(u128, String), RateController> = Map::new("rate_controllers");
 
 /// CDT redemption premium, opt-in mechanism.
 /// This is the premium that the user will pay to redeem their debt token.
-pub const REDEMPTION_OPT_IN: Map<u128, Vec<RedemptionInfo>> = Map::new("redemption_opt_in"); 
+/// Keyed by (basket_id, premium).
+pub const REDEMPTION_OPT_IN: Map<(u128, u128), Vec<RedemptionInfo>> = Map::new("basket_redemption_opt_in"); 
+
+/// Position operators, (basket_id, owner, operator)
+pub const OPERATORS: Map<(u128, Addr, Addr), OperatorAllowance> = Map::new("basket_operators");
+
+/// Conditional Position orders, ((basket_id, order_id), order)
+pub const POSITION_ORDERS: Map<(u128, u64), PositionOrder> = Map::new("position_orders");
+/// Last order ID used
+pub const ORDER_ID: Item<u64> = Item::new("order_id");
+/// Open order IDs per Position, ((basket_id, position_id), order_ids)
+pub const POSITION_ORDER_IDS: Map<(u128, u128), Vec<u64>> = Map::new("position_order_ids");
+
+/// Position transfers waiting on the new owner's acceptance, ((basket_id, position_id), transfer)
+pub const PENDING_TRANSFERS: Map<(u128, u128), PendingTransfer> = Map::new("pending_position_transfers");
+
+/// Position snapshots, (((basket_id, owner), position_id, block time), snapshot)
+pub const POSITION_SNAPSHOTS: Map<((u128, Addr), u128, u64), PositionSnapshot> = Map::new("basket_position_snapshots");
 
 /// Config ownership transfer
 pub const OWNERSHIP_TRANSFER: Item<Addr> = Item::new("ownership_transfer");
@@ -87,8 +154,19 @@ pub const OWNERSHIP_TRANSFER: Item<Addr> = Item::new("ownership_transfer");
 pub const WITHDRAW: Item<WithdrawPropagation> = Item::new("withdraw_propagation");
 pub const LIQUIDATION: Item<LiquidationPropagation> = Item::new("repay_propagation");
 pub const CLOSE_POSITION: Item<ClosePositionPropagation> = Item::new("close_position_propagation");
-//Freeze Timer
-pub const FREEZE_TIMER: Item<Timer> = Item::new("freeze_timer");
+pub const LOOP_POSITION: Item<LoopPositionPropagation> = Item::new("loop_position_propagation");
+pub const FLASH_MINT: Item<FlashMintPropagation> = Item::new("flash_mint_propagation");
+//Freeze Timers, (basket_id, timer)
+pub const FREEZE_TIMER: Map<u128, Timer> = Map::new("basket_freeze_timer");
+
+//Single basket storage, only read when migrating to the keyed storage above
+pub const LEGACY_BASKET: Item<Basket> = Item::new("basket");
+pub const LEGACY_POSITIONS: Map<Addr, Vec<Position>> = Map::new("positions");
+pub const LEGACY_REDEMPTION_OPT_IN: Map<u128, Vec<RedemptionInfo>> = Map::new("redemption_opt_in");
+pub const LEGACY_FREEZE_TIMER: Item<Timer> = Item::new("freeze_timer");
+pub const LEGACY_VOLATILITY: Map<String, CollateralVolatility> = Map::new("volatility");
+pub const LEGACY_STORED_PRICES: Map<String, StoredPrice> = Map::new("stored_prices");
+pub const LEGACY_OPERATORS: Map<(Addr, Addr), OperatorAllowance> = Map::new("operators");
 
 //Helper functions
 /// Update asset claims a Position has
@@ -97,6 +175,7 @@ pub fn update_position_claims(
     querier: QuerierWrapper,
     env: Env,
     config: Config,
+    basket_id: Uint128,
     position_id: Uint128,
     position_owner: Addr,
     liquidated_asset: AssetInfo,
@@ -108,7 +187,7 @@ pub fn update_position_claims(
 
     POSITIONS.update(
         storage,
-        position_owner,
+        (basket_id.u128(), position_owner),
         |old_positions| -> StdResult<Vec<Position>> {
             if let Some(old_positions) = old_positions {
                 let new_positions = old_positions
@@ -159,6 +238,8 @@ pub fn update_position_claims(
         pool_info: None,
         rate_index: Decimal::one(),
         hike_rates: Some(false),
+        isolated_debt_ceiling: None,
+        rate_model: None,
     }];
 
     //If there is no credit, basket tallies were updated in the repay function
@@ -166,10 +247,10 @@ pub fn update_position_claims(
         return Ok(());
     }
 
-    let mut basket = BASKET.load(storage)?;
+    let mut basket = BASKETS.load(storage, basket_id.u128())?;
     match update_basket_tally(storage, querier, env, &mut basket, collateral_assets, target_position.unwrap().collateral_assets, false, config, false) {
         Ok(_res) => {
-            BASKET.save(storage, &basket)?;
+            BASKETS.save(storage, basket_id.u128(), &basket)?;
         }
         Err(err) => {
             return Err(StdError::GenericErr {
@@ -181,14 +262,29 @@ pub fn update_position_claims(
     Ok(())
 }
 
+/// Returns the Basket whose credit asset is the given asset
+pub fn get_basket_by_credit_asset(
+    storage: &dyn Storage,
+    credit_asset: &AssetInfo,
+) -> StdResult<Basket> {
+    BASKETS
+        .range(storage, None, None, Order::Ascending)
+        .find_map(|item| match item {
+            Ok((_id, basket)) if basket.credit_asset.info.equal(credit_asset) => Some(basket),
+            _ => None,
+        })
+        .ok_or_else(|| StdError::GenericErr { msg: format!("No Basket uses {} as its credit asset", credit_asset) })
+}
+
 /// Returns Position & index of Position in User's list
 pub fn get_target_position(
     storage: &dyn Storage,
+    basket_id: Uint128,
     valid_position_owner: Addr,
     position_id: Uint128,
 ) -> Result<(usize, Position), ContractError> {
     let positions: Vec<Position> = match POSITIONS.load(
-        storage, valid_position_owner
+        storage, (basket_id.u128(), valid_position_owner)
     ){
         Err(_) => return Err(ContractError::NoUserPositions {}),
         Ok(positions) => positions,
@@ -204,13 +300,14 @@ pub fn get_target_position(
 /// Replace Position data in state
 pub fn update_position(
     storage: &mut dyn Storage,
+    basket_id: Uint128,
     valid_position_owner: Addr,
     new_position: Position,
 ) -> StdResult<()>{
 
     POSITIONS.update(
         storage,
-        valid_position_owner,
+        (basket_id.u128(), valid_position_owner),
         |old_positions| -> StdResult<Vec<Position>> {
             match old_positions {
                 Some(old_positions) => {
@@ -8,11 +8,12 @@ mod tests {
 
     use membrane::liq_queue::LiquidatibleResponse as LQ_LiquidatibleResponse;
     use membrane::math::Uint256;
-    use membrane::oracle::{AssetResponse, PriceResponse};
+    use membrane::oracle::{AssetResponse, PriceMetadata, PriceResponse};
     use membrane::osmosis_proxy::{GetDenomResponse, TokenInfoResponse, OwnerResponse};
     use membrane::cdp::{ExecuteMsg, InstantiateMsg, QueryMsg, EditBasket, UpdateConfig, CreateBasket};
     use membrane::stability_pool::LiquidatibleResponse as SP_LiquidatibleResponse;
     use membrane::staking::Config as Staking_Config;
+    use membrane::system_discounts::UserDiscountResponse;
     use membrane::types::{
         cAsset, Asset, AssetInfo, AssetOracleInfo, Deposit, LiquidityInfo, TWAPPoolInfo,
         UserInfo, MultiAssetSupplyCap, AssetPool, StakeDistribution, PoolType, DebtCap, Owner, PoolStateResponse
@@ -20,7 +21,7 @@ mod tests {
     use membrane::liquidity_check::LiquidityResponse;
 
     use cosmwasm_std::{
-        attr, coin, to_binary, Addr, Binary, Coin, Decimal, Empty, Response, StdError, StdResult,
+        attr, coin, to_binary, Addr, BankMsg, Binary, Coin, Decimal, Empty, Response, StdError, StdResult,
         Uint128,
     };
     use cw_multi_test::{App, AppBuilder, BankKeeper, Contract, ContractWrapper, Executor};
@@ -53,6 +54,7 @@ mod tests {
             bid_for: AssetInfo,
             max_premium: Uint128,
             bid_threshold: Uint256,
+            lp_pool_id: Option<u64>,
         },
         EditQueue {
             bid_for: AssetInfo,
@@ -63,6 +65,7 @@ mod tests {
             bid_for: AssetInfo,
             max_premium: Option<Uint128>,
             bid_threshold: Option<Uint256>,
+            lp_pool_id: Option<u64>,
         },
     }
 
@@ -154,6 +157,7 @@ mod tests {
                         bid_for: _,
                         max_premium: _,
                         bid_threshold: _,
+                        lp_pool_id: _,
                     } => Ok(Response::new()),
                     LQ_MockExecuteMsg::EditQueue {
                         bid_for,
@@ -164,6 +168,7 @@ mod tests {
                         bid_for,
                         max_premium,
                         bid_threshold,
+                        lp_pool_id: _,
                     } => {
                         let premium = max_premium.unwrap_or_default();
                         if premium != Uint128::new(10) && premium != Uint128::new(12) {
@@ -302,6 +307,7 @@ mod tests {
                         bid_for: _,
                         max_premium: _,
                         bid_threshold: _,
+                        lp_pool_id: _,
                     } => Ok(Response::new()),
                     LQ_MockExecuteMsg::EditQueue {
                         bid_for: _,
@@ -312,6 +318,7 @@ mod tests {
                         bid_for,
                         max_premium,
                         bid_threshold,
+                        lp_pool_id: _,
                     } => Ok(Response::new()),
                 }
             },
@@ -352,6 +359,7 @@ mod tests {
                         bid_for: _,
                         max_premium: _,
                         bid_threshold: _,
+                        lp_pool_id: _,
                     } => Ok(Response::new()),
                     LQ_MockExecuteMsg::EditQueue {
                         bid_for: _,
@@ -362,6 +370,7 @@ mod tests {
                         bid_for,
                         max_premium,
                         bid_threshold,
+                        lp_pool_id: _,
                     } => Ok(Response::new()),
                 }
             },
@@ -418,6 +427,7 @@ mod tests {
                         bid_for: _,
                         max_premium: _,
                         bid_threshold: _,
+                        lp_pool_id: _,
                     } => Ok(Response::new()),
                     LQ_MockExecuteMsg::EditQueue {
                         bid_for: _,
@@ -428,6 +438,7 @@ mod tests {
                         bid_for,
                         max_premium,
                         bid_threshold,
+                        lp_pool_id: _,
                     } => Ok(Response::new()),
                 }
             },
@@ -851,6 +862,10 @@ mod tests {
             max_supply: Option<Uint128>,
             liquidity_multiplier: Option<Decimal>,
         },
+        ExecuteSwaps {
+            token_out: String,
+            max_slippage: Decimal,
+        },
     }
 
     
@@ -875,7 +890,7 @@ mod tests {
 
     pub fn osmosis_proxy_contract() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
-            |_, _, _, msg: Osmo_MockExecuteMsg| -> StdResult<Response> {
+            |_, _, info, msg: Osmo_MockExecuteMsg| -> StdResult<Response> {
                 match msg {
                     Osmo_MockExecuteMsg::MintTokens {
                         denom,
@@ -887,6 +902,9 @@ mod tests {
                                 String::from("credit_fulldenom 1428 revenue_collector"),
                                 format!("{} {} {}", denom, amount.to_string(), mint_to_address)
                             );
+                        } else if amount == Uint128::new(3_024_000_000u128) {
+                            //Operator mints go to the Position owner
+                            assert_eq!(mint_to_address, String::from("user"));
                         }
 
                         Ok(Response::new())
@@ -918,6 +936,17 @@ mod tests {
                                 .to_string(),
                         ),
                     ])),
+                    //Swaps the sent tokens 1:1 for the token out
+                    Osmo_MockExecuteMsg::ExecuteSwaps {
+                        token_out,
+                        max_slippage: _,
+                    } => {
+                        let amount: Uint128 = info.funds.iter().map(|coin| coin.amount).sum();
+                        Ok(Response::new().add_message(BankMsg::Send {
+                            to_address: info.sender.to_string(),
+                            amount: vec![coin(amount.u128(), token_out)],
+                        }))
+                    },
                 }
             },
             |_, _, _, _: Osmo_MockInstantiateMsg| -> StdResult<Response> {
@@ -1007,6 +1036,10 @@ mod tests {
                                 .to_string(),
                         ),
                     ])),
+                    Osmo_MockExecuteMsg::ExecuteSwaps {
+                        token_out: _,
+                        max_slippage: _,
+                    } => Ok(Response::new()),
                 }
             },
             |_, _, _, _: Osmo_MockInstantiateMsg| -> StdResult<Response> {
@@ -1104,6 +1137,40 @@ mod tests {
         Box::new(contract)
     }
 
+    //Mock Flash Mint Receiver Contract
+    #[cw_serde]
+    pub enum FlashMint_MockExecuteMsg {
+        ReturnCredit { amount: Uint128 },
+    }
+
+    #[cw_serde]
+    pub struct FlashMint_MockInstantiateMsg {}
+
+    #[cw_serde]
+    pub enum FlashMint_MockQueryMsg {}
+
+    pub fn flash_mint_receiver_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            |_, _, info, msg: FlashMint_MockExecuteMsg| -> StdResult<Response> {
+                match msg {
+                    FlashMint_MockExecuteMsg::ReturnCredit { amount } => {
+                        Ok(Response::new().add_message(BankMsg::Send {
+                            to_address: info.sender.to_string(),
+                            amount: vec![coin(amount.u128(), "credit_fulldenom")],
+                        }))
+                    }
+                }
+            },
+            |_, _, _, _: FlashMint_MockInstantiateMsg| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_, _, _: FlashMint_MockQueryMsg| -> StdResult<Binary> {
+                Ok(to_binary(&MockResponse {})?)
+            },
+        );
+        Box::new(contract)
+    }
+
     //Mock Auction Contract
      #[cw_serde]    
     pub enum Auction_MockExecuteMsg {
@@ -1214,9 +1281,9 @@ mod tests {
                     Discounts_MockQueryMsg::UserDiscount { user } => {
 
                         if user == String::from("discounty"){
-                            Ok(to_binary(&Decimal::percent(90))?)
+                            Ok(to_binary(&UserDiscountResponse { user, discount: Decimal::percent(90) })?)
                         } else {
-                            Ok(to_binary(&Decimal::zero())?)
+                            Ok(to_binary(&UserDiscountResponse { user, discount: Decimal::zero() })?)
                         }
                         
                     }
@@ -1249,6 +1316,7 @@ mod tests {
             asset_infos: Vec<AssetInfo>,
             twap_timeframe: u64,
             oracle_time_limit: u64,
+            basket_id: Option<Uint128>,
         },
         Assets {
             asset_infos: Vec<AssetInfo>,
@@ -1273,12 +1341,13 @@ mod tests {
             |_, _, _, _: Oracle_MockInstantiateMsg| -> StdResult<Response> {
                 Ok(Response::default())
             },
-            |_, _, msg: Oracle_MockQueryMsg| -> StdResult<Binary> {
+            |_, env, msg: Oracle_MockQueryMsg| -> StdResult<Binary> {
                 match msg {
                     Oracle_MockQueryMsg::Prices {
                         asset_infos,
                         twap_timeframe,
                         oracle_time_limit,
+                        basket_id,
                     } => {
                         let mut prices = vec![];
                         for asset_info in asset_infos.iter() {
@@ -1287,18 +1356,38 @@ mod tests {
                                     prices: vec![],
                                     price: Decimal::percent(98),
                                     decimals: 6,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             } else if asset_info.to_string() == String::from("lp_denom") {
                                 prices.push(PriceResponse {
                                     prices: vec![],
                                     price: Decimal::from_ratio(2u128, 1u128),
                                     decimals: 18,
+                                    degraded: false,
+                                    metadata: None,
+                                });
+                            } else if asset_info.to_string() == String::from("debit") {
+                                //Only checked when a price policy is set
+                                prices.push(PriceResponse {
+                                    prices: vec![],
+                                    price: Decimal::one(),
+                                    decimals: 6,
+                                    degraded: false,
+                                    metadata: Some(PriceMetadata {
+                                        publish_time: env.block.time.seconds() - 120,
+                                        source: String::from("pyth"),
+                                        confidence: Some(Decimal::permille(5)),
+                                        twap_window: None,
+                                    }),
                                 });
                             } else {
                                 prices.push(PriceResponse {
                                     prices: vec![],
                                     price: Decimal::one(),
                                     decimals: 6,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             }
                         }
@@ -1322,6 +1411,8 @@ mod tests {
                                 decimals: 6,
                                 pyth_price_feed_id: None,
                                 vault_info: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         },
                         AssetResponse {
@@ -1340,6 +1431,8 @@ mod tests {
                                 decimals: 6,
                                 pyth_price_feed_id: None,
                                 vault_info: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         }
                     ])?),
@@ -1373,6 +1466,7 @@ mod tests {
                         asset_infos,
                         twap_timeframe,
                         oracle_time_limit,
+                        basket_id,
                     } => {
                         
                         let mut prices = vec![];
@@ -1382,18 +1476,24 @@ mod tests {
                                     prices: vec![],
                                     price: Decimal::percent(102),
                                     decimals: 6,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             } else if asset_info.to_string() == String::from("lp_denom") {
                                 prices.push(PriceResponse {
                                     prices: vec![],
                                     price: Decimal::from_ratio(2u128, 1u128),
                                     decimals: 18,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             } else {
                                 prices.push(PriceResponse {
                                     prices: vec![],
                                     price: Decimal::one(),
                                     decimals: 6,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             }
                         }
@@ -1417,6 +1517,8 @@ mod tests {
                                 decimals: 6,
                                 pyth_price_feed_id: None,
                                 vault_info: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         },
                         AssetResponse {
@@ -1435,6 +1537,8 @@ mod tests {
                                 decimals: 6,
                                 pyth_price_feed_id: None,
                                 vault_info: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         }
                     ])?),
@@ -1791,6 +1895,8 @@ mod tests {
                 pool_info: None,
                 rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
             }],
             credit_asset: Asset {
                 info: AssetInfo::NativeToken {
@@ -1846,6 +1952,9 @@ mod tests {
             multi_asset_supply_caps: None,
             credit_pool_infos: None,
             take_revenue: None,
+            close_factor: None,
+            soft_liquidation: None,
+            rate_ramp_duration: None,
         });
         let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
         app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -1859,9 +1968,11 @@ mod tests {
         use cosmwasm_std::{coins, BlockInfo};
         use membrane::cdp::{
             CollateralInterestResponse, Config, BasketPositionsResponse,
-            ExecuteMsg, InsolvencyResponse, PositionResponse, InterestResponse, RedeemabilityResponse
+            ExecuteMsg, CallbackMsg, InsolvencyResponse, PositionResponse, InterestResponse, RedeemabilityResponse, OperatorResponse, ParameterRampsResponse,
+            RedemptionSimulationResponse, PositionSnapshot, DebtCapDashboardResponse, CollateralVolatilityResponse, PositionRateResponse, AppliedDiscount,
         };
-        use membrane::types::{InsolventPosition, LPAssetInfo, PoolInfo, SupplyCap, UserInfo, Basket};
+        use membrane::oracle::PricePolicy;
+        use membrane::types::{InsolventPosition, LPAssetInfo, PoolInfo, SupplyCap, UserInfo, Basket, OperatorAllowance, SoftLiquidation, CollateralDeprecation, RateModel, RedemptionCollateralFilter, OrderAction, OrderTrigger, PositionOrder, VolatilityConfig, VolatilitySmoothing, DiscountSource, DiscountSourceType, DiscountTier};
 
         #[test]
         fn freeze(){
@@ -1894,6 +2005,9 @@ mod tests {
                 frozen: Some(true),
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -1930,6 +2044,9 @@ mod tests {
                 frozen: Some(false),
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -1967,6 +2084,9 @@ mod tests {
                 frozen: Some(true),
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -1983,6 +2103,7 @@ mod tests {
                     }
                 ],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -2001,6 +2122,9 @@ mod tests {
                 frozen: Some(false),
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -2017,6 +2141,7 @@ mod tests {
                     }
                 ],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -2035,6 +2160,9 @@ mod tests {
                 frozen: Some(true),
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -2045,6 +2173,7 @@ mod tests {
                 amount: Some(Uint128::from(10_000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -2063,6 +2192,9 @@ mod tests {
                 frozen: Some(false),
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -2073,6 +2205,7 @@ mod tests {
                 amount: Some(Uint128::from(10_000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -2099,6 +2232,9 @@ mod tests {
                 frozen: Some(true),
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -2128,6 +2264,9 @@ mod tests {
                 frozen: Some(false),
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -2157,6 +2296,9 @@ mod tests {
                 frozen: Some(true),
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -2165,6 +2307,7 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: USER.to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -2183,6 +2326,9 @@ mod tests {
                 frozen: Some(false),
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -2191,6 +2337,7 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: USER.to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -2207,6 +2354,7 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: USER.to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -2249,6 +2397,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -2320,6 +2471,8 @@ mod tests {
                         pool_info: None,  
                         rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                     }
                 ]
             );
@@ -2338,6 +2491,8 @@ mod tests {
                         pool_info: None,  
                         rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                     }
                 ]
             );
@@ -2359,6 +2514,7 @@ mod tests {
                     }
                 ],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -2375,6 +2531,7 @@ mod tests {
                     },
                 ],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -2417,6 +2574,7 @@ mod tests {
                 amount: Some(Uint128::from(10_000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -2435,6 +2593,7 @@ mod tests {
                 amount: Some(Uint128::from(20_000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -2484,6 +2643,7 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(2u128),
                 position_owner: USER.to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -2540,6 +2700,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -2573,6 +2735,9 @@ mod tests {
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
                 take_revenue: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -2605,6 +2770,7 @@ mod tests {
                 amount: Some(Uint128::from(2000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -2658,6 +2824,7 @@ mod tests {
                 amount: Some(Uint128::from(5_000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -2680,6 +2847,7 @@ mod tests {
                     },
                 ],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -2702,6 +2870,7 @@ mod tests {
                     },
                 ],
                 send_to: Some(String::from("very_trusted_contract")),
+                position_owner: None,
             };
 
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
@@ -2725,6 +2894,7 @@ mod tests {
                     },
                 ],
                 send_to: Some(String::from("very_trusted_contract")),
+                position_owner: None,
             };
 
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
@@ -2812,6 +2982,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -2838,6 +3011,7 @@ mod tests {
                 amount: Some(Uint128::from(50_001_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("test"), cosmos_msg)
@@ -2849,6 +3023,7 @@ mod tests {
                 amount: Some(Uint128::from(1_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("test"), cosmos_msg)
@@ -2862,6 +3037,7 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
@@ -2892,6 +3068,7 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
@@ -2945,6 +3122,23 @@ mod tests {
                 String::from("[DebtCap { collateral: NativeToken { denom: \"debit\" }, debt_total: Uint128(50000000000), cap: Uint128(249995050000) }]")
             );
 
+            //Query Debt Cap Dashboard
+            let res: DebtCapDashboardResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetDebtCapDashboard { })
+                .unwrap();
+            assert_eq!(res.collateral_caps.len(), 1);
+            assert_eq!(res.collateral_caps[0].collateral, AssetInfo::NativeToken { denom: "debit".to_string() });
+            assert_eq!(res.collateral_caps[0].debt_total, Uint128::new(50000000000));
+            assert_eq!(res.collateral_caps[0].debt_cap, Uint128::new(249995050000));
+            assert_eq!(res.collateral_caps[0].remaining_mintable, Uint128::new(199995050000));
+            assert_eq!(res.collateral_caps[0].supply_ratio, Decimal::one());
+            assert_eq!(res.collateral_caps[0].supply_cap_ratio, Decimal::one());
+            assert!(res.collateral_caps[0].volatility_adjusted_cap_ratio <= Decimal::one());
+            assert_eq!(res.collateral_caps[0].stability_pool_ratio, None);
+            assert_eq!(res.collateral_caps[0].stability_pool_cap, None);
+            assert_eq!(res.multi_asset_caps, vec![]);
+
             //Excess Repayment
             let msg = ExecuteMsg::Repay {
                 position_id: Uint128::from(1u128),
@@ -2996,6 +3190,7 @@ mod tests {
                     amount: Uint128::from(100_000_000_000u128),
                 }],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
             app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
@@ -3049,6 +3244,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -3060,6 +3257,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -3079,6 +3279,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -3090,6 +3292,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -3129,6 +3334,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -3181,6 +3388,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -3210,6 +3420,7 @@ mod tests {
                 amount: Some(Uint128::from(99_999_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -3233,6 +3444,7 @@ mod tests {
                 amount: Some(Uint128::from(1_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.set_block(BlockInfo {
@@ -3297,6 +3509,7 @@ mod tests {
                     amount: Uint128::from(95_239_000_000_000_000_000_000u128),
                 }],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.set_block(BlockInfo {
@@ -3489,6 +3702,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -3500,6 +3715,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -3519,6 +3737,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -3530,6 +3750,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -3569,6 +3792,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -3621,6 +3846,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -3668,6 +3896,7 @@ mod tests {
                 amount: Some(Uint128::from(40_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.set_block(BlockInfo {
@@ -3708,6 +3937,7 @@ mod tests {
                 amount: Some(Uint128::from(100_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();            
             app.set_block(BlockInfo {
@@ -3810,6 +4040,9 @@ mod tests {
                 cpc_multiplier: None,
                 rate_slope_multiplier: None,
                 rate_hike_rate: None,
+                flash_mint_cap: None,
+                flash_mint_fee: None,
+                price_policy: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -3837,6 +4070,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -3848,6 +4083,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -3867,6 +4105,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -3878,6 +4118,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -3917,6 +4160,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -3969,6 +4214,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -3998,6 +4246,7 @@ mod tests {
                 amount: Some(Uint128::from(99_999_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("discounty"), cosmos_msg)
@@ -4049,6 +4298,76 @@ mod tests {
                 .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
                 .unwrap();
             assert_eq!(res[0].positions[0].credit_amount, Uint128::new((100570_428457)) );
+
+            //Query the Position's rate breakdown
+            let query_msg = QueryMsg::GetPositionRate {
+                position_owner: "discounty".to_string(),
+                position_id: Uint128::new(1),
+            };
+            let res: PositionRateResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+                .unwrap();
+            assert_eq!(res.undiscounted_rate, Decimal::from_str("0.057143417153945362").unwrap());
+            assert_eq!(res.discounts, vec![AppliedDiscount {
+                source: String::from("system_discounts"),
+                contract: String::from("contract8"),
+                discount: Decimal::percent(90),
+                applied: Decimal::percent(90),
+            }]);
+            assert_eq!(res.total_discount, Decimal::percent(90));
+            assert_eq!(res.effective_rate, res.undiscounted_rate * Decimal::percent(10));
+
+            //Error: Unauthorized
+            let partner_source = DiscountSource {
+                source_type: DiscountSourceType::Partner {},
+                contract: String::from("contract8"),
+                cap: Decimal::percent(5),
+            };
+            let msg = ExecuteMsg::EditDiscountSources { sources: vec![partner_source.clone()] };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+
+            //Error: Cap over 100%
+            let msg = ExecuteMsg::EditDiscountSources { sources: vec![DiscountSource {
+                cap: Decimal::percent(101),
+                ..partner_source.clone()
+            }] };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Error: 0 threshold tier
+            let msg = ExecuteMsg::EditDiscountSources { sources: vec![DiscountSource {
+                source_type: DiscountSourceType::Staking { tiers: vec![DiscountTier { threshold: Uint128::zero(), discount: Decimal::percent(5) }] },
+                ..partner_source.clone()
+            }] };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Add a capped partner source
+            let msg = ExecuteMsg::EditDiscountSources { sources: vec![partner_source.clone()] };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let res: Vec<DiscountSource> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetDiscountSources { })
+                .unwrap();
+            assert_eq!(res, vec![partner_source]);
+
+            //Discounts stack up to 100%
+            let res: PositionRateResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &query_msg)
+                .unwrap();
+            assert_eq!(res.discounts[1], AppliedDiscount {
+                source: String::from("partner"),
+                contract: String::from("contract8"),
+                discount: Decimal::percent(90),
+                applied: Decimal::percent(5),
+            });
+            assert_eq!(res.total_discount, Decimal::percent(95));
+            assert_eq!(res.effective_rate, res.undiscounted_rate * Decimal::percent(5));
         }
 
         #[test]
@@ -4087,6 +4406,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -4113,6 +4435,7 @@ mod tests {
                 amount: Some(Uint128::from(49_999_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
@@ -4132,6 +4455,7 @@ mod tests {
                 amount: Some(Uint128::from(1_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.set_block(BlockInfo {
@@ -4197,6 +4521,7 @@ mod tests {
                     amount: Uint128::from(96_080_000000u128),
                 }],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.set_block(BlockInfo {
@@ -4264,6 +4589,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -4295,6 +4623,7 @@ mod tests {
                 amount: Some(Uint128::from(49_999_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
@@ -4313,6 +4642,7 @@ mod tests {
                 amount: Some(Uint128::from(2_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
 
@@ -4417,6 +4747,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -4444,6 +4777,7 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -4510,6 +4844,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -4521,6 +4857,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -4540,6 +4879,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -4551,6 +4892,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -4590,6 +4934,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(true),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -4642,6 +4988,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -4686,6 +5035,7 @@ mod tests {
                 premium: Some(99), 
                 max_loan_repayment: Some(Decimal::percent(100)),
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             let err = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap_err();
@@ -4699,6 +5049,7 @@ mod tests {
                 amount: Some(Uint128::from(2_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -4757,6 +5108,7 @@ mod tests {
                 amount: Some(Uint128::from(196_000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -4818,6 +5170,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -4868,6 +5223,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -4930,6 +5288,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -4956,6 +5317,7 @@ mod tests {
                 amount: Some(Uint128::from(49_999_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
@@ -5044,6 +5406,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -5070,6 +5435,7 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
@@ -5078,6 +5444,7 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: "test".to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -5167,6 +5534,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -5197,6 +5566,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -5227,6 +5599,7 @@ mod tests {
                 amount: Some(Uint128::from(5_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -5235,6 +5608,7 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: USER.to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -5347,6 +5721,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -5373,6 +5750,7 @@ mod tests {
                 amount: Some(Uint128::from(5_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -5381,6 +5759,7 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: USER.to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -5463,6 +5842,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -5489,6 +5871,7 @@ mod tests {
                 amount: Some(Uint128::from(5_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -5497,6 +5880,7 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: USER.to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -5588,6 +5972,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -5614,6 +6001,7 @@ mod tests {
                 amount: Some(Uint128::from(5_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -5622,6 +6010,7 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: USER.to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -5684,34 +6073,61 @@ mod tests {
         }
 
         #[test]
-        fn liquidate_LPs() {
-            let (mut app, cdp_contract, lq_contract) =
+        fn partial_liquidation_settings() {
+            let (mut app, cdp_contract, _lq_contract) =
                 proper_instantiate(false, false, false, false);
 
-            let res: Config = app
-                .wrap()
-                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {})
-                .unwrap();
-            let sp_addr = res.stability_pool.unwrap();
-            let router_addr = res.dex_router.unwrap();
-            let staking_contract = res.staking_contract.unwrap();
+            //Error: Close factor of 0
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: None,
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: Some(Decimal::zero()),
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"Close factor must be between 0% & 100%\""));
 
-            //Add LP pool assets first: Base
+            //Error: Soft liquidation caller fee of 100%
             let msg = ExecuteMsg::EditBasket(EditBasket {
                 take_revenue: None,
-                added_cAsset: Some(cAsset {
-                    asset: Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: "base".to_string(),
-                        },
-                        amount: Uint128::zero(),
-                    },
-                    max_borrow_LTV: Decimal::percent(40),
-                    max_LTV: Decimal::percent(60),
-                    pool_info: None,
-                    rate_index: Decimal::one(),
-                    hike_rates: Some(false),
+                added_cAsset: None,
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: Some(SoftLiquidation {
+                    repay_ratio: Decimal::percent(10),
+                    caller_fee: Decimal::one(),
                 }),
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"Soft liquidation repay ratio can't exceed 100% & the caller fee must be less than 100%\""));
+
+            //Set close factor & soft liquidations
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: None,
                 liq_queue: None,
                 credit_pool_infos: None,
                 collateral_supply_caps: None,
@@ -5722,26 +6138,30 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: Some(Decimal::percent(50)),
+                soft_liquidation: Some(SoftLiquidation {
+                    repay_ratio: Decimal::percent(10),
+                    caller_fee: Decimal::percent(1),
+                }),
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Add LP pool assets first: Quote
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
+                .unwrap();
+            assert_eq!(basket.close_factor, Some(Decimal::percent(50)));
+            assert_eq!(basket.soft_liquidation, Some(SoftLiquidation {
+                repay_ratio: Decimal::percent(10),
+                caller_fee: Decimal::percent(1),
+            }));
+
+            //Turn both off
             let msg = ExecuteMsg::EditBasket(EditBasket {
                 take_revenue: None,
-                added_cAsset: Some(cAsset {
-                    asset: Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: "quote".to_string(),
-                        },
-                        amount: Uint128::zero(),
-                    },
-                    max_borrow_LTV: Decimal::percent(60),
-                    max_LTV: Decimal::percent(80),
-                    pool_info: None,
-                    rate_index: Decimal::one(),
-                    hike_rates: Some(false),
-                }),
+                added_cAsset: None,
                 liq_queue: None,
                 credit_pool_infos: None,
                 collateral_supply_caps: None,
@@ -5752,117 +6172,147 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: Some(Decimal::one()),
+                soft_liquidation: Some(SoftLiquidation {
+                    repay_ratio: Decimal::zero(),
+                    caller_fee: Decimal::zero(),
+                }),
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Add LP asset
-            //Set supply caps
-            //Set general parameters
-            //Add LQ
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
+                .unwrap();
+            assert_eq!(basket.close_factor, None);
+            assert_eq!(basket.soft_liquidation, None);
+        }
+
+        #[test]
+        fn liquidate_many() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            let res: Config = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {})
+                .unwrap();
+            let staking_contract = res.staking_contract.unwrap();
+
+            //Add liq-queue to the initial basket
             let msg = ExecuteMsg::EditBasket(EditBasket {
                 take_revenue: None,
                 added_cAsset: Some(cAsset {
                     asset: Asset {
                         info: AssetInfo::NativeToken {
-                            denom: "lp_denom".to_string(),
+                            denom: "2nddebit".to_string(),
                         },
                         amount: Uint128::zero(),
                     },
                     max_borrow_LTV: Decimal::percent(40),
                     max_LTV: Decimal::percent(60),
-                    pool_info: Some(PoolInfo {
-                        pool_id: 99u64,
-                        asset_infos: vec![
-                            LPAssetInfo {
-                                info: AssetInfo::NativeToken {
-                                    denom: String::from("base"),
-                                },
-                                decimals: 6u64,
-                                ratio: Decimal::percent(50),
-                            },
-                            LPAssetInfo {
-                                info: AssetInfo::NativeToken {
-                                    denom: String::from("quote"),
-                                },
-                                decimals: 6u64,
-                                ratio: Decimal::percent(50),
-                            },
-                        ],
-                    }),
+                    pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
-                collateral_supply_caps: Some(vec![
-                    SupplyCap {
-                        asset_info: AssetInfo::NativeToken {
-                            denom: "debit".to_string(),
-                        },
-                        current_supply: Uint128::zero(),
-                        debt_total: Uint128::zero(),
-                        supply_cap_ratio: Decimal::percent(100),
-                        lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
+                collateral_supply_caps: Some(vec![SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
                     },
-                    SupplyCap {
-                        asset_info: AssetInfo::NativeToken {
-                            denom: "base".to_string(),
-                        },
-                        current_supply: Uint128::zero(),
-                        debt_total: Uint128::zero(),
-                        supply_cap_ratio: Decimal::percent(100),
-                        lp: false,
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
                         stability_pool_ratio_for_debt_cap: None,
+                },
+                SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "2nddebit".to_string(),
                     },
-                    SupplyCap {
-                        asset_info: AssetInfo::NativeToken {
-                            denom: "quote".to_string(),
-                        },
-                        current_supply: Uint128::zero(),
-                        debt_total: Uint128::zero(),
-                        supply_cap_ratio: Decimal::percent(100),
-                        lp: false,
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
                         stability_pool_ratio_for_debt_cap: None,
-                    },
-                    SupplyCap {
-                        asset_info: AssetInfo::NativeToken {
-                            denom: "lp_denom".to_string(),
-                        },
-                        current_supply: Uint128::zero(),
-                        debt_total: Uint128::zero(),
-                        supply_cap_ratio: Decimal::percent(100),
-                        lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                    },
-                ]),
-                base_interest_rate: Some(Decimal::percent(10)),
+                }]),
+                base_interest_rate: None,
                 credit_asset_twap_price_source: None,
                 negative_rates: None,
                 cpc_margin_of_error: None,
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
             //Initial Deposit
             let msg = ExecuteMsg::Deposit {
-                position_owner: Some("bigger_bank".to_string()),
+                position_owner: Some(USER.to_string()),
                 position_id: None,
             };
             let cosmos_msg = cdp_contract
                 .call(
                     msg,
                     vec![Coin {
-                        denom: "lp_denom".to_string(),
-                        amount: Uint128::from(100_000_000_000_000_000_000_000u128),
+                        denom: "debit".to_string(),
+                        amount: Uint128::from(100_000_000000u128),
+                    },
+                    Coin {
+                        denom: "2nddebit".to_string(),
+                        amount: Uint128::from(100_000_000000u128),
                     }],
                 )
                 .unwrap();
-            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Debtless Deposit
+            let msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(
+                    msg,
+                    vec![Coin {
+                        denom: "debit".to_string(),
+                        amount: Uint128::from(1_000_000u128),
+                    }],
+                )
+                .unwrap();
+            app.execute(Addr::unchecked("test"), cosmos_msg).unwrap();
+
+            //Error: No insolvent positions to liquidate
+            let msg = ExecuteMsg::LiquidateMany {
+                positions: vec![
+                    UserInfo {
+                        position_id: Uint128::new(1u128),
+                        position_owner: USER.to_string(),
+                    },
+                    UserInfo {
+                        position_id: Uint128::new(2u128),
+                        position_owner: String::from("test"),
+                    },
+                ],
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"No insolvent positions to liquidate\""));
+
+            //Error: Empty list
+            let msg = ExecuteMsg::LiquidateMany { positions: vec![] };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"Must liquidate between 1 and 10 positions\""));
 
             //Increase Debt
             let msg = ExecuteMsg::IncreaseDebt {
@@ -5870,86 +6320,89 @@ mod tests {
                 amount: Some(Uint128::from(5_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Call liquidate on CDP contract
-            let msg = ExecuteMsg::Liquidate {
+            //Callbacks are only callable by the contract
+            let msg = ExecuteMsg::Callback(CallbackMsg::LiquidatePosition {
                 position_id: Uint128::new(1u128),
-                position_owner: "bigger_bank".to_string(),
-            };
+                position_owner: Addr::unchecked(USER),
+                fee_recipient: Addr::unchecked("keeper"),
+            });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
-
-            //Call LiqRepay to mimic a successfull SP Liquidate call
-            let msg = ExecuteMsg::LiqRepay {};
+            app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap_err();
 
-            let cosmos_msg = cdp_contract
-                .call(msg, vec![coin(1611_000000, "credit_fulldenom")])
-                .unwrap();
-            app.execute(Addr::unchecked(sp_addr.clone()), cosmos_msg)
-                .unwrap();
-
-
-            let query_msg = QueryMsg::GetBasketPositions {
-                start_after: None, 
-                limit: None,
-                user: None,
-                user_info: Some(
+            //Liquidate the insolvent position, skipping the debtless, nonexistent & duplicate entries
+            let msg = ExecuteMsg::LiquidateMany {
+                positions: vec![
                     UserInfo {
-                        position_id: Uint128::new(1),
-                        position_owner: "bigger_bank".to_string(),
-                    }
-                ),
+                        position_id: Uint128::new(1u128),
+                        position_owner: USER.to_string(),
+                    },
+                    UserInfo {
+                        position_id: Uint128::new(2u128),
+                        position_owner: String::from("test"),
+                    },
+                    UserInfo {
+                        position_id: Uint128::new(3u128),
+                        position_owner: USER.to_string(),
+                    },
+                    UserInfo {
+                        position_id: Uint128::new(1u128),
+                        position_owner: USER.to_string(),
+                    },
+                ],
             };
-            let res: Vec<BasketPositionsResponse> = app
-                .wrap()
-                .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap();
+
+            let response = res
+                .events
+                .into_iter()
+                .find(|e| e.attributes.iter().any(|attr| attr.value == "liquidate_many"))
+                .ok_or_else(|| panic!("unable to find LIQUIDATE_MANY event"))
                 .unwrap();
-            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(97405_616667055555555986));
-            //2777 credit liquidated at $1
-            //lp_denom is worth $2
-            //Assert sell wall wasn't sent assets
             assert_eq!(
-                app.wrap().query_all_balances(router_addr.clone()).unwrap(),
-                vec![]
+                response.attributes[1..],
+                vec![
+                    attr("method", "liquidate_many"),
+                    attr("liquidated_positions", format!("{}:1", USER)),
+                    attr("skipped_positions", format!("test:2, {}:3", USER)),
+                ]
             );
 
-            //Assert fees were sent.
+            //Assert caller fees went to the keeper & protocol fees to the staking contract
+            assert_eq!(
+                app.wrap().query_all_balances("keeper").unwrap(),
+                vec![coin(312_500_000, "2nddebit"), coin(312_500_000, "debit")]
+            );
             assert_eq!(
                 app.wrap()
                     .query_all_balances(staking_contract.clone())
                     .unwrap(),
-                vec![coin(13_888888888888888875, "lp_denom")]
-            );
-            assert_eq!(
-                app.wrap().query_all_balances(USER).unwrap(),
-                vec![coin(100000000000, "2nddebit"), coin(100000000000, "debit"), coin(416_666_666_666_666_666_250, "lp_denom")]
-            );
-
-            //Assert collateral to be liquidated was sent
-            assert_eq!(
-                app.wrap().query_all_balances(sp_addr.clone()).unwrap(),
-                vec![coin(1166_777777, "credit_fulldenom"), coin(886_050000000000000000, "lp_denom")]
+                vec![coin(12_500_000, "2nddebit"), coin(12_500_000, "debit")]
             );
+            //Assert collateral to be liquidated was sent to the LQ
             assert_eq!(
                 app.wrap().query_all_balances(lq_contract.addr()).unwrap(),
-                vec![coin(1277_777_777_388_888_888_889, "lp_denom")]
+                vec![coin(1250_000_000, "2nddebit"), coin(1250_000_000, "debit")]
             );
-            
-            /////////SP Errors////
-            ///
+        }
+
+        #[test]
+        fn price_policy() {
             let (mut app, cdp_contract, lq_contract) =
-                proper_instantiate(true, false, false, false);
+                proper_instantiate(false, false, false, false);
 
-            //Add LP pool assets first: Base
+            //Add liq-queue to the initial basket
             let msg = ExecuteMsg::EditBasket(EditBasket {
                 take_revenue: None,
                 added_cAsset: Some(cAsset {
                     asset: Asset {
                         info: AssetInfo::NativeToken {
-                            denom: "base".to_string(),
+                            denom: "2nddebit".to_string(),
                         },
                         amount: Uint128::zero(),
                     },
@@ -5958,10 +6411,31 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
-                liq_queue: None,
-                credit_pool_infos: None,
-                collateral_supply_caps: None,
+                liq_queue: Some(lq_contract.addr().to_string()),
+                credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
+                collateral_supply_caps: Some(vec![SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
+                    },
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
+                    stability_pool_ratio_for_debt_cap: None,
+                },
+                SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "2nddebit".to_string(),
+                    },
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
+                    stability_pool_ratio_for_debt_cap: None,
+                }]),
                 base_interest_rate: None,
                 credit_asset_twap_price_source: None,
                 negative_rates: None,
@@ -5969,217 +6443,148 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Add LP pool assets first: Quote
-            let msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
-                added_cAsset: Some(cAsset {
-                    asset: Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: "quote".to_string(),
-                        },
-                        amount: Uint128::zero(),
+            //Initial Deposit
+            let msg = ExecuteMsg::Deposit {
+                position_owner: Some(USER.to_string()),
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(
+                    msg,
+                    vec![Coin {
+                        denom: "debit".to_string(),
+                        amount: Uint128::from(100_000_000000u128),
                     },
-                    max_borrow_LTV: Decimal::percent(60),
-                    max_LTV: Decimal::percent(80),
-                    pool_info: None,
-                    rate_index: Decimal::one(),
-                    hike_rates: Some(false),
-                }),
-                liq_queue: None,
-                credit_pool_infos: None,
-                collateral_supply_caps: None,
-                base_interest_rate: None,
-                credit_asset_twap_price_source: None,
-                negative_rates: None,
-                cpc_margin_of_error: None,
-                frozen: None,
-                rev_to_stakers: None,
-                multi_asset_supply_caps: None,
+                    Coin {
+                        denom: "2nddebit".to_string(),
+                        amount: Uint128::from(100_000_000000u128),
+                    }],
+                )
+                .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //The mock oracle publishes debit 120 seconds ago w/ a 0.5% confidence interval
+            let publish_time = app.block_info().time.seconds() - 120;
+            let policy_msg = |price_policy: PricePolicy| ExecuteMsg::UpdateConfig(UpdateConfig {
+                owner: None,
+                stability_pool: None,
+                dex_router: None,
+                osmosis_proxy: None,
+                debt_auction: None,
+                staking_contract: None,
+                oracle_contract: None,
+                liquidity_contract: None,
+                discounts_contract: None,
+                liq_fee: None,
+                debt_minimum: None,
+                base_debt_cap_multiplier: None,
+                oracle_time_limit: None,
+                collateral_twap_timeframe: None,
+                credit_twap_timeframe: None,
+                cpc_multiplier: None,
+                rate_slope_multiplier: None,
+                rate_hike_rate: None,
+                flash_mint_cap: None,
+                flash_mint_fee: None,
+                price_policy: Some(price_policy),
             });
-            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let debt_msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(5_000_000000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+
+            //Error: Stale prices can't back new debt
+            let cosmos_msg = cdp_contract.call(policy_msg(PricePolicy { max_staleness: Some(60), max_confidence: None }), vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Add LP asset
-            //Set supply caps
-            //Set general parameters
-            //Add LQ
-            let msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
-                added_cAsset: Some(cAsset {
-                    asset: Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: "lp_denom".to_string(),
-                        },
-                        amount: Uint128::zero(),
-                    },
-                    max_borrow_LTV: Decimal::percent(40),
-                    max_LTV: Decimal::percent(60),
-                    pool_info: Some(PoolInfo {
-                        pool_id: 99u64,
-                        asset_infos: vec![
-                            LPAssetInfo {
-                                info: AssetInfo::NativeToken {
-                                    denom: String::from("base"),
-                                },
-                                decimals: 6u64,
-                                ratio: Decimal::percent(50),
-                            },
-                            LPAssetInfo {
-                                info: AssetInfo::NativeToken {
-                                    denom: String::from("quote"),
-                                },
-                                decimals: 6u64,
-                                ratio: Decimal::percent(50),
-                            },
-                        ],
-                    }),
-                    rate_index: Decimal::one(),
-                    hike_rates: Some(false),
-                }),
-                liq_queue: Some(lq_contract.addr().to_string()),
-                credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
-                collateral_supply_caps: Some(vec![
-                    SupplyCap {
-                        asset_info: AssetInfo::NativeToken {
-                            denom: "debit".to_string(),
-                        },
-                        current_supply: Uint128::zero(),
-                        debt_total: Uint128::zero(),
-                        supply_cap_ratio: Decimal::percent(100),
-                        lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                    },
-                    SupplyCap {
-                        asset_info: AssetInfo::NativeToken {
-                            denom: "base".to_string(),
-                        },
-                        current_supply: Uint128::zero(),
-                        debt_total: Uint128::zero(),
-                        supply_cap_ratio: Decimal::percent(100),
-                        lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                    },
-                    SupplyCap {
-                        asset_info: AssetInfo::NativeToken {
-                            denom: "quote".to_string(),
-                        },
-                        current_supply: Uint128::zero(),
-                        debt_total: Uint128::zero(),
-                        supply_cap_ratio: Decimal::percent(100),
-                        lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                    },
-                    SupplyCap {
-                        asset_info: AssetInfo::NativeToken {
-                            denom: "lp_denom".to_string(),
-                        },
-                        current_supply: Uint128::zero(),
-                        debt_total: Uint128::zero(),
-                        supply_cap_ratio: Decimal::percent(100),
-                        lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                    },
-                ]),
-                base_interest_rate: Some(Decimal::percent(10)),
-                credit_asset_twap_price_source: None,
-                negative_rates: None,
-                cpc_margin_of_error: None,
-                frozen: None,
-                rev_to_stakers: None,
-                multi_asset_supply_caps: None,
-            });
-            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let cosmos_msg = cdp_contract.call(debt_msg.clone(), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), format!("Generic error: debit price is stale, published at {}", publish_time));
+
+            //Error: Uncertain prices can't back new debt
+            let cosmos_msg = cdp_contract.call(policy_msg(PricePolicy { max_staleness: Some(600), max_confidence: Some(Decimal::permille(1)) }), vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Initial Deposit
-            let msg = ExecuteMsg::Deposit {
-                position_owner: Some("bigger_bank".to_string()),
-                position_id: None,
-            };
-            let cosmos_msg = cdp_contract
-                .call(
-                    msg,
-                    vec![Coin {
-                        denom: "lp_denom".to_string(),
-                        amount: Uint128::from(100_000_000_000_000_000_000_000u128),
-                    }],
-                )
-                .unwrap();
-            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
+            let cosmos_msg = cdp_contract.call(debt_msg.clone(), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Generic error: debit price confidence interval is too wide: 0.005"));
 
-            //Increase Debt
-            let msg = ExecuteMsg::IncreaseDebt {
+            //Prices within the policy can back new debt
+            let cosmos_msg = cdp_contract.call(policy_msg(PricePolicy { max_staleness: Some(600), max_confidence: Some(Decimal::percent(1)) }), vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let cosmos_msg = cdp_contract.call(debt_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Error: Stale prices can't back the debt left after a withdrawal
+            let cosmos_msg = cdp_contract.call(policy_msg(PricePolicy { max_staleness: Some(60), max_confidence: None }), vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let msg = ExecuteMsg::Withdraw {
                 position_id: Uint128::from(1u128),
-                amount: Some(Uint128::from(5_000_000000u128)),
-                LTV: None,
-                mint_to_addr: None,
+                assets: vec![Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
+                    },
+                    amount: Uint128::from(1_000_000u128),
+                }],
+                send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), format!("Generic error: debit price is stale, published at {}", publish_time));
 
-            //Call liquidate on CDP contract: SP Errors which cancels liq
-            let msg = ExecuteMsg::Liquidate {
-                position_id: Uint128::new(1u128),
-                position_owner: "bigger_bank".to_string(),
+            //Stale prices don't block liquidations
+            let msg = ExecuteMsg::LiquidateMany {
+                positions: vec![
+                    UserInfo {
+                        position_id: Uint128::new(1u128),
+                        position_owner: USER.to_string(),
+                    },
+                ],
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            //Send CDP the LP pool assets to mimic a withdrawal
-            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
-
-            // let query_msg = QueryMsg::GetBasketPositions {
-            //     start_after: None, 
-            //     limit: None,
-            //     user: None,
-            //     user_info: Some(
-            //         UserInfo {
-            //             position_id: Uint128::new(1),
-            //             position_owner: "bigger_bank".to_string(),
-            //         }
-            //     ),
-            // };
-            // let res: Vec<BasketPositionsResponse> = app
-            //     .wrap()
-            //     .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
-            //     .unwrap();
-            // assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(98291_777778055555555986));
-
-
-            // //Assert 1% fee was sent.
-            // //This is 13 instead of 27 bc the share token is the only collateral worth $2 instead of 1.
-            // assert_eq!(
-            //     app.wrap()
-            //         .query_all_balances(staking_contract.clone())
-            //         .unwrap(),
-            //     vec![coin(13_888888888888888875, "lp_denom")]
-            // );
-            // //Assert 30% fee
-            // //Same here, 416 instead of 833 if it were valued at a $1.
-            // assert_eq!(
-            //     app.wrap().query_all_balances(USER).unwrap(),
-            //     vec![coin(100000000000, "2nddebit"), coin(100000000000, "debit"), coin(416_666666666666666250, "lp_denom")]
-            // );
+            let res = app.execute(Addr::unchecked("keeper"), cosmos_msg).unwrap();
 
-            // //Assert collateral to be liquidated was sent
-            // assert_eq!(
-            //     app.wrap().query_all_balances(lq_contract.addr()).unwrap(),
-            //     vec![coin(1277_666666388888888889, "lp_denom")]
-            // );            
-            // //Assert SP wasn't sent any due to the Error
-            // assert_eq!(
-            //     app.wrap().query_all_balances(sp_addr.clone()).unwrap(),
-            //     vec![coin(2777_777777, "credit_fulldenom")]
-            // );
+            let response = res
+                .events
+                .into_iter()
+                .find(|e| e.attributes.iter().any(|attr| attr.value == "liquidate_many"))
+                .ok_or_else(|| panic!("unable to find LIQUIDATE_MANY event"))
+                .unwrap();
+            assert_eq!(
+                response.attributes[1..],
+                vec![
+                    attr("method", "liquidate_many"),
+                    attr("liquidated_positions", format!("{}:1", USER)),
+                    attr("skipped_positions", String::from("")),
+                ]
+            );
+        }
 
-            //////LQ Errors///
-            /// The query erroring will skip all LQ msgs and use the SP.
-            /// In real-time if the query doesn't error but the execution does, the liquidation msg will fail.
-            ///
+        #[test]
+        fn liquidate_LPs() {
             let (mut app, cdp_contract, lq_contract) =
-                proper_instantiate(false, true, false, false);
+                proper_instantiate(false, false, false, false);
+
+            let res: Config = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {})
+                .unwrap();
+            let sp_addr = res.stability_pool.unwrap();
+            let router_addr = res.dex_router.unwrap();
+            let staking_contract = res.staking_contract.unwrap();
 
             //Add LP pool assets first: Base
             let msg = ExecuteMsg::EditBasket(EditBasket {
@@ -6196,6 +6601,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -6207,6 +6614,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -6226,6 +6636,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -6237,6 +6649,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -6277,6 +6692,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -6329,6 +6746,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -6355,6 +6775,7 @@ mod tests {
                 amount: Some(Uint128::from(5_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
@@ -6363,19 +6784,21 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: "bigger_bank".to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();    
-            
-            //Call LiqRepay to mimic a successfull SP Liquidate call by both the initial SP and then LQ reply
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Call LiqRepay to mimic a successfull SP Liquidate call
             let msg = ExecuteMsg::LiqRepay {};
 
             let cosmos_msg = cdp_contract
-                .call(msg, vec![coin(2777_777777, "credit_fulldenom")])
+                .call(msg, vec![coin(1611_000000, "credit_fulldenom")])
                 .unwrap();
             app.execute(Addr::unchecked(sp_addr.clone()), cosmos_msg)
                 .unwrap();
 
+
             let query_msg = QueryMsg::GetBasketPositions {
                 start_after: None, 
                 limit: None,
@@ -6391,8 +6814,9 @@ mod tests {
                 .wrap()
                 .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
                 .unwrap();
-            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(98041_666667094444444875));
-
+            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(97405_616667055555555986));
+            //2777 credit liquidated at $1
+            //lp_denom is worth $2
             //Assert sell wall wasn't sent assets
             assert_eq!(
                 app.wrap().query_all_balances(router_addr.clone()).unwrap(),
@@ -6408,26 +6832,24 @@ mod tests {
             );
             assert_eq!(
                 app.wrap().query_all_balances(USER).unwrap(),
-                vec![coin(100000_000000, "2nddebit"), coin(100000_000000, "debit"), coin(416_666666666666666250, "lp_denom")]
+                vec![coin(100000000000, "2nddebit"), coin(100000000000, "debit"), coin(416_666_666_666_666_666_250, "lp_denom")]
             );
 
             //Assert collateral to be liquidated was sent
-            //$2447 worth
             assert_eq!(
                 app.wrap().query_all_balances(sp_addr.clone()).unwrap(),
-                vec![coin(1527_777777350000000000, "lp_denom")]
+                vec![coin(1166_777777, "credit_fulldenom"), coin(886_050000000000000000, "lp_denom")]
             );
-            //Assert LQ wasn't sent any due to the Error
             assert_eq!(
                 app.wrap().query_all_balances(lq_contract.addr()).unwrap(),
-                vec![]
+                vec![coin(1277_777_777_388_888_888_889, "lp_denom")]
             );
-
-            //////Both Errors/////
+            
+            /////////SP Errors////
             ///
             let (mut app, cdp_contract, lq_contract) =
-                proper_instantiate(true, true, false, false);
-            
+                proper_instantiate(true, false, false, false);
+
             //Add LP pool assets first: Base
             let msg = ExecuteMsg::EditBasket(EditBasket {
                 take_revenue: None,
@@ -6443,6 +6865,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -6454,6 +6878,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -6473,6 +6900,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -6484,6 +6913,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -6524,6 +6956,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -6576,6 +7010,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -6602,25 +7039,21 @@ mod tests {
                 amount: Some(Uint128::from(5_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
 
-            //Call liquidate on CDP contract: Both errored
+            //Call liquidate on CDP contract: SP Errors which cancels liq
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: "bigger_bank".to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             //Send CDP the LP pool assets to mimic a withdrawal
-            app.send_tokens(
-                Addr::unchecked("bigger_bank"),
-                Addr::unchecked(cdp_contract.clone().addr()),
-                &vec![coin(1388_888888, "base"), coin(1388_888888, "quote")],
-            )
-            .unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
-           
+
             // let query_msg = QueryMsg::GetBasketPositions {
             //     start_after: None, 
             //     limit: None,
@@ -6636,74 +7069,205 @@ mod tests {
             //     .wrap()
             //     .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
             //     .unwrap();
-            // assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(98180_555555944444444875));
+            // assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(98291_777778055555555986));
 
-            // //Assert sell wall was sent assets all Assets
-            // //For $2777 worth of liquidations
-            // assert_eq!(
-            //     app.wrap().query_all_balances(router_addr.clone()).unwrap(),
-            //     vec![coin(1388_888888, "base"), coin(1388_888888, "quote")]
-            // );
 
-            // //Assert fees were sent.
+            // //Assert 1% fee was sent.
+            // //This is 13 instead of 27 bc the share token is the only collateral worth $2 instead of 1.
             // assert_eq!(
             //     app.wrap()
             //         .query_all_balances(staking_contract.clone())
             //         .unwrap(),
             //     vec![coin(13_888888888888888875, "lp_denom")]
             // );
+            // //Assert 30% fee
+            // //Same here, 416 instead of 833 if it were valued at a $1.
             // assert_eq!(
             //     app.wrap().query_all_balances(USER).unwrap(),
-            //     vec![coin(100000_000000, "2nddebit"), coin(100000_000000, "debit"), coin(416_666666666666666250, "lp_denom")]
+            //     vec![coin(100000000000, "2nddebit"), coin(100000000000, "debit"), coin(416_666666666666666250, "lp_denom")]
             // );
 
-            // //Assert neither module was sent any due to the Error
+            // //Assert collateral to be liquidated was sent
+            // assert_eq!(
+            //     app.wrap().query_all_balances(lq_contract.addr()).unwrap(),
+            //     vec![coin(1277_666666388888888889, "lp_denom")]
+            // );            
+            // //Assert SP wasn't sent any due to the Error
             // assert_eq!(
             //     app.wrap().query_all_balances(sp_addr.clone()).unwrap(),
             //     vec![coin(2777_777777, "credit_fulldenom")]
             // );
-            // assert_eq!(
-            //     app.wrap().query_all_balances(lq_contract.addr()).unwrap(),
-            //     vec![]
-            // );
-        }
 
-        #[test]
-        fn liquidate_bignums() {
-            let (mut app, cdp_contract, lq_contract) =
-                proper_instantiate(false, false, false, true);
+            //////LQ Errors///
+            /// The query erroring will skip all LQ msgs and use the SP.
+            /// In real-time if the query doesn't error but the execution does, the liquidation msg will fail.
+            ///
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, true, false, false);
 
-            let res: Config = app
-                .wrap()
-                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {})
-                .unwrap();
-            let sp_addr = res.stability_pool.unwrap();
-            let router_addr = res.dex_router.unwrap();
-            let staking_contract = res.staking_contract.unwrap();
+            //Add LP pool assets first: Base
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "base".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(40),
+                    max_LTV: Decimal::percent(60),
+                    pool_info: None,
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Add liq-queue to the initial basket
+            //Add LP pool assets first: Quote
             let msg = ExecuteMsg::EditBasket(EditBasket {
                 take_revenue: None,
-                added_cAsset: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "quote".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(60),
+                    max_LTV: Decimal::percent(80),
+                    pool_info: None,
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Add LP asset
+            //Set supply caps
+            //Set general parameters
+            //Add LQ
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "lp_denom".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(40),
+                    max_LTV: Decimal::percent(60),
+                    pool_info: Some(PoolInfo {
+                        pool_id: 99u64,
+                        asset_infos: vec![
+                            LPAssetInfo {
+                                info: AssetInfo::NativeToken {
+                                    denom: String::from("base"),
+                                },
+                                decimals: 6u64,
+                                ratio: Decimal::percent(50),
+                            },
+                            LPAssetInfo {
+                                info: AssetInfo::NativeToken {
+                                    denom: String::from("quote"),
+                                },
+                                decimals: 6u64,
+                                ratio: Decimal::percent(50),
+                            },
+                        ],
+                    }),
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
-                collateral_supply_caps: Some(vec![SupplyCap {
-                    asset_info: AssetInfo::NativeToken {
-                        denom: "debit".to_string(),
+                collateral_supply_caps: Some(vec![
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "debit".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
                     },
-                    current_supply: Uint128::zero(),
-                    debt_total: Uint128::zero(),
-                    supply_cap_ratio: Decimal::percent(100),
-                    lp: false,
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "base".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
                         stability_pool_ratio_for_debt_cap: None,
-                }]),
-                base_interest_rate: None,
+                    },
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "quote".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "lp_denom".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                ]),
+                base_interest_rate: Some(Decimal::percent(10)),
                 credit_asset_twap_price_source: None,
                 negative_rates: None,
                 cpc_margin_of_error: None,
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -6717,44 +7281,38 @@ mod tests {
                 .call(
                     msg,
                     vec![Coin {
-                        denom: "debit".to_string(),
-                        amount: Uint128::from(100_000_000_000_000u128),
+                        denom: "lp_denom".to_string(),
+                        amount: Uint128::from(100_000_000_000_000_000_000_000u128),
                     }],
                 )
                 .unwrap();
-            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
-                .unwrap();
+            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
 
             //Increase Debt
             let msg = ExecuteMsg::IncreaseDebt {
                 position_id: Uint128::from(1u128),
-                amount: Some(Uint128::from(5_000_000_000_000u128)),
+                amount: Some(Uint128::from(5_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
-                .unwrap();
+            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
 
             //Call liquidate on CDP contract
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: "bigger_bank".to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
-
-            //Call LiqRepay to mimic a successfull SP Liquidate call
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();    
+            
+            //Call LiqRepay to mimic a successfull SP Liquidate call by both the initial SP and then LQ reply
             let msg = ExecuteMsg::LiqRepay {};
 
-            app.send_tokens(
-                Addr::unchecked("coin_God"),
-                Addr::unchecked(sp_addr.clone()),
-                &vec![coin(222_222_222_222, "credit_fulldenom")],
-            )
-            .unwrap();
             let cosmos_msg = cdp_contract
-                .call(msg, vec![coin(222_222_222_222, "credit_fulldenom")])
+                .call(msg, vec![coin(2777_777777, "credit_fulldenom")])
                 .unwrap();
             app.execute(Addr::unchecked(sp_addr.clone()), cosmos_msg)
                 .unwrap();
@@ -6774,10 +7332,7 @@ mod tests {
                 .wrap()
                 .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
                 .unwrap();
-            assert_eq!(
-                res[0].positions[0].collateral_assets[0].asset.amount,
-                Uint128::new(97288888888893)
-            );
+            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(98041_666667094444444875));
 
             //Assert sell wall wasn't sent assets
             assert_eq!(
@@ -6790,56 +7345,51 @@ mod tests {
                 app.wrap()
                     .query_all_balances(staking_contract.clone())
                     .unwrap(),
-                vec![coin(22_222_222_222, "debit")]
+                vec![coin(13_888888888888888875, "lp_denom")]
             );
             assert_eq!(
                 app.wrap().query_all_balances(USER).unwrap(),
-                vec![coin(100000000000, "2nddebit"), coin(544_444_444_444, "debit")]
+                vec![coin(100000_000000, "2nddebit"), coin(100000_000000, "debit"), coin(416_666666666666666250, "lp_denom")]
             );
 
             //Assert collateral to be liquidated was sent
+            //$2447 worth
             assert_eq!(
                 app.wrap().query_all_balances(sp_addr.clone()).unwrap(),
-                vec![
-                    coin(2777_777777, "credit_fulldenom"),
-                    coin(244_444444444, "debit")
-                ]
+                vec![coin(1527_777777350000000000, "lp_denom")]
             );
+            //Assert LQ wasn't sent any due to the Error
             assert_eq!(
                 app.wrap().query_all_balances(lq_contract.addr()).unwrap(),
-                vec![coin(1_999_999_999_997, "debit")]
+                vec![]
             );
-        }
 
-        #[test]
-        fn liquidate_minimums() {
+            //////Both Errors/////
+            ///
             let (mut app, cdp_contract, lq_contract) =
-                proper_instantiate(false, false, true, false);
-
-            let res: Config = app
-                .wrap()
-                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {})
-                .unwrap();
-            let sp_addr = res.stability_pool.unwrap();
-            let router_addr = res.dex_router.unwrap();
-            let staking_contract = res.staking_contract.unwrap();
-
-            //Add liq-queue to the initial basket
+                proper_instantiate(true, true, false, false);
+            
+            //Add LP pool assets first: Base
             let msg = ExecuteMsg::EditBasket(EditBasket {
                 take_revenue: None,
-                added_cAsset: None,
-                liq_queue: Some(lq_contract.addr().to_string()),
-                credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
-                collateral_supply_caps: Some(vec![SupplyCap {
-                    asset_info: AssetInfo::NativeToken {
-                        denom: "debit".to_string(),
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "base".to_string(),
+                        },
+                        amount: Uint128::zero(),
                     },
-                    current_supply: Uint128::zero(),
-                    debt_total: Uint128::zero(),
-                    supply_cap_ratio: Decimal::percent(100),
-                    lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                }]),
+                    max_borrow_LTV: Decimal::percent(40),
+                    max_LTV: Decimal::percent(60),
+                    pool_info: None,
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
                 base_interest_rate: None,
                 credit_asset_twap_price_source: None,
                 negative_rates: None,
@@ -6847,130 +7397,387 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Lower debt minimum
-            let msg = ExecuteMsg::UpdateConfig(UpdateConfig {
-                owner: None,
-                stability_pool: None,
-                dex_router: None,
-                osmosis_proxy: None,
-                debt_auction: None,
-                staking_contract: None,
-                oracle_contract: None,
-                liquidity_contract: None,
-                discounts_contract: None,
-                liq_fee: None,
-                debt_minimum: Some(Uint128::new(500u128)),
-                base_debt_cap_multiplier: None,
-                oracle_time_limit: None,
-                collateral_twap_timeframe: None,
-                credit_twap_timeframe: None,
-                cpc_multiplier: None,
-                rate_slope_multiplier: None,
-                rate_hike_rate: None,
+            //Add LP pool assets first: Quote
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "quote".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(60),
+                    max_LTV: Decimal::percent(80),
+                    pool_info: None,
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Add LP asset
+            //Set supply caps
+            //Set general parameters
+            //Add LQ
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "lp_denom".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(40),
+                    max_LTV: Decimal::percent(60),
+                    pool_info: Some(PoolInfo {
+                        pool_id: 99u64,
+                        asset_infos: vec![
+                            LPAssetInfo {
+                                info: AssetInfo::NativeToken {
+                                    denom: String::from("base"),
+                                },
+                                decimals: 6u64,
+                                ratio: Decimal::percent(50),
+                            },
+                            LPAssetInfo {
+                                info: AssetInfo::NativeToken {
+                                    denom: String::from("quote"),
+                                },
+                                decimals: 6u64,
+                                ratio: Decimal::percent(50),
+                            },
+                        ],
+                    }),
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: Some(lq_contract.addr().to_string()),
+                credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
+                collateral_supply_caps: Some(vec![
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "debit".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "base".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "quote".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "lp_denom".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                ]),
+                base_interest_rate: Some(Decimal::percent(10)),
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
             //Initial Deposit
             let msg = ExecuteMsg::Deposit {
-                position_owner: Some(USER.to_string()),
+                position_owner: Some("bigger_bank".to_string()),
                 position_id: None,
             };
             let cosmos_msg = cdp_contract
                 .call(
                     msg,
                     vec![Coin {
-                        denom: "debit".to_string(),
-                        amount: Uint128::from(100_000_000000u128),
+                        denom: "lp_denom".to_string(),
+                        amount: Uint128::from(100_000_000_000_000_000_000_000u128),
                     }],
                 )
                 .unwrap();
-            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
 
-            //Increase Debt to a point where a liquidations:
-            //1. Liquidates less than the debt_minimum
-            //2. Brings the position below the minimum debt
-            //..Which then results in a full liquidation
+            //Increase Debt
             let msg = ExecuteMsg::IncreaseDebt {
                 position_id: Uint128::from(1u128),
-                amount: Some(Uint128::from(999_000000u128)),
+                amount: Some(Uint128::from(5_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
 
-            //Call liquidate on CDP contract
+            //Call liquidate on CDP contract: Both errored
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
-                position_owner: USER.to_string(),
+                position_owner: "bigger_bank".to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            //Send CDP the LP pool assets to mimic a withdrawal
+            app.send_tokens(
+                Addr::unchecked("bigger_bank"),
+                Addr::unchecked(cdp_contract.clone().addr()),
+                &vec![coin(1388_888888, "base"), coin(1388_888888, "quote")],
+            )
+            .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+           
+            // let query_msg = QueryMsg::GetBasketPositions {
+            //     start_after: None, 
+            //     limit: None,
+            //     user: None,
+            //     user_info: Some(
+            //         UserInfo {
+            //             position_id: Uint128::new(1),
+            //             position_owner: "bigger_bank".to_string(),
+            //         }
+            //     ),
+            // };
+            // let res: Vec<BasketPositionsResponse> = app
+            //     .wrap()
+            //     .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+            //     .unwrap();
+            // assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(98180_555555944444444875));
 
-            //Call LiqRepay to mimic a successfull SP Liquidate call
-            let msg = ExecuteMsg::LiqRepay {};
+            // //Assert sell wall was sent assets all Assets
+            // //For $2777 worth of liquidations
+            // assert_eq!(
+            //     app.wrap().query_all_balances(router_addr.clone()).unwrap(),
+            //     vec![coin(1388_888888, "base"), coin(1388_888888, "quote")]
+            // );
 
-            let cosmos_msg = cdp_contract
-                .call(msg, vec![coin(499_999999, "credit_fulldenom")])
-                .unwrap();
-            app.execute(Addr::unchecked(sp_addr.clone()), cosmos_msg)
-                .unwrap();
+            // //Assert fees were sent.
+            // assert_eq!(
+            //     app.wrap()
+            //         .query_all_balances(staking_contract.clone())
+            //         .unwrap(),
+            //     vec![coin(13_888888888888888875, "lp_denom")]
+            // );
+            // assert_eq!(
+            //     app.wrap().query_all_balances(USER).unwrap(),
+            //     vec![coin(100000_000000, "2nddebit"), coin(100000_000000, "debit"), coin(416_666666666666666250, "lp_denom")]
+            // );
 
-            let query_msg = QueryMsg::GetBasketPositions {
-                start_after: None, 
-                limit: None,
-                user: None,
-                user_info: Some(
-                    UserInfo {
-                        position_id: Uint128::new(1),
-                        position_owner: USER.to_string(),
-                    }
-                ),
-            };
-            let res: Vec<BasketPositionsResponse> = app
+            // //Assert neither module was sent any due to the Error
+            // assert_eq!(
+            //     app.wrap().query_all_balances(sp_addr.clone()).unwrap(),
+            //     vec![coin(2777_777777, "credit_fulldenom")]
+            // );
+            // assert_eq!(
+            //     app.wrap().query_all_balances(lq_contract.addr()).unwrap(),
+            //     vec![]
+            // );
+        }
+
+        #[test]
+        fn liquidate_bignums() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, true);
+
+            let res: Config = app
                 .wrap()
-                .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {})
                 .unwrap();
-            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(98741_210001));
-
-            //Assert sell wall wasn't sent assets
-            assert_eq!(
-                app.wrap().query_all_balances(router_addr.clone()).unwrap(),
-                vec![]
-            );
+            let sp_addr = res.stability_pool.unwrap();
+            let router_addr = res.dex_router.unwrap();
+            let staking_contract = res.staking_contract.unwrap();
 
-            //Assert fees were sent.
-            assert_eq!(
-                app.wrap()
-                    .query_all_balances(staking_contract.clone())
-                    .unwrap(),
-                vec![coin(9_990_000, "debit")]
-            );
-            assert_eq!(
-                app.wrap().query_all_balances(USER).unwrap(),
-                vec![coin(100000_000000, "2nddebit"), coin(199_800000, "debit")]
-            );
+            //Add liq-queue to the initial basket
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: None,
+                liq_queue: Some(lq_contract.addr().to_string()),
+                credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
+                collateral_supply_caps: Some(vec![SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
+                    },
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                }]),
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Initial Deposit
+            let msg = ExecuteMsg::Deposit {
+                position_owner: Some("bigger_bank".to_string()),
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(
+                    msg,
+                    vec![Coin {
+                        denom: "debit".to_string(),
+                        amount: Uint128::from(100_000_000_000_000u128),
+                    }],
+                )
+                .unwrap();
+            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
+                .unwrap();
+
+            //Increase Debt
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(5_000_000_000_000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
+                .unwrap();
+
+            //Call liquidate on CDP contract
+            let msg = ExecuteMsg::Liquidate {
+                position_id: Uint128::new(1u128),
+                position_owner: "bigger_bank".to_string(),
+                unwrap_lp: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Call LiqRepay to mimic a successfull SP Liquidate call
+            let msg = ExecuteMsg::LiqRepay {};
+
+            app.send_tokens(
+                Addr::unchecked("coin_God"),
+                Addr::unchecked(sp_addr.clone()),
+                &vec![coin(222_222_222_222, "credit_fulldenom")],
+            )
+            .unwrap();
+            let cosmos_msg = cdp_contract
+                .call(msg, vec![coin(222_222_222_222, "credit_fulldenom")])
+                .unwrap();
+            app.execute(Addr::unchecked(sp_addr.clone()), cosmos_msg)
+                .unwrap();
+
+            let query_msg = QueryMsg::GetBasketPositions {
+                start_after: None, 
+                limit: None,
+                user: None,
+                user_info: Some(
+                    UserInfo {
+                        position_id: Uint128::new(1),
+                        position_owner: "bigger_bank".to_string(),
+                    }
+                ),
+            };
+            let res: Vec<BasketPositionsResponse> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+                .unwrap();
+            assert_eq!(
+                res[0].positions[0].collateral_assets[0].asset.amount,
+                Uint128::new(97288888888893)
+            );
+
+            //Assert sell wall wasn't sent assets
+            assert_eq!(
+                app.wrap().query_all_balances(router_addr.clone()).unwrap(),
+                vec![]
+            );
+
+            //Assert fees were sent.
+            assert_eq!(
+                app.wrap()
+                    .query_all_balances(staking_contract.clone())
+                    .unwrap(),
+                vec![coin(22_222_222_222, "debit")]
+            );
+            assert_eq!(
+                app.wrap().query_all_balances(USER).unwrap(),
+                vec![coin(100000000000, "2nddebit"), coin(544_444_444_444, "debit")]
+            );
 
             //Assert collateral to be liquidated was sent
             assert_eq!(
                 app.wrap().query_all_balances(sp_addr.clone()).unwrap(),
-                vec![coin(2277_777778, "credit_fulldenom"), coin(549_999_998, "debit")]
+                vec![
+                    coin(2777_777777, "credit_fulldenom"),
+                    coin(244_444444444, "debit")
+                ]
             );
             assert_eq!(
                 app.wrap().query_all_balances(lq_contract.addr()).unwrap(),
-                vec![coin(499_000001, "debit")]
+                vec![coin(1_999_999_999_997, "debit")]
             );
         }
 
         #[test]
-        fn liquidate_all_user_repay() {
+        fn liquidate_minimums() {
             let (mut app, cdp_contract, lq_contract) =
-                proper_instantiate(false, false, true, true);
+                proper_instantiate(false, false, true, false);
 
             let res: Config = app
                 .wrap()
@@ -7003,6 +7810,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -7027,6 +7837,9 @@ mod tests {
                 cpc_multiplier: None,
                 rate_slope_multiplier: None,
                 rate_hike_rate: None,
+                flash_mint_cap: None,
+                flash_mint_fee: None,
+                price_policy: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -7047,12 +7860,16 @@ mod tests {
                 .unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Mint
+            //Increase Debt to a point where a liquidations:
+            //1. Liquidates less than the debt_minimum
+            //2. Brings the position below the minimum debt
+            //..Which then results in a full liquidation
             let msg = ExecuteMsg::IncreaseDebt {
                 position_id: Uint128::from(1u128),
                 amount: Some(Uint128::from(999_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -7061,10 +7878,20 @@ mod tests {
             let msg = ExecuteMsg::Liquidate {
                 position_id: Uint128::new(1u128),
                 position_owner: USER.to_string(),
+                unwrap_lp: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
+            //Call LiqRepay to mimic a successfull SP Liquidate call
+            let msg = ExecuteMsg::LiqRepay {};
+
+            let cosmos_msg = cdp_contract
+                .call(msg, vec![coin(499_999999, "credit_fulldenom")])
+                .unwrap();
+            app.execute(Addr::unchecked(sp_addr.clone()), cosmos_msg)
+                .unwrap();
+
             let query_msg = QueryMsg::GetBasketPositions {
                 start_after: None, 
                 limit: None,
@@ -7080,7 +7907,13 @@ mod tests {
                 .wrap()
                 .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
                 .unwrap();
-            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(99790_210000));
+            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(98741_210001));
+
+            //Assert sell wall wasn't sent assets
+            assert_eq!(
+                app.wrap().query_all_balances(router_addr.clone()).unwrap(),
+                vec![]
+            );
 
             //Assert fees were sent.
             assert_eq!(
@@ -7094,21 +7927,21 @@ mod tests {
                 vec![coin(100000_000000, "2nddebit"), coin(199_800000, "debit")]
             );
 
-            //Assert collateral to be liquidated wasn't sent bc user repaid themselves
+            //Assert collateral to be liquidated was sent
             assert_eq!(
                 app.wrap().query_all_balances(sp_addr.clone()).unwrap(),
-                vec![coin(2777_777777, "credit_fulldenom")]
+                vec![coin(2277_777778, "credit_fulldenom"), coin(549_999_998, "debit")]
             );
             assert_eq!(
                 app.wrap().query_all_balances(lq_contract.addr()).unwrap(),
-                vec![]
+                vec![coin(499_000001, "debit")]
             );
         }
 
         #[test]
-        fn debt_caps() {
+        fn liquidate_all_user_repay() {
             let (mut app, cdp_contract, lq_contract) =
-                proper_instantiate(false, true, false, false);
+                proper_instantiate(false, false, true, true);
 
             let res: Config = app
                 .wrap()
@@ -7118,25 +7951,22 @@ mod tests {
             let router_addr = res.dex_router.unwrap();
             let staking_contract = res.staking_contract.unwrap();
 
-            //Add LP pool assets first
+            //Add liq-queue to the initial basket
             let msg = ExecuteMsg::EditBasket(EditBasket {
                 take_revenue: None,
-                added_cAsset: Some(cAsset {
-                    asset: Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: "base".to_string(),
-                        },
-                        amount: Uint128::zero(),
+                added_cAsset: None,
+                liq_queue: Some(lq_contract.addr().to_string()),
+                credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
+                collateral_supply_caps: Some(vec![SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
                     },
-                    max_borrow_LTV: Decimal::percent(40),
-                    max_LTV: Decimal::percent(60),
-                    pool_info: None,
-                    rate_index: Decimal::one(),
-                    hike_rates: Some(false),
-                }),
-                liq_queue: None,
-                credit_pool_infos: None,
-                collateral_supply_caps: None,
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                }]),
                 base_interest_rate: None,
                 credit_asset_twap_price_source: None,
                 negative_rates: None,
@@ -7144,61 +7974,220 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Add LP pool assets first
-            let msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
-                added_cAsset: Some(cAsset {
-                    asset: Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: "quote".to_string(),
-                        },
-                        amount: Uint128::zero(),
-                    },
-                    max_borrow_LTV: Decimal::percent(40),
-                    max_LTV: Decimal::percent(60),
-                    pool_info: None,
-                    rate_index: Decimal::one(),
-                    hike_rates: Some(false),
-                }),
-                liq_queue: None,
-                credit_pool_infos: None,
-                collateral_supply_caps: None,
-                base_interest_rate: None,
-                credit_asset_twap_price_source: None,
-                negative_rates: None,
-                cpc_margin_of_error: None,
-                frozen: None,
-                rev_to_stakers: None,
-                multi_asset_supply_caps: None,
+            //Lower debt minimum
+            let msg = ExecuteMsg::UpdateConfig(UpdateConfig {
+                owner: None,
+                stability_pool: None,
+                dex_router: None,
+                osmosis_proxy: None,
+                debt_auction: None,
+                staking_contract: None,
+                oracle_contract: None,
+                liquidity_contract: None,
+                discounts_contract: None,
+                liq_fee: None,
+                debt_minimum: Some(Uint128::new(500u128)),
+                base_debt_cap_multiplier: None,
+                oracle_time_limit: None,
+                collateral_twap_timeframe: None,
+                credit_twap_timeframe: None,
+                cpc_multiplier: None,
+                rate_slope_multiplier: None,
+                rate_hike_rate: None,
+                flash_mint_cap: None,
+                flash_mint_fee: None,
+                price_policy: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Edit initial basket
-            let msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
-                added_cAsset: Some(cAsset {
-                    asset: Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: "lp_denom".to_string(),
-                        },
-                        amount: Uint128::zero(),
-                    },
-                    max_borrow_LTV: Decimal::percent(40),
-                    max_LTV: Decimal::percent(60),
-                    pool_info: Some(PoolInfo {
-                        pool_id: 99u64,
-                        asset_infos: vec![
-                            LPAssetInfo {
-                                info: AssetInfo::NativeToken {
-                                    denom: String::from("base"),
-                                },
-                                decimals: 6u64,
-                                ratio: Decimal::percent(50),
+            //Initial Deposit
+            let msg = ExecuteMsg::Deposit {
+                position_owner: Some(USER.to_string()),
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(
+                    msg,
+                    vec![Coin {
+                        denom: "debit".to_string(),
+                        amount: Uint128::from(100_000_000000u128),
+                    }],
+                )
+                .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Mint
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(999_000000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Call liquidate on CDP contract
+            let msg = ExecuteMsg::Liquidate {
+                position_id: Uint128::new(1u128),
+                position_owner: USER.to_string(),
+                unwrap_lp: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let query_msg = QueryMsg::GetBasketPositions {
+                start_after: None, 
+                limit: None,
+                user: None,
+                user_info: Some(
+                    UserInfo {
+                        position_id: Uint128::new(1),
+                        position_owner: USER.to_string(),
+                    }
+                ),
+            };
+            let res: Vec<BasketPositionsResponse> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+                .unwrap();
+            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(99790_210000));
+
+            //Assert fees were sent.
+            assert_eq!(
+                app.wrap()
+                    .query_all_balances(staking_contract.clone())
+                    .unwrap(),
+                vec![coin(9_990_000, "debit")]
+            );
+            assert_eq!(
+                app.wrap().query_all_balances(USER).unwrap(),
+                vec![coin(100000_000000, "2nddebit"), coin(199_800000, "debit")]
+            );
+
+            //Assert collateral to be liquidated wasn't sent bc user repaid themselves
+            assert_eq!(
+                app.wrap().query_all_balances(sp_addr.clone()).unwrap(),
+                vec![coin(2777_777777, "credit_fulldenom")]
+            );
+            assert_eq!(
+                app.wrap().query_all_balances(lq_contract.addr()).unwrap(),
+                vec![]
+            );
+        }
+
+        #[test]
+        fn debt_caps() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, true, false, false);
+
+            let res: Config = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {})
+                .unwrap();
+            let sp_addr = res.stability_pool.unwrap();
+            let router_addr = res.dex_router.unwrap();
+            let staking_contract = res.staking_contract.unwrap();
+
+            //Add LP pool assets first
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "base".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(40),
+                    max_LTV: Decimal::percent(60),
+                    pool_info: None,
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Add LP pool assets first
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "quote".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(40),
+                    max_LTV: Decimal::percent(60),
+                    pool_info: None,
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Edit initial basket
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "lp_denom".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(40),
+                    max_LTV: Decimal::percent(60),
+                    pool_info: Some(PoolInfo {
+                        pool_id: 99u64,
+                        asset_infos: vec![
+                            LPAssetInfo {
+                                info: AssetInfo::NativeToken {
+                                    denom: String::from("base"),
+                                },
+                                decimals: 6u64,
+                                ratio: Decimal::percent(50),
                             },
                             LPAssetInfo {
                                 info: AssetInfo::NativeToken {
@@ -7211,6 +8200,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -7263,6 +8254,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -7290,6 +8284,7 @@ mod tests {
                 amount: Some(Uint128::from(249_997_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -7346,6 +8341,7 @@ mod tests {
                 position_id: Uint128::from(1u128),
                 assets,
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -7392,6 +8388,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -7424,6 +8422,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -7456,6 +8457,7 @@ mod tests {
                 amount: Some(Uint128::from(2000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
@@ -7512,6 +8514,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -7556,6 +8561,7 @@ mod tests {
                 amount: Some(Uint128::from(2000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -7607,6 +8613,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -7651,33 +8660,104 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
                 .unwrap();
             
             //Query the indebted position
-            // let query_msg = QueryMsg::GetPositionInsolvency {
-            //     position_id: Uint128::new(1),
-            //     position_owner: String::from("bigger_bank"),
-            // };
-            // let res: InsolvencyResponse = app
-            //     .wrap()
-            //     .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
-            //     .unwrap();
-            // //Assert no insolvencies
-            // assert_eq!(
-            //     res.insolvent_positions,
-            //     vec![InsolventPosition {
-            //         insolvent: false,
-            //         position_info: UserInfo {
-            //             position_id: Uint128::new(1),
-            //             position_owner: String::from("bigger_bank"),
-            //         },
-            //         current_LTV: Decimal::percent(5) * Decimal::percent(10),
-            //         available_fee: Uint128::zero(),
-            //     }]
-            // );
+            let query_msg = QueryMsg::GetPositionInsolvency {
+                user_info: Some(UserInfo {
+                    position_id: Uint128::new(1),
+                    position_owner: String::from("bigger_bank"),
+                }),
+                start_after: None,
+                limit: None,
+                min_LTV: None,
+                collateral_asset: None,
+            };
+            let res: InsolvencyResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+                .unwrap();
+            //Assert no insolvencies
+            assert_eq!(
+                res.insolvent_positions,
+                vec![InsolventPosition {
+                    insolvent: false,
+                    position_info: UserInfo {
+                        position_id: Uint128::new(1),
+                        position_owner: String::from("bigger_bank"),
+                    },
+                    current_LTV: Decimal::percent(5) * Decimal::percent(10),
+                    avg_max_LTV: Decimal::percent(70),
+                    LTV_buffer: Decimal::percent(70) - (Decimal::percent(5) * Decimal::percent(10)),
+                    available_fee: Uint128::zero(),
+                }]
+            );
+
+            //Scan all positions
+            let query_msg = QueryMsg::GetPositionInsolvency {
+                user_info: None,
+                start_after: None,
+                limit: None,
+                min_LTV: None,
+                collateral_asset: None,
+            };
+            let res: InsolvencyResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+                .unwrap();
+            assert_eq!(res.insolvent_positions.len(), 2);
+            assert_eq!(res.insolvent_positions[1].position_info.position_owner, String::from("little_bank"));
+            assert_eq!(res.insolvent_positions[1].current_LTV, Decimal::zero());
+            assert_eq!(res.last_position_owner, Some(String::from("little_bank")));
+
+            //Paginate
+            let query_msg = QueryMsg::GetPositionInsolvency {
+                user_info: None,
+                start_after: Some(String::from("bigger_bank")),
+                limit: Some(1),
+                min_LTV: None,
+                collateral_asset: None,
+            };
+            let res: InsolvencyResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+                .unwrap();
+            assert_eq!(res.insolvent_positions.len(), 1);
+            assert_eq!(res.insolvent_positions[0].position_info.position_owner, String::from("little_bank"));
+
+            //Filter by min LTV
+            let query_msg = QueryMsg::GetPositionInsolvency {
+                user_info: None,
+                start_after: None,
+                limit: None,
+                min_LTV: Some(Decimal::percent(5) * Decimal::percent(10)),
+                collateral_asset: None,
+            };
+            let res: InsolvencyResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+                .unwrap();
+            assert_eq!(res.insolvent_positions.len(), 1);
+            assert_eq!(res.insolvent_positions[0].position_info.position_owner, String::from("bigger_bank"));
+
+            //Filter by collateral asset
+            let query_msg = QueryMsg::GetPositionInsolvency {
+                user_info: None,
+                start_after: None,
+                limit: None,
+                min_LTV: None,
+                collateral_asset: Some(AssetInfo::NativeToken { denom: String::from("2nddebit") }),
+            };
+            let res: InsolvencyResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+                .unwrap();
+            assert_eq!(res.insolvent_positions, vec![]);
+            assert_eq!(res.last_position_owner, Some(String::from("little_bank")));
         }
 
         // #[test]
@@ -7708,6 +8788,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -7740,6 +8822,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -7773,6 +8858,7 @@ mod tests {
                 amount: Some(Uint128::from(4999_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -7827,6 +8913,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -7859,6 +8947,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -7892,6 +8983,7 @@ mod tests {
                 amount: Some(Uint128::from(249_995_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -7946,6 +9038,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -7957,6 +9051,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -7976,6 +9073,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -7987,6 +9086,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -8024,6 +9126,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -8076,6 +9180,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -8103,6 +9210,7 @@ mod tests {
                 amount: Some(Uint128::from(100_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             let err = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap_err();
@@ -8134,6 +9242,7 @@ mod tests {
                 amount: Some(Uint128::from(2000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
@@ -8159,6 +9268,7 @@ mod tests {
                     amount: Uint128::from(100_000_000_000_000_000_000u128),
                 }],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -8180,6 +9290,7 @@ mod tests {
                     amount: Uint128::from(1000u128),
                 }],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -8214,6 +9325,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -8225,6 +9338,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -8244,6 +9360,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -8255,6 +9373,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -8292,6 +9413,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -8344,6 +9467,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -8371,6 +9497,7 @@ mod tests {
                 amount: Some(Uint128::from(100_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             let err = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap_err();
@@ -8402,6 +9529,7 @@ mod tests {
                 amount: Some(Uint128::from(2000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
@@ -8427,6 +9555,7 @@ mod tests {
                     amount: Uint128::from(100_000_000_000_000_000_000u128),
                 }],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -8448,6 +9577,7 @@ mod tests {
                     amount: Uint128::from(1000u128),
                 }],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg)
@@ -8482,6 +9612,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -8493,6 +9625,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -8512,6 +9647,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -8523,6 +9660,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -8560,6 +9700,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -8620,6 +9762,9 @@ mod tests {
                         supply_cap_ratio: Decimal::percent(50),
                     }
                 ]),
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -8670,6 +9815,7 @@ mod tests {
                 amount: Some(Uint128::from(2000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             let err = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap_err();
@@ -8687,6 +9833,7 @@ mod tests {
                     }
                 ],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract
                 .call(
@@ -8722,6 +9869,7 @@ mod tests {
                 amount: Some(Uint128::from(2000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
@@ -8729,113 +9877,2075 @@ mod tests {
             
         }
 
-
+
+        #[test]
+        fn LP_oracle() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            let res: Config = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {})
+                .unwrap();
+            let sp_addr = res.stability_pool.unwrap();
+            let router_addr = res.dex_router.unwrap();
+            let staking_contract = res.staking_contract.unwrap();
+
+            //Add LP pool assets first
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "base".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(40),
+                    max_LTV: Decimal::percent(60),
+                    pool_info: None,
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Add LP pool assets first
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "quote".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(40),
+                    max_LTV: Decimal::percent(60),
+                    pool_info: None,
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Add LP to the initial basket
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "lp_denom".to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    max_borrow_LTV: Decimal::percent(40),
+                    max_LTV: Decimal::percent(60),
+                    pool_info: Some(PoolInfo {
+                        pool_id: 99u64,
+                        asset_infos: vec![
+                            LPAssetInfo {
+                                info: AssetInfo::NativeToken {
+                                    denom: String::from("base"),
+                                },
+                                decimals: 6u64,
+                                ratio: Decimal::percent(50),
+                            },
+                            LPAssetInfo {
+                                info: AssetInfo::NativeToken {
+                                    denom: String::from("quote"),
+                                },
+                                decimals: 6u64,
+                                ratio: Decimal::percent(50),
+                            },
+                        ],
+                    }),
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: Some(lq_contract.addr().to_string()),
+                credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
+                collateral_supply_caps: Some(vec![
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "debit".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "base".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "quote".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "lp_denom".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                ]),
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Initial Deposit
+            let msg = ExecuteMsg::Deposit {
+                position_owner: Some("lp_tester".to_string()),
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(
+                    msg,
+                    vec![Coin {
+                        denom: "lp_denom".to_string(),
+                        amount: Uint128::from(100_000_000_000_000_000_000_000u128),
+                    }],
+                )
+                .unwrap();
+            app.execute(Addr::unchecked("lp_tester"), cosmos_msg)
+                .unwrap();
+
+            //The value of the position should be 200_000
+            //So at 40% borrow LTV I should be able to borrow 80_000
+            //We'll error at the edge first to confirm
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(80_001_000000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("lp_tester"), cosmos_msg)
+                .unwrap_err();
+
+            //Successful Increase
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(80_000_000000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("lp_tester"), cosmos_msg)
+                .unwrap();
+        }
+
+        ///Contract Test Migration
+        #[test]
+        fn misc_query() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, false);
+          
+            //Edit Basket 1
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: None,
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: Some(vec![SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
+                    },
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                }]),
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Initial deposit to Basket 1
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: Some(String::from("sender88")),
+                position_id: None,
+            };
+            app.send_tokens(
+                Addr::unchecked("little_bank"),
+                Addr::unchecked("sender88"),
+                &vec![coin(22, "debit")],
+            )
+            .unwrap();
+            let cosmos_msg = cdp_contract
+                .call(exec_msg, vec![coin(11, "debit")])
+                .unwrap();
+            let res = app
+                .execute(Addr::unchecked("sender88"), cosmos_msg)
+                .unwrap();
+
+            //Simulate LTV mint Query
+            let msg = QueryMsg::SimulateMint { 
+                position_info: UserInfo {
+                    position_id: Uint128::one(),
+                    position_owner: String::from("sender88"),
+                }, 
+                LTV: Decimal::percent(50), 
+            };
+            let resp: Uint128 = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &msg.clone())
+                .unwrap();
+            assert_eq!(
+                resp,
+                Uint128::new(5),
+            );
+
+            //Query BasketPositions
+            let msg = QueryMsg::GetBasketPositions {
+                start_after: Some(String::from("sender88")),
+                limit: None,
+                user: None,
+                user_info: None,
+            };
+
+            let resp: Vec<BasketPositionsResponse> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &msg.clone())
+                .unwrap();
+            assert_eq!(
+                resp.len().to_string(),
+                String::from("0"),
+            );
+
+            //Query UserPositions
+            let msg = QueryMsg::GetBasketPositions {
+                start_after: None, 
+                limit: None,
+                user: Some( String::from("sender88")),
+                user_info: None,
+            };
+            let resp: Vec<BasketPositionsResponse> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &msg.clone())
+                .unwrap();
+            assert_eq!(
+                resp[0].positions[0],
+                PositionResponse { 
+                    position_id: Uint128::new(1), 
+                    collateral_assets: vec![
+                        cAsset {
+                            asset: Asset {
+                                info: AssetInfo::NativeToken {
+                                    denom: "debit".to_string(),
+                                },
+                                amount: Uint128::from(11u128),
+                            },
+                            max_borrow_LTV: Decimal::percent(50),
+                            max_LTV: Decimal::percent(70),
+                            pool_info: None,
+                            rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                        }
+                    ], 
+                    cAsset_ratios: vec![], //arent calc'd in queries anymore
+                    credit_amount: Uint128::zero(), 
+                    avg_borrow_LTV: Decimal::percent(0), //arent calc'd in queries anymore
+                    avg_max_LTV: Decimal::percent(0), //arent calc'd in queries anymore
+                },
+            );
+
+            //Update Config
+            let msg = ExecuteMsg::UpdateConfig(UpdateConfig { 
+                owner: Some(String::from("new_owner")), 
+                stability_pool: Some(String::from("new_sp")), 
+                dex_router: Some(String::from("new_router")),  
+                osmosis_proxy: Some(String::from("new_op")),  
+                debt_auction: Some(String::from("new_auction")),  
+                staking_contract: Some(String::from("new_staking")),  
+                oracle_contract: Some(String::from("new_oracle")),  
+                liquidity_contract: Some(String::from("new_liq_check")),
+                discounts_contract: Some( String::from("new_dc")),
+                liq_fee: Some(Decimal::percent(13)), 
+                debt_minimum: Some(Uint128::zero()), 
+                base_debt_cap_multiplier: Some(Uint128::new(48497)), 
+                oracle_time_limit: Some(33u64), 
+                credit_twap_timeframe: Some(33u64), 
+                collateral_twap_timeframe: Some(33u64), 
+                cpc_multiplier: Some(Decimal::percent(50)),
+                rate_slope_multiplier: Some(Decimal::percent(2)), 
+                rate_hike_rate: Some(Decimal::one()),
+                flash_mint_cap: None,
+                flash_mint_fee: None,
+                price_policy: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let resp: Config = app
+            .wrap()
+            .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {  }.clone())
+            .unwrap();
+
+            assert_eq!(
+                resp,
+                Config { 
+                    owner: Addr::unchecked(ADMIN), 
+                    stability_pool: Some( Addr::unchecked("new_sp")), 
+                    dex_router: Some( Addr::unchecked("new_router")),  
+                    osmosis_proxy: Some( Addr::unchecked("new_op")),  
+                    debt_auction: Some( Addr::unchecked("new_auction")),  
+                    staking_contract: Some( Addr::unchecked("new_staking")),  
+                    oracle_contract: Some( Addr::unchecked("new_oracle")),  
+                    liquidity_contract: Some( Addr::unchecked("new_liq_check")),
+                    discounts_contract: Some( Addr::unchecked("new_dc")),
+                    liq_fee: Decimal::percent(13), 
+                    debt_minimum: Uint128::zero(), 
+                    base_debt_cap_multiplier: Uint128::new(48497), 
+                    oracle_time_limit: 33u64, 
+                    credit_twap_timeframe: 33u64, 
+                    collateral_twap_timeframe: 33u64, 
+                    cpc_multiplier: Decimal::percent(50),
+                    rate_slope_multiplier: Decimal::percent(2), 
+                    rate_hike_rate: Some(Decimal::one()),
+                    flash_mint_cap: None,
+                    flash_mint_fee: None,
+                    price_policy: None,
+                }
+            );
+
+            //Update owner after new owner calls the function
+            //Update Config
+            let msg = ExecuteMsg::UpdateConfig(UpdateConfig { 
+                owner: None, 
+                stability_pool: None, 
+                dex_router: None, 
+                osmosis_proxy: None, 
+                debt_auction: None, 
+                staking_contract: None, 
+                oracle_contract: None, 
+                liquidity_contract: None, 
+                discounts_contract: None, 
+                liq_fee: None, 
+                debt_minimum: None, 
+                base_debt_cap_multiplier: None, 
+                oracle_time_limit: None, 
+                credit_twap_timeframe: None, 
+                collateral_twap_timeframe: None, 
+                cpc_multiplier: None, 
+                rate_slope_multiplier: Some(Decimal::percent(3)), 
+                rate_hike_rate: None,
+                flash_mint_cap: None,
+                flash_mint_fee: None,
+                price_policy: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("new_owner"), cosmos_msg).unwrap();
+
+            let resp: Config = app
+            .wrap()
+            .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {  }.clone())
+            .unwrap();
+
+            assert_eq!(
+                resp,
+                Config { 
+                    owner: Addr::unchecked("new_owner"), 
+                    stability_pool: Some( Addr::unchecked("new_sp")), 
+                    dex_router: Some( Addr::unchecked("new_router")),  
+                    osmosis_proxy: Some( Addr::unchecked("new_op")),  
+                    debt_auction: Some( Addr::unchecked("new_auction")),  
+                    staking_contract: Some( Addr::unchecked("new_staking")),  
+                    oracle_contract: Some( Addr::unchecked("new_oracle")),  
+                    liquidity_contract: Some( Addr::unchecked("new_liq_check")),
+                    discounts_contract: Some( Addr::unchecked("new_dc")),
+                    liq_fee: Decimal::percent(13), 
+                    debt_minimum: Uint128::zero(), 
+                    base_debt_cap_multiplier: Uint128::new(48497), 
+                    oracle_time_limit: 33u64, 
+                    credit_twap_timeframe: 33u64, 
+                    collateral_twap_timeframe: 33u64, 
+                    cpc_multiplier: Decimal::percent(50),
+                    rate_slope_multiplier: Decimal::percent(3),
+                    rate_hike_rate: Some(Decimal::one()),
+                    flash_mint_cap: None,
+                    flash_mint_fee: None,
+                    price_policy: None,
+                }
+            );
+
+        }
+
+        #[test]
+        fn edit_cAsset() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            //Invalid Asset
+            let edit_msg = ExecuteMsg::EditcAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "not_debit".to_string(),
+                },
+                max_borrow_LTV: None,
+                max_LTV: None,
+                isolated_debt_ceiling: None,
+                rate_model: None,
+                remove_isolation: None,
+                ramp_duration: None,
+                volatility_config: None,
+            };
+            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Add liq-queue to the initial basket
+            let msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: None,
+                liq_queue: Some(lq_contract.addr().to_string()),
+                credit_pool_infos: None,
+                collateral_supply_caps: None,
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Successfull edit
+            let edit_msg = ExecuteMsg::EditcAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "debit".to_string(),
+                },
+                max_borrow_LTV: Some(Decimal::percent(82)),
+                max_LTV: Some(Decimal::percent(83)),
+                isolated_debt_ceiling: None,
+                rate_model: None,
+                remove_isolation: None,
+                ramp_duration: None,
+                volatility_config: None,
+            };
+            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Query Basket
+            let resp: Basket = app
+                .wrap()
+                .query_wasm_smart(
+                    cdp_contract.addr(),
+                    &QueryMsg::GetBasket { },
+                )
+                .unwrap();
+
+            assert_eq!(
+                resp.collateral_types[0].max_borrow_LTV,
+                Decimal::percent(82)
+            );
+            assert_eq!(resp.collateral_types[0].max_LTV, Decimal::percent(83));
+
+            //Error: Borrow LTV too high
+            let edit_msg = ExecuteMsg::EditcAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "debit".to_string(),
+                },
+                max_borrow_LTV: Some(Decimal::percent(100)),
+                max_LTV: Some(Decimal::percent(100)),
+                isolated_debt_ceiling: None,
+                rate_model: None,
+                remove_isolation: None,
+                ramp_duration: None,
+                volatility_config: None,
+            };
+            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Successfull edit
+            let edit_msg = ExecuteMsg::EditcAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "debit".to_string(),
+                },
+                max_borrow_LTV: None,
+                max_LTV: Some(Decimal::percent(100)),
+                isolated_debt_ceiling: None,
+                rate_model: None,
+                remove_isolation: None,
+                ramp_duration: None,
+                volatility_config: None,
+            };
+            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Trackers w/o a config return the default
+            let resp: Vec<CollateralVolatilityResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    cdp_contract.addr(),
+                    &QueryMsg::GetCollateralVolatility { },
+                )
+                .unwrap();
+            assert_eq!(resp[0].volatility.config, Some(VolatilityConfig::default()));
+            assert_eq!(resp[0].volatility.index, Decimal::one());
+
+            //Error: Floor above ceiling
+            let volatility_config = VolatilityConfig {
+                window: 24,
+                sampling_interval: 3600,
+                smoothing: VolatilitySmoothing::EMA {},
+                multiplier_floor: Decimal::percent(60),
+                multiplier_ceiling: Decimal::percent(50),
+            };
+            let edit_msg = ExecuteMsg::EditcAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "debit".to_string(),
+                },
+                max_borrow_LTV: None,
+                max_LTV: None,
+                isolated_debt_ceiling: None,
+                rate_model: None,
+                remove_isolation: None,
+                ramp_duration: None,
+                volatility_config: Some(volatility_config.clone()),
+            };
+            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Error: Empty window
+            let edit_msg = ExecuteMsg::EditcAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "debit".to_string(),
+                },
+                max_borrow_LTV: None,
+                max_LTV: None,
+                isolated_debt_ceiling: None,
+                rate_model: None,
+                remove_isolation: None,
+                ramp_duration: None,
+                volatility_config: Some(VolatilityConfig {
+                    window: 0,
+                    multiplier_ceiling: Decimal::percent(90),
+                    ..volatility_config.clone()
+                }),
+            };
+            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Successful volatility config edit
+            let volatility_config = VolatilityConfig {
+                multiplier_ceiling: Decimal::percent(90),
+                ..volatility_config
+            };
+            let edit_msg = ExecuteMsg::EditcAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "debit".to_string(),
+                },
+                max_borrow_LTV: None,
+                max_LTV: None,
+                isolated_debt_ceiling: None,
+                rate_model: None,
+                remove_isolation: None,
+                ramp_duration: None,
+                volatility_config: Some(volatility_config.clone()),
+            };
+            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Index is bound by the new ceiling
+            let resp: Vec<CollateralVolatilityResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    cdp_contract.addr(),
+                    &QueryMsg::GetCollateralVolatility { },
+                )
+                .unwrap();
+            assert_eq!(resp[0].asset, AssetInfo::NativeToken { denom: "debit".to_string() });
+            assert_eq!(resp[0].volatility.config, Some(volatility_config));
+            assert_eq!(resp[0].volatility.index, Decimal::percent(90));
+        }
+
+        #[test]
+        fn open_position_deposit() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            //Add supply caps and a new cAsset (2nddebit)
+            let edit_basket_msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: Some(cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "2nddebit".to_string(),
+                        },
+                        amount: Uint128::from(0u128),
+                    },
+                    max_borrow_LTV: Decimal::percent(50),
+                    max_LTV: Decimal::percent(70),
+                    pool_info: None,
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }),
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: Some(vec![
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "debit".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                    SupplyCap {
+                        asset_info: AssetInfo::NativeToken {
+                            denom: "2nddebit".to_string(),
+                        },
+                        current_supply: Uint128::zero(),
+                        debt_total: Uint128::zero(),
+                        supply_cap_ratio: Decimal::percent(100),
+                        lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                    },
+                ]),
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Testing Position creation
+
+            //Invalid id test
+            let error_exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: Some(Uint128::from(3u128)),
+            };
+
+            //Fail due to a non-existent position
+            //First msg deposits since no positions were initially found, meaning the _id never got tested
+            app.send_tokens(
+                Addr::unchecked("little_bank"),
+                Addr::unchecked("owner"),
+                &vec![coin(22, "debit")],
+            )
+            .unwrap();
+            let cosmos_msg = cdp_contract
+                .call(error_exec_msg, vec![coin(11, "debit")])
+                .unwrap();
+            app.execute(Addr::unchecked("owner"), cosmos_msg.clone())
+                .unwrap();
+            app.execute(Addr::unchecked("owner"), cosmos_msg)
+                .unwrap_err();
+
+            //Fail due to invalid collateral
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(exec_msg, vec![coin(666, "fake_debit")])
+                .unwrap();
+            app.execute(Addr::unchecked("faker"), cosmos_msg)
+                .unwrap_err();
+
+            //Successful attempt
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            app.send_tokens(
+                Addr::unchecked(USER),
+                Addr::unchecked("owner"),
+                &vec![coin(11, "2nddebit"), coin(11, "debit")],
+            )
+            .unwrap();
+            let cosmos_msg = cdp_contract
+                .call(exec_msg.clone(), vec![coin(11, "debit"), coin(11, "2nddebit")])
+                .unwrap();
+            let res = app.execute(Addr::unchecked("owner"), cosmos_msg).unwrap();
+
+            let response = res
+                .events
+                .into_iter()
+                .find(|e| e.attributes.iter().any(|attr| attr.key == "method" && attr.value == "deposit"))
+                .ok_or_else(|| panic!("unable to find deposit event"))
+                .unwrap();
+
+            assert_eq!(
+                response.attributes[1..4],
+                vec![
+                    attr("method", "deposit"),
+                    attr("position_owner", "owner"),
+                    attr("position_id", "2"),
+                ]
+            );
+            assert_eq!(response.attributes[4].value, String::from("[Asset { info: NativeToken { denom: \"debit\" }, amount: Uint128(11) }, Asset { info: NativeToken { denom: \"2nddebit\" }, amount: Uint128(11) }]") );
+
+            //Test max Position amount
+            let cosmos_msg = cdp_contract
+                .call(exec_msg, vec![coin(1, "debit")])
+                .unwrap();
+            let res = app.execute(Addr::unchecked("owner"), cosmos_msg.clone()).unwrap();
+            //This one should fail
+            let res = app.execute(Addr::unchecked("owner"), cosmos_msg.clone()).unwrap_err();
+        }
+
+        #[test]
+        fn repay() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            let edit_basket_msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: None,
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: Some(vec![SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
+                    },
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                }]),
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //NoUserPositions Error
+            let repay_msg = ExecuteMsg::Repay {
+                position_id: Uint128::from(1u128),
+                position_owner: None,
+                send_excess_to: None,
+            };
+            let cosmos_msg = cdp_contract.call(repay_msg, vec![]).unwrap();
+            let res = app
+                .execute(Addr::unchecked("sender88"), cosmos_msg)
+                .unwrap_err();
+
+            //Initial deposit
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract.call(exec_msg, coins(11, "debit")).unwrap();
+            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Invalid Collateral Error
+            let repay_msg = ExecuteMsg::Repay {
+                position_id: Uint128::from(1u128),
+                position_owner: Some(USER.to_string()),
+                send_excess_to: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(repay_msg, vec![coin(666, "fake_debit")])
+                .unwrap();
+            let res = app
+                .execute(Addr::unchecked("faker"), cosmos_msg)
+                .unwrap_err();
+
+            //NonExistent Position Error
+            let repay_msg = ExecuteMsg::Repay {
+                position_id: Uint128::from(3u128),
+                position_owner: Some(USER.to_string()),
+                send_excess_to: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(repay_msg, vec![coin(111, "credit_fulldenom")])
+                .unwrap();
+            let res = app
+                .execute(Addr::unchecked("coin_God"), cosmos_msg)
+                .unwrap_err();
+        }
+
+        #[test]
+        fn increase_debt() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            let edit_basket_msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: None,
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: Some(vec![SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
+                    },
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                }]),
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //NoUserPositions Error
+            let increase_debt_msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(1u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(increase_debt_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Initial deposit
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(exec_msg, vec![coin(11_000_000_000_000, "debit")])
+                .unwrap();
+            let res = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
+
+            //NonExistentPosition Error
+            let increase_debt_msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(3u128),
+                amount: Some(Uint128::from(1u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(increase_debt_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap_err();
+
+            //Increase_debt by LTV: Insolvent Error
+            let increase_debt_msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: None,
+                LTV: Some(Decimal::percent(100)),
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(increase_debt_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap_err();
+
+            //Increase_debt by LTV: No amount inputs
+            let increase_debt_msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: None,
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(increase_debt_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap_err();
+
+            //Increase_debt by LTV: Success
+            let increase_debt_msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: None,
+                LTV: Some(Decimal::percent(40)),
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(increase_debt_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
+
+           //Query indebted position
+        //    let query_msg = QueryMsg::GetPositionInsolvency {
+        //     position_id: Uint128::new(1),
+        //     position_owner: String::from("bigger_bank"),
+        //     };
+        //     let res: InsolvencyResponse = app
+        //         .wrap()
+        //         .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
+        //         .unwrap();
+        //     //Assert LTV
+        //     assert_eq!(
+        //         res.insolvent_positions,
+        //         vec![InsolventPosition {
+        //             insolvent: false,
+        //             position_info: UserInfo {
+        //                 position_id: Uint128::new(1),
+        //                 position_owner: String::from("bigger_bank"),
+        //             },
+        //             current_LTV: Decimal::percent(40),
+        //             available_fee: Uint128::zero(),
+        //         }]
+        //     );
+        }
+
+        #[test]
+        fn withdrawal_errors() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            let edit_basket_msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: None,
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: Some(vec![SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
+                    },
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
+                        stability_pool_ratio_for_debt_cap: None,
+                }]),
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let valid_assets: Vec<Asset> = vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "debit".to_string(),
+                },
+                amount: Uint128::from(5u128),
+            }];
+
+            //User has no positions in the basket error
+            let withdrawal_msg = ExecuteMsg::Withdraw {
+                position_id: Uint128::from(1u128),
+                assets: valid_assets.clone(),
+                send_to: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Initial Deposit
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract.call(exec_msg, coins(11, "debit")).unwrap();
+            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Non-existent position error but user still has positions in the basket
+            let withdrawal_msg = ExecuteMsg::Withdraw {
+                position_id: Uint128::from(3u128),
+                assets: vec![Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
+                    },
+                    amount: Uint128::zero(),
+                }],
+                send_to: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+
+            //Invalid collateral fail
+            let assets: Vec<Asset> = vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "notdebit".to_string(),
+                },
+                amount: Uint128::from(10u128),
+            }];
+
+            let withdrawal_msg = ExecuteMsg::Withdraw {
+                position_id: Uint128::from(1u128),
+                assets: assets.clone(),
+                send_to: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+
+            //Withdrawing too much error
+            let assets: Vec<Asset> = vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "debit".to_string(),
+                },
+                amount: Uint128::from(333333333u128),
+            }];
+
+            let withdrawal_msg = ExecuteMsg::Withdraw {
+                position_id: Uint128::from(1u128),
+                assets,
+                send_to: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+        }
+
+        #[test]
+        fn close_position_errors() {
+            let (mut app, cdp_contract, lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            //User has no positions in the basket error
+            let close_msg = ExecuteMsg::ClosePosition {
+                position_id: Uint128::from(1u128),
+                max_spread: Decimal::percent(1),
+                send_to: None,
+            };
+            let cosmos_msg = cdp_contract.call(close_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+
+            //Initial Deposit
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract.call(exec_msg, coins(100_000, "debit")).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Invalid spread error
+            let close_msg = ExecuteMsg::ClosePosition {
+                position_id: Uint128::from(1u128),
+                max_spread: Decimal::percent(100),
+                send_to: None,
+            };
+            let cosmos_msg = cdp_contract.call(close_msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Max spread must be less than 100%\"")
+            );
+
+            //Only the owner can close the Position
+            let close_msg = ExecuteMsg::ClosePosition {
+                position_id: Uint128::from(1u128),
+                max_spread: Decimal::percent(1),
+                send_to: None,
+            };
+            let cosmos_msg = cdp_contract.call(close_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("not_owner"), cosmos_msg).unwrap_err();
+
+            //No debt to repay error
+            let close_msg = ExecuteMsg::ClosePosition {
+                position_id: Uint128::from(1u128),
+                max_spread: Decimal::percent(1),
+                send_to: None,
+            };
+            let cosmos_msg = cdp_contract.call(close_msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Position has no debt to repay, use Withdraw instead\"")
+            );
+        }
+
+        #[test]
+        fn close_position() {
+            let (mut app, cdp_contract, _lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            let res: Config = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {})
+                .unwrap();
+            let osmosis_proxy = res.osmosis_proxy.unwrap();
+
+            //Fund the proxy for its mock swaps
+            app.send_tokens(
+                Addr::unchecked("sender"),
+                osmosis_proxy.clone(),
+                &[coin(10_000_000_000, "credit_fulldenom")],
+            ).unwrap();
+
+            //Set supply caps
+            let edit_basket_msg = ExecuteMsg::EditBasket(EditBasket {
+                take_revenue: None,
+                added_cAsset: None,
+                liq_queue: None,
+                credit_pool_infos: None,
+                collateral_supply_caps: Some(vec![SupplyCap {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "debit".to_string(),
+                    },
+                    current_supply: Uint128::zero(),
+                    debt_total: Uint128::zero(),
+                    supply_cap_ratio: Decimal::percent(100),
+                    lp: false,
+                    stability_pool_ratio_for_debt_cap: None,
+                }]),
+                base_interest_rate: None,
+                credit_asset_twap_price_source: None,
+                negative_rates: None,
+                cpc_margin_of_error: None,
+                frozen: None,
+                rev_to_stakers: None,
+                multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
+            });
+            let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Initial Deposit
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract.call(exec_msg, coins(10_000_000_000, "debit")).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Increase Debt
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(3_000_000_000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Close the Position, selling the debt's value + the max spread
+            let close_msg = ExecuteMsg::ClosePosition {
+                position_id: Uint128::from(1u128),
+                max_spread: Decimal::percent(1),
+                send_to: None,
+            };
+            let cosmos_msg = cdp_contract.call(close_msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Debt was repaid with the sale
+            let response = res
+                .events
+                .iter()
+                .find(|e| e.attributes.iter().any(|attr| attr.value == "close_position_reply"))
+                .ok_or_else(|| panic!("unable to find CLOSE_POSITION_REPLY event"))
+                .unwrap();
+            assert!(response.attributes.contains(&attr("credit_received", "3030000000")));
+            assert!(response.attributes.contains(&attr(
+                "withdrawn_assets",
+                format!("{:?}", vec![Asset {
+                    info: AssetInfo::NativeToken { denom: String::from("debit") },
+                    amount: Uint128::new(6_970_000_000),
+                }])
+            )));
+
+            //Sold collateral went to the proxy
+            assert_eq!(
+                app.wrap().query_balance(osmosis_proxy, "debit").unwrap(),
+                coin(3_030_000_000, "debit")
+            );
+            //Excess credit & leftover collateral were returned to the owner
+            assert_eq!(
+                app.wrap().query_all_balances(USER).unwrap(),
+                vec![coin(100_000_000_000, "2nddebit"), coin(30_000_000, "credit_fulldenom"), coin(96_970_000_000, "debit")]
+            );
+
+            //Position was removed
+            let query_msg = QueryMsg::GetBasketPositions {
+                start_after: None,
+                limit: None,
+                user: Some(USER.to_string()),
+                user_info: None,
+            };
+            app.wrap()
+                .query_wasm_smart::<Vec<BasketPositionsResponse>>(cdp_contract.addr(), &query_msg)
+                .unwrap_err();
+
+            //Debt was removed from the Basket
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
+                .unwrap();
+            assert_eq!(basket.credit_asset.amount, Uint128::zero());
+        }
+
+        #[test]
+        fn position_orders() {
+            let (mut app, cdp_contract, _lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            //Initial Deposit
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract.call(exec_msg, coins(100_000, "debit")).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Error: Position doesn't hold the trigger asset
+            let order_msg = ExecuteMsg::SetPositionOrder {
+                position_id: Uint128::from(1u128),
+                trigger: OrderTrigger::PriceBelow {
+                    asset: AssetInfo::NativeToken { denom: String::from("2nddebit") },
+                    price: Decimal::percent(50),
+                },
+                action: OrderAction::Close { max_spread: Decimal::percent(1) },
+            };
+            let cosmos_msg = cdp_contract.call(order_msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Position doesn't hold trigger asset: 2nddebit\"")
+            );
+
+            //Only the owner can set orders
+            let order_msg = ExecuteMsg::SetPositionOrder {
+                position_id: Uint128::from(1u128),
+                trigger: OrderTrigger::LTVAbove { LTV: Decimal::percent(50) },
+                action: OrderAction::Close { max_spread: Decimal::percent(1) },
+            };
+            let cosmos_msg = cdp_contract.call(order_msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked("executor"), cosmos_msg).unwrap_err();
+
+            //Take profit order w/ a tip
+            let take_profit_msg = ExecuteMsg::SetPositionOrder {
+                position_id: Uint128::from(1u128),
+                trigger: OrderTrigger::PriceAbove {
+                    asset: AssetInfo::NativeToken { denom: String::from("debit") },
+                    price: Decimal::percent(90),
+                },
+                action: OrderAction::Withdraw {
+                    assets: vec![Asset {
+                        info: AssetInfo::NativeToken { denom: String::from("debit") },
+                        amount: Uint128::new(40_000),
+                    }],
+                },
+            };
+            //Error: Tips must be the Basket's credit asset or a collateral type
+            let cosmos_msg = cdp_contract.call(take_profit_msg.clone(), coins(1_000, "2nddebit")).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Tip must be the Basket's credit asset or a collateral type\"")
+            );
+            let cosmos_msg = cdp_contract.call(take_profit_msg, coins(1_000, "debit")).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Stop loss order
+            let cosmos_msg = cdp_contract.call(order_msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Query orders
+            let orders: Vec<PositionOrder> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetPositionOrders {
+                    position_owner: Some(String::from(USER)),
+                    start_after: None,
+                    limit: None,
+                })
+                .unwrap();
+            assert_eq!(orders.len(), 2);
+            assert_eq!(orders[0].order_id, 1u64);
+            assert_eq!(orders[0].tip, Some(Asset {
+                info: AssetInfo::NativeToken { denom: String::from("debit") },
+                amount: Uint128::new(1_000),
+            }));
+            assert_eq!(orders[1].tip, None);
+
+            //Error: Stop loss hasn't triggered, the Position has no debt
+            let cosmos_msg = cdp_contract.call(ExecuteMsg::ExecutePositionOrder { order_id: 2 }, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("executor"), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Order 2 hasn't been triggered\"")
+            );
+
+            //Execute the take profit order as a non-owner
+            assert_eq!(app.wrap().query_all_balances(Addr::unchecked("executor")).unwrap(), vec![]);
+            let cosmos_msg = cdp_contract.call(ExecuteMsg::ExecutePositionOrder { order_id: 1 }, vec![]).unwrap();
+            app.execute(Addr::unchecked("executor"), cosmos_msg).unwrap();
+
+            //Executor was tipped
+            assert_eq!(
+                app.wrap().query_all_balances(Addr::unchecked("executor")).unwrap(),
+                vec![coin(1_000, "debit")]
+            );
+            //Collateral was withdrawn from the Position
+            let res: Vec<BasketPositionsResponse> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasketPositions {
+                    start_after: None,
+                    limit: None,
+                    user: Some(String::from(USER)),
+                    user_info: None,
+                })
+                .unwrap();
+            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(60_000));
+
+            //Error: Orders execute once
+            let cosmos_msg = cdp_contract.call(ExecuteMsg::ExecutePositionOrder { order_id: 1 }, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("executor"), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Order 1 doesn't exist\"")
+            );
+
+            //Only the owner can cancel
+            let cosmos_msg = cdp_contract.call(ExecuteMsg::CancelPositionOrder { order_id: 2 }, vec![]).unwrap();
+            app.execute(Addr::unchecked("executor"), cosmos_msg).unwrap_err();
+
+            //Cancel the stop loss
+            let cosmos_msg = cdp_contract.call(ExecuteMsg::CancelPositionOrder { order_id: 2 }, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let orders: Vec<PositionOrder> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetPositionOrders {
+                    position_owner: None,
+                    start_after: None,
+                    limit: None,
+                })
+                .unwrap();
+            assert_eq!(orders.len(), 0);
+
+            //Set an order w/ a tip then empty the Position
+            let cosmos_msg = cdp_contract.call(order_msg, coins(500, "debit")).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            let user_balance = app.wrap().query_balance(Addr::unchecked(USER), "debit").unwrap().amount;
+
+            let withdrawal_msg = ExecuteMsg::Withdraw {
+                position_id: Uint128::from(1u128),
+                assets: vec![Asset {
+                    info: AssetInfo::NativeToken { denom: String::from("debit") },
+                    amount: Uint128::new(60_000),
+                }],
+                send_to: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //The closed Position's orders were removed & the tip refunded
+            let orders: Vec<PositionOrder> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetPositionOrders {
+                    position_owner: None,
+                    start_after: None,
+                    limit: None,
+                })
+                .unwrap();
+            assert_eq!(orders.len(), 0);
+            assert_eq!(
+                app.wrap().query_balance(Addr::unchecked(USER), "debit").unwrap().amount,
+                user_balance + Uint128::new(60_500)
+            );
+        }
+
+        #[test]
+        fn position_snapshots() {
+            let (mut app, cdp_contract, _lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            //Initial Deposit
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract.call(exec_msg, coins(100_000_000_000, "debit")).unwrap();
+            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Deposit emits a versioned health event
+            let health_event = res.events.iter().find(|event| event.ty == "wasm-position_health").unwrap();
+            assert!(health_event.attributes.iter().any(|attr| attr.key == "version" && attr.value == "1"));
+            assert!(health_event.attributes.iter().any(|attr| attr.key == "action" && attr.value == "deposit"));
+            assert!(health_event.attributes.iter().any(|attr| attr.key == "LTV" && attr.value == "0"));
+
+            //Increase Debt
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(10_000_000_000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            let health_event = res.events.iter().find(|event| event.ty == "wasm-position_health").unwrap();
+            assert!(health_event.attributes.iter().any(|attr| attr.key == "action" && attr.value == "increase_debt"));
+            assert!(health_event.attributes.iter().any(|attr| attr.key == "credit_amount" && attr.value == "10000000000"));
+
+            //Actions in the same block share a snapshot
+            let snapshots: Vec<PositionSnapshot> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetPositionSnapshots {
+                    position_owner: String::from(USER),
+                    position_id: Uint128::one(),
+                    start_after: None,
+                    limit: None,
+                })
+                .unwrap();
+            assert_eq!(snapshots.len(), 1);
+            assert_eq!(snapshots[0].credit_amount, Uint128::new(10_000_000_000));
+            assert!(!snapshots[0].LTV.unwrap().is_zero());
+
+            //Withdraw in a later block
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(3600u64);
+            });
+            let withdrawal_msg = ExecuteMsg::Withdraw {
+                position_id: Uint128::from(1u128),
+                assets: vec![Asset {
+                    info: AssetInfo::NativeToken { denom: String::from("debit") },
+                    amount: Uint128::from(10_000_000_000u128),
+                }],
+                send_to: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let snapshots: Vec<PositionSnapshot> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetPositionSnapshots {
+                    position_owner: String::from(USER),
+                    position_id: Uint128::one(),
+                    start_after: None,
+                    limit: None,
+                })
+                .unwrap();
+            assert_eq!(snapshots.len(), 2);
+            assert_eq!(snapshots[1].collateral_assets[0].amount, Uint128::new(90_000_000_000));
+            assert!(snapshots[1].LTV.unwrap() > snapshots[0].LTV.unwrap());
+
+            //Paginate by snapshot time
+            let paginated: Vec<PositionSnapshot> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetPositionSnapshots {
+                    position_owner: String::from(USER),
+                    position_id: Uint128::one(),
+                    start_after: Some(snapshots[0].time),
+                    limit: None,
+                })
+                .unwrap();
+            assert_eq!(paginated, vec![snapshots[1].clone()]);
+        }
+
+        #[test]
+        fn loop_position_errors() {
+            let (mut app, cdp_contract, _lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            //User has no positions in the basket error
+            let loop_msg = ExecuteMsg::LoopPosition {
+                position_id: Uint128::from(1u128),
+                target_LTV: Decimal::percent(40),
+                max_slippage: Decimal::percent(1),
+                num_loops: None,
+            };
+            let cosmos_msg = cdp_contract.call(loop_msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("User has no positions in this basket")
+            );
+
+            //Initial Deposit
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract.call(exec_msg, coins(100_000, "debit")).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Invalid slippage error
+            let loop_msg = ExecuteMsg::LoopPosition {
+                position_id: Uint128::from(1u128),
+                target_LTV: Decimal::percent(40),
+                max_slippage: Decimal::percent(100),
+                num_loops: None,
+            };
+            let cosmos_msg = cdp_contract.call(loop_msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Max slippage must be less than 100%\"")
+            );
+
+            //Invalid loop count error
+            let loop_msg = ExecuteMsg::LoopPosition {
+                position_id: Uint128::from(1u128),
+                target_LTV: Decimal::percent(40),
+                max_slippage: Decimal::percent(1),
+                num_loops: Some(11),
+            };
+            let cosmos_msg = cdp_contract.call(loop_msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Number of loops must be between 1 and 10\"")
+            );
+
+            //Only the owner can loop the Position
+            let loop_msg = ExecuteMsg::LoopPosition {
+                position_id: Uint128::from(1u128),
+                target_LTV: Decimal::percent(40),
+                max_slippage: Decimal::percent(1),
+                num_loops: None,
+            };
+            let cosmos_msg = cdp_contract.call(loop_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("not_owner"), cosmos_msg).unwrap_err();
+
+            //Target LTV above the Position's borrow LTV error
+            let loop_msg = ExecuteMsg::LoopPosition {
+                position_id: Uint128::from(1u128),
+                target_LTV: Decimal::percent(60),
+                max_slippage: Decimal::percent(1),
+                num_loops: None,
+            };
+            let cosmos_msg = cdp_contract.call(loop_msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Invalid target_LTV for debt increase: 0.6")
+            );
+        }
+
+        #[test]
+        fn flash_mint() {
+            let (mut app, cdp_contract, _lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            //Instantiate the receiver
+            let receiver_id = app.store_code(flash_mint_receiver_contract());
+            let receiver = app
+                .instantiate_contract(
+                    receiver_id,
+                    Addr::unchecked(ADMIN),
+                    &FlashMint_MockInstantiateMsg {},
+                    &[],
+                    "test",
+                    None,
+                )
+                .unwrap();
+            //The mock proxy doesn't mint so the receiver is prefunded
+            app.send_tokens(
+                Addr::unchecked("sender"),
+                receiver.clone(),
+                &[coin(10_000_000_000, "credit_fulldenom")],
+            )
+            .unwrap();
+
+            //Error: Flash minting is disabled by default
+            let msg = ExecuteMsg::FlashMint {
+                amount: Uint128::new(1_000_000_000),
+                receiver: receiver.to_string(),
+                msg: to_binary(&FlashMint_MockExecuteMsg::ReturnCredit { amount: Uint128::new(1_001_000_000) }).unwrap(),
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Flash minting is disabled\"")
+            );
+
+            //Set the cap & fee
+            let msg = ExecuteMsg::UpdateConfig(UpdateConfig {
+                owner: None,
+                stability_pool: None,
+                dex_router: None,
+                osmosis_proxy: None,
+                debt_auction: None,
+                staking_contract: None,
+                oracle_contract: None,
+                liquidity_contract: None,
+                discounts_contract: None,
+                liq_fee: None,
+                debt_minimum: None,
+                base_debt_cap_multiplier: None,
+                oracle_time_limit: None,
+                credit_twap_timeframe: None,
+                collateral_twap_timeframe: None,
+                cpc_multiplier: None,
+                rate_slope_multiplier: None,
+                rate_hike_rate: None,
+                flash_mint_cap: Some(Uint128::new(5_000_000_000)),
+                flash_mint_fee: Some(Decimal::from_ratio(1u128, 1000u128)),
+                price_policy: None,
+            });
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Error: Over the cap
+            let msg = ExecuteMsg::FlashMint {
+                amount: Uint128::new(5_000_000_001),
+                receiver: receiver.to_string(),
+                msg: to_binary(&FlashMint_MockExecuteMsg::ReturnCredit { amount: Uint128::new(5_005_000_001) }).unwrap(),
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Flash mint amount must be between 0 and 5000000000\"")
+            );
+
+            //Error: Fee wasn't returned
+            let msg = ExecuteMsg::FlashMint {
+                amount: Uint128::new(1_000_000_000),
+                receiver: receiver.to_string(),
+                msg: to_binary(&FlashMint_MockExecuteMsg::ReturnCredit { amount: Uint128::new(1_000_000_000) }).unwrap(),
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Flash mint wasn't repaid: 1000000000 < 1001000000\"")
+            );
+
+            //Successful flash mint
+            let msg = ExecuteMsg::FlashMint {
+                amount: Uint128::new(1_000_000_000),
+                receiver: receiver.to_string(),
+                msg: to_binary(&FlashMint_MockExecuteMsg::ReturnCredit { amount: Uint128::new(1_001_000_000) }).unwrap(),
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Fee is added to pending revenue
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
+                .unwrap();
+            assert_eq!(basket.pending_revenue, Uint128::new(1_000_000));
+        }
+
+        #[test]
+        fn multi_basket() {
+            let (mut app, cdp_contract, _lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            let create_basket = CreateBasket {
+                basket_id: Uint128::new(2u128),
+                collateral_types: vec![cAsset {
+                    asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "2nddebit".to_string(),
+                        },
+                        amount: Uint128::from(0u128),
+                    },
+                    max_borrow_LTV: Decimal::percent(50),
+                    max_LTV: Decimal::percent(70),
+                    pool_info: None,
+                    rate_index: Decimal::one(),
+                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
+                }],
+                credit_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "eur_credit".to_string(),
+                    },
+                    amount: Uint128::from(0u128),
+                },
+                credit_price: Decimal::percent(108),
+                base_interest_rate: None,
+                credit_pool_infos: vec![],
+                liq_queue: None,
+            };
+
+            //Error: Only the owner can create Baskets
+            let cosmos_msg = cdp_contract.call(ExecuteMsg::CreateBasket(create_basket.clone()), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+
+            //Error: Credit assets are unique
+            let mut duplicate_credit = create_basket.clone();
+            duplicate_credit.credit_asset.info = AssetInfo::NativeToken { denom: "credit_fulldenom".to_string() };
+            let cosmos_msg = cdp_contract.call(ExecuteMsg::CreateBasket(duplicate_credit), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"credit_fulldenom is already a Basket's credit asset\"")
+            );
+
+            //Create the EUR Basket
+            let cosmos_msg = cdp_contract.call(ExecuteMsg::CreateBasket(create_basket.clone()), vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Error: Basket IDs are unique
+            let cosmos_msg = cdp_contract.call(ExecuteMsg::CreateBasket(create_basket), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Basket 2 already exists\"")
+            );
+
+            //Error: Basket doesn't exist
+            let msg = ExecuteMsg::WithBasket {
+                basket_id: Uint128::new(3u128),
+                msg: Box::new(ExecuteMsg::Deposit {
+                    position_owner: None,
+                    position_id: None,
+                }),
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![coin(1_000, "2nddebit")]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Basket 3 doesn't exist\"")
+            );
+
+            //Error: The default Basket doesn't accept the EUR Basket's collateral
+            let msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![coin(1_000, "2nddebit")]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+
+            //Deposit into the EUR Basket
+            let msg = ExecuteMsg::WithBasket {
+                basket_id: Uint128::new(2u128),
+                msg: Box::new(ExecuteMsg::Deposit {
+                    position_owner: None,
+                    position_id: None,
+                }),
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![coin(1_000, "2nddebit")]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Position is keyed to the EUR Basket
+            let query_msg = QueryMsg::GetBasketPositions {
+                start_after: None,
+                limit: None,
+                user: Some(USER.to_string()),
+                user_info: None,
+            };
+            let res: Vec<BasketPositionsResponse> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::WithBasket {
+                    basket_id: Uint128::new(2u128),
+                    msg: Box::new(query_msg.clone()),
+                })
+                .unwrap();
+            assert_eq!(res[0].positions[0].position_id, Uint128::one());
+            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(1_000));
+            //...and not to the default Basket
+            app
+                .wrap()
+                .query_wasm_smart::<Vec<BasketPositionsResponse>>(cdp_contract.addr(), &query_msg)
+                .unwrap_err();
+
+            //Each Basket tracks its own supply
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::WithBasket {
+                    basket_id: Uint128::new(2u128),
+                    msg: Box::new(QueryMsg::GetBasket { }),
+                })
+                .unwrap();
+            assert_eq!(basket.collateral_supply_caps[0].current_supply, Uint128::new(1_000));
+            assert_eq!(basket.current_position_id, Uint128::new(2u128));
+
+            //Query all Baskets
+            let baskets: Vec<Basket> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBaskets { start_after: None, limit: None })
+                .unwrap();
+            assert_eq!(baskets.len(), 2);
+            assert_eq!(baskets[0].basket_id, Uint128::one());
+            assert_eq!(baskets[1].credit_asset.info, AssetInfo::NativeToken { denom: "eur_credit".to_string() });
+
+            let baskets: Vec<Basket> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBaskets { start_after: Some(Uint128::one()), limit: None })
+                .unwrap();
+            assert_eq!(baskets.len(), 1);
+            assert_eq!(baskets[0].basket_id, Uint128::new(2u128));
+
+            //Operator allowances are per Basket
+            let allowance = OperatorAllowance {
+                position_ids: Some(vec![Uint128::one()]),
+                withdraw: true,
+                increase_debt: false,
+                debt_limit: None,
+                edit_redeemability: false,
+                expiration: None,
+            };
+            let operator_withdraw = ExecuteMsg::WithBasket {
+                basket_id: Uint128::new(2u128),
+                msg: Box::new(ExecuteMsg::Withdraw {
+                    position_id: Uint128::one(),
+                    assets: vec![Asset {
+                        info: AssetInfo::NativeToken { denom: "2nddebit".to_string() },
+                        amount: Uint128::new(10u128),
+                    }],
+                    send_to: None,
+                    position_owner: Some(USER.to_string()),
+                }),
+            };
+
+            //Error: An allowance for the default Basket doesn't cover the EUR Basket's Position 1
+            let msg = ExecuteMsg::EditOperator { operator: String::from("operator"), allowance: Some(allowance.clone()) };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let cosmos_msg = cdp_contract.call(operator_withdraw.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked("operator"), cosmos_msg).unwrap_err();
+
+            //Allowance for the EUR Basket
+            let msg = ExecuteMsg::WithBasket {
+                basket_id: Uint128::new(2u128),
+                msg: Box::new(ExecuteMsg::EditOperator { operator: String::from("operator"), allowance: Some(allowance) }),
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let cosmos_msg = cdp_contract.call(operator_withdraw, vec![]).unwrap();
+            app.execute(Addr::unchecked("operator"), cosmos_msg).unwrap();
+
+            let res: Vec<BasketPositionsResponse> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::WithBasket {
+                    basket_id: Uint128::new(2u128),
+                    msg: Box::new(query_msg),
+                })
+                .unwrap();
+            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::new(990));
+        }
+
+        #[test]
+        fn rate_models() {
+            let (mut app, cdp_contract, _lq_contract) =
+                proper_instantiate(false, false, false, false);
+
+            //Initial Deposit
+            let msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![coin(50_000_000_000, "debit")]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let edit_msg = |rate_model: RateModel| ExecuteMsg::EditcAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "debit".to_string(),
+                },
+                max_borrow_LTV: None,
+                max_LTV: None,
+                isolated_debt_ceiling: None,
+                rate_model: Some(rate_model),
+                remove_isolation: None,
+                ramp_duration: None,
+                volatility_config: None,
+            };
+            let accrue_msg = ExecuteMsg::Accrue {
+                position_owner: None,
+                position_ids: vec![Uint128::one()],
+            };
+
+            //Defaults to the two slope model
+            let cosmos_msg = cdp_contract.call(accrue_msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            let res: CollateralInterestResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetCollateralInterest { })
+                .unwrap();
+            assert_eq!(res.rate_models, vec![RateModel::TwoSlope {}]);
+
+            //Error: Kink has to be between 0 & 100%
+            let cosmos_msg = cdp_contract.call(edit_msg(RateModel::Kinked {
+                base_rate: Decimal::percent(1),
+                kink: Decimal::one(),
+                slope_1: Decimal::percent(4),
+                slope_2: Decimal::percent(75),
+            }), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Invalid kinked rate model parameters\"")
+            );
+
+            //Error: Only the owner can set rate models
+            let cosmos_msg = cdp_contract.call(edit_msg(RateModel::Fixed { rate: Decimal::percent(5) }), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+
+            //Fixed rate
+            let cosmos_msg = cdp_contract.call(edit_msg(RateModel::Fixed { rate: Decimal::percent(5) }), vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            let cosmos_msg = cdp_contract.call(accrue_msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let res: CollateralInterestResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetCollateralInterest { })
+                .unwrap();
+            assert_eq!(res.rates, vec![Decimal::percent(5)]);
+            assert_eq!(res.rate_models, vec![RateModel::Fixed { rate: Decimal::percent(5) }]);
+
+            //Credit deviation, the credit oracle is skipped due to low liquidity so there is no deviation
+            let cosmos_msg = cdp_contract.call(edit_msg(RateModel::CreditDeviation {
+                base_rate: Decimal::percent(3),
+                multiplier: Decimal::percent(200),
+                max_rate: Decimal::percent(20),
+            }), vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            let cosmos_msg = cdp_contract.call(accrue_msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let res: CollateralInterestResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetCollateralInterest { })
+                .unwrap();
+            assert_eq!(res.rates, vec![Decimal::percent(3)]);
+
+            //PID controller holds the min rate while at or under its target utilization
+            let pid = RateModel::PID {
+                target_utilization: Decimal::one(),
+                kp: Decimal::percent(10),
+                ki: Decimal::percent(1),
+                kd: Decimal::zero(),
+                min_rate: Decimal::percent(1),
+                max_rate: Decimal::percent(50),
+            };
+            let cosmos_msg = cdp_contract.call(edit_msg(pid.clone()), vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            let cosmos_msg = cdp_contract.call(accrue_msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(86_400u64);
+            });
+            let cosmos_msg = cdp_contract.call(accrue_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let res: CollateralInterestResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetCollateralInterest { })
+                .unwrap();
+            assert_eq!(res.rates, vec![Decimal::percent(1)]);
+            assert_eq!(res.rate_models, vec![pid]);
+        }
+
         #[test]
-        fn LP_oracle() {
+        fn collateral_deprecation() {
             let (mut app, cdp_contract, lq_contract) =
                 proper_instantiate(false, false, false, false);
 
-            let res: Config = app
-                .wrap()
-                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {})
-                .unwrap();
-            let sp_addr = res.stability_pool.unwrap();
-            let router_addr = res.dex_router.unwrap();
-            let staking_contract = res.staking_contract.unwrap();
-
-            //Add LP pool assets first
-            let msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
-                added_cAsset: Some(cAsset {
-                    asset: Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: "base".to_string(),
-                        },
-                        amount: Uint128::zero(),
-                    },
-                    max_borrow_LTV: Decimal::percent(40),
-                    max_LTV: Decimal::percent(60),
-                    pool_info: None,
-                    rate_index: Decimal::one(),
-                    hike_rates: Some(false),
-                }),
-                liq_queue: None,
-                credit_pool_infos: None,
-                collateral_supply_caps: None,
-                base_interest_rate: None,
-                credit_asset_twap_price_source: None,
-                negative_rates: None,
-                cpc_margin_of_error: None,
-                frozen: None,
-                rev_to_stakers: None,
-                multi_asset_supply_caps: None,
-            });
-            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
-
-            //Add LP pool assets first
+            //Add cAsset to deprecate
             let msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
                 added_cAsset: Some(cAsset {
                     asset: Asset {
                         info: AssetInfo::NativeToken {
-                            denom: "quote".to_string(),
+                            denom: "2nddebit".to_string(),
                         },
-                        amount: Uint128::zero(),
+                        amount: Uint128::from(0u128),
                     },
-                    max_borrow_LTV: Decimal::percent(40),
-                    max_LTV: Decimal::percent(60),
+                    max_borrow_LTV: Decimal::percent(50),
+                    max_LTV: Decimal::percent(90),
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
-                }),
-                liq_queue: None,
-                credit_pool_infos: None,
-                collateral_supply_caps: None,
-                base_interest_rate: None,
-                credit_asset_twap_price_source: None,
-                negative_rates: None,
-                cpc_margin_of_error: None,
-                frozen: None,
-                rev_to_stakers: None,
-                multi_asset_supply_caps: None,
-            });
-            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
-
-            //Add LP to the initial basket
-            let msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
-                added_cAsset: Some(cAsset {
-                    asset: Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: "lp_denom".to_string(),
-                        },
-                        amount: Uint128::zero(),
-                    },
-                    max_borrow_LTV: Decimal::percent(40),
-                    max_LTV: Decimal::percent(60),
-                    pool_info: Some(PoolInfo {
-                        pool_id: 99u64,
-                        asset_infos: vec![
-                            LPAssetInfo {
-                                info: AssetInfo::NativeToken {
-                                    denom: String::from("base"),
-                                },
-                                decimals: 6u64,
-                                ratio: Decimal::percent(50),
-                            },
-                            LPAssetInfo {
-                                info: AssetInfo::NativeToken {
-                                    denom: String::from("quote"),
-                                },
-                                decimals: 6u64,
-                                ratio: Decimal::percent(50),
-                            },
-                        ],
-                    }),
-                    rate_index: Decimal::one(),
-                    hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(lq_contract.addr().to_string()),
                 credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
@@ -8852,27 +11962,7 @@ mod tests {
                     },
                     SupplyCap {
                         asset_info: AssetInfo::NativeToken {
-                            denom: "base".to_string(),
-                        },
-                        current_supply: Uint128::zero(),
-                        debt_total: Uint128::zero(),
-                        supply_cap_ratio: Decimal::percent(100),
-                        lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                    },
-                    SupplyCap {
-                        asset_info: AssetInfo::NativeToken {
-                            denom: "quote".to_string(),
-                        },
-                        current_supply: Uint128::zero(),
-                        debt_total: Uint128::zero(),
-                        supply_cap_ratio: Decimal::percent(100),
-                        lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                    },
-                    SupplyCap {
-                        asset_info: AssetInfo::NativeToken {
-                            denom: "lp_denom".to_string(),
+                            denom: "2nddebit".to_string(),
                         },
                         current_supply: Uint128::zero(),
                         debt_total: Uint128::zero(),
@@ -8888,374 +11978,360 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                take_revenue: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Initial Deposit
+            //Position holding the deprecated asset
             let msg = ExecuteMsg::Deposit {
-                position_owner: Some("lp_tester".to_string()),
+                position_owner: None,
                 position_id: None,
             };
             let cosmos_msg = cdp_contract
-                .call(
-                    msg,
-                    vec![Coin {
-                        denom: "lp_denom".to_string(),
-                        amount: Uint128::from(100_000_000_000_000_000_000_000u128),
-                    }],
-                )
-                .unwrap();
-            app.execute(Addr::unchecked("lp_tester"), cosmos_msg)
+                .call(msg, vec![coin(100_000_000_000, "2nddebit")])
                 .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //The value of the position should be 200_000
-            //So at 40% borrow LTV I should be able to borrow 80_000
-            //We'll error at the edge first to confirm
-            let msg = ExecuteMsg::IncreaseDebt {
-                position_id: Uint128::from(1u128),
-                amount: Some(Uint128::from(80_001_000000u128)),
-                LTV: None,
-                mint_to_addr: None,
+            //Error: Only the owner can deprecate
+            let msg = ExecuteMsg::DeprecatecAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "2nddebit".to_string(),
+                },
+                ramp_duration: 1000,
+                final_max_LTV: Decimal::percent(40),
+                liquidation_incentive: Decimal::percent(5),
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked("lp_tester"), cosmos_msg)
-                .unwrap_err();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
 
-            //Successful Increase
-            let msg = ExecuteMsg::IncreaseDebt {
-                position_id: Uint128::from(1u128),
-                amount: Some(Uint128::from(80_000_000000u128)),
-                LTV: None,
-                mint_to_addr: None,
+            //Error: max_LTV can only ramp down
+            let msg = ExecuteMsg::DeprecatecAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "2nddebit".to_string(),
+                },
+                ramp_duration: 1000,
+                final_max_LTV: Decimal::percent(95),
+                liquidation_incentive: Decimal::percent(5),
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked("lp_tester"), cosmos_msg)
-                .unwrap();
-        }
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
 
-        ///Contract Test Migration
-        #[test]
-        fn misc_query() {
-            let (mut app, cdp_contract, lq_contract) =
-                proper_instantiate(false, false, false, false);
-          
-            //Edit Basket 1
-            let msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
-                added_cAsset: None,
-                liq_queue: None,
-                credit_pool_infos: None,
-                collateral_supply_caps: Some(vec![SupplyCap {
-                    asset_info: AssetInfo::NativeToken {
-                        denom: "debit".to_string(),
-                    },
-                    current_supply: Uint128::zero(),
-                    debt_total: Uint128::zero(),
-                    supply_cap_ratio: Decimal::percent(100),
-                    lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                }]),
-                base_interest_rate: None,
-                credit_asset_twap_price_source: None,
-                negative_rates: None,
-                cpc_margin_of_error: None,
-                frozen: None,
-                rev_to_stakers: None,
-                multi_asset_supply_caps: None,
-            });
+            //Successful deprecation
+            let msg = ExecuteMsg::DeprecatecAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "2nddebit".to_string(),
+                },
+                ramp_duration: 1000,
+                final_max_LTV: Decimal::percent(40),
+                liquidation_incentive: Decimal::percent(5),
+            };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Initial deposit to Basket 1
-            let exec_msg = ExecuteMsg::Deposit {
-                position_owner: Some(String::from("sender88")),
-                position_id: None,
-            };
-            app.send_tokens(
-                Addr::unchecked("little_bank"),
-                Addr::unchecked("sender88"),
-                &vec![coin(22, "debit")],
-            )
-            .unwrap();
-            let cosmos_msg = cdp_contract
-                .call(exec_msg, vec![coin(11, "debit")])
+            let res: Vec<CollateralDeprecation> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetCollateralDeprecations { })
                 .unwrap();
-            let res = app
-                .execute(Addr::unchecked("sender88"), cosmos_msg)
+            assert_eq!(res.len(), 1);
+            assert_eq!(res[0].initial_max_LTV, Decimal::percent(90));
+            assert_eq!(res[0].end_time - res[0].start_time, 1000);
+            assert!(!res[0].removed);
+
+            //Borrow LTV is zeroed
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
                 .unwrap();
+            assert_eq!(basket.collateral_types[1].max_borrow_LTV, Decimal::zero());
 
-            //Simulate LTV mint Query
-            let msg = QueryMsg::SimulateMint { 
-                position_info: UserInfo {
-                    position_id: Uint128::one(),
-                    position_owner: String::from("sender88"),
-                }, 
-                LTV: Decimal::percent(50), 
+            //Error: Deposits are disabled
+            let msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: Some(Uint128::from(1u128)),
             };
-            let resp: Uint128 = app
-                .wrap()
-                .query_wasm_smart(cdp_contract.addr(), &msg.clone())
+            let cosmos_msg = cdp_contract
+                .call(msg, vec![coin(100_000_000, "2nddebit")])
                 .unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
             assert_eq!(
-                resp,
-                Uint128::new(5),
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"2nddebit is deprecated, deposits are disabled\"")
             );
 
-            //Query BasketPositions
-            let msg = QueryMsg::GetBasketPositions {
-                start_after: Some(String::from("sender88")),
-                limit: None,
-                user: None,
-                user_info: None,
+            //Error: Can't borrow against deprecated collateral
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(3000_000_000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
             };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
 
-            let resp: Vec<BasketPositionsResponse> = app
-                .wrap()
-                .query_wasm_smart(cdp_contract.addr(), &msg.clone())
-                .unwrap();
-            assert_eq!(
-                resp.len().to_string(),
-                String::from("0"),
-            );
+            //max_LTV ramps down halfway after half the duration
+            app.set_block(BlockInfo {
+                height: app.block_info().height,
+                time: app.block_info().time.plus_seconds(500u64),
+                chain_id: app.block_info().chain_id,
+            });
+            let msg = ExecuteMsg::Accrue { position_owner: None, position_ids: vec![Uint128::one()] };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Query UserPositions
-            let msg = QueryMsg::GetBasketPositions {
-                start_after: None, 
-                limit: None,
-                user: Some( String::from("sender88")),
-                user_info: None,
-            };
-            let resp: Vec<BasketPositionsResponse> = app
+            let basket: Basket = app
                 .wrap()
-                .query_wasm_smart(cdp_contract.addr(), &msg.clone())
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
                 .unwrap();
-            assert_eq!(
-                resp[0].positions[0],
-                PositionResponse { 
-                    position_id: Uint128::new(1), 
-                    collateral_assets: vec![
-                        cAsset {
-                            asset: Asset {
-                                info: AssetInfo::NativeToken {
-                                    denom: "debit".to_string(),
-                                },
-                                amount: Uint128::from(11u128),
-                            },
-                            max_borrow_LTV: Decimal::percent(50),
-                            max_LTV: Decimal::percent(70),
-                            pool_info: None,
-                            rate_index: Decimal::one(),
-                    hike_rates: Some(false),
-                        }
-                    ], 
-                    cAsset_ratios: vec![], //arent calc'd in queries anymore
-                    credit_amount: Uint128::zero(), 
-                    avg_borrow_LTV: Decimal::percent(0), //arent calc'd in queries anymore
-                    avg_max_LTV: Decimal::percent(0), //arent calc'd in queries anymore
+            assert_eq!(basket.collateral_types[1].max_LTV, Decimal::percent(65));
+
+            //Error: Only the owner can remove
+            let msg = ExecuteMsg::RemovecAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "2nddebit".to_string(),
                 },
-            );
+            };
+            let cosmos_msg = cdp_contract.call(msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
 
-            //Update Config
-            let msg = ExecuteMsg::UpdateConfig(UpdateConfig { 
-                owner: Some(String::from("new_owner")), 
-                stability_pool: Some(String::from("new_sp")), 
-                dex_router: Some(String::from("new_router")),  
-                osmosis_proxy: Some(String::from("new_op")),  
-                debt_auction: Some(String::from("new_auction")),  
-                staking_contract: Some(String::from("new_staking")),  
-                oracle_contract: Some(String::from("new_oracle")),  
-                liquidity_contract: Some(String::from("new_liq_check")),
-                discounts_contract: Some( String::from("new_dc")),
-                liq_fee: Some(Decimal::percent(13)), 
-                debt_minimum: Some(Uint128::zero()), 
-                base_debt_cap_multiplier: Some(Uint128::new(48497)), 
-                oracle_time_limit: Some(33u64), 
-                credit_twap_timeframe: Some(33u64), 
-                collateral_twap_timeframe: Some(33u64), 
-                cpc_multiplier: Some(Decimal::percent(50)),
-                rate_slope_multiplier: Some(Decimal::percent(2)), 
-                rate_hike_rate: Some(Decimal::one()),
-            });
+            //Remove the deprecated asset, its supply is 0 since the Position has no debt
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            let resp: Config = app
-            .wrap()
-            .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {  }.clone())
-            .unwrap();
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
+                .unwrap();
+            assert_eq!(basket.collateral_types.len(), 1);
+            assert_eq!(basket.collateral_supply_caps.len(), 1);
+            assert_eq!(basket.lastest_collateral_rates.len(), 1);
 
+            //Error: Can't remove twice
+            let msg = ExecuteMsg::RemovecAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "2nddebit".to_string(),
+                },
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
             assert_eq!(
-                resp,
-                Config { 
-                    owner: Addr::unchecked(ADMIN), 
-                    stability_pool: Some( Addr::unchecked("new_sp")), 
-                    dex_router: Some( Addr::unchecked("new_router")),  
-                    osmosis_proxy: Some( Addr::unchecked("new_op")),  
-                    debt_auction: Some( Addr::unchecked("new_auction")),  
-                    staking_contract: Some( Addr::unchecked("new_staking")),  
-                    oracle_contract: Some( Addr::unchecked("new_oracle")),  
-                    liquidity_contract: Some( Addr::unchecked("new_liq_check")),
-                    discounts_contract: Some( Addr::unchecked("new_dc")),
-                    liq_fee: Decimal::percent(13), 
-                    debt_minimum: Uint128::zero(), 
-                    base_debt_cap_multiplier: Uint128::new(48497), 
-                    oracle_time_limit: 33u64, 
-                    credit_twap_timeframe: 33u64, 
-                    collateral_twap_timeframe: 33u64, 
-                    cpc_multiplier: Decimal::percent(50),
-                    rate_slope_multiplier: Decimal::percent(2), 
-                    rate_hike_rate: Some(Decimal::one()),
-                }
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Only deprecated collateral can be removed\"")
             );
 
-            //Update owner after new owner calls the function
-            //Update Config
-            let msg = ExecuteMsg::UpdateConfig(UpdateConfig { 
-                owner: None, 
-                stability_pool: None, 
-                dex_router: None, 
-                osmosis_proxy: None, 
-                debt_auction: None, 
-                staking_contract: None, 
-                oracle_contract: None, 
-                liquidity_contract: None, 
-                discounts_contract: None, 
-                liq_fee: None, 
-                debt_minimum: None, 
-                base_debt_cap_multiplier: None, 
-                oracle_time_limit: None, 
-                credit_twap_timeframe: None, 
-                collateral_twap_timeframe: None, 
-                cpc_multiplier: None, 
-                rate_slope_multiplier: Some(Decimal::percent(3)), 
-                rate_hike_rate: None,
-            });
-            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
-            app.execute(Addr::unchecked("new_owner"), cosmos_msg).unwrap();
-
-            let resp: Config = app
-            .wrap()
-            .query_wasm_smart(cdp_contract.addr(), &QueryMsg::Config {  }.clone())
-            .unwrap();
+            //Add listed collateral to the Position
+            let msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: Some(Uint128::from(1u128)),
+            };
+            let cosmos_msg = cdp_contract
+                .call(msg, vec![coin(10_000_000_000, "debit")])
+                .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
+            //Error: Removed collateral can't back new debt
+            let debt_msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(3_000_000_000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(debt_msg.clone(), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
             assert_eq!(
-                resp,
-                Config { 
-                    owner: Addr::unchecked("new_owner"), 
-                    stability_pool: Some( Addr::unchecked("new_sp")), 
-                    dex_router: Some( Addr::unchecked("new_router")),  
-                    osmosis_proxy: Some( Addr::unchecked("new_op")),  
-                    debt_auction: Some( Addr::unchecked("new_auction")),  
-                    staking_contract: Some( Addr::unchecked("new_staking")),  
-                    oracle_contract: Some( Addr::unchecked("new_oracle")),  
-                    liquidity_contract: Some( Addr::unchecked("new_liq_check")),
-                    discounts_contract: Some( Addr::unchecked("new_dc")),
-                    liq_fee: Decimal::percent(13), 
-                    debt_minimum: Uint128::zero(), 
-                    base_debt_cap_multiplier: Uint128::new(48497), 
-                    oracle_time_limit: 33u64, 
-                    credit_twap_timeframe: 33u64, 
-                    collateral_twap_timeframe: 33u64, 
-                    cpc_multiplier: Decimal::percent(50),
-                    rate_slope_multiplier: Decimal::percent(3),
-                    rate_hike_rate: Some(Decimal::one()),
-                }
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"2nddebit was removed from the Basket, withdraw it before taking new debt\"")
             );
 
+            //Removed collateral can still be withdrawn
+            let msg = ExecuteMsg::Withdraw {
+                position_id: Uint128::from(1u128),
+                assets: vec![Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "2nddebit".to_string(),
+                    },
+                    amount: Uint128::from(100_000_000_000u128),
+                }],
+                send_to: None,
+                position_owner: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Debt can be taken once the removed collateral is withdrawn
+            let cosmos_msg = cdp_contract.call(debt_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
         }
 
         #[test]
-        fn edit_cAsset() {
-            let (mut app, cdp_contract, lq_contract) =
+        fn parameter_ramps() {
+            let (mut app, cdp_contract, _lq_contract) =
                 proper_instantiate(false, false, false, false);
 
-            //Invalid Asset
-            let edit_msg = ExecuteMsg::EditcAsset {
+            //Initial Position
+            let msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract
+                .call(msg, vec![coin(100_000_000_000, "debit")])
+                .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Error: Only the owner can edit
+            let msg = ExecuteMsg::EditcAsset {
                 asset: AssetInfo::NativeToken {
-                    denom: "not_debit".to_string(),
+                    denom: "debit".to_string(),
                 },
-                max_borrow_LTV: None,
-                max_LTV: None,
-            };
-            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
-            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+                max_borrow_LTV: Some(Decimal::percent(30)),
+                max_LTV: Some(Decimal::percent(50)),
+                isolated_debt_ceiling: None,
+                rate_model: None,
+                remove_isolation: None,
+                ramp_duration: Some(1000),
+                volatility_config: None,
+            };
+            let cosmos_msg = cdp_contract.call(msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
 
-            //Add liq-queue to the initial basket
+            //Ramp LTVs down over 1000 seconds
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Basket LTVs are unchanged until the ramp progresses
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
+                .unwrap();
+            assert_eq!(basket.collateral_types[0].max_borrow_LTV, Decimal::percent(50));
+            assert_eq!(basket.collateral_types[0].max_LTV, Decimal::percent(70));
+
+            let res: ParameterRampsResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetParameterRamps { })
+                .unwrap();
+            assert_eq!(res.base_interest_rate, None);
+            assert_eq!(res.cAsset_LTVs.len(), 1);
+            assert_eq!(res.cAsset_LTVs[0].max_LTV.start_value, Decimal::percent(70));
+            assert_eq!(res.cAsset_LTVs[0].max_LTV.end_value, Decimal::percent(50));
+
+            //Ramp the base rate up over 1000 seconds
             let msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
                 added_cAsset: None,
-                liq_queue: Some(lq_contract.addr().to_string()),
+                liq_queue: None,
                 credit_pool_infos: None,
                 collateral_supply_caps: None,
-                base_interest_rate: None,
+                base_interest_rate: Some(Decimal::percent(10)),
                 credit_asset_twap_price_source: None,
                 negative_rates: None,
                 cpc_margin_of_error: None,
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                take_revenue: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: Some(1000),
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Successfull edit
-            let edit_msg = ExecuteMsg::EditcAsset {
-                asset: AssetInfo::NativeToken {
-                    denom: "debit".to_string(),
-                },
-                max_borrow_LTV: Some(Decimal::percent(82)),
-                max_LTV: Some(Decimal::percent(83)),
-            };
-            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
+                .unwrap();
+            assert_eq!(basket.base_interest_rate, Decimal::zero());
 
-            //Query Basket
-            let resp: Basket = app
+            //Parameters are halfway after half the duration
+            app.set_block(BlockInfo {
+                height: app.block_info().height,
+                time: app.block_info().time.plus_seconds(500u64),
+                chain_id: app.block_info().chain_id,
+            });
+            let msg = ExecuteMsg::Accrue { position_owner: None, position_ids: vec![Uint128::one()] };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let basket: Basket = app
                 .wrap()
-                .query_wasm_smart(
-                    cdp_contract.addr(),
-                    &QueryMsg::GetBasket { },
-                )
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
                 .unwrap();
+            assert_eq!(basket.collateral_types[0].max_borrow_LTV, Decimal::percent(40));
+            assert_eq!(basket.collateral_types[0].max_LTV, Decimal::percent(60));
+            assert_eq!(basket.base_interest_rate, Decimal::percent(5));
 
-            assert_eq!(
-                resp.collateral_types[0].max_borrow_LTV,
-                Decimal::percent(82)
-            );
-            assert_eq!(resp.collateral_types[0].max_LTV, Decimal::percent(83));
+            //Parameters stop at their end values
+            app.set_block(BlockInfo {
+                height: app.block_info().height,
+                time: app.block_info().time.plus_seconds(1000u64),
+                chain_id: app.block_info().chain_id,
+            });
+            let msg = ExecuteMsg::Accrue { position_owner: None, position_ids: vec![Uint128::one()] };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Error: Borrow LTV too high
-            let edit_msg = ExecuteMsg::EditcAsset {
-                asset: AssetInfo::NativeToken {
-                    denom: "debit".to_string(),
-                },
-                max_borrow_LTV: Some(Decimal::percent(100)),
-                max_LTV: Some(Decimal::percent(100)),
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
+                .unwrap();
+            assert_eq!(basket.collateral_types[0].max_borrow_LTV, Decimal::percent(30));
+            assert_eq!(basket.collateral_types[0].max_LTV, Decimal::percent(50));
+            assert_eq!(basket.base_interest_rate, Decimal::percent(10));
+
+            //Position borrow limits use the ramped LTV
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: None,
+                LTV: Some(Decimal::percent(40)),
+                mint_to_addr: None,
+                position_owner: None,
             };
-            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
-            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
 
-            //Successfull edit
-            let edit_msg = ExecuteMsg::EditcAsset {
+            //Edits without a ramp are immediate & clear the LTV ramp
+            let msg = ExecuteMsg::EditcAsset {
                 asset: AssetInfo::NativeToken {
                     denom: "debit".to_string(),
                 },
                 max_borrow_LTV: None,
-                max_LTV: Some(Decimal::percent(100)),
+                max_LTV: Some(Decimal::percent(60)),
+                isolated_debt_ceiling: None,
+                rate_model: None,
+                remove_isolation: None,
+                ramp_duration: None,
+                volatility_config: None,
             };
-            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let basket: Basket = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetBasket { })
+                .unwrap();
+            assert_eq!(basket.collateral_types[0].max_borrow_LTV, Decimal::percent(30));
+            assert_eq!(basket.collateral_types[0].max_LTV, Decimal::percent(60));
+
+            let res: ParameterRampsResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetParameterRamps { })
+                .unwrap();
+            assert_eq!(res.cAsset_LTVs.len(), 0);
+            assert!(res.base_interest_rate.is_some());
         }
 
         #[test]
-        fn open_position_deposit() {
+        fn isolated_collateral() {
             let (mut app, cdp_contract, lq_contract) =
                 proper_instantiate(false, false, false, false);
 
-            //Add supply caps and a new cAsset (2nddebit)
-            let edit_basket_msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
+            //Add isolated cAsset
+            let msg = ExecuteMsg::EditBasket(EditBasket {
                 added_cAsset: Some(cAsset {
                     asset: Asset {
                         info: AssetInfo::NativeToken {
@@ -9264,13 +12340,15 @@ mod tests {
                         amount: Uint128::from(0u128),
                     },
                     max_borrow_LTV: Decimal::percent(50),
-                    max_LTV: Decimal::percent(70),
+                    max_LTV: Decimal::percent(90),
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: Some(Uint128::new(6000_000_000)),
+                    rate_model: None,
                 }),
-                liq_queue: None,
-                credit_pool_infos: None,
+                liq_queue: Some(lq_contract.addr().to_string()),
+                credit_pool_infos: Some(vec![PoolType::Balancer { pool_id: 1u64 }]),
                 collateral_supply_caps: Some(vec![
                     SupplyCap {
                         asset_info: AssetInfo::NativeToken {
@@ -9300,281 +12378,163 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                take_revenue: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
-            let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            //Testing Position creation
-
-            //Invalid id test
-            let error_exec_msg = ExecuteMsg::Deposit {
+            //Error: Isolated collateral can't be deposited w/ other assets
+            let msg = ExecuteMsg::Deposit {
                 position_owner: None,
-                position_id: Some(Uint128::from(3u128)),
+                position_id: None,
             };
-
-            //Fail due to a non-existent position
-            //First msg deposits since no positions were initially found, meaning the _id never got tested
-            app.send_tokens(
-                Addr::unchecked("little_bank"),
-                Addr::unchecked("owner"),
-                &vec![coin(22, "debit")],
-            )
-            .unwrap();
             let cosmos_msg = cdp_contract
-                .call(error_exec_msg, vec![coin(11, "debit")])
-                .unwrap();
-            app.execute(Addr::unchecked("owner"), cosmos_msg.clone())
+                .call(msg, vec![coin(100_000_000_000, "2nddebit"), coin(100_000_000_000, "debit")])
                 .unwrap();
-            app.execute(Addr::unchecked("owner"), cosmos_msg)
-                .unwrap_err();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Isolated collateral (2nddebit) can't be mixed with other assets\"")
+            );
 
-            //Fail due to invalid collateral
-            let exec_msg = ExecuteMsg::Deposit {
+            //Isolated Position
+            let msg = ExecuteMsg::Deposit {
                 position_owner: None,
                 position_id: None,
             };
             let cosmos_msg = cdp_contract
-                .call(exec_msg, vec![coin(666, "fake_debit")])
+                .call(msg, vec![coin(100_000_000_000, "2nddebit")])
                 .unwrap();
-            app.execute(Addr::unchecked("faker"), cosmos_msg)
-                .unwrap_err();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Successful attempt
-            let exec_msg = ExecuteMsg::Deposit {
+            //Non-isolated Position
+            let msg = ExecuteMsg::Deposit {
                 position_owner: None,
                 position_id: None,
             };
-            app.send_tokens(
-                Addr::unchecked(USER),
-                Addr::unchecked("owner"),
-                &vec![coin(11, "2nddebit"), coin(11, "debit")],
-            )
-            .unwrap();
-            let cosmos_msg = cdp_contract
-                .call(exec_msg.clone(), vec![coin(11, "debit"), coin(11, "2nddebit")])
-                .unwrap();
-            let res = app.execute(Addr::unchecked("owner"), cosmos_msg).unwrap();
-
-            let response = res
-                .events
-                .into_iter()
-                .find(|e| e.attributes.iter().any(|attr| attr.key == "method" && attr.value == "deposit"))
-                .ok_or_else(|| panic!("unable to find deposit event"))
-                .unwrap();
-
-            assert_eq!(
-                response.attributes[1..4],
-                vec![
-                    attr("method", "deposit"),
-                    attr("position_owner", "owner"),
-                    attr("position_id", "2"),
-                ]
-            );
-            assert_eq!(response.attributes[4].value, String::from("[Asset { info: NativeToken { denom: \"debit\" }, amount: Uint128(11) }, Asset { info: NativeToken { denom: \"2nddebit\" }, amount: Uint128(11) }]") );
-
-            //Test max Position amount
             let cosmos_msg = cdp_contract
-                .call(exec_msg, vec![coin(1, "debit")])
+                .call(msg, vec![coin(100_000_000_000, "debit")])
                 .unwrap();
-            let res = app.execute(Addr::unchecked("owner"), cosmos_msg.clone()).unwrap();
-            //This one should fail
-            let res = app.execute(Addr::unchecked("owner"), cosmos_msg.clone()).unwrap_err();
-        }
-
-        #[test]
-        fn repay() {
-            let (mut app, cdp_contract, lq_contract) =
-                proper_instantiate(false, false, false, false);
-
-            let edit_basket_msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
-                added_cAsset: None,
-                liq_queue: None,
-                credit_pool_infos: None,
-                collateral_supply_caps: Some(vec![SupplyCap {
-                    asset_info: AssetInfo::NativeToken {
-                        denom: "debit".to_string(),
-                    },
-                    current_supply: Uint128::zero(),
-                    debt_total: Uint128::zero(),
-                    supply_cap_ratio: Decimal::percent(100),
-                    lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                }]),
-                base_interest_rate: None,
-                credit_asset_twap_price_source: None,
-                negative_rates: None,
-                cpc_margin_of_error: None,
-                frozen: None,
-                rev_to_stakers: None,
-                multi_asset_supply_caps: None,
-            });
-            let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
-            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
-
-            //NoUserPositions Error
-            let repay_msg = ExecuteMsg::Repay {
-                position_id: Uint128::from(1u128),
-                position_owner: None,
-                send_excess_to: None,
-            };
-            let cosmos_msg = cdp_contract.call(repay_msg, vec![]).unwrap();
-            let res = app
-                .execute(Addr::unchecked("sender88"), cosmos_msg)
-                .unwrap_err();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Initial deposit
-            let exec_msg = ExecuteMsg::Deposit {
+            //Error: Can't add other assets to the isolated Position
+            let msg = ExecuteMsg::Deposit {
                 position_owner: None,
-                position_id: None,
-            };
-            let cosmos_msg = cdp_contract.call(exec_msg, coins(11, "debit")).unwrap();
-            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
-
-            //Invalid Collateral Error
-            let repay_msg = ExecuteMsg::Repay {
-                position_id: Uint128::from(1u128),
-                position_owner: Some(USER.to_string()),
-                send_excess_to: None,
+                position_id: Some(Uint128::from(1u128)),
             };
             let cosmos_msg = cdp_contract
-                .call(repay_msg, vec![coin(666, "fake_debit")])
+                .call(msg, vec![coin(100_000_000, "debit")])
                 .unwrap();
-            let res = app
-                .execute(Addr::unchecked("faker"), cosmos_msg)
-                .unwrap_err();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Isolated collateral (2nddebit) can't be mixed with other assets\"")
+            );
 
-            //NonExistent Position Error
-            let repay_msg = ExecuteMsg::Repay {
-                position_id: Uint128::from(3u128),
-                position_owner: Some(USER.to_string()),
-                send_excess_to: None,
+            //Error: Can't add isolated assets to other Positions
+            let msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: Some(Uint128::from(2u128)),
             };
             let cosmos_msg = cdp_contract
-                .call(repay_msg, vec![coin(111, "credit_fulldenom")])
+                .call(msg, vec![coin(100_000_000, "2nddebit")])
                 .unwrap();
-            let res = app
-                .execute(Addr::unchecked("coin_God"), cosmos_msg)
-                .unwrap_err();
-        }
-
-        #[test]
-        fn increase_debt() {
-            let (mut app, cdp_contract, lq_contract) =
-                proper_instantiate(false, false, false, false);
-
-            let edit_basket_msg = ExecuteMsg::EditBasket(EditBasket {
-                take_revenue: None,
-                added_cAsset: None,
-                liq_queue: None,
-                credit_pool_infos: None,
-                collateral_supply_caps: Some(vec![SupplyCap {
-                    asset_info: AssetInfo::NativeToken {
-                        denom: "debit".to_string(),
-                    },
-                    current_supply: Uint128::zero(),
-                    debt_total: Uint128::zero(),
-                    supply_cap_ratio: Decimal::percent(100),
-                    lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
-                }]),
-                base_interest_rate: None,
-                credit_asset_twap_price_source: None,
-                negative_rates: None,
-                cpc_margin_of_error: None,
-                frozen: None,
-                rev_to_stakers: None,
-                multi_asset_supply_caps: None,
-            });
-            let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
-            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Isolated collateral (2nddebit) can't be mixed with other assets\"")
+            );
 
-            //NoUserPositions Error
-            let increase_debt_msg = ExecuteMsg::IncreaseDebt {
+            //Successful debt increase within the ceiling
+            let msg = ExecuteMsg::IncreaseDebt {
                 position_id: Uint128::from(1u128),
-                amount: Some(Uint128::from(1u128)),
+                amount: Some(Uint128::from(3000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
-            let cosmos_msg = cdp_contract.call(increase_debt_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Initial deposit
-            let exec_msg = ExecuteMsg::Deposit {
+            //Error: Debt increase over the isolated debt ceiling
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(4000_000_000u128)),
+                LTV: None,
+                mint_to_addr: None,
                 position_owner: None,
-                position_id: None,
             };
-            let cosmos_msg = cdp_contract
-                .call(exec_msg, vec![coin(11_000_000_000_000, "debit")])
-                .unwrap();
-            let res = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                String::from("Custom Error val: \"Isolated debt ceiling for 2nddebit is over the limit (7000000000 > 6000000000)\"")
+            );
 
-            //NonExistentPosition Error
-            let increase_debt_msg = ExecuteMsg::IncreaseDebt {
-                position_id: Uint128::from(3u128),
-                amount: Some(Uint128::from(1u128)),
+            //Non-isolated debt isn't counted toward the ceiling
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(2u128),
+                amount: Some(Uint128::from(4000_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
-            let cosmos_msg = cdp_contract.call(increase_debt_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap_err();
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Increase_debt by LTV: Insolvent Error
-            let increase_debt_msg = ExecuteMsg::IncreaseDebt {
+            //Repayments free up the ceiling
+            let msg = ExecuteMsg::Repay {
                 position_id: Uint128::from(1u128),
-                amount: None,
-                LTV: Some(Decimal::percent(100)),
-                mint_to_addr: None,
+                position_owner: None,
+                send_excess_to: None,
             };
-            let cosmos_msg = cdp_contract.call(increase_debt_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap_err();
+            let cosmos_msg = cdp_contract
+                .call(msg, vec![coin(500_000_000, "credit_fulldenom")])
+                .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Increase_debt by LTV: No amount inputs
-            let increase_debt_msg = ExecuteMsg::IncreaseDebt {
+            let msg = ExecuteMsg::IncreaseDebt {
                 position_id: Uint128::from(1u128),
-                amount: None,
+                amount: Some(Uint128::from(3500_000_000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
-            let cosmos_msg = cdp_contract.call(increase_debt_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap_err();
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Increase_debt by LTV: Success
-            let increase_debt_msg = ExecuteMsg::IncreaseDebt {
-                position_id: Uint128::from(1u128),
-                amount: None,
-                LTV: Some(Decimal::percent(40)),
-                mint_to_addr: None,
+            //Remove isolation
+            let edit_msg = ExecuteMsg::EditcAsset {
+                asset: AssetInfo::NativeToken {
+                    denom: "2nddebit".to_string(),
+                },
+                max_borrow_LTV: None,
+                max_LTV: None,
+                isolated_debt_ceiling: None,
+                rate_model: None,
+                remove_isolation: Some(true),
+                ramp_duration: None,
+                volatility_config: None,
             };
-            let cosmos_msg = cdp_contract.call(increase_debt_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
+            let cosmos_msg = cdp_contract.call(edit_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-           //Query indebted position
-        //    let query_msg = QueryMsg::GetPositionInsolvency {
-        //     position_id: Uint128::new(1),
-        //     position_owner: String::from("bigger_bank"),
-        //     };
-        //     let res: InsolvencyResponse = app
-        //         .wrap()
-        //         .query_wasm_smart(cdp_contract.addr(), &query_msg.clone())
-        //         .unwrap();
-        //     //Assert LTV
-        //     assert_eq!(
-        //         res.insolvent_positions,
-        //         vec![InsolventPosition {
-        //             insolvent: false,
-        //             position_info: UserInfo {
-        //                 position_id: Uint128::new(1),
-        //                 position_owner: String::from("bigger_bank"),
-        //             },
-        //             current_LTV: Decimal::percent(40),
-        //             available_fee: Uint128::zero(),
-        //         }]
-        //     );
+            //Assets can be mixed now
+            let msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: Some(Uint128::from(1u128)),
+            };
+            let cosmos_msg = cdp_contract
+                .call(msg, vec![coin(100_000_000, "debit")])
+                .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
         }
 
         #[test]
-        fn withdrawal_errors() {
+        fn transfer_position__operators() {
             let (mut app, cdp_contract, lq_contract) =
                 proper_instantiate(false, false, false, false);
 
@@ -9591,7 +12551,7 @@ mod tests {
                     debt_total: Uint128::zero(),
                     supply_cap_ratio: Decimal::percent(100),
                     lp: false,
-                        stability_pool_ratio_for_debt_cap: None,
+                    stability_pool_ratio_for_debt_cap: None,
                 }]),
                 base_interest_rate: None,
                 credit_asset_twap_price_source: None,
@@ -9600,79 +12560,251 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
 
-            let valid_assets: Vec<Asset> = vec![Asset {
+            //Initial Deposit
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: None,
+            };
+            let cosmos_msg = cdp_contract.call(exec_msg, coins(100_000, "debit")).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let withdraw_assets = vec![Asset {
                 info: AssetInfo::NativeToken {
                     denom: "debit".to_string(),
                 },
-                amount: Uint128::from(5u128),
+                amount: Uint128::from(10u128),
             }];
 
-            //User has no positions in the basket error
+            //Unauthorized: No operator allowance
             let withdrawal_msg = ExecuteMsg::Withdraw {
                 position_id: Uint128::from(1u128),
-                assets: valid_assets.clone(),
+                assets: withdraw_assets.clone(),
                 send_to: None,
+                position_owner: Some(USER.to_string()),
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            app.execute(Addr::unchecked("operator"), cosmos_msg).unwrap_err();
+
+            //Approve withdraw-only operator
+            let operator_msg = ExecuteMsg::EditOperator {
+                operator: String::from("operator"),
+                allowance: Some(OperatorAllowance {
+                    position_ids: None,
+                    withdraw: true,
+                    increase_debt: false,
+                    debt_limit: None,
+                    edit_redeemability: false,
+                    expiration: None,
+                }),
+            };
+            let cosmos_msg = cdp_contract.call(operator_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
-            //Initial Deposit
-            let exec_msg = ExecuteMsg::Deposit {
-                position_owner: None,
-                position_id: None,
+            //Query operators
+            let res: Vec<OperatorResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    cdp_contract.addr(),
+                    &QueryMsg::GetOperators { position_owner: USER.to_string() },
+                )
+                .unwrap();
+            assert_eq!(res.len(), 1);
+            assert_eq!(res[0].operator, String::from("operator"));
+
+            //Unauthorized: Operator can't increase debt
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(2_000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: Some(USER.to_string()),
             };
-            let cosmos_msg = cdp_contract.call(exec_msg, coins(11, "debit")).unwrap();
-            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("operator"), cosmos_msg).unwrap_err();
 
-            //Non-existent position error but user still has positions in the basket
+            //Error: Operators can't set a withdrawal recipient
             let withdrawal_msg = ExecuteMsg::Withdraw {
-                position_id: Uint128::from(3u128),
-                assets: vec![Asset {
-                    info: AssetInfo::NativeToken {
-                        denom: "debit".to_string(),
-                    },
-                    amount: Uint128::zero(),
-                }],
-                send_to: None,
+                position_id: Uint128::from(1u128),
+                assets: withdraw_assets.clone(),
+                send_to: Some(String::from("operator")),
+                position_owner: Some(USER.to_string()),
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
-
-            //Invalid collateral fail
-            let assets: Vec<Asset> = vec![Asset {
-                info: AssetInfo::NativeToken {
-                    denom: "notdebit".to_string(),
-                },
-                amount: Uint128::from(10u128),
-            }];
+            app.execute(Addr::unchecked("operator"), cosmos_msg).unwrap_err();
 
+            //Successful operator withdrawal, sent to the owner
             let withdrawal_msg = ExecuteMsg::Withdraw {
                 position_id: Uint128::from(1u128),
-                assets: assets.clone(),
+                assets: withdraw_assets.clone(),
                 send_to: None,
+                position_owner: Some(USER.to_string()),
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            app.execute(Addr::unchecked("operator"), cosmos_msg).unwrap();
 
-            //Withdrawing too much error
-            let assets: Vec<Asset> = vec![Asset {
-                info: AssetInfo::NativeToken {
-                    denom: "debit".to_string(),
-                },
-                amount: Uint128::from(333333333u128),
-            }];
+            assert_eq!(
+                app.wrap().query_all_balances(USER).unwrap(),
+                vec![coin(100_000_000_000, "2nddebit"), coin(99_999_900_010, "debit")]
+            );
+
+            //Add collateral to back the operator's mint
+            let exec_msg = ExecuteMsg::Deposit {
+                position_owner: None,
+                position_id: Some(Uint128::from(1u128)),
+            };
+            let cosmos_msg = cdp_contract.call(exec_msg, coins(10_000_000_000, "debit")).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Approve the operator to increase debt
+            let operator_msg = ExecuteMsg::EditOperator {
+                operator: String::from("operator"),
+                allowance: Some(OperatorAllowance {
+                    position_ids: None,
+                    withdraw: true,
+                    increase_debt: true,
+                    debt_limit: None,
+                    edit_redeemability: false,
+                    expiration: None,
+                }),
+            };
+            let cosmos_msg = cdp_contract.call(operator_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Error: Operators can't redirect minted debt
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(2_000u128)),
+                LTV: None,
+                mint_to_addr: Some(String::from("operator")),
+                position_owner: Some(USER.to_string()),
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("operator"), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"Operators can't set a mint recipient\""));
+
+            //Successful operator mint, sent to the owner
+            let msg = ExecuteMsg::IncreaseDebt {
+                position_id: Uint128::from(1u128),
+                amount: Some(Uint128::from(3_024_000_000u128)),
+                LTV: None,
+                mint_to_addr: None,
+                position_owner: Some(USER.to_string()),
+            };
+            let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("operator"), cosmos_msg).unwrap();
+
+            //Set an order w/ a tip, it's removed on transfer
+            let order_msg = ExecuteMsg::SetPositionOrder {
+                position_id: Uint128::from(1u128),
+                trigger: OrderTrigger::LTVAbove { LTV: Decimal::percent(90) },
+                action: OrderAction::Close { max_spread: Decimal::percent(1) },
+            };
+            let cosmos_msg = cdp_contract.call(order_msg, coins(1_000, "debit")).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            assert_eq!(app.wrap().query_balance(USER, "debit").unwrap().amount, Uint128::new(89_999_899_010));
+
+            //Offer the Position
+            let transfer_msg = ExecuteMsg::TransferPosition {
+                position_id: Uint128::from(1u128),
+                new_owner: String::from("new_owner"),
+            };
+            let cosmos_msg = cdp_contract.call(transfer_msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //The Position stays w/ the owner until accepted
+            let query_msg = QueryMsg::GetBasketPositions {
+                start_after: None,
+                limit: None,
+                user: Some(String::from("new_owner")),
+                user_info: None,
+            };
+            app.wrap()
+                .query_wasm_smart::<Vec<BasketPositionsResponse>>(cdp_contract.addr(), &query_msg)
+                .unwrap_err();
+
+            //Error: Only the new owner can accept
+            let accept_msg = ExecuteMsg::AcceptPositionTransfer {
+                position_id: Uint128::from(1u128),
+            };
+            let cosmos_msg = cdp_contract.call(accept_msg.clone(), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("operator"), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Unauthorized, owner is new_owner"));
+
+            //Error: Only the owner can cancel
+            let cancel_msg = ExecuteMsg::CancelPositionTransfer {
+                position_id: Uint128::from(1u128),
+            };
+            let cosmos_msg = cdp_contract.call(cancel_msg.clone(), vec![]).unwrap();
+            app.execute(Addr::unchecked("new_owner"), cosmos_msg).unwrap_err();
+
+            //Owner cancels the offer
+            let cosmos_msg = cdp_contract.call(cancel_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            //Error: No pending transfer
+            let cosmos_msg = cdp_contract.call(accept_msg.clone(), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked("new_owner"), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"Position 1 has no pending transfer\""));
+
+            //Offer again & accept
+            let cosmos_msg = cdp_contract.call(transfer_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            let cosmos_msg = cdp_contract.call(accept_msg, vec![]).unwrap();
+            app.execute(Addr::unchecked("new_owner"), cosmos_msg).unwrap();
+
+            //The order was removed & its tip refunded to the previous owner
+            assert_eq!(app.wrap().query_balance(USER, "debit").unwrap().amount, Uint128::new(89_999_900_010));
+            let orders: Vec<PositionOrder> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::GetPositionOrders {
+                    position_owner: None,
+                    start_after: None,
+                    limit: None,
+                })
+                .unwrap();
+            assert_eq!(orders.len(), 0);
+
+            //Query Positions
+            let query_msg = QueryMsg::GetBasketPositions {
+                start_after: None,
+                limit: None,
+                user: Some(String::from("new_owner")),
+                user_info: None,
+            };
+            let res: Vec<BasketPositionsResponse> = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &query_msg)
+                .unwrap();
+            assert_eq!(res[0].positions[0].position_id, Uint128::from(1u128));
+            assert_eq!(res[0].positions[0].collateral_assets[0].asset.amount, Uint128::from(10_000_099_990u128));
+            assert_eq!(res[0].positions[0].credit_amount, Uint128::from(3_024_000_000u128));
+
+            let query_msg = QueryMsg::GetBasketPositions {
+                start_after: None,
+                limit: None,
+                user: Some(USER.to_string()),
+                user_info: None,
+            };
+            app.wrap()
+                .query_wasm_smart::<Vec<BasketPositionsResponse>>(cdp_contract.addr(), &query_msg)
+                .unwrap_err();
 
+            //Operator allowance doesn't carry over to the new owner
             let withdrawal_msg = ExecuteMsg::Withdraw {
                 position_id: Uint128::from(1u128),
-                assets,
+                assets: withdraw_assets,
                 send_to: None,
+                position_owner: Some(String::from("new_owner")),
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
-            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+            app.execute(Addr::unchecked("operator"), cosmos_msg).unwrap_err();
         }
 
         #[test]
@@ -9695,6 +12827,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -9725,6 +12859,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();           
@@ -9760,6 +12897,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap(); 
@@ -9776,6 +12916,7 @@ mod tests {
                     }
                 ],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -9798,6 +12939,7 @@ mod tests {
                     }
                 ],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -9814,6 +12956,7 @@ mod tests {
                     }
                 ],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -9836,6 +12979,7 @@ mod tests {
                     }
                 ],
                 send_to: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(withdrawal_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10164,6 +13308,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -10196,6 +13342,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -10216,6 +13365,7 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10236,6 +13386,7 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10248,6 +13399,7 @@ mod tests {
                 premium: Some(100), 
                 max_loan_repayment: None,
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -10260,6 +13412,7 @@ mod tests {
                 premium: Some(99), 
                 max_loan_repayment: Some(Decimal::percent(101)),
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -10272,6 +13425,7 @@ mod tests {
                 premium: Some(10), 
                 max_loan_repayment: None, 
                 restricted_collateral_assets: Some(vec![String::from("debit")]),
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10299,6 +13453,7 @@ mod tests {
                 premium: None,
                 max_loan_repayment: None, 
                 restricted_collateral_assets: Some(vec![]),
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10323,6 +13478,7 @@ mod tests {
                 premium: Some(2),
                 max_loan_repayment: Some(Decimal::one()), 
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -10335,6 +13491,7 @@ mod tests {
                 premium: None,
                 max_loan_repayment: None, 
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -10347,6 +13504,7 @@ mod tests {
                 premium: None,
                 max_loan_repayment: None, 
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -10360,6 +13518,7 @@ mod tests {
                 premium: None,
                 max_loan_repayment: None,
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10382,6 +13541,7 @@ mod tests {
                 premium: None,
                 max_loan_repayment: None,
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -10393,6 +13553,7 @@ mod tests {
                 premium: Some(10),
                 max_loan_repayment: None,
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10416,6 +13577,7 @@ mod tests {
                 premium: Some(20),
                 max_loan_repayment: None,
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10443,6 +13605,7 @@ mod tests {
                 premium: None,
                 max_loan_repayment: None,
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10452,6 +13615,7 @@ mod tests {
                 premium: Some(20),
                 max_loan_repayment: None,
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10466,6 +13630,7 @@ mod tests {
                 restricted_collateral_assets: Some(
                     vec![String::from("I_don't_want_this_asset")]
                 ),
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             let err = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
@@ -10479,6 +13644,7 @@ mod tests {
                 restricted_collateral_assets: Some(
                     vec![String::from("debit")]
                 ),
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10523,6 +13689,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -10555,6 +13723,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(edit_basket_msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -10575,6 +13746,7 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10595,13 +13767,14 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
             
             ////Redeem///// (No enabled positions yet)
             //Error
-            let redemption_msg = ExecuteMsg::RedeemCollateral { max_collateral_premium: None };
+            let redemption_msg = ExecuteMsg::RedeemCollateral { max_collateral_premium: None, collateral_filter: None, min_value_received: None };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![coin(100_000_000000, "credit_fulldenom")]).unwrap();
             let err = app.execute(Addr::unchecked("redeemer"), cosmos_msg).unwrap_err();
             assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"No collateral to redeem with at this max premium\""));  
@@ -10613,6 +13786,7 @@ mod tests {
                 premium: Some(10),
                 max_loan_repayment: Some(Decimal::percent(10)),
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10624,13 +13798,14 @@ mod tests {
                 premium: Some(20),
                 max_loan_repayment: Some(Decimal::percent(20)),
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
 
             ////Redeem///// (15k max currently)
             //Error: Wrong asset
-            let redemption_msg = ExecuteMsg::RedeemCollateral { max_collateral_premium: None };
+            let redemption_msg = ExecuteMsg::RedeemCollateral { max_collateral_premium: None, collateral_filter: None, min_value_received: None };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![coin(1, "not_redeemable")]).unwrap();
             let err = app.execute(Addr::unchecked("redeemer"), cosmos_msg).unwrap_err();
             assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"Must send only the Basket's debt token\""));           
@@ -10644,6 +13819,71 @@ mod tests {
             let cosmos_msg = cdp_contract.call(msg, vec![coin(46_000_000000, "credit_fulldenom")]).unwrap();
             app.execute(Addr::unchecked("bigger_bank"), cosmos_msg).unwrap();
 
+            //Simulate the redemption
+            let simulation: RedemptionSimulationResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::SimulateRedemption {
+                    credit_amount: Uint128::new(100_000_000000),
+                    max_collateral_premium: None,
+                    collateral_filter: None,
+                })
+                .unwrap();
+            assert_eq!(simulation.premium_tiers.len(), 2);
+            assert_eq!(simulation.premium_tiers[0].premium, 10u128);
+            assert_eq!(simulation.premium_tiers[0].positions[0].position_info.position_id, Uint128::one());
+            assert_eq!(simulation.premium_tiers[0].positions[0].credit_redeemed, Uint128::new(4000_000000));
+            assert_eq!(simulation.premium_tiers[0].collateral_received[0].amount, Uint128::new(3600_000000));
+            assert_eq!(simulation.premium_tiers[1].premium, 20u128);
+            assert_eq!(simulation.premium_tiers[1].positions[0].position_info.position_owner, String::from("bigger_bank"));
+            assert_eq!(simulation.premium_tiers[1].positions[0].credit_redeemed, Uint128::new(10000_000000));
+            assert_eq!(simulation.premium_tiers[1].collateral_received[0].amount, Uint128::new(8000_000000));
+            assert_eq!(simulation.collateral_received[0].amount, Uint128::new(11600_000000));
+            assert_eq!(simulation.unredeemed_credit, Uint128::new(86000_000000));
+
+            //Simulation stops at the max premium
+            let simulation: RedemptionSimulationResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::SimulateRedemption {
+                    credit_amount: Uint128::new(100_000_000000),
+                    max_collateral_premium: Some(15),
+                    collateral_filter: None,
+                })
+                .unwrap();
+            assert_eq!(simulation.premium_tiers.len(), 1);
+            assert_eq!(simulation.unredeemed_credit, Uint128::new(96000_000000));
+
+            //Simulation skips collateral outside of the whitelist
+            let simulation: RedemptionSimulationResponse = app
+                .wrap()
+                .query_wasm_smart(cdp_contract.addr(), &QueryMsg::SimulateRedemption {
+                    credit_amount: Uint128::new(100_000_000000),
+                    max_collateral_premium: None,
+                    collateral_filter: Some(RedemptionCollateralFilter::Whitelist { denoms: vec![String::from("2nddebit")] }),
+                })
+                .unwrap();
+            assert_eq!(simulation.premium_tiers.len(), 0);
+            assert_eq!(simulation.unredeemed_credit, Uint128::new(100_000_000000));
+
+            //Error: Redeemer blacklisted the only redeemable collateral
+            let filtered_msg = ExecuteMsg::RedeemCollateral { 
+                max_collateral_premium: None, 
+                collateral_filter: Some(RedemptionCollateralFilter::Blacklist { denoms: vec![String::from("debit")] }), 
+                min_value_received: None,
+            };
+            let cosmos_msg = cdp_contract.call(filtered_msg, vec![coin(100_000_000000, "credit_fulldenom")]).unwrap();
+            let err = app.execute(Addr::unchecked("redeemer"), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"No collateral to redeem with at this max premium\""));
+
+            //Error: Collateral received is worth less than the redeemer's minimum
+            let min_value_msg = ExecuteMsg::RedeemCollateral { 
+                max_collateral_premium: None, 
+                collateral_filter: Some(RedemptionCollateralFilter::Whitelist { denoms: vec![String::from("debit")] }), 
+                min_value_received: Some(Decimal::from_ratio(20_000_000000u128, 1u128)),
+            };
+            let cosmos_msg = cdp_contract.call(min_value_msg, vec![coin(100_000_000000, "credit_fulldenom")]).unwrap();
+            let err = app.execute(Addr::unchecked("redeemer"), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"Redeemed collateral value 11600000000 is below the minimum 20000000000\""));
+
             //Success, but Send too much (15k max currently)
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![coin(100_000_000000, "credit_fulldenom")]).unwrap();
             app.execute(Addr::unchecked("redeemer"), cosmos_msg).unwrap();
@@ -10714,6 +13954,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -10725,6 +13967,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -10744,6 +13989,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -10755,6 +14002,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -10774,6 +14024,8 @@ mod tests {
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -10785,6 +14037,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -10822,6 +14077,8 @@ mod tests {
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 credit_pool_infos: None,
@@ -10884,6 +14141,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -10912,6 +14172,7 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10923,6 +14184,7 @@ mod tests {
                 premium: Some(10),
                 max_loan_repayment: Some(Decimal::percent(10)),
                 restricted_collateral_assets: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10946,6 +14208,7 @@ mod tests {
                 amount: Some(Uint128::from(50_000_000000u128)),
                 LTV: None,
                 mint_to_addr: None,
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -10957,19 +14220,20 @@ mod tests {
                 premium: Some(10),
                 max_loan_repayment: Some(Decimal::percent(10)),
                 restricted_collateral_assets: Some(vec![String::from("2nddebit")]),
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
             ////Redeem from a multi-collateral position///
-            let redemption_msg = ExecuteMsg::RedeemCollateral { max_collateral_premium: Some(9) };          
+            let redemption_msg = ExecuteMsg::RedeemCollateral { max_collateral_premium: Some(9), collateral_filter: None, min_value_received: None };          
             //Error: Nothing redeemed since premium is too low
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![coin(100_000_000000, "credit_fulldenom")]).unwrap();
             let err = app.execute(Addr::unchecked("redeemer"), cosmos_msg).unwrap_err();
             assert_eq!(err.root_cause().to_string(), String::from("Custom Error val: \"No collateral to redeem with at this max premium\""));  
 
             //Successful redemption
-            let redemption_msg = ExecuteMsg::RedeemCollateral { max_collateral_premium: Some(10) };          
+            let redemption_msg = ExecuteMsg::RedeemCollateral { max_collateral_premium: Some(10), collateral_filter: None, min_value_received: None };          
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![coin(100_000_000000, "credit_fulldenom")]).unwrap();
             app.execute(Addr::unchecked("redeemer"), cosmos_msg).unwrap();
             //Assert that the collateral & excess was sent to the redeemer
@@ -11023,6 +14287,7 @@ mod tests {
                 premium: Some(10),
                 max_loan_repayment: Some(Decimal::percent(10)),
                 restricted_collateral_assets: Some(vec![String::from("lp_denom")]),
+                position_owner: None,
             };
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![]).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
@@ -11040,7 +14305,7 @@ mod tests {
             assert_eq!(res.premium_infos[0].users_of_premium[0].position_infos[1].remaining_loan_repayment, Uint128::new(4500_000000));
 
             //Successful restricted redemption
-            let redemption_msg = ExecuteMsg::RedeemCollateral { max_collateral_premium: None };          
+            let redemption_msg = ExecuteMsg::RedeemCollateral { max_collateral_premium: None, collateral_filter: None, min_value_received: None };          
             let cosmos_msg = cdp_contract.call(redemption_msg.clone(), vec![coin(4_000_000000, "credit_fulldenom")]).unwrap();
             app.execute(Addr::unchecked("redeemer"), cosmos_msg).unwrap();
             //Assert that the collateral & excess was sent to the redeemer
@@ -52,6 +52,7 @@ mod tests {
             bid_for: AssetInfo,
             max_premium: Uint128,
             bid_threshold: Uint256,
+            lp_pool_id: Option<u64>,
         },
         EditQueue {
             bid_for: AssetInfo,
@@ -62,6 +63,7 @@ mod tests {
             bid_for: AssetInfo,
             max_premium: Option<Uint128>,
             bid_threshold: Option<Uint256>,
+            lp_pool_id: Option<u64>,
         },
     }
 
@@ -153,6 +155,7 @@ mod tests {
                         bid_for: _,
                         max_premium: _,
                         bid_threshold: _,
+                        lp_pool_id: _,
                     } => Ok(Response::new()),
                     LQ_MockExecuteMsg::EditQueue {
                         bid_for,
@@ -163,6 +166,7 @@ mod tests {
                         bid_for,
                         max_premium,
                         bid_threshold,
+                        lp_pool_id: _,
                     } => {
                         let premium = max_premium.unwrap_or_default();
                         if premium != Uint128::new(10) && premium != Uint128::new(12) {
@@ -771,6 +775,8 @@ mod tests {
                                     prices: vec![],
                                     price: Decimal::one(),
                                     decimals: 6,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             }
                                                 
@@ -792,6 +798,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         },
                         AssetResponse {
@@ -809,6 +817,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         }
                     ])?),
@@ -848,6 +858,8 @@ mod tests {
                                     prices: vec![],
                                     price: Decimal::percent(200),
                                     decimals: 6,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             }
                                                 
@@ -869,6 +881,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         },
                         AssetResponse {
@@ -886,6 +900,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         }
                     ])?),
@@ -925,6 +941,8 @@ mod tests {
                                     prices: vec![],
                                     price: Decimal::percent(150),
                                     decimals: 6,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             }
                            
@@ -947,6 +965,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         },
                         AssetResponse {
@@ -964,6 +984,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         }
                     ])?),
@@ -1003,6 +1025,8 @@ mod tests {
                                     prices: vec![],
                                     price: Decimal::percent(300),
                                     decimals: 6,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             }
                            
@@ -1025,6 +1049,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         },
                         AssetResponse {
@@ -1042,6 +1068,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         }
                     ])?),
@@ -1081,6 +1109,8 @@ mod tests {
                                     prices: vec![],
                                     price: Decimal::percent(500),
                                     decimals: 6,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             }
                                                 
@@ -1102,6 +1132,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         },
                         AssetResponse {
@@ -1119,6 +1151,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         }
                     ])?),
@@ -1158,6 +1192,8 @@ mod tests {
                                 prices: vec![],
                                 price: Decimal::percent(520),
                                 decimals: 6,
+                                degraded: false,
+                                metadata: None,
                             });
                         }
                         
@@ -1180,6 +1216,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         },
                         AssetResponse {
@@ -1197,6 +1235,8 @@ mod tests {
                                 lp_pool_info: None,
                                 decimals: 6,
                                 pyth_price_feed_id: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             }],
                         }
                     ])?),
@@ -1617,6 +1657,9 @@ mod tests {
             frozen: None,
             rev_to_stakers: None,
             multi_asset_supply_caps: None,
+            close_factor: None,
+            soft_liquidation: None,
+            rate_ramp_duration: None,
         });
         let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
         app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -1659,6 +1702,9 @@ mod tests {
             multi_asset_supply_caps: None,
             credit_pool_infos: None,
             take_revenue: None,
+            close_factor: None,
+            soft_liquidation: None,
+            rate_ramp_duration: None,
         });
         let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
         app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -5916,6 +5962,9 @@ mod tests {
                 frozen: None,
                 rev_to_stakers: None,
                 multi_asset_supply_caps: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let cosmos_msg = cdp_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
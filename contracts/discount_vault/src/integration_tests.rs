@@ -74,7 +74,9 @@ mod tests {
                             credit_price: PriceResponse { 
                                 prices: vec![], 
                                 price: Decimal::one(), 
-                                decimals: 0
+                                decimals: 0,
+                                degraded: false,
+                                metadata: None,
                             },
                             liq_queue: None,
                             base_interest_rate: Decimal::zero(),
@@ -87,6 +89,8 @@ mod tests {
                             credit_last_accrued: 0,
                             rates_last_accrued: 0,
                             oracle_set: true,
+                            close_factor: None,
+                            soft_liquidation: None,
                         })?)
                     },
                     CDP_MockQueryMsg::GetUserPositions { user, limit } => {
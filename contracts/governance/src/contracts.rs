@@ -682,7 +682,8 @@ pub fn check_messages(
                     position_id, 
                     amount: Some(cdp_config.debt_minimum * Uint128::new(1_000_000)), 
                     LTV: None, 
-                    mint_to_addr: None
+                    mint_to_addr: None,
+                    position_owner: None,
                 })?,
                 funds: vec![],
             }));
@@ -718,7 +719,8 @@ pub fn check_messages(
                             amount: uosmo_balance,
                         }
                     ],
-                    send_to: None
+                    send_to: None,
+                    position_owner: None,
                 })?,
                 funds: vec![],
             }));
@@ -1374,6 +1376,9 @@ fn freeze_positions(
                     cpc_margin_of_error: None,
                     rev_to_stakers: None,
                     take_revenue: None,
+                    close_factor: None,
+                    soft_liquidation: None,
+                    rate_ramp_duration: None,
                 }))?,
                 funds: vec![],
         })))   
@@ -1394,6 +1399,9 @@ fn freeze_positions(
                     cpc_margin_of_error: None,
                     rev_to_stakers: None,
                     take_revenue: None,
+                    close_factor: None,
+                    soft_liquidation: None,
+                    rate_ramp_duration: None,
                 }))?,
                 funds: vec![],
         })))        
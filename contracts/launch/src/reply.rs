@@ -400,6 +400,8 @@ pub fn handle_gov_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respon
                             decimals: 6,
                             pyth_price_feed_id: Some(String::from("b00b60f88b03a6a625a8d1c048c3f66653edf217439983d037e7222c4e612819")),
                             vault_info: None,
+                            max_source_deviation: None,
+                            sources: vec![],
                         },
                     })?, 
                     funds: vec![],
@@ -419,6 +421,8 @@ pub fn handle_gov_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respon
                             decimals: 6,
                             pyth_price_feed_id: Some(String::from("5867f5683c757393a0670ef0f701490950fe93fdb006d181c8265a831ac0c5c6")),
                             vault_info: None,
+                            max_source_deviation: None,
+                            sources: vec![],
                         },
                     })?, 
                     funds: vec![],
@@ -444,6 +448,8 @@ pub fn handle_gov_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respon
                             decimals: 6,
                             pyth_price_feed_id: None, //We don't set a pyth price feed for axlUSDC bc its a non-IBC bridged asset
                             vault_info: None,
+                            max_source_deviation: None,
+                            sources: vec![],
                         },
                     })?, 
                     funds: vec![],
@@ -465,6 +471,8 @@ pub fn handle_gov_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respon
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 },
                 cAsset {
                     asset: Asset {
@@ -478,6 +486,8 @@ pub fn handle_gov_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respon
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 },                
                 cAsset {
                     asset: Asset {
@@ -491,6 +501,8 @@ pub fn handle_gov_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respon
                     pool_info: None,
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }],
                 credit_asset: Asset {
                     info: AssetInfo::NativeToken {
@@ -768,6 +780,7 @@ pub fn handle_lq_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respons
                         pyth_osmosis_address: None,
                         osmo_usd_pyth_feed_id: None,
                         pools_for_usd_par_twap: None,
+                        price_history: None,
                     })?, 
                     funds: vec![],
                 }));
@@ -791,6 +804,8 @@ pub fn handle_lq_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respons
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: Some(addrs.clone().liq_queue.to_string()),
                 collateral_supply_caps: Some(vec![
@@ -833,6 +848,9 @@ pub fn handle_lq_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respons
                 multi_asset_supply_caps: None,
                 credit_pool_infos: None,
                 take_revenue: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let msg = CosmosMsg::Wasm(WasmMsg::Execute { 
                 contract_addr: addrs.clone().positions.to_string(), 
@@ -859,6 +877,8 @@ pub fn handle_lq_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respons
                     }),
                     rate_index: Decimal::one(),
                     hike_rates: Some(false),
+                    isolated_debt_ceiling: None,
+                    rate_model: None,
                 }),
                 liq_queue: None,
                 collateral_supply_caps: None,
@@ -871,6 +891,9 @@ pub fn handle_lq_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respons
                 multi_asset_supply_caps: None,
                 credit_pool_infos: None,
                 take_revenue: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let msg = CosmosMsg::Wasm(WasmMsg::Execute { 
                 contract_addr: addrs.clone().positions.to_string(), 
@@ -885,6 +908,7 @@ pub fn handle_lq_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respons
                 bid_for: AssetInfo::NativeToken { denom: config.clone().osmo_denom }, 
                 max_premium: Uint128::new(35), 
                 bid_threshold: Uint256::from(1_000_000_000_000u128), 
+                lp_pool_id: None,
             };
             let msg = CosmosMsg::Wasm(WasmMsg::Execute { 
                 contract_addr: addrs.clone().liq_queue.to_string(), 
@@ -897,6 +921,7 @@ pub fn handle_lq_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respons
                 bid_for: AssetInfo::NativeToken { denom: config.clone().atom_denom }, 
                 max_premium: Uint128::new(35), 
                 bid_threshold: Uint256::from(1_000_000_000_000u128), 
+                lp_pool_id: None,
             };
             let msg = CosmosMsg::Wasm(WasmMsg::Execute { 
                 contract_addr: addrs.clone().liq_queue.to_string(), 
@@ -909,6 +934,7 @@ pub fn handle_lq_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respons
                 bid_for: AssetInfo::NativeToken { denom: config.clone().usdc_denom }, 
                 max_premium: Uint128::new(10), 
                 bid_threshold: Uint256::from(1_000_000_000_000u128), 
+                lp_pool_id: None,
             };
             let msg = CosmosMsg::Wasm(WasmMsg::Execute { 
                 contract_addr: addrs.clone().liq_queue.to_string(), 
@@ -924,7 +950,8 @@ pub fn handle_lq_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Respons
                 osmosis_proxy_contract: None,
                 waiting_period: None, 
                 minimum_bid: None, 
-                maximum_waiting_bids: None 
+                maximum_waiting_bids: None,
+                price_policy: None, 
             };
             let msg = CosmosMsg::Wasm(WasmMsg::Execute { 
                 contract_addr: addrs.clone().liq_queue.to_string(), 
@@ -1313,6 +1340,9 @@ pub fn handle_auction_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Re
                 multi_asset_supply_caps: None,
                 credit_pool_infos: None,
                 take_revenue: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let msg = CosmosMsg::Wasm(WasmMsg::Execute { 
                 contract_addr: addrs.clone().positions.to_string(), 
@@ -1338,7 +1368,8 @@ pub fn handle_auction_reply(deps: DepsMut, _env: Env, msg: Reply)-> StdResult<Re
                                 base_asset_denom: config.clone().osmo_denom.to_string(), 
                                 quote_asset_denom: config.clone().usdc_denom.to_string(),  
                             }
-                        ])
+                        ]),
+                        price_history: None,
                     })?, 
                     funds: vec![],
                 }));
@@ -1435,6 +1466,8 @@ pub fn handle_balancer_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<R
                                 decimals: 18,
                                 pyth_price_feed_id: None,
                                 vault_info: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             },
                         })?, 
                         funds: vec![],
@@ -1459,6 +1492,8 @@ pub fn handle_balancer_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<R
                                 decimals: 6,
                                 pyth_price_feed_id: None,
                                 vault_info: None,
+                                max_source_deviation: None,
+                                sources: vec![],
                             },
                         })?, 
                         funds: vec![],
@@ -1537,6 +1572,9 @@ pub fn handle_balancer_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<R
                 frozen: None,
                 rev_to_stakers: None,
                 take_revenue: None,
+                close_factor: None,
+                soft_liquidation: None,
+                rate_ramp_duration: None,
             });
             let msg = CosmosMsg::Wasm(WasmMsg::Execute { 
                 contract_addr: addrs.clone().positions.to_string(), 
@@ -1603,7 +1641,8 @@ pub fn handle_balancer_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<R
                                 base_asset_denom: config.clone().osmo_denom.to_string(), 
                                 quote_asset_denom: config.clone().usdc_denom.to_string(),  
                             }
-                        ])
+                        ]),
+                        price_history: None,
                     })?, 
                     funds: vec![],
                 }));
@@ -1655,6 +1694,9 @@ pub fn handle_balancer_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<R
                         cpc_multiplier: None,
                         rate_slope_multiplier: None,
                         rate_hike_rate: None,
+                        flash_mint_cap: None,
+                        flash_mint_fee: None,
+                        price_policy: None,
                     }))?, 
                     funds: vec![],
                 }));
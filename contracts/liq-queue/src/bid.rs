@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{
     attr, to_binary, Addr, CosmosMsg, DepsMut, Env,
@@ -9,7 +11,7 @@ use membrane::osmosis_proxy::ExecuteMsg as OP_ExecuteMsg;
 use membrane::liq_queue::Config;
 use membrane::oracle::{PriceResponse256, PriceResponse};
 use membrane::types::{Asset, AssetInfo, Bid, BidInput, PremiumSlot, Queue};
-use membrane::helpers::{validate_position_owner, withdrawal_msg};
+use membrane::helpers::{pool_query_and_exit, validate_position_owner, withdrawal_msg};
 
 use crate::error::ContractError;
 use crate::state::{CONFIG, QUEUES};
@@ -370,6 +372,11 @@ pub fn execute_liquidation(
         return Err(ContractError::Unauthorized {});
     }
 
+    //Reject prices that are too stale or uncertain
+    if let Some(price_policy) = config.clone().price_policy {
+        price_policy.check(&bid_for.to_string(), &collateral_price, env.block.time.seconds())?;
+    }
+
     let price: PriceResponse256 = collateral_price.to_decimal256()?;
 
     let mut remaining_collateral_to_liquidate = collateral_amount;
@@ -575,12 +582,38 @@ pub fn claim_liquidations(
     if !claim_amount.is_zero() {
         let c_amount: u128 = claim_amount.into();
 
-        let withdrawal_asset = Asset {
-            info: bid_for.clone(),
-            amount: Uint128::new(c_amount),
-        };
+        match queue.lp_pool_id {
+            //Exit the LP shares & send the underlying assets
+            Some(pool_id) => {
+                let config: Config = CONFIG.load(deps.storage)?;
+                let (exit_msg, share_assets) = pool_query_and_exit(
+                    deps.querier,
+                    env,
+                    config.osmosis_proxy_contract.to_string(),
+                    pool_id,
+                    Uint128::new(c_amount),
+                )?;
+                messages.push(exit_msg);
+
+                for share_asset in share_assets {
+                    let withdrawal_asset = Asset {
+                        info: AssetInfo::NativeToken { denom: share_asset.denom },
+                        amount: Uint128::from_str(&share_asset.amount)?,
+                    };
+                    if !withdrawal_asset.amount.is_zero() {
+                        messages.push(withdrawal_msg(withdrawal_asset, info.sender.clone())?);
+                    }
+                }
+            },
+            None => {
+                let withdrawal_asset = Asset {
+                    info: bid_for.clone(),
+                    amount: Uint128::new(c_amount),
+                };
 
-        messages.push(withdrawal_msg(withdrawal_asset, info.sender)?);
+                messages.push(withdrawal_msg(withdrawal_asset, info.sender)?);
+            }
+        }
     }
 
     Ok(Response::new().add_messages(messages).add_attributes(vec![
@@ -10,6 +10,7 @@ use cw2::set_contract_version;
 use membrane::cdp::QueryMsg as CDP_QueryMsg;
 use membrane::liq_queue::{Config, ExecuteMsg, InstantiateMsg, QueryMsg, MigrateMsg};
 use membrane::math::{Decimal256, Uint256};
+use membrane::oracle::PricePolicy;
 use membrane::types::{Asset, AssetInfo, PremiumSlot, Queue, Basket};
 
 use crate::bid::{claim_liquidations, execute_liquidation, retract_bid, submit_bid};
@@ -30,6 +31,7 @@ use crate::state::{CONFIG, QUEUES, OWNERSHIP_TRANSFER};
 // - Removed bid_with, instead saving the bid_asset from the Positions contract
 // - Don't error if the full collateral amount isn't liquidated, just update the returning attribute
 // - bid_for is a String in functions that require .as_bytes() to allow LP tokens to work
+// - Queues for LP shares can settle claims in the pool's underlying assets
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:liq-queue";
@@ -67,6 +69,7 @@ pub fn instantiate(
             bid_asset,
             minimum_bid: msg.minimum_bid,
             maximum_waiting_bids: msg.maximum_waiting_bids,
+            price_policy: None,
         };
     } else {
         config = Config {
@@ -78,6 +81,7 @@ pub fn instantiate(
             bid_asset,
             minimum_bid: msg.minimum_bid,
             maximum_waiting_bids: msg.maximum_waiting_bids,
+            price_policy: None,
         };
     }
 
@@ -129,12 +133,14 @@ pub fn execute(
             bid_for,
             max_premium,
             bid_threshold,
-        } => add_queue(deps, info, bid_for, max_premium, bid_threshold),
+            lp_pool_id,
+        } => add_queue(deps, info, bid_for, max_premium, bid_threshold, lp_pool_id),
         ExecuteMsg::UpdateQueue {
             bid_for,
             max_premium,
             bid_threshold,
-        } => edit_queue(deps, info, bid_for, max_premium, bid_threshold),
+            lp_pool_id,
+        } => edit_queue(deps, info, bid_for, max_premium, bid_threshold, lp_pool_id),
         ExecuteMsg::UpdateConfig {
             owner,
             positions_contract,
@@ -142,6 +148,7 @@ pub fn execute(
             waiting_period,
             minimum_bid,
             maximum_waiting_bids,
+            price_policy,
         } => update_config(
             deps,
             info,
@@ -151,6 +158,7 @@ pub fn execute(
             waiting_period,
             minimum_bid,
             maximum_waiting_bids,
+            price_policy,
         ),
     }
 } //Functions assume Cw20 asset amounts are taken from Messageinfo
@@ -165,6 +173,7 @@ fn update_config(
     waiting_period: Option<u64>,
     minimum_bid: Option<Uint128>,
     maximum_waiting_bids: Option<u64>,
+    price_policy: Option<PricePolicy>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
     let mut attrs = vec![attr("method", "update_config")];
@@ -203,6 +212,10 @@ fn update_config(
     if let Some(maximum_waiting_bids) = maximum_waiting_bids {
         config.maximum_waiting_bids = maximum_waiting_bids;
     }
+    if let Some(price_policy) = price_policy {
+        price_policy.validate()?;
+        config.price_policy = Some(price_policy);
+    }
 
     CONFIG.save(deps.storage, &config)?;
 
@@ -218,6 +231,7 @@ fn edit_queue(
     bid_for: AssetInfo,
     max_premium: Option<Uint128>,
     bid_threshold: Option<Uint256>,
+    lp_pool_id: Option<u64>,
 ) -> Result<Response, ContractError> {
 
     let config = CONFIG.load(deps.storage)?;
@@ -274,6 +288,9 @@ fn edit_queue(
         } 
         queue.bid_threshold = bid_threshold;
     }
+    if let Some(lp_pool_id) = lp_pool_id {
+        queue.lp_pool_id = Some(lp_pool_id);
+    }
 
     QUEUES.save(deps.storage, bid_for.to_string(), &queue)?;
 
@@ -281,6 +298,7 @@ fn edit_queue(
         attr("method", "edit_queue"),
         attr("max_premium", queue.max_premium.to_string()),
         attr("bid_threshold", queue.bid_threshold.to_string()),
+        attr("lp_pool_id", format!("{:?}", queue.lp_pool_id)),
     ]))
 }
 
@@ -291,6 +309,7 @@ fn add_queue(
     bid_for: AssetInfo,
     max_premium: Uint128, //A slot for each premium is created when queue is created
     bid_threshold: Uint256,
+    lp_pool_id: Option<u64>, //LP share bids are settled in the pool's underlying assets
 ) -> Result<Response, ContractError> {
 
     let mut config = CONFIG.load(deps.storage)?;
@@ -330,6 +349,7 @@ fn add_queue(
         slots,
         current_bid_id: Uint128::from(1u128),
         bid_threshold,
+        lp_pool_id,
     };
 
     //Save new queue
@@ -358,6 +378,7 @@ fn add_queue(
         attr("bid_asset", bid_asset.to_string()),
         attr("max_premium", max_premium.to_string()),
         attr("bid_threshold", bid_threshold.to_string()),
+        attr("lp_pool_id", format!("{:?}", lp_pool_id)),
     ]))
 }
 
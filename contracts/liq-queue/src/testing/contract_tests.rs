@@ -7,7 +7,7 @@ use membrane::liq_queue::{
 use membrane::math::{Decimal256, Uint256};
 use membrane::osmosis_proxy::ExecuteMsg as OP_ExecuteMsg;
 use membrane::types::{AssetInfo, BidInput};
-use membrane::oracle::PriceResponse;
+use membrane::oracle::{PriceMetadata, PricePolicy, PriceResponse};
 
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
 use cosmwasm_std::{
@@ -50,6 +50,7 @@ fn proper_initialization() {
             },
             minimum_bid: Uint128::zero(),
             maximum_waiting_bids: 100u64,
+            price_policy: None,
         }
     );
 }
@@ -78,6 +79,7 @@ fn update_config() {
         waiting_period: None,
         minimum_bid: None,
         maximum_waiting_bids: None,
+        price_policy: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -99,6 +101,7 @@ fn update_config() {
             },
             minimum_bid: Uint128::zero(),
             maximum_waiting_bids: 100u64,
+            price_policy: None,
         }
     );
 
@@ -111,6 +114,7 @@ fn update_config() {
         waiting_period: Some(100u64),
         minimum_bid: Some(Uint128::one()),
         maximum_waiting_bids: Some(10),
+        price_policy: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -132,6 +136,7 @@ fn update_config() {
             },            
             minimum_bid: Uint128::one(),
             maximum_waiting_bids: 10u64,
+            price_policy: None,
         }
     );
 
@@ -144,6 +149,7 @@ fn update_config() {
         waiting_period: Some(60u64),
         minimum_bid: None,
         maximum_waiting_bids: None,
+        price_policy: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -165,6 +171,7 @@ fn update_config() {
          waiting_period: None,
          minimum_bid: None,
          maximum_waiting_bids: None,
+         price_policy: None,
      };
  
      let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -186,6 +193,7 @@ fn update_config() {
              },            
              minimum_bid: Uint128::one(),
              maximum_waiting_bids: 10u64,
+             price_policy: None,
          }
      );
 }
@@ -212,6 +220,7 @@ fn submit_bid() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000u128),
+        lp_pool_id: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -346,6 +355,7 @@ fn submit_bid() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(0u128),
+        lp_pool_id: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, queue_msg).unwrap();
@@ -422,6 +432,7 @@ fn submit_bid() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000u128),
+        lp_pool_id: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, queue_msg).unwrap();
@@ -434,6 +445,7 @@ fn submit_bid() {
         owner: None,
         waiting_period: None,
         minimum_bid: None,
+        price_policy: None,
         
     };
     let info = mock_info("owner0000", &[]);
@@ -545,6 +557,7 @@ fn retract_bid() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -662,6 +675,7 @@ fn execute_bid() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     let info = mock_info("owner0000", &[]);
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -693,11 +707,15 @@ fn execute_bid() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(500_000u128),
         bid_for: AssetInfo::NativeToken {
@@ -753,11 +771,15 @@ fn execute_bid() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(500_000_000_000u128),
         bid_for: AssetInfo::NativeToken {
@@ -776,6 +798,124 @@ fn execute_bid() {
     ]);
 }
 
+#[test]
+fn price_policy() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: None, //Defaults to sender
+        positions_contract: String::from("positions_contract"),
+        osmosis_proxy_contract: String::from("osmosis_proxy_contract"),
+        waiting_period: 60u64,
+        minimum_bid: Uint128::zero(),
+        maximum_waiting_bids: 100u64,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::AddQueue {
+        bid_for: AssetInfo::NativeToken {
+            denom: "osmo".to_string(),
+        },
+        max_premium: Uint128::new(10u128),
+        bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::SubmitBid {
+        bid_input: BidInput {
+            bid_for: AssetInfo::NativeToken {
+                denom: "osmo".to_string(),
+            },
+            liq_premium: 1u8,
+        },
+        bid_owner: None,
+    };
+    let info = mock_info(
+        "owner0000",
+        &[Coin {
+            denom: "cdt".to_string(),
+            amount: Uint128::from(500_000u128),
+        }],
+    );
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    //Set a price policy
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        positions_contract: None,
+        osmosis_proxy_contract: None,
+        waiting_period: None,
+        minimum_bid: None,
+        maximum_waiting_bids: None,
+        price_policy: Some(PricePolicy {
+            max_staleness: Some(60u64),
+            max_confidence: Some(Decimal::percent(1)),
+        }),
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env = mock_env();
+    let liq_msg = |metadata: PriceMetadata| ExecuteMsg::Liquidate {
+        credit_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+            degraded: false,
+            metadata: None,
+        },
+        collateral_price: PriceResponse {
+            prices: vec![],
+            price: Decimal::one(),
+            decimals: 6u64,
+            degraded: false,
+            metadata: Some(metadata),
+        },
+        collateral_amount: Uint256::from(5_000u128),
+        bid_for: AssetInfo::NativeToken {
+            denom: "osmo".to_string(),
+        },
+    };
+    let info = mock_info(
+        "positions_contract",
+        &[Coin {
+            denom: "cdt".to_string(),
+            amount: Uint128::from(5_000u128),
+        }],
+    );
+
+    //Stale price errors
+    let stale_time = env.block.time.seconds() - 61;
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), liq_msg(PriceMetadata {
+        publish_time: stale_time,
+        source: String::from("pyth"),
+        confidence: Some(Decimal::permille(5)),
+        twap_window: None,
+    })).unwrap_err();
+    assert_eq!(err.to_string(), format!("Generic error: osmo price is stale, published at {}", stale_time));
+
+    //Uncertain price errors
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), liq_msg(PriceMetadata {
+        publish_time: env.block.time.seconds(),
+        source: String::from("pyth"),
+        confidence: Some(Decimal::percent(2)),
+        twap_window: None,
+    })).unwrap_err();
+    assert_eq!(err.to_string(), String::from("Generic error: osmo price confidence interval is too wide: 0.02"));
+
+    //Fresh & certain price succeeds
+    execute(deps.as_mut(), env.clone(), info, liq_msg(PriceMetadata {
+        publish_time: env.block.time.seconds() - 60,
+        source: String::from("pyth"),
+        confidence: Some(Decimal::permille(5)),
+        twap_window: None,
+    })).unwrap();
+}
+
 #[test]
 fn claim_liquidations() {
     let mut deps = mock_dependencies();
@@ -798,6 +938,7 @@ fn claim_liquidations() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
 
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -827,11 +968,15 @@ fn claim_liquidations() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(5000u128),
         bid_for: AssetInfo::NativeToken {
@@ -904,6 +1049,7 @@ fn update_queue() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     //Unauthorized
     let unauth_info = mock_info("owner0000", &[]);
@@ -935,6 +1081,7 @@ fn update_queue() {
         },
         max_premium: Some(Uint128::new(20u128)),
         bid_threshold: Some(Uint256::from(5_000_000u128)),
+        lp_pool_id: Some(1u64),
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -946,6 +1093,7 @@ fn update_queue() {
         queue_response.bid_threshold,
         Uint256::from(5_000_000u128)
     );
+    assert_eq!(queue_response.lp_pool_id, Some(1u64));
 
     //Query Slots to Assert increase
     let query_msg = QueryMsg::PremiumSlots { 
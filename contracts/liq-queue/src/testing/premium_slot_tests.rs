@@ -31,6 +31,7 @@ fn one_bidder_distribution() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -59,11 +60,15 @@ fn one_bidder_distribution() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(5000u128),
         bid_for: AssetInfo::NativeToken {
@@ -132,6 +137,7 @@ fn two_bidder_distribution() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::zero(),
+        lp_pool_id: None,
     };
 
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -161,11 +167,15 @@ fn two_bidder_distribution() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::from_ratio(10u128, 1u128),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         }, 
         collateral_amount: Uint256::from(4u128),
         bid_for: AssetInfo::NativeToken {
@@ -200,11 +210,15 @@ fn two_bidder_distribution() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::from_ratio(20u128, 1u128),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         }, 
         collateral_amount: Uint256::from(6u128),
         bid_for: AssetInfo::NativeToken {
@@ -307,6 +321,7 @@ fn two_bidder_distribution_big_number() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::zero(),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -336,11 +351,15 @@ fn two_bidder_distribution_big_number() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::from_ratio(10u128, 1u128),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         }, 
         collateral_amount: Uint256::from(400_000_000u128),
         bid_for: AssetInfo::NativeToken {
@@ -375,11 +394,15 @@ fn two_bidder_distribution_big_number() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::from_ratio(20u128, 1u128),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         }, 
         collateral_amount: Uint256::from(600_000_000u128),
         bid_for: AssetInfo::NativeToken {
@@ -482,6 +505,7 @@ fn one_user_two_slots() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(5_000_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -523,11 +547,15 @@ fn one_user_two_slots() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::from_ratio(10u128, 1u128),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         }, 
         collateral_amount: Uint256::from(5_000_000u128),
         bid_for: AssetInfo::NativeToken {
@@ -563,11 +591,15 @@ fn one_user_two_slots() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::from_ratio(10u128, 1u128),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         }, 
         collateral_amount: Uint256::from(10_000_000u128),
         bid_for: AssetInfo::NativeToken {
@@ -655,6 +687,7 @@ fn completely_empty_pool() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(5_000_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -684,11 +717,15 @@ fn completely_empty_pool() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price:  Decimal::from_ratio(50u128, 1u128),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         }, 
         collateral_amount: Uint256::from(20_000_000u128),
         bid_for: AssetInfo::NativeToken {
@@ -752,11 +789,15 @@ fn completely_empty_pool() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price:  Decimal::from_ratio(50u128, 1u128),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         }, 
         collateral_amount: Uint256::from(20_000_000u128),
         bid_for: AssetInfo::NativeToken {
@@ -842,6 +883,7 @@ fn product_truncated_to_zero() {
         },
         max_premium: Uint128::new(30u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(10_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -872,11 +914,15 @@ fn product_truncated_to_zero() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
             collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
             collateral_amount: Uint256::from(999_999_995u128), //5 uusd residue //999_999_999
             bid_for: AssetInfo::NativeToken {
@@ -962,6 +1008,7 @@ fn two_bidder_distribution_multiple_common_slots() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(5_000_000_000_000u128),
+        lp_pool_id: None,
     };
 
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -1052,11 +1099,15 @@ fn two_bidder_distribution_multiple_common_slots() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::from_ratio(10u128, 1u128),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(32_000_000u128),
         bid_for: AssetInfo::NativeToken {
@@ -1168,6 +1219,7 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(5_000_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -1236,11 +1288,15 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(100u128),
         bid_for: AssetInfo::NativeToken {
@@ -1299,11 +1355,15 @@ fn scalable_reward_distribution_after_multiple_liquidations() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(50u128),
         bid_for: AssetInfo::NativeToken {
@@ -1376,6 +1436,7 @@ fn not_enough_bid_for_collateral() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::zero(),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -1427,11 +1488,15 @@ fn not_enough_bid_for_collateral() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::from_ratio(3u128, 1u128),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(100u128),
         bid_for: AssetInfo::NativeToken {
@@ -25,6 +25,8 @@ fn stress_tests() {
             prices: vec![],
             price: Decimal::percent(2000),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         1000000000000u128,
         49999999999,
@@ -37,6 +39,8 @@ fn stress_tests() {
             prices: vec![],
             price: Decimal::percent(2000),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         10000000u128,
         499999,
@@ -51,6 +55,8 @@ fn stress_tests() {
             prices: vec![],
             price: Decimal::percent(1000000),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         1000000000000u128,
         99999999,
@@ -63,6 +69,8 @@ fn stress_tests() {
             prices: vec![],
             price: Decimal::percent(1000000),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         10001000000u128,
         1000000,
@@ -77,6 +85,8 @@ fn stress_tests() {
             prices: vec![],
             price: Decimal::percent(5000),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         1000000000000u128,
         19999999999,
@@ -89,6 +99,8 @@ fn stress_tests() {
             prices: vec![],
             price: Decimal::percent(5000),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         100000000u128,
         1999999,
@@ -102,6 +114,8 @@ fn stress_tests() {
             prices: vec![],
             price: Decimal::percent(10000),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         100000000000u128,
         999999999,
@@ -115,6 +129,8 @@ fn stress_tests() {
             prices: vec![],
             price: Decimal::percent(10),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         }, // 0.1 USD/asset
         100000000000u128,
         999999999900, // 10 micros of residue
@@ -138,6 +154,7 @@ fn instantiate_and_whitelist(deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQu
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(10_000_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 }
@@ -185,6 +202,8 @@ fn simulate_bids_with_2_liq_amounts(
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
                 collateral_price: asset_price,
                 collateral_amount: Uint256::from(liq_amount_1),
@@ -206,6 +225,8 @@ fn simulate_bids_with_2_liq_amounts(
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
                 collateral_price: asset_price,
                 collateral_amount: Uint256::from(liq_amount_2),
@@ -30,6 +30,7 @@ fn partial_one_collateral_one_slot() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -61,6 +62,8 @@ fn partial_one_collateral_one_slot() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(999u128),
         credit_info: AssetInfo::NativeToken {
@@ -70,6 +73,8 @@ fn partial_one_collateral_one_slot() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
     };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
@@ -81,11 +86,15 @@ fn partial_one_collateral_one_slot() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(999u128),
         bid_for: AssetInfo::NativeToken {
@@ -118,6 +127,7 @@ fn partial_one_collateral_one_slot_w_fees() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -150,6 +160,8 @@ fn partial_one_collateral_one_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(1110u128),
         credit_info: AssetInfo::NativeToken {
@@ -159,6 +171,8 @@ fn partial_one_collateral_one_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
     };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
@@ -170,11 +184,15 @@ fn partial_one_collateral_one_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(1110u128),
         bid_for: AssetInfo::NativeToken {
@@ -207,6 +225,7 @@ fn one_collateral_one_slot() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -238,6 +257,8 @@ fn one_collateral_one_slot() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(1000u128),
         credit_info: AssetInfo::NativeToken {
@@ -247,6 +268,8 @@ fn one_collateral_one_slot() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
     };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
@@ -258,11 +281,15 @@ fn one_collateral_one_slot() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(1000u128),
         bid_for: AssetInfo::NativeToken {
@@ -295,6 +322,7 @@ fn one_collateral_one_slot_w_fees() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -327,6 +355,8 @@ fn one_collateral_one_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(1112u128),
         credit_info: AssetInfo::NativeToken {
@@ -336,6 +366,8 @@ fn one_collateral_one_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
     };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
@@ -347,11 +379,15 @@ fn one_collateral_one_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(1112u128),
         bid_for: AssetInfo::NativeToken {
@@ -384,6 +420,7 @@ fn two_slot_w_fees() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -435,6 +472,8 @@ fn two_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(2000u128),
         credit_info: AssetInfo::NativeToken {
@@ -444,6 +483,8 @@ fn two_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
     };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
@@ -455,11 +496,15 @@ fn two_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(2000u128),
         bid_for: AssetInfo::NativeToken {
@@ -492,6 +537,7 @@ fn partial_two_slot_w_fees() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -543,6 +589,8 @@ fn partial_two_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(2222u128),
         credit_info: AssetInfo::NativeToken {
@@ -552,6 +600,8 @@ fn partial_two_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
     };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
@@ -564,11 +614,15 @@ fn partial_two_slot_w_fees() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(2111u128),
         bid_for: AssetInfo::NativeToken {
@@ -601,6 +655,7 @@ fn partial_two_slot_w_fees_bignums() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(100_000_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -652,6 +707,8 @@ fn partial_two_slot_w_fees_bignums() {
             prices: vec![],
             price: Decimal::percent(200),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(1_111_111_111u128),
         credit_info: AssetInfo::NativeToken {
@@ -661,6 +718,8 @@ fn partial_two_slot_w_fees_bignums() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
     };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
@@ -673,11 +732,15 @@ fn partial_two_slot_w_fees_bignums() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_price: PriceResponse {
             prices: vec![],
             price: Decimal::percent(200),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(1_111_111_111u128),
         bid_for: AssetInfo::NativeToken {
@@ -33,6 +33,7 @@ fn query_liquidatible() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
 
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -65,6 +66,8 @@ fn query_liquidatible() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
         collateral_amount: Uint256::from(10_000u128),
         credit_info: AssetInfo::NativeToken {
@@ -74,6 +77,8 @@ fn query_liquidatible() {
             prices: vec![],
             price: Decimal::one(),
             decimals: 6u64,
+            degraded: false,
+            metadata: None,
         },
     };
     let res = query(deps.as_ref(), mock_env(), msg).unwrap();
@@ -109,6 +114,7 @@ fn query_bid() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -245,6 +251,7 @@ fn query_slots_queues() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
@@ -254,6 +261,7 @@ fn query_slots_queues() {
         },
         max_premium: Uint128::new(10u128), //A slot for each premium is created when queue is created
         bid_threshold: Uint256::from(1_000_000_000u128),
+        lp_pool_id: None,
     };
     execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -277,6 +285,7 @@ fn query_slots_queues() {
                 max_premium: Uint128::new(10),
                 current_bid_id: Uint128::new(1),
                 bid_threshold: Uint128::new(1000000000).into(),
+                lp_pool_id: None,
             },
             QueueResponse {
                 bid_asset: Asset {
@@ -288,6 +297,7 @@ fn query_slots_queues() {
                 max_premium: Uint128::new(10),
                 current_bid_id: Uint128::new(1),
                 bid_threshold: Uint128::new(1000000000).into(),
+                lp_pool_id: None,
             }
         ]
     );
@@ -410,6 +420,7 @@ fn query_slots_queues() {
             max_premium: Uint128::new(10),
             current_bid_id: Uint128::new(2),
             bid_threshold: Uint128::new(1000000000).into(),
+            lp_pool_id: None,
         }
     );
 
@@ -434,6 +445,7 @@ fn query_slots_queues() {
             max_premium: Uint128::new(10),
             current_bid_id: Uint128::new(2),
             bid_threshold: Uint128::new(1000000000).into(),
+            lp_pool_id: None,
         }
     );
     assert_eq!(resp.len().to_string(), String::from("2"));
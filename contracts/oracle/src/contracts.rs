@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use cosmwasm_std::{
-    attr, entry_point, to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order, QuerierWrapper, QueryRequest, Response, StdError, StdResult, Storage, Uint128, WasmQuery
+    attr, entry_point, to_binary, Api, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order, QuerierWrapper, QueryRequest, Response, StdError, StdResult, Storage, Uint128, WasmQuery
 };
 use cw2::set_contract_version;
 
@@ -10,14 +10,15 @@ use pyth_sdk_cw::{PriceFeedResponse, query_price_feed, PriceIdentifier, PriceFee
 use osmosis_std::types::osmosis::twap::v1beta1 as TWAP;
 
 use membrane::math::{decimal_division, decimal_multiplication};
-use membrane::cdp::QueryMsg as CDP_QueryMsg;
+use membrane::cdp::{Config as CDP_Config, QueryMsg as CDP_QueryMsg};
 use membrane::osmosis_proxy::{QueryMsg as OP_QueryMsg, Config as OP_Config};
-use membrane::oracle::{Config, AssetResponse, ExecuteMsg, InstantiateMsg, PriceResponse, QueryMsg, MigrateMsg};
+use membrane::oracle::{merge_price_metadata, Config, AssetResponse, ExecuteMsg, InstantiateMsg, OracleTWAPResponse, PriceHistoryConfig, PriceHistoryResponse, PriceMetadata, PricePathHop, PricePathResponse, PriceRecord, PriceResponse, PriceSample, QueryMsg, MigrateMsg};
 use membrane::mars_vault_token::QueryMsg as Vault_QueryMsg;
-use membrane::types::{AssetInfo, AssetOracleInfo, Basket, Owner, PoolInfo, PoolStateResponse, PriceInfo, TWAPPoolInfo, VaultTokenInfo};
+use membrane::types::{AssetInfo, AssetOracleInfo, Basket, OracleSourceType, Owner, PoolInfo, PoolStateResponse, PriceInfo, TWAPPoolInfo, VaultTokenInfo};
 
 use crate::error::ContractError;
-use crate::state::{ASSETS, CONFIG, OWNERSHIP_TRANSFER};
+use crate::sources::{get_pyth_metadata, query_source, scale_pyth_price, SourceContext};
+use crate::state::{ASSETS, CONFIG, OWNERSHIP_TRANSFER, PRICE_HISTORY};
 
 // Contract name and version used for migration.
 const CONTRACT_NAME: &str = "oracle";
@@ -28,6 +29,10 @@ const STATIC_USD_PRICE: Decimal = Decimal::one();
 // Mainnet Pyth Price ID
 // https://pyth.network/developers/price-feed-ids#cosmwasm-stable
 const OSMO_USD_PRICE_ID: &str = "5867f5683c757393a0670ef0f701490950fe93fdb006d181c8265a831ac0c5c6"; 
+//Source of backup prices, these aren't saved to the price history
+const PRICE_HISTORY_SOURCE: &str = "price_history";
+//Decimals of TWAP path quote assets that aren't in the oracle
+const DEFAULT_QUOTE_DECIMALS: u64 = 6;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -47,6 +52,7 @@ pub fn instantiate(
             pyth_osmosis_address: Some(deps.api.addr_validate(&"osmo1hpdzqku55lmfmptpyj6wdlugqs5etr6teqf7r4yqjjrxjznjhtuqqu5kdh")?), //mainnet: osmo13ge29x4e2s63a8ytz2px8gurtyznmue4a69n5275692v3qn3ks8q7cwck7
             osmo_usd_pyth_feed_id: PriceIdentifier::from_hex(OSMO_USD_PRICE_ID).unwrap(),
             pools_for_usd_par_twap: vec![],
+            price_history: PriceHistoryConfig::default(),
         };
     } else {
         config = Config {
@@ -56,6 +62,7 @@ pub fn instantiate(
             pyth_osmosis_address: Some(deps.api.addr_validate(&"osmo1hpdzqku55lmfmptpyj6wdlugqs5etr6teqf7r4yqjjrxjznjhtuqqu5kdh")?), //mainnet: osmo13ge29x4e2s63a8ytz2px8gurtyznmue4a69n5275692v3qn3ks8q7cwck7
             osmo_usd_pyth_feed_id: PriceIdentifier::from_hex(OSMO_USD_PRICE_ID).unwrap(),
             pools_for_usd_par_twap: vec![],
+            price_history: PriceHistoryConfig::default(),
         };
     }
 
@@ -139,8 +146,11 @@ pub fn execute(
             osmosis_proxy_contract,
             pyth_osmosis_address,
             osmo_usd_pyth_feed_id,
-            pools_for_usd_par_twap
-        } => update_config(deps, env, info, owner, positions_contract, osmosis_proxy_contract, osmo_usd_pyth_feed_id, pyth_osmosis_address, pools_for_usd_par_twap),
+            pools_for_usd_par_twap,
+            price_history,
+        } => update_config(deps, env, info, owner, positions_contract, osmosis_proxy_contract, osmo_usd_pyth_feed_id, pyth_osmosis_address, pools_for_usd_par_twap, price_history),
+        ExecuteMsg::UpdatePriceHistory { asset_infos, basket_id } => update_price_history(deps, env, asset_infos, basket_id),
+        ExecuteMsg::RecordPrices { prices } => record_prices(deps, env, info, prices),
     }
 }
 
@@ -179,11 +189,23 @@ fn edit_asset(
                 .filter(|owner| owner.is_position_contract)
                 .collect::<Vec<Owner>>();
 
-            //Query each positions contract for asset being removed
+            //Query every Basket of each positions contract for asset being removed
             for positions_owner in positions_contracts {
-                let basket: Basket = deps.querier.query_wasm_smart(positions_owner.owner, &CDP_QueryMsg::GetBasket {  })?;
-                if basket.collateral_supply_caps.iter().any(|cap| cap.asset_info == asset_info && cap.current_supply > Uint128::zero()) {
-                    return Err(ContractError::AssetInUse { asset: asset_info.to_string() });
+                let mut start_after: Option<Uint128> = None;
+                loop {
+                    let baskets: Vec<Basket> = deps.querier.query_wasm_smart(
+                        positions_owner.owner.clone(),
+                        &CDP_QueryMsg::GetBaskets { start_after, limit: None },
+                    )?;
+                    if baskets.iter().any(|basket| basket.collateral_supply_caps.iter().any(|cap| cap.asset_info == asset_info && cap.current_supply > Uint128::zero())) {
+                        return Err(ContractError::AssetInUse { asset: asset_info.to_string() });
+                    }
+
+                    //Page until no Baskets are left
+                    match baskets.last() {
+                        Some(basket) => start_after = Some(basket.basket_id),
+                        None => break,
+                    }
                 }
             }
         }
@@ -200,6 +222,7 @@ fn edit_asset(
         ASSETS.remove(deps.storage, asset_info.to_string());
     } else if oracle_info.is_some() {
         let oracle_info = oracle_info.unwrap();
+        validate_oracle_info(deps.storage, deps.api, &env, &asset_info, &oracle_info)?;
         //Update Asset
         ASSETS.update(
             deps.storage,
@@ -270,6 +293,7 @@ fn add_asset(
         }
         AssetInfo::NativeToken { denom: _ } => {}
     };
+    validate_oracle_info(deps.storage, deps.api, &env, &asset_info, &oracle_info)?;
 
     //Save Oracle
     match ASSETS.load(deps.storage, asset_info.to_string()) {
@@ -317,6 +341,56 @@ fn add_asset(
     Ok(Response::new().add_attributes(attrs))
 }
 
+/// Validate an asset's oracle info
+fn validate_oracle_info(storage: &dyn Storage, api: &dyn Api, env: &Env, asset_info: &AssetInfo, oracle_info: &AssetOracleInfo) -> Result<(), ContractError> {
+    if let Some(max_deviation) = oracle_info.max_source_deviation {
+        if max_deviation.is_zero() || max_deviation > Decimal::one() {
+            return Err(ContractError::CustomError { val: String::from("Max source deviation must be between 0 & 100%") });
+        }
+    }
+
+    //Validate the TWAP path is continuous, each hop's quote is the next hop's base.
+    //Quote assets other than OSMO need to be in the oracle so each hop is scaled by its decimals.
+    let mut path_denom = match asset_info {
+        AssetInfo::NativeToken { denom } => Some(denom.clone()),
+        AssetInfo::Token { .. } => None,
+    };
+    for pool in oracle_info.pools_for_osmo_twap.iter() {
+        if let Some(denom) = path_denom {
+            if pool.base_asset_denom != denom {
+                return Err(ContractError::CustomError { val: format!("TWAP path is broken at pool {}: expected base {}, found {}", pool.pool_id, denom, pool.base_asset_denom) });
+            }
+        }
+        if pool.quote_asset_denom != String::from("uosmo") && !ASSETS.has(storage, pool.quote_asset_denom.clone()) {
+            return Err(ContractError::CustomError { val: format!("{} needs to be added to the oracle to price TWAP paths through it", pool.quote_asset_denom) });
+        }
+        path_denom = Some(pool.quote_asset_denom.clone());
+    }
+
+    //Validate price sources
+    if !oracle_info.sources.is_empty() && oracle_info.sources.iter().all(|source| source.weight.is_zero()) {
+        return Err(ContractError::CustomError { val: String::from("Price sources need at least 1 weighted source") });
+    }
+    for source in oracle_info.sources.iter() {
+        match source.source_type.clone() {
+            OracleSourceType::Pyth { price_feed_id } => {
+                PriceIdentifier::from_hex(&price_feed_id).map_err(|err| ContractError::CustomError { val: err.to_string() })?;
+            },
+            OracleSourceType::PriceFeed { contract, .. } => {
+                api.addr_validate(&contract)?;
+            },
+            OracleSourceType::Manual { price, expiry } => {
+                if price.is_zero() || expiry <= env.block.time.seconds() {
+                    return Err(ContractError::CustomError { val: String::from("Manual prices must be non-zero & expire in the future") });
+                }
+            },
+            OracleSourceType::OsmosisTwap {} | OracleSourceType::VaultToken { .. } => {},
+        }
+    }
+
+    Ok(())
+}
+
 /// Update contract configuration
 pub fn update_config(
     deps: DepsMut,
@@ -328,6 +402,7 @@ pub fn update_config(
     osmo_usd_pyth_feed_id: Option<PriceIdentifier>,
     pyth_osmosis_address: Option<String>,
     pools_for_usd_par_twap: Option<Vec<TWAPPoolInfo>>,
+    price_history: Option<PriceHistoryConfig>,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
     let mut attrs = vec![attr("method", "update_config")];
@@ -373,6 +448,10 @@ pub fn update_config(
     if let Some(usd_par_pools) = pools_for_usd_par_twap{
         config.pools_for_usd_par_twap = usd_par_pools;
     }
+    if let Some(price_history) = price_history {
+        price_history.validate()?;
+        config.price_history = price_history;
+    }
 
     CONFIG.save(deps.storage, &config)?;
     attrs.push(attr("updated_config", format!("{:?}", config)));
@@ -406,6 +485,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             asset_infos,
             twap_timeframe,
             oracle_time_limit,
+            basket_id,
         } => to_binary(&get_asset_prices(
             deps.storage, 
             deps.querier,
@@ -413,11 +493,26 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             asset_infos,
             twap_timeframe,
             oracle_time_limit,
-            None,
+            basket_id,
             None,
             None,
         )?),
         QueryMsg::Assets { asset_infos } => to_binary(&get_assets(deps, asset_infos)?),
+        QueryMsg::PriceHistory {
+            asset_info,
+            basket_id,
+            limit,
+        } => to_binary(&get_price_history(deps, asset_info, basket_id, limit)?),
+        QueryMsg::OracleTWAP {
+            asset_info,
+            window,
+            basket_id,
+        } => to_binary(&get_oracle_twap(deps, env, asset_info, window, basket_id)?),
+        QueryMsg::SimulatePricePath {
+            asset_info,
+            twap_timeframe,
+            basket_id,
+        } => to_binary(&simulate_price_path(deps, env, asset_info, twap_timeframe, basket_id)?),
     }
 }
 
@@ -478,6 +573,8 @@ pub fn get_vault_token_price(
         prices: oracle_sources,
         price: vault_token_value,
         decimals,
+        degraded: underlying_price.degraded,
+        metadata: underlying_price.metadata,
     }, osmo_quote))
 }
 
@@ -505,7 +602,7 @@ pub fn get_lp_price(
     let mut asset_values: Vec<Decimal> = vec![];
 
     //Get asset prices
-    let (asset_prices, oracle_sources) = {
+    let (asset_prices, oracle_sources, degraded, metadata) = {
         let res = get_asset_prices(
             storage,
             querier.clone(),
@@ -527,8 +624,12 @@ pub fn get_lp_price(
                 {
                     price_infos.extend(price.clone().prices);
                 });
+        //LPs are degraded if any of their assets are
+        let degraded = res.iter().any(|price| price.degraded);
+        //LPs are as stale & uncertain as their assets
+        let metadata = res.iter().fold(None, |metadata, price| merge_price_metadata(metadata, price.clone().metadata));
         
-        (res, price_infos)
+        (res, price_infos, degraded, metadata)
     };
 
     //Calculate share value
@@ -578,6 +679,8 @@ pub fn get_lp_price(
         prices: oracle_sources,
         price: LP_price,
         decimals: 18u64,
+        degraded,
+        metadata,
     })
 }
 
@@ -630,150 +733,406 @@ fn get_asset_price(
         });
     };
 
-    //twap_timeframe = MINUTES * SECONDS_PER_MINUTE
-    let twap_timeframe: u64 = (twap_timeframe * 60);
-    let start_time: u64 = env.block.time.seconds() - twap_timeframe;
+    let ctx = SourceContext {
+        storage,
+        querier,
+        env,
+        config,
+        asset_info,
+        oracle_info: oracle_info.clone(),
+        twap_timeframe,
+        oracle_time_limit,
+        basket_id_field,
+        queried_asset_prices,
+        osmo_quote_price,
+    };
+    let sources = oracle_info.get_sources();
+    let max_source_deviation = oracle_info.get_max_source_deviation();
+
+    let mut weighted_prices: Vec<(PriceResponse, Decimal)> = vec![];
+    let mut backup_prices: Vec<PriceResponse> = vec![];
+    let mut quote_price: Option<Decimal> = None;
+    let mut source_error = StdError::GenericErr { msg: String::from("No price sources") };
+    let mut source_failed = false;
+
+    //Query weighted sources in order, skipping those that fail
+    for source in sources.iter().filter(|source| !source.weight.is_zero()) {
+        match query_source(&ctx, source) {
+            Ok((price, quote)) => {
+                quote_price = quote_price.or(quote);
+                weighted_prices.push((price, source.weight));
+            },
+            Err(err) => {
+                source_failed = true;
+                source_error = err;
+            },
+        }
+    }
 
-    let mut oracle_prices = vec![];
-    let mut asset_price_in_osmo_steps = vec![];
-    let mut usd_par_prices = vec![];
-    let mut quote_price = Decimal::zero();
+    //Backups are queried if no weighted source returned a price or to check deviation
+    if weighted_prices.is_empty() || max_source_deviation.is_some() {
+        for source in sources.iter().filter(|source| source.weight.is_zero()) {
+            match query_source(&ctx, source) {
+                Ok((price, quote)) => {
+                    quote_price = quote_price.or(quote);
+                    backup_prices.push(price);
+                },
+                Err(err) => {
+                    source_failed = true;
+                    source_error = err;
+                },
+            }
+        }
+    }
 
-    let mut pyth_feed_errored = false;
+    //Use the weighted price or the first backup
+    let (mut price, check_prices) = if !weighted_prices.is_empty() {
+        let check_prices: Vec<PriceResponse> = weighted_prices.iter().map(|(price, _)| price.clone()).collect();
+        (get_weighted_price(weighted_prices)?, check_prices)
+    } else if !backup_prices.is_empty() {
+        (backup_prices.remove(0), vec![])
+    } else if let Some(history_price) = get_backup_price(
+        storage,
+        &ctx.config.price_history,
+        ctx.asset_info.to_string(),
+        basket_id,
+        oracle_info.decimals,
+        ctx.env.block.time.seconds(),
+    )? {
+        //Use the latest saved price if every source failed
+        return Ok((history_price, quote_price));
+    } else {
+        return Err(source_error);
+    };
 
-    //Use Pyth USD-quoted price feeds first if available
-    if let Some(pyth_osmosis_address) = config.clone().pyth_osmosis_address {
-        if let Some(feed_id) = oracle_info.clone().pyth_price_feed_id {
-            //Query USD price from Pyth
-            let price_feed_response: PriceFeedResponse = match query_price_feed(
-                &querier, 
-                pyth_osmosis_address,
-                PriceIdentifier::from_hex(&feed_id).map_err(|err| StdError::GenericErr { msg: err.to_string() })?,
-            ){
-                    Ok(res) => res,
-                    Err(_) => {
-                        pyth_feed_errored = true;
-                        //If Pyth fails, skip to USD-par pricing
-                        PriceFeedResponse {
-                            price_feed: PriceFeed::default(),
-                        }
-                    }
-                };
-            
-            //Query unscaled price
-            let price_feed = price_feed_response.price_feed;
-            let price = price_feed
-                .get_ema_price_no_older_than(env.block.time.seconds() as i64, oracle_time_limit);
+    //Flag the price as degraded if its sources deviate
+    if let Some(max_deviation) = max_source_deviation {
+        price = flag_source_deviation(price, check_prices, backup_prices, source_failed, max_deviation)?;
+    }
 
-            //If price was queried && within the time limit, scale it & use it
-            //If not, skip to Osmosis TWAP pricing
-            let mut pyth_price: Decimal = Decimal::zero();
-            match price {
-                Some(price) => {
-                    //Scale price using given exponent
-                    match price.expo > 0 {
-                        true => {
-                            pyth_price = decimal_multiplication(
-                                Decimal::from_str(&price.price.to_string())?, 
-                                Decimal::from_ratio(Uint128::new(10), Uint128::one()).checked_pow(price.expo as u32)?
-                            )?;
-                        },
-                        //If the exponent is negative we divide, it should be for most if not all
-                        false => {
-                            pyth_price = decimal_division(
-                                Decimal::from_str(&price.price.to_string())?, 
-                                Decimal::from_ratio(Uint128::new(10), Uint128::one()).checked_pow((price.expo*-1) as u32)?
-                            )?;
-                        }
-                    };                   
-                    
+    Ok((price, quote_price))
+}
 
-                    //Push Pyth USD price
-                    oracle_prices.push(PriceInfo {
-                        source: String::from("pyth"),
-                        price: pyth_price,
-                    });
-                },
-                None => {
-                    pyth_feed_errored = true;
-                }
-            }
+/// Flag the price as degraded if a weighted or backup source deviates from it by more than the max deviation,
+/// or if a source failed & can't be checked against
+pub fn flag_source_deviation(
+    mut price: PriceResponse,
+    check_prices: Vec<PriceResponse>,
+    backup_prices: Vec<PriceResponse>,
+    source_failed: bool,
+    max_deviation: Decimal,
+) -> StdResult<PriceResponse> {
+    if source_failed {
+        price.degraded = true;
+    }
+    for source_price in check_prices {
+        if get_source_deviation(&price, &source_price)? > max_deviation {
+            price.degraded = true;
+        }
+    }
+    for backup_price in backup_prices {
+        price = check_source_deviation(price, backup_price, max_deviation)?;
+    }
 
-            //Return Pyth only price if it was queried successfully
-            if !pyth_feed_errored {
-                return Ok((PriceResponse {
-                    prices: oracle_prices,
-                    price: pyth_price,
-                    decimals: oracle_info.decimals,
-                }, None));
-            }
+    Ok(price)
+}
+
+/// Save the current price of each asset to its price history.
+/// Prices use the Positions contract's collateral TWAP timeframe & oracle time limit.
+fn update_price_history(
+    deps: DepsMut,
+    env: Env,
+    asset_infos: Vec<AssetInfo>,
+    basket_id: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let basket_id = basket_id.unwrap_or(Uint128::new(1u128));
+
+    let positions_contract = match config.clone().positions_contract {
+        Some(positions_contract) => positions_contract,
+        None => return Err(ContractError::CustomError { val: String::from("Positions contract isn't set") }),
+    };
+    let cdp_config: CDP_Config = deps.querier.query_wasm_smart(positions_contract, &CDP_QueryMsg::Config {})?;
+
+    let prices = get_asset_prices(
+        deps.storage,
+        deps.querier,
+        env.clone(),
+        asset_infos.clone(),
+        cdp_config.collateral_twap_timeframe,
+        cdp_config.oracle_time_limit,
+        Some(basket_id),
+        None,
+        None,
+    )?;
+
+    let mut saved = vec![];
+    for (asset_info, price) in asset_infos.into_iter().zip(prices) {
+        if save_price_sample(deps.storage, &config.price_history, asset_info.to_string(), basket_id, &price, env.block.time.seconds())? {
+            saved.push(asset_info.to_string());
         }
     }
-    /// If there is no return above, query starting from Osmosis TWAPs
 
-    //Query OSMO price from the TWAP sources
-    //This can use multiple pools to calculate our price
-    for pool in oracle_info.pools_for_osmo_twap.clone() {
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "update_price_history"),
+        attr("saved", saved.join(", ")),
+    ]))
+}
 
-        let res: TWAP::GeometricTwapToNowResponse = TWAP::TwapQuerier::new(&querier).geometric_twap_to_now(
-            pool.clone().pool_id, 
-            pool.clone().base_asset_denom, 
-            pool.clone().quote_asset_denom, 
-            Some(osmosis_std::shim::Timestamp {
-                seconds:  start_time as i64,
-                nanos: 0,
-            }),
-        )?;
+/// Save prices queried by the Positions contract to their price history
+fn record_prices(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    prices: Vec<PriceRecord>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.positions_contract.is_none() || info.sender != config.clone().positions_contract.unwrap() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut saved = vec![];
+    for record in prices {
+        //Prices are saved at the time they were queried, which can't be in the future
+        let time = record.time.min(env.block.time.seconds());
+        if save_price_sample(deps.storage, &config.price_history, record.asset_info.to_string(), record.basket_id, &record.price, time)? {
+            saved.push(record.asset_info.to_string());
+        }
+    }
 
-        //Push TWAP
-        asset_price_in_osmo_steps.push(Decimal::from_str(&res.geometric_twap)?);
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "record_prices"),
+        attr("saved", saved.join(", ")),
+    ]))
+}
+
+/// Add a price to an asset's history if the sampling interval has passed since the last price.
+/// The oldest price is dropped once the history is full.
+/// Returns whether the price was saved.
+fn save_price_sample(
+    storage: &mut dyn Storage,
+    history_config: &PriceHistoryConfig,
+    asset: String,
+    basket_id: Uint128,
+    price: &PriceResponse,
+    time: u64,
+) -> StdResult<bool> {
+    //Backup prices come from the history so they aren't saved again
+    if price.price.is_zero() || price.prices.iter().any(|price| price.source == PRICE_HISTORY_SOURCE) {
+        return Ok(false);
+    }
+
+    let mut samples = PRICE_HISTORY.may_load(storage, (asset.clone(), basket_id.u128()))?.unwrap_or_default();
+    if let Some(last_sample) = samples.last() {
+        if time < last_sample.time + history_config.sampling_interval {
+            return Ok(false);
+        }
+    }
+
+    samples.push(PriceSample {
+        price: price.price,
+        time,
+    });
+    while samples.len() > history_config.length as usize {
+        samples.remove(0);
     }
+    PRICE_HISTORY.save(storage, (asset, basket_id.u128()), &samples)?;
 
-    //Multiply prices to denominate in OSMO or the final quote asset...
-    //...twe'll then find the final price in OSMO further down
+    Ok(true)
+}
+
+/// Return the latest saved price if it's within the max backup age
+fn get_backup_price(
+    storage: &dyn Storage,
+    history_config: &PriceHistoryConfig,
+    asset: String,
+    basket_id: Uint128,
+    decimals: u64,
+    current_time: u64,
+) -> StdResult<Option<PriceResponse>> {
+    if history_config.max_backup_age == 0 {
+        return Ok(None);
+    }
+
+    let sample = match PRICE_HISTORY.may_load(storage, (asset, basket_id.u128()))?.and_then(|samples| samples.last().cloned()) {
+        Some(sample) => sample,
+        None => return Ok(None),
+    };
+    if current_time.saturating_sub(sample.time) > history_config.max_backup_age {
+        return Ok(None);
+    }
+
+    //Backups are degraded so new debt can't be taken against them
+    Ok(Some(PriceResponse {
+        prices: vec![PriceInfo {
+            source: String::from(PRICE_HISTORY_SOURCE),
+            price: sample.price,
+        }],
+        price: sample.price,
+        decimals,
+        degraded: true,
+        metadata: Some(PriceMetadata {
+            publish_time: sample.time,
+            source: String::from(PRICE_HISTORY_SOURCE),
+            confidence: None,
+            twap_window: None,
+        }),
+    }))
+}
+
+/// Return an asset's saved prices, oldest first
+fn get_price_history(
+    deps: Deps,
+    asset_info: AssetInfo,
+    basket_id: Option<Uint128>,
+    limit: Option<u32>,
+) -> StdResult<PriceHistoryResponse> {
+    let basket_id = basket_id.unwrap_or(Uint128::new(1u128));
+    let mut prices = PRICE_HISTORY.may_load(deps.storage, (asset_info.to_string(), basket_id.u128()))?.unwrap_or_default();
+
+    //Keep the latest prices
+    if let Some(limit) = limit {
+        let skip = prices.len().saturating_sub(limit as usize);
+        prices = prices.split_off(skip);
+    }
+
+    Ok(PriceHistoryResponse { asset_info, prices })
+}
+
+/// Return the time-weighted average of an asset's saved prices over the window
+fn get_oracle_twap(
+    deps: Deps,
+    env: Env,
+    asset_info: AssetInfo,
+    window: u64, //in seconds
+    basket_id: Option<Uint128>,
+) -> StdResult<OracleTWAPResponse> {
+    let basket_id = basket_id.unwrap_or(Uint128::new(1u128));
+    let samples = PRICE_HISTORY.may_load(deps.storage, (asset_info.to_string(), basket_id.u128()))?.unwrap_or_default();
+
+    calc_oracle_twap(&samples, env.block.time.seconds(), window)
+}
+
+/// Each price is weighted by the time until the next price, or now for the latest.
+/// The price before the window starts is weighted from the start of the window.
+pub fn calc_oracle_twap(samples: &[PriceSample], current_time: u64, window: u64) -> StdResult<OracleTWAPResponse> {
+    if window == 0 {
+        return Err(StdError::GenericErr { msg: String::from("TWAP window must be greater than 0") });
+    }
+    let start_time = current_time.saturating_sub(window);
+
+    let mut weighted_total = Decimal::zero();
+    let mut window_covered = 0u64;
+    let mut used_samples = vec![];
+    for (i, sample) in samples.iter().enumerate() {
+        let end_time = match samples.get(i + 1) {
+            Some(next_sample) => next_sample.time,
+            None => current_time,
+        };
+        if end_time <= start_time {
+            continue;
+        }
+
+        let duration = end_time - sample.time.max(start_time);
+        weighted_total += decimal_multiplication(sample.price, Decimal::from_ratio(duration, 1u64))?;
+        window_covered += duration;
+        used_samples.push(sample);
+    }
+
+    let price = match used_samples.last() {
+        //Prices saved this block have no duration
+        Some(sample) if window_covered == 0 => sample.price,
+        Some(_) => decimal_division(weighted_total, Decimal::from_ratio(window_covered, 1u64))?,
+        None => return Err(StdError::GenericErr { msg: String::from("No saved prices in the window") }),
+    };
+
+    Ok(OracleTWAPResponse {
+        price,
+        window_covered,
+        samples: used_samples.len() as u32,
+    })
+}
+
+/// Return the weighted average of source prices.
+/// Metadata is merged & the price is degraded if any source is.
+fn get_weighted_price(prices: Vec<(PriceResponse, Decimal)>) -> StdResult<PriceResponse> {
+    //A single source is returned as is
+    if prices.len() == 1 {
+        return Ok(prices[0].0.clone());
+    }
+
+    let total_weight = prices.iter().fold(Decimal::zero(), |total, (_, weight)| total + *weight);
+    let mut weighted_total = Decimal::zero();
+    let mut price_infos = vec![];
+    let mut degraded = false;
+    let mut metadata = None;
+    for (price, weight) in prices.clone() {
+        weighted_total += decimal_multiplication(price.price, weight)?;
+        price_infos.extend(price.prices);
+        degraded = degraded || price.degraded;
+        metadata = merge_price_metadata(metadata, price.metadata);
+    }
+
+    Ok(PriceResponse {
+        prices: price_infos,
+        price: decimal_division(weighted_total, total_weight)?,
+        decimals: prices[0].0.decimals,
+        degraded,
+        metadata,
+    })
+}
+
+/// Return the Osmosis TWAP price of an asset.
+/// TWAP paths that don't end in OSMO are priced using the path's quote asset price.
+pub fn get_osmosis_price(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    env: Env,
+    config: Config,
+    asset_info: AssetInfo,
+    oracle_info: AssetOracleInfo,
+    twap_timeframe: u64, //in minutes
+    oracle_time_limit: u64, //in seconds
+    basket_id_field: Option<Uint128>,
+    //For Multi-Asset queries or recursive queries
+    queried_asset_prices: Option<Vec<(String, PriceResponse)>>, //Asset & Price
+    osmo_quote_price: Option<Decimal>, 
+) -> StdResult<(PriceResponse, Option<Decimal>)> { //Return Asset Price & Quote Price (OSMO/USD)
+    let basket_id = oracle_info.basket_id;
+
+    //twap_timeframe = MINUTES * SECONDS_PER_MINUTE
+    let twap_timeframe: u64 = (twap_timeframe * 60);
+    let start_time: u64 = env.block.time.seconds() - twap_timeframe;
+
+    let mut oracle_prices = vec![];
+    let mut usd_par_prices = vec![];
+    let mut quote_price = Decimal::zero();
+    //TWAPs are queried to now
+    let mut metadata = PriceMetadata {
+        publish_time: env.block.time.seconds(),
+        source: String::from("osmosis"),
+        confidence: None,
+        twap_window: if oracle_info.pools_for_osmo_twap.is_empty() { None } else { Some(twap_timeframe) },
+    };
+
+    //Query the OSMO price from the TWAP path.
+    //Each hop is scaled by its own decimals so the path can mix decimal places.
+    let price_path = get_price_path(storage, querier, start_time, oracle_info.decimals, &oracle_info.pools_for_osmo_twap, basket_id)?;
+
+    //Find the asset price in OSMO or the path's final quote asset
     let asset_price_in_osmo = {
-        let mut final_price = Decimal::one();
         //If no prices were queried, return error unless its OSMO
-        if asset_price_in_osmo_steps.len() == 0 && asset_info.to_string() != String::from("uosmo"){
+        if price_path.is_empty() && asset_info.to_string() != String::from("uosmo"){
             return Err(StdError::GenericErr {
                 msg: String::from("No OSMO TWAP prices found"),
             });
         }
 
-        //Find asset price in OSMO
         if asset_info.to_string() == String::from("uosmo"){
-            final_price = Decimal::one();
+            Decimal::one()
         } else {
-            //Multiply prices to get the desired Quote
-            for price in asset_price_in_osmo_steps {
-                final_price = decimal_multiplication(final_price, price)?;
-            } 
+            price_path[price_path.len()-1].cumulative_price
         }
-
-        //Get oracle info for the last pool
-        if oracle_info.pools_for_osmo_twap.len() != 0 {
-            let trailing_oracle_list = ASSETS.load(storage, oracle_info.pools_for_osmo_twap[oracle_info.pools_for_osmo_twap.len()-1].quote_asset_denom.clone())?;
-            //Find the basket 
-            let trailing_oracle_info = match trailing_oracle_list.into_iter().find(|oracle| oracle.basket_id == basket_id){
-                Some(oracle) => oracle,
-                None => {
-                    return Err(StdError::GenericErr {
-                        msg: String::from("Invalid basket_id"),
-                    });
-                }
-            };
-            
-            //Transform price by moving its decimal point by the difference in decimals from the last quote asset
-            //WARNING: This may not work if multiple assets in the path are different decimal places
-            //We do this bc if the 1st asset is 18 decimals & the quote is 6, the price will be 12 decimals off.
-            //Furthermore if the 1st asset is 6 decimals & the quote is 18, the price will be 12 decimals off.
-            if oracle_info.decimals > trailing_oracle_info.decimals {
-                final_price = decimal_multiplication(final_price, Decimal::from_ratio(Uint128::new(10).checked_pow(oracle_info.decimals as u32 - trailing_oracle_info.decimals as u32)?, Uint128::one()))?;
-            } else if oracle_info.decimals < trailing_oracle_info.decimals {
-                final_price = decimal_division(final_price, Decimal::from_ratio(Uint128::one(), Uint128::new(10).checked_pow(trailing_oracle_info.decimals as u32 - oracle_info.decimals as u32)?))?;
-            }
-        }
-        final_price
     };
     //Results in slight error: (https://medium.com/reflexer-labs/analysis-of-the-rai-twap-oracle-20a01af2e49d)
 
@@ -810,6 +1169,8 @@ fn get_asset_price(
                         //Multiply prices to get the desired Quote
                         price: decimal_multiplication(asset_price_in_osmo, price.1.price)?,
                         decimals: oracle_info.decimals,
+                        degraded: price.1.degraded,
+                        metadata: merge_price_metadata(Some(metadata), price.1.metadata),
                     }, osmo_quote_price));
                 },
                 None => {
@@ -844,6 +1205,8 @@ fn get_asset_price(
                                 //Multiply prices to get the desired Quote
                                 price: decimal_multiplication(asset_price_in_osmo, res.price)?,
                                 decimals: oracle_info.decimals,
+                                degraded: res.degraded,
+                                metadata: merge_price_metadata(Some(metadata), res.metadata),
                             }, quote));
                         },
                         Err(_) => {
@@ -891,28 +1254,14 @@ fn get_asset_price(
                 Some(price) => {
                     if !usd_price_failed {
                         //Scale price using given exponent
-                        match price.expo > 0 {
-                            true => {
-                                quote_price = decimal_multiplication(
-                                    Decimal::from_str(&price.price.to_string())?, 
-                                    Decimal::from_ratio(Uint128::new(10), Uint128::one()).checked_pow(price.expo as u32)?
-                                )?;
-                            },
-                            //If the exponent is negative we divide, it should be for most if not all
-                            false => {
-                                quote_price = decimal_division(
-                                    Decimal::from_str(&price.price.to_string())?, 
-                                    Decimal::from_ratio(Uint128::new(10), Uint128::one()).checked_pow((price.expo*-1) as u32)?
-                                )?;
-                            }
-                        };                   
-                        
+                        quote_price = scale_pyth_price(price)?;
 
                         //Push Pyth OSMO USD price
                         oracle_prices.push(PriceInfo {
                             source: String::from("pyth"),
                             price: quote_price,
                         });
+                        metadata = metadata.merge(get_pyth_metadata(price));
                     }
                 },
                 None => {
@@ -967,6 +1316,7 @@ fn get_asset_price(
                     source: String::from("osmosis"),
                     price: quote_price,
                 });
+                metadata.twap_window = Some(twap_timeframe);
             } else {
                 return Err(StdError::GenericErr { msg: String::from("No USD-par price feeds") })
             }
@@ -987,9 +1337,136 @@ fn get_asset_price(
         prices: oracle_prices,
         price: asset_price,
         decimals: oracle_info.decimals,
+        degraded: false,
+        metadata: Some(metadata),
     }, Some(quote_price)))
 }
 
+/// Query each hop of a TWAP path.
+/// Hop prices are in whole tokens, scaled by the hop's base & quote decimals.
+/// The first hop's base is the priced asset, each following hop's base is the last hop's quote.
+pub fn get_price_path(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    start_time: u64,
+    asset_decimals: u64,
+    pools: &[TWAPPoolInfo],
+    basket_id: Uint128,
+) -> StdResult<Vec<PricePathHop>> {
+    let mut hops: Vec<PricePathHop> = vec![];
+    let mut base_decimals = asset_decimals;
+    let mut cumulative_price = Decimal::one();
+
+    for pool in pools {
+        let res: TWAP::GeometricTwapToNowResponse = TWAP::TwapQuerier::new(&querier).geometric_twap_to_now(
+            pool.clone().pool_id, 
+            pool.clone().base_asset_denom, 
+            pool.clone().quote_asset_denom, 
+            Some(osmosis_std::shim::Timestamp {
+                seconds:  start_time as i64,
+                nanos: 0,
+            }),
+        )?;
+        let twap = Decimal::from_str(&res.geometric_twap)?;
+
+        //Move the decimal point by the difference between the base & quote decimals.
+        //Ex: If the base is 18 decimals & the quote is 6, the TWAP is 12 decimals too small.
+        let quote_decimals = get_asset_decimals(storage, &pool.quote_asset_denom, basket_id)?;
+        let price = if base_decimals > quote_decimals {
+            decimal_multiplication(twap, Decimal::from_ratio(Uint128::new(10).checked_pow((base_decimals - quote_decimals) as u32)?, Uint128::one()))?
+        } else if base_decimals < quote_decimals {
+            decimal_division(twap, Decimal::from_ratio(Uint128::new(10).checked_pow((quote_decimals - base_decimals) as u32)?, Uint128::one()))?
+        } else {
+            twap
+        };
+        cumulative_price = decimal_multiplication(cumulative_price, price)?;
+
+        hops.push(PricePathHop {
+            pool_id: pool.pool_id,
+            base_asset_denom: pool.clone().base_asset_denom,
+            base_decimals,
+            quote_asset_denom: pool.clone().quote_asset_denom,
+            quote_decimals,
+            twap,
+            price,
+            cumulative_price,
+        });
+        base_decimals = quote_decimals;
+    }
+
+    Ok(hops)
+}
+
+/// Return the decimals of a registered asset.
+/// Unregistered quote assets (OSMO & paths saved before hop scaling) default to 6 decimals.
+fn get_asset_decimals(storage: &dyn Storage, denom: &str, basket_id: Uint128) -> StdResult<u64> {
+    let oracle_list = ASSETS.may_load(storage, denom.to_string())?.unwrap_or_default();
+    //Decimals are the same across baskets, prefer the basket's oracle info
+    match oracle_list.iter().find(|oracle| oracle.basket_id == basket_id).or(oracle_list.first()) {
+        Some(oracle) => Ok(oracle.decimals),
+        None => Ok(DEFAULT_QUOTE_DECIMALS),
+    }
+}
+
+/// Return the price at each hop of an asset's TWAP path
+fn simulate_price_path(
+    deps: Deps,
+    env: Env,
+    asset_info: AssetInfo,
+    twap_timeframe: u64, //in minutes
+    basket_id: Option<Uint128>,
+) -> StdResult<PricePathResponse> {
+    let basket_id = basket_id.unwrap_or(Uint128::new(1u128));
+    let oracle_info = match ASSETS.load(deps.storage, asset_info.to_string())?
+        .into_iter()
+        .find(|oracle| oracle.basket_id == basket_id)
+    {
+        Some(oracle_info) => oracle_info,
+        None => return Err(StdError::GenericErr { msg: String::from("Invalid basket_id") }),
+    };
+
+    let start_time = env.block.time.seconds() - (twap_timeframe * 60);
+    let hops = get_price_path(deps.storage, deps.querier, start_time, oracle_info.decimals, &oracle_info.pools_for_osmo_twap, basket_id)?;
+    let price = match hops.last() {
+        Some(hop) => hop.cumulative_price,
+        None => Decimal::one(),
+    };
+
+    Ok(PricePathResponse { hops, price })
+}
+
+/// Flag the primary price as degraded if it deviates from the secondary price
+/// by more than the max deviation.
+/// The primary price is kept & both sources are returned.
+pub fn check_source_deviation(
+    primary: PriceResponse,
+    secondary: PriceResponse,
+    max_deviation: Decimal,
+) -> StdResult<PriceResponse> {
+    let deviation = get_source_deviation(&primary, &secondary)?;
+
+    let mut prices = primary.prices;
+    prices.extend(secondary.prices);
+
+    Ok(PriceResponse {
+        prices,
+        price: primary.price,
+        decimals: primary.decimals,
+        degraded: primary.degraded || secondary.degraded || deviation > max_deviation,
+        metadata: primary.metadata,
+    })
+}
+
+/// Return the % deviation of a source price from the primary price
+fn get_source_deviation(primary: &PriceResponse, source: &PriceResponse) -> StdResult<Decimal> {
+    //A zero price can't be checked
+    if primary.price.is_zero() {
+        Ok(Decimal::one())
+    } else {
+        decimal_division(primary.price.abs_diff(source.price), primary.price)
+    }
+}
+
 /// Return list of asset price info as list of PriceResponse
 fn get_asset_prices(
     storage: &dyn Storage,
@@ -1038,47 +1515,26 @@ fn get_asset_prices(
                 )?);
             },
             None => {
-                //If its a vault token get the vault token price
-                if let Some(vault_info) = asset_info.clone().vault_info {
-                    let (price, quote_price) = get_vault_token_price(
-                        storage,
-                        querier.clone(),
-                        env.clone(),
-                        CONFIG.load(storage)?,
-                        vault_info,
-                        asset_info.decimals,
-                        twap_timeframe,
-                        oracle_time_limit,
-                        basket_id_field,
-                        Some(price_propagations.clone()), //Vault tokens will never be queried first during Basket queries so we don't need to save their prices
-                        osmo_quote_price,
-                    )?;
-                    price_propagations.push((asset.to_string(), price.clone()));
-                    osmo_quote_price = quote_price;
-                    price_responses.push(price);
-                } else {
-
-                    //If asset is not an LP && the price isn't in the list of propogated prices, get the asset price
-                    if let Some(price) = price_propagations.clone().into_iter().find(|price| price.0 == asset.to_string()) {
-                        price_responses.push(price.1);
-                        continue;
-                    }
-                    //Query price if not found
-                    let (price, quote_price) = get_asset_price(
-                        storage,
-                        querier.clone(),
-                        env.clone(),
-                        asset.clone(),
-                        twap_timeframe,
-                        oracle_time_limit,
-                        basket_id_field,
-                        Some(price_propagations.clone()),
-                        osmo_quote_price,
-                    )?;
-                    price_propagations.push((asset.to_string(), price.clone()));
-                    osmo_quote_price = quote_price;
-                    price_responses.push(price);
+                //If asset is not an LP && the price isn't in the list of propogated prices, get the asset price
+                if let Some(price) = price_propagations.clone().into_iter().find(|price| price.0 == asset.to_string()) {
+                    price_responses.push(price.1);
+                    continue;
                 }
+                //Query price from the asset's sources if not found, vault tokens included
+                let (price, quote_price) = get_asset_price(
+                    storage,
+                    querier.clone(),
+                    env.clone(),
+                    asset.clone(),
+                    twap_timeframe,
+                    oracle_time_limit,
+                    basket_id_field,
+                    Some(price_propagations.clone()),
+                    osmo_quote_price,
+                )?;
+                price_propagations.push((asset.to_string(), price.clone()));
+                osmo_quote_price = quote_price;
+                price_responses.push(price);
             }
         }
     }
@@ -5,6 +5,7 @@
 pub mod contracts;
 pub mod error;
 pub mod helpers;
+pub mod sources;
 pub mod state;
 
 #[cfg(test)]
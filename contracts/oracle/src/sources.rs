@@ -0,0 +1,238 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{Decimal, Env, QuerierWrapper, StdError, StdResult, Storage, Uint128};
+
+use pyth_sdk_cw::{query_price_feed, Price, PriceIdentifier};
+
+use membrane::math::{decimal_division, decimal_multiplication};
+use membrane::oracle::{Config, FeedPriceResponse, PriceFeedQueryMsg, PriceMetadata, PriceResponse};
+use membrane::types::{AssetInfo, AssetOracleInfo, OracleSource, OracleSourceType, PriceInfo, VaultTokenInfo};
+
+use crate::contracts::{get_osmosis_price, get_vault_token_price};
+
+/// Inputs shared by every price source of an asset
+pub struct SourceContext<'a> {
+    pub storage: &'a dyn Storage,
+    pub querier: QuerierWrapper<'a>,
+    pub env: Env,
+    pub config: Config,
+    pub asset_info: AssetInfo,
+    pub oracle_info: AssetOracleInfo,
+    pub twap_timeframe: u64, //in minutes
+    pub oracle_time_limit: u64, //in seconds
+    pub basket_id_field: Option<Uint128>,
+    //For Multi-Asset queries or recursive queries
+    pub queried_asset_prices: Option<Vec<(String, PriceResponse)>>, //Asset & Price
+    pub osmo_quote_price: Option<Decimal>,
+}
+
+/// Oracle source adapter.
+/// New sources implement this & are added to get_adapter.
+pub trait PriceSource {
+    /// Return the source's price & the OSMO/USD quote price if one was queried
+    fn query_price(&self, ctx: &SourceContext) -> StdResult<(PriceResponse, Option<Decimal>)>;
+}
+
+/// Return the adapter for a source type
+pub fn get_adapter(source_type: OracleSourceType) -> Box<dyn PriceSource> {
+    match source_type {
+        OracleSourceType::Pyth { price_feed_id } => Box::new(PythSource { price_feed_id }),
+        OracleSourceType::OsmosisTwap {} => Box::new(OsmosisTwapSource {}),
+        OracleSourceType::VaultToken { vault_info } => Box::new(VaultTokenSource { vault_info }),
+        OracleSourceType::PriceFeed { contract, feed_id } => Box::new(PriceFeedSource { contract, feed_id }),
+        OracleSourceType::Manual { price, expiry } => Box::new(ManualSource { price, expiry }),
+    }
+}
+
+/// Query a source's price & enforce its staleness limit
+pub fn query_source(ctx: &SourceContext, source: &OracleSource) -> StdResult<(PriceResponse, Option<Decimal>)> {
+    let (price, quote_price) = get_adapter(source.source_type.clone()).query_price(ctx)?;
+
+    if let (Some(max_staleness), Some(metadata)) = (source.max_staleness, price.metadata.clone()) {
+        if ctx.env.block.time.seconds().saturating_sub(metadata.publish_time) > max_staleness {
+            return Err(StdError::GenericErr { msg: format!("{} price is stale", source.source_type) });
+        }
+    }
+
+    Ok((price, quote_price))
+}
+
+/// Pyth USD price feed
+pub struct PythSource {
+    pub price_feed_id: String,
+}
+
+impl PriceSource for PythSource {
+    fn query_price(&self, ctx: &SourceContext) -> StdResult<(PriceResponse, Option<Decimal>)> {
+        let pyth_osmosis_address = match ctx.config.pyth_osmosis_address.clone() {
+            Some(address) => address,
+            None => return Err(StdError::GenericErr { msg: String::from("No Pyth contract set") }),
+        };
+
+        //Query USD price from Pyth
+        let price_feed = query_price_feed(
+            &ctx.querier,
+            pyth_osmosis_address,
+            PriceIdentifier::from_hex(&self.price_feed_id).map_err(|err| StdError::GenericErr { msg: err.to_string() })?,
+        )?.price_feed;
+
+        //Only use prices within the time limit
+        let price = match price_feed.get_ema_price_no_older_than(ctx.env.block.time.seconds() as i64, ctx.oracle_time_limit) {
+            Some(price) => price,
+            None => return Err(StdError::GenericErr { msg: String::from("Pyth price is outside the oracle time limit") }),
+        };
+        let pyth_price = scale_pyth_price(price)?;
+
+        Ok((PriceResponse {
+            prices: vec![PriceInfo {
+                source: String::from("pyth"),
+                price: pyth_price,
+            }],
+            price: pyth_price,
+            decimals: ctx.oracle_info.decimals,
+            degraded: false,
+            metadata: Some(get_pyth_metadata(price)),
+        }, None))
+    }
+}
+
+/// Osmosis TWAP priced in USD through OSMO or the path's quote asset
+pub struct OsmosisTwapSource {}
+
+impl PriceSource for OsmosisTwapSource {
+    fn query_price(&self, ctx: &SourceContext) -> StdResult<(PriceResponse, Option<Decimal>)> {
+        get_osmosis_price(
+            ctx.storage,
+            ctx.querier,
+            ctx.env.clone(),
+            ctx.config.clone(),
+            ctx.asset_info.clone(),
+            ctx.oracle_info.clone(),
+            ctx.twap_timeframe,
+            ctx.oracle_time_limit,
+            ctx.basket_id_field,
+            ctx.queried_asset_prices.clone(),
+            ctx.osmo_quote_price,
+        )
+    }
+}
+
+/// Vault token valued by its underlying token
+pub struct VaultTokenSource {
+    pub vault_info: VaultTokenInfo,
+}
+
+impl PriceSource for VaultTokenSource {
+    fn query_price(&self, ctx: &SourceContext) -> StdResult<(PriceResponse, Option<Decimal>)> {
+        get_vault_token_price(
+            ctx.storage,
+            ctx.querier,
+            ctx.env.clone(),
+            ctx.config.clone(),
+            self.vault_info.clone(),
+            ctx.oracle_info.decimals,
+            ctx.twap_timeframe,
+            ctx.oracle_time_limit,
+            ctx.basket_id_field,
+            ctx.queried_asset_prices.clone(),
+            ctx.osmo_quote_price,
+        )
+    }
+}
+
+/// CosmWasm price feed contract
+pub struct PriceFeedSource {
+    pub contract: String,
+    pub feed_id: String,
+}
+
+impl PriceSource for PriceFeedSource {
+    fn query_price(&self, ctx: &SourceContext) -> StdResult<(PriceResponse, Option<Decimal>)> {
+        let res: FeedPriceResponse = ctx.querier.query_wasm_smart(
+            self.contract.clone(),
+            &PriceFeedQueryMsg::Price { feed_id: self.feed_id.clone() },
+        )?;
+        if res.price.is_zero() {
+            return Err(StdError::GenericErr { msg: format!("Price feed {} returned a zero price", self.contract) });
+        }
+
+        Ok((PriceResponse {
+            prices: vec![PriceInfo {
+                source: String::from("price_feed"),
+                price: res.price,
+            }],
+            price: res.price,
+            decimals: ctx.oracle_info.decimals,
+            degraded: false,
+            metadata: Some(PriceMetadata {
+                publish_time: res.publish_time,
+                source: String::from("price_feed"),
+                confidence: res.confidence,
+                twap_window: None,
+            }),
+        }, None))
+    }
+}
+
+/// Governance set price, valid until its expiry
+pub struct ManualSource {
+    pub price: Decimal,
+    pub expiry: u64,
+}
+
+impl PriceSource for ManualSource {
+    fn query_price(&self, ctx: &SourceContext) -> StdResult<(PriceResponse, Option<Decimal>)> {
+        if ctx.env.block.time.seconds() >= self.expiry {
+            return Err(StdError::GenericErr { msg: String::from("Manual price has expired") });
+        }
+
+        Ok((PriceResponse {
+            prices: vec![PriceInfo {
+                source: String::from("manual"),
+                price: self.price,
+            }],
+            price: self.price,
+            decimals: ctx.oracle_info.decimals,
+            degraded: false,
+            //Manual prices are current until they expire
+            metadata: Some(PriceMetadata {
+                publish_time: ctx.env.block.time.seconds(),
+                source: String::from("manual"),
+                confidence: None,
+                twap_window: None,
+            }),
+        }, None))
+    }
+}
+
+/// Scale a Pyth price using its exponent
+pub fn scale_pyth_price(price: Price) -> StdResult<Decimal> {
+    match price.expo > 0 {
+        true => decimal_multiplication(
+            Decimal::from_str(&price.price.to_string())?,
+            Decimal::from_ratio(Uint128::new(10), Uint128::one()).checked_pow(price.expo as u32)?
+        ),
+        //If the exponent is negative we divide, it should be for most if not all
+        false => decimal_division(
+            Decimal::from_str(&price.price.to_string())?,
+            Decimal::from_ratio(Uint128::new(10), Uint128::one()).checked_pow((price.expo*-1) as u32)?
+        ),
+    }
+}
+
+/// Return the publish time & confidence of a Pyth price
+pub fn get_pyth_metadata(price: Price) -> PriceMetadata {
+    //Confidence as a % of the price, both use the same exponent
+    let confidence = if price.price > 0 {
+        Some(Decimal::from_ratio(price.conf, price.price as u64))
+    } else {
+        None
+    };
+
+    PriceMetadata {
+        publish_time: price.publish_time.max(0) as u64,
+        source: String::from("pyth"),
+        confidence,
+        twap_window: None,
+    }
+}
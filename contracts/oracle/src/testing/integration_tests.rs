@@ -3,12 +3,12 @@ mod tests {
 
     use crate::helpers::OracleContract;
 
-    use membrane::oracle::{ExecuteMsg, InstantiateMsg, QueryMsg, PriceResponse};
+    use membrane::oracle::{merge_price_metadata, ExecuteMsg, FeedPriceResponse, InstantiateMsg, PriceFeedQueryMsg, PriceMetadata, PricePolicy, QueryMsg, PriceResponse};
     use membrane::osmosis_proxy::Config as OP_Config;
-    use membrane::types::{AssetInfo, AssetOracleInfo, TWAPPoolInfo, PriceInfo, Asset, Basket, SupplyCap, Owner};
+    use membrane::types::{AssetInfo, AssetOracleInfo, OracleSource, OracleSourceType, TWAPPoolInfo, PriceInfo, Asset, Basket, SupplyCap, Owner};
 
     use cosmwasm_std::{
-        coin, to_binary, Addr, Binary, Empty, Response, StdResult, Uint128, Decimal,
+        coin, to_binary, Addr, Binary, Empty, Response, StdError, StdResult, Uint128, Decimal,
     };
     use cw_multi_test::{App, AppBuilder, BankKeeper, Contract, ContractWrapper, Executor};
     use schemars::JsonSchema;
@@ -90,7 +90,41 @@ mod tests {
     #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
     #[serde(rename_all = "snake_case")]
     pub enum CDP_MockQueryMsg {
-        GetBasket {},
+        GetBaskets {
+            start_after: Option<Uint128>,
+            limit: Option<u32>,
+        },
+    }
+
+    fn mock_basket(basket_id: u128, supply_cap: SupplyCap) -> Basket {
+        Basket {
+            basket_id: Uint128::new(basket_id),
+            current_position_id: Uint128::zero(),
+            collateral_types: vec![],
+            collateral_supply_caps: vec![supply_cap],
+            lastest_collateral_rates: vec![],
+            credit_asset: Asset { info: AssetInfo::NativeToken { denom: format!("factory/cdt/#{}", basket_id) }, amount: Uint128::zero() },
+            credit_price: PriceResponse {
+                prices: vec![],
+                price: Decimal::zero(),
+                decimals: 6,
+                degraded: false,
+                metadata: None,
+            },
+            liq_queue: None,
+            base_interest_rate: Decimal::zero(),
+            pending_revenue: Uint128::zero(),
+            negative_rates: false,
+            cpc_margin_of_error: Decimal::zero(),
+            multi_asset_supply_caps: vec![],
+            frozen: false,
+            rev_to_stakers: true,
+            credit_last_accrued: 0,
+            rates_last_accrued: 0,
+            oracle_set: true,
+            close_factor: None,
+            soft_liquidation: None,
+        }
     }
 
     pub fn cdp_contract() -> Box<dyn Contract<Empty>> {
@@ -103,39 +137,65 @@ mod tests {
             },
             |_, _, msg: CDP_MockQueryMsg| -> StdResult<Binary> {
                 match msg {
-                    CDP_MockQueryMsg::GetBasket {} => Ok(to_binary(&Basket {
-                        basket_id: Uint128::zero(),
-                        current_position_id: Uint128::zero(),
-                        collateral_types: vec![],
-                        collateral_supply_caps: vec![
-                            SupplyCap { 
+                    CDP_MockQueryMsg::GetBaskets { start_after, limit: _ } => {
+                        //All Baskets fit in the 1st page, only the 2nd Basket has supply of the in use asset
+                        if start_after.is_some() {
+                            return Ok(to_binary(&Vec::<Basket>::new())?);
+                        }
+                        Ok(to_binary(&vec![
+                            mock_basket(1, SupplyCap { 
                                 asset_info: AssetInfo::NativeToken { denom: String::from("removable") }, 
                                 current_supply: Uint128::zero(), 
                                 debt_total: Uint128::zero(),  
                                 supply_cap_ratio: Decimal::zero(), 
                                 lp: false,
                                 stability_pool_ratio_for_debt_cap: None,
-                            },
-                        ],
-                        lastest_collateral_rates: vec![],
-                        credit_asset: Asset { info: AssetInfo::NativeToken { denom: String::from("factory/cdt/#1") }, amount: Uint128::zero() },
-                        credit_price: PriceResponse {
-                            prices: vec![],
-                            price: Decimal::zero(),
-                            decimals: 6,
-                        },
-                        liq_queue: None,
-                        base_interest_rate: Decimal::zero(),
-                        pending_revenue: Uint128::zero(),
-                        negative_rates: false,
-                        cpc_margin_of_error: Decimal::zero(),
-                        multi_asset_supply_caps: vec![],
-                        frozen: false,
-                        rev_to_stakers: true,
-                        credit_last_accrued: 0,
-                        rates_last_accrued: 0,
-                        oracle_set: true,
-                    })?),
+                            }),
+                            mock_basket(2, SupplyCap { 
+                                asset_info: AssetInfo::NativeToken { denom: String::from("in_use") }, 
+                                current_supply: Uint128::new(1_000_000), 
+                                debt_total: Uint128::zero(),  
+                                supply_cap_ratio: Decimal::one(), 
+                                lp: false,
+                                stability_pool_ratio_for_debt_cap: None,
+                            }),
+                        ])?)
+                    },
+                }
+            },
+        );
+        Box::new(contract)
+    }
+
+    //Mock Price Feed Contract
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Feed_MockExecuteMsg {}
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub struct Feed_MockInstantiateMsg {}
+
+    pub fn price_feed_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            |deps, _, info, msg: Feed_MockExecuteMsg| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_, _, _, _: Feed_MockInstantiateMsg| -> StdResult<Response> {
+                Ok(Response::default())
+            },
+            |_, env, msg: PriceFeedQueryMsg| -> StdResult<Binary> {
+                match msg {
+                    PriceFeedQueryMsg::Price { feed_id } => {
+                        if feed_id != String::from("feed_asset") {
+                            return Err(StdError::GenericErr { msg: String::from("Unknown feed") });
+                        }
+                        Ok(to_binary(&FeedPriceResponse {
+                            price: Decimal::percent(2_00),
+                            publish_time: env.block.time.seconds() - 30,
+                            confidence: Some(Decimal::permille(1)),
+                        })?)
+                    },
                 }
             },
         );
@@ -220,7 +280,7 @@ mod tests {
         use std::str::FromStr;
 
         use super::*;
-        use membrane::oracle::{Config, AssetResponse, PriceResponse256};
+        use membrane::oracle::{Config, AssetResponse, OracleTWAPResponse, PriceHistoryConfig, PriceHistoryResponse, PricePathResponse, PriceRecord, PriceResponse256, PriceSample};
         use membrane::math::{decimal_division, decimal_multiplication, Decimal256, Uint256};
         use pyth_sdk_cw::PriceIdentifier;
 
@@ -246,6 +306,8 @@ mod tests {
                     decimals: 6,
                     pyth_price_feed_id: None,
                     vault_info: None,
+                    max_source_deviation: None,
+                    sources: vec![],
                 },
             };
             let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
@@ -268,6 +330,8 @@ mod tests {
                     decimals: 6,
                     pyth_price_feed_id: None,
                     vault_info: None,
+                    max_source_deviation: None,
+                    sources: vec![],
                 },
             };
             let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
@@ -290,6 +354,8 @@ mod tests {
                     decimals: 6,
                     pyth_price_feed_id: None,
                     vault_info: None,
+                    max_source_deviation: None,
+                    sources: vec![],
                 },
             };
             let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
@@ -358,6 +424,8 @@ mod tests {
                     decimals: 6,
                     pyth_price_feed_id: None,
                     vault_info: None,
+                    max_source_deviation: None,
+                    sources: vec![],
                 }),
                 remove: false,
             };
@@ -395,12 +463,26 @@ mod tests {
                     decimals: 6,
                     pyth_price_feed_id: None,
                     vault_info: None,
+                    max_source_deviation: None,
+                    sources: vec![],
                 },
             };
             let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
            
 
+            //Error: Asset has supply in a non-default Basket
+            let msg = ExecuteMsg::EditAsset {
+                asset_info: AssetInfo::NativeToken {
+                    denom: String::from("in_use"),
+                },
+                oracle_info: None,
+                remove: true,
+            };
+            let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
+            let err = app.execute(cdp_contract.clone(), cosmos_msg).unwrap_err();
+            assert_eq!(err.root_cause().to_string(), String::from("Asset in use in the Positions contract: \"in_use\""));
+
             //Successful Remove
             let msg = ExecuteMsg::EditAsset {
                 asset_info: AssetInfo::NativeToken {
@@ -577,6 +659,509 @@ mod tests {
             assert_eq!(median_price, Decimal::from_ratio(2u128, 1u128));
         }
 
+        #[test]
+        fn source_deviation() {
+            let pyth_price = PriceResponse {
+                prices: vec![PriceInfo {
+                    price: Decimal::one(),
+                    source: String::from("pyth"),
+                }],
+                price: Decimal::one(),
+                decimals: 6,
+                degraded: false,
+                metadata: None,
+            };
+            let twap_price = |price: Decimal| PriceResponse {
+                prices: vec![PriceInfo {
+                    price,
+                    source: String::from("osmosis"),
+                }],
+                price,
+                decimals: 6,
+                degraded: false,
+                metadata: None,
+            };
+
+            //Within the max deviation
+            let res = crate::contracts::check_source_deviation(pyth_price.clone(), twap_price(Decimal::percent(97)), Decimal::percent(5)).unwrap();
+            assert_eq!(res.price, Decimal::one());
+            assert_eq!(res.prices.len(), 2);
+            assert!(!res.degraded);
+
+            //Past the max deviation, the primary price is kept but flagged
+            let res = crate::contracts::check_source_deviation(pyth_price.clone(), twap_price(Decimal::percent(110)), Decimal::percent(5)).unwrap();
+            assert_eq!(res.price, Decimal::one());
+            assert!(res.degraded);
+
+            //Degraded sources stay degraded
+            let mut degraded_twap = twap_price(Decimal::one());
+            degraded_twap.degraded = true;
+            let res = crate::contracts::check_source_deviation(pyth_price, degraded_twap, Decimal::percent(5)).unwrap();
+            assert!(res.degraded);
+        }
+
+        #[test]
+        fn legacy_source_deviation() {
+            //Legacy Pyth & Osmosis TWAP setup w/o a max deviation
+            let oracle_info = AssetOracleInfo {
+                basket_id: Uint128::new(1u128),
+                pools_for_osmo_twap: vec![TWAPPoolInfo {
+                    pool_id: 1u64,
+                    base_asset_denom: String::from("debit"),
+                    quote_asset_denom: String::from("uosmo"),
+                }],
+                is_usd_par: false,
+                lp_pool_info: None,
+                decimals: 6,
+                pyth_price_feed_id: Some(String::from("b00b60f88b03a6a625a8d1c048c3f66653edf217439983d037e7222c4e612819")),
+                vault_info: None,
+                max_source_deviation: None,
+                sources: vec![],
+            };
+            let max_deviation = oracle_info.get_max_source_deviation().unwrap();
+            assert_eq!(max_deviation, Decimal::percent(10));
+
+            //Single source assets skip the check, set deviations are kept
+            assert_eq!(AssetOracleInfo { pyth_price_feed_id: None, ..oracle_info.clone() }.get_max_source_deviation(), None);
+            assert_eq!(AssetOracleInfo { max_source_deviation: Some(Decimal::percent(5)), ..oracle_info }.get_max_source_deviation(), Some(Decimal::percent(5)));
+
+            let price = |source: &str, price: Decimal| PriceResponse {
+                prices: vec![PriceInfo {
+                    price,
+                    source: String::from(source),
+                }],
+                price,
+                decimals: 6,
+                degraded: false,
+                metadata: None,
+            };
+
+            //The TWAP backup is within the default deviation
+            let res = crate::contracts::flag_source_deviation(price("pyth", Decimal::one()), vec![], vec![price("osmosis", Decimal::percent(95))], false, max_deviation).unwrap();
+            assert!(!res.degraded);
+
+            //Past the default deviation the Pyth price is flagged
+            let res = crate::contracts::flag_source_deviation(price("pyth", Decimal::one()), vec![], vec![price("osmosis", Decimal::percent(85))], false, max_deviation).unwrap();
+            assert_eq!(res.price, Decimal::one());
+            assert!(res.degraded);
+
+            //A failed TWAP can't be checked against
+            let res = crate::contracts::flag_source_deviation(price("pyth", Decimal::one()), vec![], vec![], true, max_deviation).unwrap();
+            assert!(res.degraded);
+        }
+
+        #[test]
+        fn price_metadata() {
+            let pyth_metadata = PriceMetadata {
+                publish_time: 100,
+                source: String::from("pyth"),
+                confidence: Some(Decimal::permille(1)),
+                twap_window: None,
+            };
+            let twap_metadata = PriceMetadata {
+                publish_time: 90,
+                source: String::from("osmosis"),
+                confidence: None,
+                twap_window: Some(3600),
+            };
+
+            //Merging keeps the oldest publish time, widest confidence & shortest TWAP window
+            let merged = merge_price_metadata(Some(pyth_metadata.clone()), Some(twap_metadata.clone())).unwrap();
+            assert_eq!(merged, PriceMetadata {
+                publish_time: 90,
+                source: String::from("pyth, osmosis"),
+                confidence: Some(Decimal::permille(1)),
+                twap_window: Some(3600),
+            });
+            let merged = merge_price_metadata(
+                Some(pyth_metadata.clone()),
+                Some(PriceMetadata { confidence: Some(Decimal::permille(5)), ..pyth_metadata.clone() }),
+            ).unwrap();
+            assert_eq!(merged.source, String::from("pyth"));
+            assert_eq!(merged.confidence, Some(Decimal::permille(5)));
+
+            //Missing metadata keeps whichever is reported
+            assert_eq!(merge_price_metadata(None, Some(twap_metadata.clone())), Some(twap_metadata));
+            assert_eq!(merge_price_metadata(None, None), None);
+
+            let price = PriceResponse {
+                prices: vec![],
+                price: Decimal::one(),
+                decimals: 6,
+                degraded: false,
+                metadata: Some(merged),
+            };
+
+            //Staleness
+            let policy = PricePolicy { max_staleness: Some(100), max_confidence: None };
+            policy.check("asset", &price, 200).unwrap();
+            let err = policy.check("asset", &price, 201).unwrap_err();
+            assert_eq!(err.to_string(), String::from("Generic error: asset price is stale, published at 100"));
+
+            //Confidence
+            let policy = PricePolicy { max_staleness: None, max_confidence: Some(Decimal::permille(4)) };
+            let err = policy.check("asset", &price, 200).unwrap_err();
+            assert_eq!(err.to_string(), String::from("Generic error: asset price confidence interval is too wide: 0.005"));
+            PricePolicy { max_staleness: None, max_confidence: Some(Decimal::permille(5)) }.check("asset", &price, 200).unwrap();
+
+            //Prices without metadata are accepted
+            policy.check("asset", &PriceResponse { metadata: None, ..price }, 200).unwrap();
+        }
+
+        #[test]
+        fn source_adapters() {
+            let (mut app, oracleContract, _cdp_contract) = proper_instantiate();
+
+            //Instantiate Price Feed contract
+            let feed_id = app.store_code(price_feed_contract());
+            let feed_contract_addr = app
+                .instantiate_contract(
+                    feed_id,
+                    Addr::unchecked(ADMIN),
+                    &Feed_MockInstantiateMsg {},
+                    &[],
+                    "test",
+                    None,
+                )
+                .unwrap();
+
+            let expiry = app.block_info().time.seconds() + 3600;
+            let oracle_info = |sources: Vec<OracleSource>| AssetOracleInfo {
+                basket_id: Uint128::new(1u128),
+                pools_for_osmo_twap: vec![],
+                is_usd_par: false,
+                lp_pool_info: None,
+                decimals: 6,
+                pyth_price_feed_id: None,
+                vault_info: None,
+                max_source_deviation: Some(Decimal::percent(10)),
+                sources,
+            };
+            let feed_source = OracleSource {
+                source_type: OracleSourceType::PriceFeed {
+                    contract: feed_contract_addr.to_string(),
+                    feed_id: String::from("feed_asset"),
+                },
+                weight: Decimal::one(),
+                max_staleness: Some(60),
+            };
+            let manual_source = |price: Decimal, weight: Decimal| OracleSource {
+                source_type: OracleSourceType::Manual { price, expiry },
+                weight,
+                max_staleness: None,
+            };
+            //Price queries return a list
+            let query_price = |app: &App| app.wrap().query_wasm_smart::<Vec<PriceResponse>>(
+                oracleContract.addr(),
+                &QueryMsg::Price {
+                    asset_info: AssetInfo::NativeToken { denom: String::from("feed_asset") },
+                    twap_timeframe: 60,
+                    oracle_time_limit: 60,
+                    basket_id: None,
+                },
+            ).map(|prices| prices[0].clone());
+
+            //Sources need a weighted source
+            let msg = ExecuteMsg::AddAsset {
+                asset_info: AssetInfo::NativeToken { denom: String::from("feed_asset") },
+                oracle_info: oracle_info(vec![manual_source(Decimal::one(), Decimal::zero())]),
+            };
+            let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Weighted average of the feed & manual prices
+            let msg = ExecuteMsg::AddAsset {
+                asset_info: AssetInfo::NativeToken { denom: String::from("feed_asset") },
+                oracle_info: oracle_info(vec![
+                    feed_source.clone(),
+                    manual_source(Decimal::percent(2_30), Decimal::percent(50)),
+                ]),
+            };
+            let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //(2 * 1 + 2.3 * 0.5) / 1.5
+            let price = query_price(&app).unwrap();
+            assert_eq!(price.price, Decimal::percent(2_10));
+            assert_eq!(price.prices.len(), 2);
+            assert!(!price.degraded);
+            let metadata = price.metadata.unwrap();
+            assert_eq!(metadata.publish_time, app.block_info().time.seconds() - 30);
+            assert_eq!(metadata.source, String::from("price_feed, manual"));
+            //Manual prices don't report a confidence interval
+            assert_eq!(metadata.confidence, Some(Decimal::permille(1)));
+
+            //Backup sources are used for the deviation check
+            let msg = ExecuteMsg::EditAsset {
+                asset_info: AssetInfo::NativeToken { denom: String::from("feed_asset") },
+                oracle_info: Some(oracle_info(vec![
+                    feed_source.clone(),
+                    manual_source(Decimal::percent(3_00), Decimal::zero()),
+                ])),
+                remove: false,
+            };
+            let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let price = query_price(&app).unwrap();
+            assert_eq!(price.price, Decimal::percent(2_00));
+            assert!(price.degraded);
+
+            //A failed backup can't be checked against, so the price is degraded
+            let msg = ExecuteMsg::EditAsset {
+                asset_info: AssetInfo::NativeToken { denom: String::from("feed_asset") },
+                oracle_info: Some(oracle_info(vec![
+                    feed_source.clone(),
+                    OracleSource {
+                        source_type: OracleSourceType::PriceFeed {
+                            contract: feed_contract_addr.to_string(),
+                            feed_id: String::from("unknown_asset"),
+                        },
+                        weight: Decimal::zero(),
+                        max_staleness: Some(60),
+                    },
+                ])),
+                remove: false,
+            };
+            let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let price = query_price(&app).unwrap();
+            assert_eq!(price.price, Decimal::percent(2_00));
+            assert_eq!(price.prices.len(), 1);
+            assert!(price.degraded);
+
+            //Stale sources are skipped for the backup
+            let msg = ExecuteMsg::EditAsset {
+                asset_info: AssetInfo::NativeToken { denom: String::from("feed_asset") },
+                oracle_info: Some(oracle_info(vec![
+                    OracleSource { max_staleness: Some(10), ..feed_source },
+                    manual_source(Decimal::percent(3_00), Decimal::zero()),
+                ])),
+                remove: false,
+            };
+            let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let price = query_price(&app).unwrap();
+            assert_eq!(price.price, Decimal::percent(3_00));
+            assert_eq!(price.prices[0].source, String::from("manual"));
+
+            //Expired manual prices error
+            app.update_block(|block| block.time = block.time.plus_seconds(3600));
+            query_price(&app).unwrap_err();
+        }
+
+        #[test]
+        fn price_history() {
+            let (mut app, oracleContract, cdp_contract) = proper_instantiate();
+            let start_time = app.block_info().time.seconds();
+            let asset_info = AssetInfo::NativeToken { denom: String::from("history_asset") };
+
+            //Manual price expires after 1000 seconds
+            let msg = ExecuteMsg::AddAsset {
+                asset_info: asset_info.clone(),
+                oracle_info: AssetOracleInfo {
+                    basket_id: Uint128::new(1u128),
+                    pools_for_osmo_twap: vec![],
+                    is_usd_par: false,
+                    lp_pool_info: None,
+                    decimals: 6,
+                    pyth_price_feed_id: None,
+                    vault_info: None,
+                    max_source_deviation: None,
+                    sources: vec![OracleSource {
+                        source_type: OracleSourceType::Manual { price: Decimal::percent(2_50), expiry: start_time + 1000 },
+                        weight: Decimal::one(),
+                        max_staleness: None,
+                    }],
+                },
+            };
+            let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let record_prices = |price: Decimal, time: u64| ExecuteMsg::RecordPrices {
+                prices: vec![PriceRecord {
+                    asset_info: asset_info.clone(),
+                    basket_id: Uint128::new(1u128),
+                    price: PriceResponse {
+                        prices: vec![PriceInfo { source: String::from("manual"), price }],
+                        price,
+                        decimals: 6,
+                        degraded: false,
+                        metadata: None,
+                    },
+                    time,
+                }],
+            };
+            let query_history = |app: &App, limit: Option<u32>| -> PriceHistoryResponse {
+                app.wrap().query_wasm_smart(
+                    oracleContract.addr(),
+                    &QueryMsg::PriceHistory { asset_info: asset_info.clone(), basket_id: None, limit },
+                ).unwrap()
+            };
+
+            //Only the Positions contract can record prices
+            let cosmos_msg = oracleContract.call(record_prices(Decimal::one(), start_time), vec![]).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+
+            let cosmos_msg = oracleContract.call(record_prices(Decimal::one(), start_time), vec![]).unwrap();
+            app.execute(cdp_contract.clone(), cosmos_msg).unwrap();
+
+            //Prices within the sampling interval are skipped
+            let cosmos_msg = oracleContract.call(record_prices(Decimal::percent(1_50), start_time), vec![]).unwrap();
+            app.execute(cdp_contract.clone(), cosmos_msg).unwrap();
+            assert_eq!(query_history(&app, None).prices.len(), 1);
+
+            //Prices are saved at the time they were queried
+            app.update_block(|block| block.time = block.time.plus_seconds(700));
+            let cosmos_msg = oracleContract.call(record_prices(Decimal::percent(2_00), start_time + 600), vec![]).unwrap();
+            app.execute(cdp_contract.clone(), cosmos_msg).unwrap();
+            app.update_block(|block| block.time = block.time.plus_seconds(200));
+
+            assert_eq!(
+                query_history(&app, None).prices,
+                vec![
+                    PriceSample { price: Decimal::one(), time: start_time },
+                    PriceSample { price: Decimal::percent(2_00), time: start_time + 600 },
+                ]
+            );
+            assert_eq!(
+                query_history(&app, Some(1)).prices,
+                vec![PriceSample { price: Decimal::percent(2_00), time: start_time + 600 }]
+            );
+
+            //300 seconds at 1 & 300 seconds at 2
+            let twap: OracleTWAPResponse = app.wrap().query_wasm_smart(
+                oracleContract.addr(),
+                &QueryMsg::OracleTWAP { asset_info: asset_info.clone(), window: 600, basket_id: None },
+            ).unwrap();
+            assert_eq!(twap, OracleTWAPResponse { price: Decimal::percent(1_50), window_covered: 600, samples: 2 });
+
+            //The latest saved price is a degraded backup once every source fails
+            app.update_block(|block| block.time = block.time.plus_seconds(100));
+            let price = app.wrap().query_wasm_smart::<Vec<PriceResponse>>(
+                oracleContract.addr(),
+                &QueryMsg::Price { asset_info: asset_info.clone(), twap_timeframe: 60, oracle_time_limit: 60, basket_id: None },
+            ).unwrap()[0].clone();
+            assert_eq!(price.price, Decimal::percent(2_00));
+            assert_eq!(price.prices[0].source, String::from("price_history"));
+            assert!(price.degraded);
+
+            //Backups older than the max backup age aren't used
+            app.update_block(|block| block.time = block.time.plus_seconds(3600));
+            app.wrap().query_wasm_smart::<Vec<PriceResponse>>(
+                oracleContract.addr(),
+                &QueryMsg::Price { asset_info, twap_timeframe: 60, oracle_time_limit: 60, basket_id: None },
+            ).unwrap_err();
+        }
+
+        #[test]
+        fn price_path_validation() {
+            let (mut app, oracleContract, _cdp_contract) = proper_instantiate();
+
+            let pool = |pool_id: u64, base: &str, quote: &str| TWAPPoolInfo {
+                pool_id,
+                base_asset_denom: String::from(base),
+                quote_asset_denom: String::from(quote),
+            };
+            let oracle_info = |pools_for_osmo_twap: Vec<TWAPPoolInfo>| AssetOracleInfo {
+                basket_id: Uint128::new(1u128),
+                pools_for_osmo_twap,
+                is_usd_par: false,
+                lp_pool_info: None,
+                decimals: 18,
+                pyth_price_feed_id: None,
+                vault_info: None,
+                max_source_deviation: None,
+                sources: vec![],
+            };
+            let add_asset = |pools: Vec<TWAPPoolInfo>| ExecuteMsg::AddAsset {
+                asset_info: AssetInfo::NativeToken { denom: String::from("path_asset") },
+                oracle_info: oracle_info(pools),
+            };
+
+            //Path has to start at the asset
+            let cosmos_msg = oracleContract.call(add_asset(vec![pool(1, "uatom", "uosmo")]), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Custom Error val: \"TWAP path is broken at pool 1: expected base path_asset, found uatom\""
+            );
+
+            //Each hop's base has to be the last hop's quote
+            let cosmos_msg = oracleContract.call(add_asset(vec![
+                pool(1, "path_asset", "uatom"),
+                pool(2, "uusdc", "uosmo"),
+            ]), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Custom Error val: \"TWAP path is broken at pool 2: expected base uatom, found uusdc\""
+            );
+
+            //Intermediate quote assets have to be in the oracle
+            let cosmos_msg = oracleContract.call(add_asset(vec![
+                pool(1, "path_asset", "uatom"),
+                pool(2, "uatom", "uosmo"),
+            ]), vec![]).unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Custom Error val: \"uatom needs to be added to the oracle to price TWAP paths through it\""
+            );
+
+            //Add the intermediate quote asset
+            let msg = ExecuteMsg::AddAsset {
+                asset_info: AssetInfo::NativeToken { denom: String::from("uatom") },
+                oracle_info: AssetOracleInfo {
+                    decimals: 6,
+                    ..oracle_info(vec![pool(3, "uatom", "uosmo")])
+                },
+            };
+            let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //Continuous path
+            let cosmos_msg = oracleContract.call(add_asset(vec![
+                pool(1, "path_asset", "uatom"),
+                pool(2, "uatom", "uosmo"),
+            ]), vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            //EditAsset is validated too
+            let msg = ExecuteMsg::EditAsset {
+                asset_info: AssetInfo::NativeToken { denom: String::from("path_asset") },
+                oracle_info: Some(oracle_info(vec![
+                    pool(1, "path_asset", "uatom"),
+                    pool(2, "uosmo", "uatom"),
+                ])),
+                remove: false,
+            };
+            let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+
+            //Assets without a path simulate to an empty path
+            let msg = ExecuteMsg::EditAsset {
+                asset_info: AssetInfo::NativeToken { denom: String::from("path_asset") },
+                oracle_info: Some(oracle_info(vec![])),
+                remove: false,
+            };
+            let cosmos_msg = oracleContract.call(msg, vec![]).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let res: PricePathResponse = app.wrap().query_wasm_smart(
+                oracleContract.addr(),
+                &QueryMsg::SimulatePricePath {
+                    asset_info: AssetInfo::NativeToken { denom: String::from("path_asset") },
+                    twap_timeframe: 60,
+                    basket_id: None,
+                },
+            ).unwrap();
+            assert_eq!(res.hops, vec![]);
+            assert_eq!(res.price, Decimal::one());
+        }
+
         #[test]
         fn scaling_test() {
             // let amount = Decimal::from_ratio(Uint128::new(999_187_931_653_491_861_157), Uint128::new(1));
@@ -589,6 +1174,8 @@ mod tests {
                 }],
                 price: Decimal::from_str("0.33460022928606451").unwrap(),
                 decimals: 18,
+                degraded: false,
+                metadata: None,
             }.get_value(15984828147841759232u128.into()));
             
             let price = 123;
@@ -596,6 +1183,8 @@ mod tests {
                 prices: vec![],
                 price: Decimal::from_str(&price.to_string()).unwrap(),
                 decimals: 18,
+                degraded: false,
+                metadata: None,
             };
             panic!("{:?}", price_response.get_value(340_280_000_000_000_000_000_000_000_001u128.into()));
             //Scale price using given exponent
@@ -628,6 +1217,7 @@ mod tests {
                 pyth_osmosis_address: Some(String::from("new_pyth_osmosis_address")),
                 osmo_usd_pyth_feed_id: Some(PriceIdentifier::from_hex("63f341689d98a12ef60a5cff1d7f85c70a9e17bf1575f0e7c0b2512d48b1c8b3").unwrap()),
                 pools_for_usd_par_twap: None,
+                price_history: None,
             };
             let cosmos_msg = oracle_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
@@ -650,6 +1240,7 @@ mod tests {
                     pyth_osmosis_address: Some(Addr::unchecked("new_pyth_osmosis_address")),
                     osmo_usd_pyth_feed_id: PriceIdentifier::from_hex("63f341689d98a12ef60a5cff1d7f85c70a9e17bf1575f0e7c0b2512d48b1c8b3").unwrap(),
                     pools_for_usd_par_twap: vec![],
+                    price_history: PriceHistoryConfig::default(),
             });
 
             //Successful ownership transfer
@@ -660,6 +1251,7 @@ mod tests {
                 pyth_osmosis_address: None,
                 osmo_usd_pyth_feed_id: None,
                 pools_for_usd_par_twap: None,
+                price_history: None,
             };
             let cosmos_msg = oracle_contract.call(msg, vec![]).unwrap();
             app.execute(Addr::unchecked("new_owner"), cosmos_msg).unwrap();
@@ -682,6 +1274,7 @@ mod tests {
                     pyth_osmosis_address: Some(Addr::unchecked("new_pyth_osmosis_address")),
                     osmo_usd_pyth_feed_id: PriceIdentifier::from_hex("63f341689d98a12ef60a5cff1d7f85c70a9e17bf1575f0e7c0b2512d48b1c8b3").unwrap(),
                     pools_for_usd_par_twap: vec![],
+                    price_history: PriceHistoryConfig::default(),
             });
         }
     }
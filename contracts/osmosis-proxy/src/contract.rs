@@ -153,6 +153,7 @@ fn execute_swaps(
                 ],
             twap_timeframe: 0u64,
             oracle_time_limit: 0u64,
+            basket_id: None,
         })?;
         let token_in_price = token_prices[0].clone();
         let token_out_price = token_prices[1].clone();
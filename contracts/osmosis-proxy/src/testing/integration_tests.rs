@@ -104,6 +104,8 @@ mod tests {
                         prices: vec![],
                         price: Decimal::one(),
                         decimals: 6,
+                        degraded: false,
+                        metadata: None,
                     })?)
                 }
             },
@@ -174,7 +176,9 @@ mod tests {
                             credit_price: PriceResponse { 
                                 prices: vec![], 
                                 price: Decimal::one(), 
-                                decimals: 6
+                                decimals: 6,
+                                degraded: false,
+                                metadata: None,
                             },
                             liq_queue: None,
                             base_interest_rate: Decimal::zero(),
@@ -187,6 +191,8 @@ mod tests {
                             credit_last_accrued: 0,
                             rates_last_accrued: 0,
                             oracle_set: true,
+                            close_factor: None,
+                            soft_liquidation: None,
                         })?)
                     },
                 }
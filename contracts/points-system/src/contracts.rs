@@ -424,6 +424,7 @@ fn liquidate_for_user(
         msg: to_json_binary(&CDP_ExecuteMsg::Liquidate {
                 position_id,
                 position_owner: position_owner.to_string(),
+                unwrap_lp: None,
             })?,
         funds: vec![] 
     });
@@ -547,6 +548,7 @@ fn allocate_points(
             asset_infos: unique_denoms.clone(),
             twap_timeframe: 60u64,
             oracle_time_limit: 600u64,
+            basket_id: None,
         })?
     })).unwrap_or(vec![]);
     //It'll error if there are no denoms passed most likely so we just return an empty vec
@@ -99,7 +99,9 @@ mod tests {
                             credit_price: PriceResponse { 
                                 prices: vec![], 
                                 price: Decimal::zero(), 
-                                decimals: 6
+                                decimals: 6,
+                                degraded: false,
+                                metadata: None,
                             },
                             liq_queue: None,
                             base_interest_rate: Decimal::zero(),
@@ -112,6 +114,8 @@ mod tests {
                             credit_last_accrued: 0,
                             rates_last_accrued: 0,
                             oracle_set: true,
+                            close_factor: None,
+                            soft_liquidation: None,
                         })?)
                     },
                 }
@@ -154,7 +158,9 @@ mod tests {
                             credit_price: PriceResponse { 
                                 prices: vec![], 
                                 price: Decimal::zero(), 
-                                decimals: 6
+                                decimals: 6,
+                                degraded: false,
+                                metadata: None,
                             },
                             liq_queue: None,
                             base_interest_rate: Decimal::zero(),
@@ -167,6 +173,8 @@ mod tests {
                             credit_last_accrued: 0,
                             rates_last_accrued: 0,
                             oracle_set: true,
+                            close_factor: None,
+                            soft_liquidation: None,
                         })?)
                     },
                 }
@@ -498,6 +506,7 @@ mod tests {
             asset_infos: Vec<AssetInfo>,
             twap_timeframe: u64,
             oracle_time_limit: u64,
+            basket_id: Option<Uint128>,
         },
     }
 
@@ -515,6 +524,7 @@ mod tests {
                         asset_infos,
                         twap_timeframe,
                         oracle_time_limit,
+                        basket_id,
                     } => 
                         {
                             let mut resp = vec![];
@@ -523,6 +533,8 @@ mod tests {
                                     prices: vec![],
                                     price: Decimal::one(),
                                     decimals: 6,
+                                    degraded: false,
+                                    metadata: None,
                                 });
                             }
                             Ok(to_json_binary(&resp)?) 
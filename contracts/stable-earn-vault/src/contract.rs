@@ -122,12 +122,16 @@ pub fn instantiate(
         vt_token_price: PriceResponse {
             price: Decimal::zero(),
             prices: vec![],
-            decimals: 6
+            decimals: 6,
+            degraded: false,
+            metadata: None,
         },
         cdt_peg_price: PriceResponse {
             price: Decimal::zero(),
             prices: vec![],
-            decimals: 6
+            decimals: 6,
+            degraded: false,
+            metadata: None,
         },
     })?;
     //Create Denom Msg
@@ -259,6 +263,7 @@ fn loop_cdp(
             amount: Some(amount_to_mint),
             LTV: None,
             mint_to_addr: None,
+            position_owner: None,
         })?,
         funds: vec![],
     });
@@ -477,6 +482,7 @@ fn unloop_cdp(
                         }
                     ],
                     send_to: None,
+                    position_owner: None,
                 })?,
                 funds: vec![],
             });
@@ -501,6 +507,7 @@ fn unloop_cdp(
                         }
                     ],
                     send_to: None,
+                    position_owner: None,
                 })?,
                 funds: vec![],
             });
@@ -627,6 +634,7 @@ fn get_cdp_position_info(
             AssetInfo::NativeToken { denom: config.cdt_denom.clone() }],
             twap_timeframe: 0, //We want current swap price
             oracle_time_limit: 0,
+            basket_id: None,
         },
     ){
         Ok(prices) => prices,
@@ -673,6 +681,7 @@ fn get_cdp_position_info(
                 position_id: config.cdp_position_id,
                 assets: assets_to_withdraw,
                 send_to: Some(config.owner.clone().to_string()),
+                position_owner: None,
             })?,
             funds: vec![],
         });
@@ -1107,12 +1116,16 @@ fn exit_vault(
         vt_token_price: PriceResponse {
             price: Decimal::zero(),
             prices: vec![],
-            decimals: 6
+            decimals: 6,
+            degraded: false,
+            metadata: None,
         },
         cdt_peg_price: PriceResponse {
             price: Decimal::zero(),
             prices: vec![],
-            decimals: 6
+            decimals: 6,
+            degraded: false,
+            metadata: None,
         },
     })?;
 
@@ -1639,6 +1652,7 @@ fn handle_unloop_reply(
                             }
                         ],
                         send_to: None,
+                        position_owner: None,
                     })?,
                     funds: vec![],
                 });
@@ -1922,12 +1936,16 @@ pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, To
         vt_token_price: PriceResponse {
             price: Decimal::zero(),
             prices: vec![],
-            decimals: 6
+            decimals: 6,
+            degraded: false,
+            metadata: None,
         },
         cdt_peg_price: PriceResponse {
             price: Decimal::zero(),
             prices: vec![],
-            decimals: 6
+            decimals: 6,
+            degraded: false,
+            metadata: None,
         },
     })?;
 
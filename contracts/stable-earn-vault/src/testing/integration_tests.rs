@@ -5,7 +5,7 @@ mod tests {
     use membrane::stability_pool_vault::APRResponse as NoCost_APRResponse;
     use membrane::stable_earn_vault::{ExecuteMsg, InstantiateMsg, QueryMsg, Config};
     // use membrane::mars_redbank::{Market, UserCollateralResponse, InterestRateModel};
-    use membrane::types::{AssetInfo, Asset, VaultInfo, AssetPool, cAsset, UserInfo, Basket};
+    use membrane::types::{AssetInfo, Asset, VaultInfo, AssetPool, cAsset, UserInfo, Basket, RateModel};
     use membrane::cdp::{PositionResponse, BasketPositionsResponse, CollateralInterestResponse, InterestResponse};
     use membrane::oracle::PriceResponse;
     use cosmwasm_std::{
@@ -154,6 +154,7 @@ mod tests {
             asset_infos: Vec<AssetInfo>,
             twap_timeframe: u64,
             oracle_time_limit: u64,
+            basket_id: Option<Uint128>,
         },
     }
 
@@ -179,18 +180,24 @@ mod tests {
                                 prices: vec![],
                                 price: Decimal::percent(98),
                                 decimals: 6,
+                                degraded: false,
+                                metadata: None,
                             });
                         } else if asset_info.to_string() == String::from("lp_denom") {
                             prices.push(PriceResponse {
                                 prices: vec![],
                                 price: Decimal::from_ratio(2u128, 1u128),
                                 decimals: 18,
+                                degraded: false,
+                                metadata: None,
                             });
                         } else {
                             prices.push(PriceResponse {
                                 prices: vec![],
                                 price: Decimal::one(),
                                 decimals: 6,
+                                degraded: false,
+                                metadata: None,
                             });
                         }
                         
@@ -201,15 +208,20 @@ mod tests {
                         asset_infos,
                         twap_timeframe,
                         oracle_time_limit,
+                        basket_id,
                     } => {
                         Ok(to_json_binary(&vec![PriceResponse {
                             prices: vec![],
                             price: Decimal::one(),
                             decimals: 6,
+                            degraded: false,
+                            metadata: None,
                         }, PriceResponse {
                             prices: vec![],
                             price: Decimal::one(),
                             decimals: 6,
+                            degraded: false,
+                            metadata: None,
                         }])?)
                     }
                 }
@@ -354,6 +366,8 @@ mod tests {
                                     rate_index: Decimal::zero(), 
                                     pool_info: None,
                                     hike_rates: Some(true),
+                                    isolated_debt_ceiling: None,
+                                    rate_model: None,
                                     
                                 }
                             ],
@@ -378,6 +392,8 @@ mod tests {
                                     rate_index: Decimal::zero(), 
                                     pool_info: None,
                                     hike_rates: Some(true),
+                                    isolated_debt_ceiling: None,
+                                    rate_model: None,
                                     
                                 }
                             ],
@@ -387,7 +403,9 @@ mod tests {
                             credit_price: PriceResponse { 
                                 prices: vec![], 
                                 price: Decimal::one(), 
-                                decimals: 6
+                                decimals: 6,
+                                degraded: false,
+                                metadata: None,
                             },
                             liq_queue: None,
                             base_interest_rate: Decimal::zero(),
@@ -400,11 +418,14 @@ mod tests {
                             credit_last_accrued: 0,
                             rates_last_accrued: 0,
                             oracle_set: true,
+                            close_factor: None,
+                            soft_liquidation: None,
                         })?)
                     },
                     CDP_MockQueryMsg::GetCollateralInterest { } => {
                         Ok(to_json_binary(&CollateralInterestResponse {
                             rates: vec![Decimal::percent(1)],
+                            rate_models: vec![RateModel::TwoSlope {}],
                         })?)
                     },
                     CDP_MockQueryMsg::GetCreditRate { } => {
@@ -507,6 +528,8 @@ mod tests {
                                     rate_index: Decimal::zero(), 
                                     pool_info: None,
                                     hike_rates: Some(true),
+                                    isolated_debt_ceiling: None,
+                                    rate_model: None,
                                     
                                 }
                             ],
@@ -531,6 +554,8 @@ mod tests {
                                     rate_index: Decimal::zero(), 
                                     pool_info: None,
                                     hike_rates: Some(true),
+                                    isolated_debt_ceiling: None,
+                                    rate_model: None,
                                     
                                 }
                             ],
@@ -540,7 +565,9 @@ mod tests {
                             credit_price: PriceResponse { 
                                 prices: vec![], 
                                 price: Decimal::one(), 
-                                decimals: 6
+                                decimals: 6,
+                                degraded: false,
+                                metadata: None,
                             },
                             liq_queue: None,
                             base_interest_rate: Decimal::zero(),
@@ -553,11 +580,14 @@ mod tests {
                             credit_last_accrued: 0,
                             rates_last_accrued: 0,
                             oracle_set: true,
+                            close_factor: None,
+                            soft_liquidation: None,
                         })?)
                     },
                     CDP_MockQueryMsg::GetCollateralInterest { } => {
                         Ok(to_json_binary(&CollateralInterestResponse {
                             rates: vec![Decimal::one()],
+                            rate_models: vec![RateModel::TwoSlope {}],
                         })?)
                     },
                     CDP_MockQueryMsg::GetCreditRate { } => {
@@ -184,7 +184,9 @@ mod tests {
                         credit_price: PriceResponse { 
                             prices: vec![], 
                             price: Decimal::one(), 
-                            decimals: 6
+                            decimals: 6,
+                            degraded: false,
+                            metadata: None,
                         },
                         liq_queue: None,
                         base_interest_rate: Decimal::zero(),
@@ -197,6 +199,8 @@ mod tests {
                         credit_last_accrued: 0,
                         rates_last_accrued: 0,
                         oracle_set: false,
+                        close_factor: None,
+                        soft_liquidation: None,
                     })?),
                 }
             },
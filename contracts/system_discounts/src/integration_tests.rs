@@ -91,7 +91,9 @@ mod tests {
                             credit_price: PriceResponse { 
                                 prices: vec![], 
                                 price: Decimal::one(), 
-                                decimals: 6
+                                decimals: 6,
+                                degraded: false,
+                                metadata: None,
                             },
                             liq_queue: None,
                             base_interest_rate: Decimal::zero(),
@@ -104,6 +106,8 @@ mod tests {
                             credit_last_accrued: 0,
                             rates_last_accrued: 0,
                             oracle_set: false,
+                            close_factor: None,
+                            soft_liquidation: None,
                         })?)
                     },
                 }
@@ -150,6 +154,8 @@ mod tests {
                             prices: vec![],
                             price: Decimal::one(),
                             decimals: 0,
+                            degraded: false,
+                            metadata: None,
                         }])?)
                         
                     }
@@ -1,9 +1,10 @@
-use cosmwasm_std::{Addr, Decimal, Uint128, StdResult, Api, StdError};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128, StdResult, Api, StdError};
 use cosmwasm_schema::cw_serde;
 
+use crate::oracle::PricePolicy;
 use crate::types::{
-    cAsset, Asset, AssetInfo, InsolventPosition,
-    SupplyCap, MultiAssetSupplyCap, TWAPPoolInfo, UserInfo, PoolType, Basket, equal, PremiumInfo,
+    cAsset, Asset, AssetInfo, CollateralVolatility, DiscountSource, InsolventPosition, LTVRamp, OperatorAllowance, OrderAction, OrderTrigger, ParameterRamp, RateModel, SoftLiquidation,
+    SupplyCap, MultiAssetSupplyCap, TWAPPoolInfo, UserInfo, PoolType, Basket, equal, PremiumInfo, RedemptionCollateralFilter, VolatilityConfig,
 };
 
 #[cw_serde]
@@ -48,6 +49,12 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Update the contract config
     UpdateConfig(UpdateConfig),
+    /// Replace the interest discount sources.
+    /// Discounts from each source are capped & stacked, up to 100%.
+    EditDiscountSources {
+        /// New discount sources
+        sources: Vec<DiscountSource>,
+    },
     /// Deposit collateral into a Position
     Deposit {
         /// Position ID to deposit into.
@@ -67,6 +74,9 @@ pub enum ExecuteMsg {
         LTV: Option<Decimal>,
         /// Mint debt tokens to this address
         mint_to_addr: Option<String>,
+        /// Position owner if the sender is an operator.
+        /// Defaults to the sender.
+        position_owner: Option<String>,
     },
     /// Withdraw collateral from a Position
     Withdraw {
@@ -76,6 +86,9 @@ pub enum ExecuteMsg {
         assets: Vec<Asset>,
         /// Send withdrawn assets to this address if not the sender
         send_to: Option<String>,
+        /// Position owner if the sender is an operator.
+        /// Defaults to the sender.
+        position_owner: Option<String>,
     },
     /// Repay debt of a Position
     Repay {
@@ -94,12 +107,27 @@ pub enum ExecuteMsg {
         position_id: Uint128,
         /// Position owner to liquidate
         position_owner: String,
+        /// Exit LP share collateral into its pool's underlying assets
+        /// before paying the caller fee & Stability Pool
+        unwrap_lp: Option<bool>,
+    },
+    /// Liquidate multiple Positions.
+    /// Solvent Positions & failed liquidations are skipped.
+    LiquidateMany {
+        /// Positions to liquidate
+        positions: Vec<UserInfo>,
     },
     /// Redeem CDT for collateral
     /// Redemption limit based on Position owner buy-in
     RedeemCollateral {
         /// Max % premium on the redeemed collateral`
         max_collateral_premium: Option<u128>,
+        /// Collateral the redeemer accepts.
+        /// Positions without accepted collateral are skipped.
+        collateral_filter: Option<RedemptionCollateralFilter>,
+        /// Minimum USD value of the collateral received.
+        /// Errors if not met.
+        min_value_received: Option<Decimal>,
     },
     /// Edit Redeemability for owned Positions
     EditRedeemability {
@@ -116,6 +144,57 @@ pub enum ExecuteMsg {
         /// These are restricted from use in redemptions.
         /// Swaps the full list.
         restricted_collateral_assets: Option<Vec<String>>,
+        /// Position owner if the sender is an operator.
+        /// Defaults to the sender.
+        position_owner: Option<String>,
+    },
+    /// Offer a Position to a new owner.
+    /// The transfer completes when the new owner accepts it.
+    TransferPosition {
+        /// Position ID to transfer
+        position_id: Uint128,
+        /// New Position owner
+        new_owner: String,
+    },
+    /// Accept a Position transfer offered to the sender
+    AcceptPositionTransfer {
+        /// Position ID to accept
+        position_id: Uint128,
+    },
+    /// Cancel the sender's pending Position transfer
+    CancelPositionTransfer {
+        /// Position ID to cancel the transfer of
+        position_id: Uint128,
+    },
+    /// Approve, edit or remove an operator for the sender's Positions in the Basket
+    EditOperator {
+        /// Operator address
+        operator: String,
+        /// Operator allowance.
+        /// Removes the operator if None.
+        allowance: Option<OperatorAllowance>,
+    },
+    /// Set a conditional order on the sender's Position.
+    /// Funds sent are escrowed as the executor's tip.
+    /// Tips must be the Basket's credit asset or a collateral type.
+    SetPositionOrder {
+        /// Position ID
+        position_id: Uint128,
+        /// Condition to execute at
+        trigger: OrderTrigger,
+        /// Action taken on execution
+        action: OrderAction,
+    },
+    /// Cancel an order & refund its tip
+    CancelPositionOrder {
+        /// Order ID
+        order_id: u64,
+    },
+    /// Execute a triggered order.
+    /// Callable by anyone, the caller receives the order's tip.
+    ExecutePositionOrder {
+        /// Order ID
+        order_id: u64,
     },
     /// Accrue interest for a Position
     Accrue { 
@@ -124,15 +203,40 @@ pub enum ExecuteMsg {
         /// Positon ID to accrue interest for
         position_ids: Vec<Uint128>
     },
-    // Close a Position by selling collateral and repaying debt
-    // ClosePosition {
-    //     /// Position ID to close
-    //     position_id: Uint128,
-    //     /// Max spread for the sale of collateral
-    //     max_spread: Decimal,
-    //     /// Send excess assets to this address if not the Position owner
-    //     send_to: Option<String>,
-    // },
+    /// Close a Position by selling collateral and repaying debt
+    ClosePosition {
+        /// Position ID to close
+        position_id: Uint128,
+        /// Max spread for the sale of collateral.
+        /// ClosePosition uses the spread as a multiplier on the collateral amount to ensure success.
+        max_spread: Decimal,
+        /// Send excess assets to this address if not the Position owner
+        send_to: Option<String>,
+    },
+    /// Lever up a Position by minting credit, swapping it into the Position's collateral & redepositing.
+    /// Repeats until the target LTV or loop limit is reached.
+    LoopPosition {
+        /// Position ID to loop
+        position_id: Uint128,
+        /// LTV to mint to each loop.
+        /// Can't be above the Position's borrow LTV.
+        target_LTV: Decimal,
+        /// Max slippage for each swap
+        max_slippage: Decimal,
+        /// Max number of loops, defaults to 10
+        num_loops: Option<u64>,
+    },
+    /// Mint credit without collateral, to be returned with a fee in the same transaction.
+    /// The receiver is called after the mint & the contract must hold amount + fee afterwards.
+    FlashMint {
+        /// Amount of credit to mint.
+        /// Can't exceed the flash_mint_cap.
+        amount: Uint128,
+        /// Contract that receives the credit & is called with msg
+        receiver: String,
+        /// Msg to execute on the receiver
+        msg: Binary,
+    },
     /// Edit the contract's Basket
     EditBasket(EditBasket),
     /// Edit a cAsset in the contract's Basket
@@ -143,6 +247,48 @@ pub enum ExecuteMsg {
         max_borrow_LTV: Option<Decimal>, 
         /// Point of liquidation
         max_LTV: Option<Decimal>,
+        /// Debt ceiling for isolated collateral.
+        /// Setting this isolates the asset.
+        isolated_debt_ceiling: Option<Uint128>,
+        /// Interest rate model for the asset
+        rate_model: Option<RateModel>,
+        /// Remove isolation from the asset
+        remove_isolation: Option<bool>,
+        /// Ramp LTV changes over this many seconds.
+        /// Changes are immediate if None or 0.
+        ramp_duration: Option<u64>,
+        /// Volatility tracker settings.
+        /// Volatility is tracked per asset, so this applies in every Basket w/ the asset.
+        volatility_config: Option<VolatilityConfig>,
+    },
+    /// Deprecate a cAsset.
+    /// Blocks deposits, zeroes its borrow LTV & ramps its max_LTV down.
+    DeprecatecAsset {
+        /// cAsset to deprecate
+        asset: AssetInfo,
+        /// Seconds to ramp max_LTV down over
+        ramp_duration: u64,
+        /// max_LTV at the end of the ramp
+        final_max_LTV: Decimal,
+        /// Added to the liquidation caller fee
+        liquidation_incentive: Decimal,
+    },
+    /// Remove a deprecated cAsset from the Basket once its supply is 0.
+    /// Owner only.
+    RemovecAsset {
+        /// cAsset to remove
+        asset: AssetInfo,
+    },
+    /// Create a Basket with its own credit asset, collateral & caps.
+    /// The first Basket created is the default.
+    CreateBasket(CreateBasket),
+    /// Execute a msg for a specific Basket.
+    /// Msgs sent without this are executed for the default Basket.
+    WithBasket {
+        /// Basket ID
+        basket_id: Uint128,
+        /// Msg to execute
+        msg: Box<ExecuteMsg>,
     },
     //Callbacks; Only callable by the contract
     Callback(CallbackMsg),
@@ -160,6 +306,17 @@ pub enum CallbackMsg {
         /// Position owner to check
         position_owner: Addr,
     },
+    /// Liquidate a Position from a LiquidateMany batch
+    LiquidatePosition {
+        /// Position ID to liquidate
+        position_id: Uint128,
+        /// Position owner to liquidate
+        position_owner: Addr,
+        /// Recipient of the caller fee
+        fee_recipient: Addr,
+    },
+    /// Assert a flash mint was returned with its fee & burn it
+    CheckFlashMintRepayment {},
 }
 
 
@@ -191,10 +348,28 @@ pub enum QueryMsg {
     GetBasket { }, 
     /// Returns Basket collateral debt caps
     GetBasketDebtCaps { },
+    /// Returns debt cap & supply cap risk metrics for each collateral & multi-asset cap
+    GetDebtCapDashboard { },
+    /// Returns the volatility tracker of each Basket collateral
+    GetCollateralVolatility { },
     /// Returns credit redemption rate
     GetCreditRate { },
     /// Returns Basket collateral interest rates
     GetCollateralInterest { },
+    /// Returns the interest discount sources
+    GetDiscountSources { },
+    /// Returns a Position's undiscounted rate, each applied discount & its effective rate
+    GetPositionRate {
+        /// Position owner
+        position_owner: String,
+        /// Position ID
+        position_id: Uint128,
+    },
+    /// Returns operators approved by a Position owner in the Basket
+    GetOperators {
+        /// Position owner
+        position_owner: String,
+    },
     /// Simulate LTV mint for a Position
     SimulateMint {
         /// Position ID to simulate mint for
@@ -202,13 +377,70 @@ pub enum QueryMsg {
         /// LTV to mint to
         LTV: Decimal,
     },
-    // Returns insolvency status of a Position
-    // GetPositionInsolvency {
-    //     /// Position ID to query
-    //     position_id: Uint128,
-    //     /// Position owner to query
-    //     position_owner: String,
-    // },
+    /// Returns the insolvency status of Positions.
+    /// Paginated by Position owner.
+    GetPositionInsolvency {
+        /// Single position
+        user_info: Option<UserInfo>,
+        /// Start after this user address
+        start_after: Option<String>,
+        /// Response limiter, in Position owners
+        limit: Option<u32>,
+        /// Only return Positions at or above this LTV
+        min_LTV: Option<Decimal>,
+        /// Only return Positions holding this collateral asset
+        collateral_asset: Option<AssetInfo>,
+    },
+    /// Returns Position orders.
+    /// Paginated by order ID.
+    GetPositionOrders {
+        /// Only return this owner's orders
+        position_owner: Option<String>,
+        /// Start after this order ID
+        start_after: Option<u64>,
+        /// Response limiter
+        limit: Option<u32>,
+    },
+    /// Returns a Position's snapshots.
+    /// Paginated by snapshot time.
+    GetPositionSnapshots {
+        /// Position owner
+        position_owner: String,
+        /// Position ID
+        position_id: Uint128,
+        /// Start after this snapshot time
+        start_after: Option<u64>,
+        /// Response limiter
+        limit: Option<u32>,
+    },
+    /// Returns deprecated collateral & their wind-down schedules
+    GetCollateralDeprecations { },
+    /// Returns pending parameter ramps
+    GetParameterRamps { },
+    /// Simulate RedeemCollateral
+    SimulateRedemption {
+        /// Amount of credit to redeem
+        credit_amount: Uint128,
+        /// Max % premium on the redeemed collateral
+        max_collateral_premium: Option<u128>,
+        /// Collateral the redeemer accepts
+        collateral_filter: Option<RedemptionCollateralFilter>,
+    },
+    /// Returns the contract's Baskets
+    GetBaskets {
+        /// Start after this Basket ID
+        start_after: Option<Uint128>,
+        /// Response limiter
+        limit: Option<u32>,
+    },
+    /// Query a specific Basket.
+    /// Queries sent without this are for the default Basket.
+    WithBasket {
+        /// Basket ID
+        basket_id: Uint128,
+        /// Query to run
+        msg: Box<QueryMsg>,
+    },
     // Used internally to test state propagation
     // Propagation {},
 }
@@ -258,10 +490,19 @@ pub struct Config {
     pub rate_slope_multiplier: Decimal,
     /// Rate hike rate
     pub rate_hike_rate: Option<Decimal>,
+    /// Max credit minted per flash mint.
+    /// Flash minting is disabled if None or 0.
+    pub flash_mint_cap: Option<Uint128>,
+    /// Flash mint fee as a % of the minted amount.
+    /// Fees are added to the Basket's pending_revenue.
+    pub flash_mint_fee: Option<Decimal>,
+    /// Max staleness & confidence interval of collateral prices.
+    /// Prices outside the policy block borrows & withdrawals w/ debt, liquidations aren't gated.
+    pub price_policy: Option<PricePolicy>,
 }
 
 
-/// Create the contract's Basket
+/// Create a Basket
 #[cw_serde]
 pub struct CreateBasket {
     /// Basket ID
@@ -321,6 +562,12 @@ pub struct UpdateConfig {
     pub rate_slope_multiplier: Option<Decimal>,
     /// Rate hike rate
     pub rate_hike_rate: Option<Decimal>,
+    /// Max credit minted per flash mint, 0 disables flash minting
+    pub flash_mint_cap: Option<Uint128>,
+    /// Flash mint fee as a % of the minted amount
+    pub flash_mint_fee: Option<Decimal>,
+    /// Collateral price staleness & confidence policy
+    pub price_policy: Option<PricePolicy>,
 }
 
 impl UpdateConfig {
@@ -397,6 +644,20 @@ impl UpdateConfig {
         if let Some(new_rate) = self.rate_hike_rate {
             config.rate_hike_rate = Some(new_rate);
         }
+        if let Some(flash_mint_cap) = self.flash_mint_cap {
+            config.flash_mint_cap = Some(flash_mint_cap);
+        }
+        if let Some(flash_mint_fee) = self.flash_mint_fee {
+            //Enforce 0-100% range
+            if flash_mint_fee >= Decimal::percent(100) {
+                return Err(StdError::GenericErr{ msg: String::from("Flash mint fee must be between 0-100%") });
+            }
+            config.flash_mint_fee = Some(flash_mint_fee);
+        }
+        if let Some(price_policy) = self.price_policy {
+            price_policy.validate()?;
+            config.price_policy = Some(price_policy);
+        }
         Ok(())
     }
 }
@@ -428,6 +689,15 @@ pub struct EditBasket {
     pub rev_to_stakers: Option<bool>,
     /// Take revenue, used as a way to distribute revenue
     pub take_revenue: Option<Uint128>,
+    /// Max % of a Position's debt repaid per liquidation.
+    /// Set to 100% to turn off.
+    pub close_factor: Option<Decimal>,
+    /// Soft liquidation settings.
+    /// Set the repay_ratio to 0 to turn off.
+    pub soft_liquidation: Option<SoftLiquidation>,
+    /// Ramp base_interest_rate changes over this many seconds.
+    /// Changes are immediate if None or 0.
+    pub rate_ramp_duration: Option<u64>,
 }
 
 impl EditBasket {    
@@ -490,6 +760,12 @@ impl EditBasket {
         if let Some(error_margin) = self.cpc_margin_of_error {
             basket.cpc_margin_of_error = error_margin;
         }
+        if let Some(close_factor) = self.close_factor {
+            basket.close_factor = if close_factor == Decimal::one() { None } else { Some(close_factor) };
+        }
+        if let Some(soft_liquidation) = self.soft_liquidation {
+            basket.soft_liquidation = if soft_liquidation.repay_ratio.is_zero() { None } else { Some(soft_liquidation) };
+        }
         if let Some(take_revenue) = self.take_revenue {
             basket.pending_revenue = match basket.pending_revenue.checked_sub(take_revenue){
                 Ok(val) => val,
@@ -504,6 +780,74 @@ impl EditBasket {
     }
 } 
 
+/// Response for GetPositionRate
+#[cw_serde]
+pub struct PositionRateResponse {
+    /// Collateral interest rate weighted by the Position's collateral ratios
+    pub undiscounted_rate: Decimal,
+    /// Discount from each source
+    pub discounts: Vec<AppliedDiscount>,
+    /// Sum of applied discounts, at most 100%
+    pub total_discount: Decimal,
+    /// Rate after discounts
+    pub effective_rate: Decimal,
+}
+
+/// Discount applied by a source
+#[cw_serde]
+pub struct AppliedDiscount {
+    /// Source type, "system_discounts" for the config's discounts contract
+    pub source: String,
+    /// Source contract
+    pub contract: String,
+    /// Discount returned by the source
+    pub discount: Decimal,
+    /// Discount after the source's cap
+    pub applied: Decimal,
+}
+
+/// Response for GetParameterRamps
+#[cw_serde]
+pub struct ParameterRampsResponse {
+    /// Base interest rate ramp
+    pub base_interest_rate: Option<ParameterRamp>,
+    /// cAsset LTV ramps
+    pub cAsset_LTVs: Vec<LTVRamp>,
+}
+
+/// Response for SimulateRedemption
+#[cw_serde]
+pub struct RedemptionSimulationResponse {
+    /// Redemptions in each premium tier
+    pub premium_tiers: Vec<PremiumRedemption>,
+    /// Total collateral received
+    pub collateral_received: Vec<Asset>,
+    /// Credit that couldn't be redeemed at the max premium
+    pub unredeemed_credit: Uint128,
+}
+
+/// Redemptions in a premium tier
+#[cw_serde]
+pub struct PremiumRedemption {
+    /// % premium on the collateral
+    pub premium: u128,
+    /// Positions redeemed from
+    pub positions: Vec<RedeemedPosition>,
+    /// Collateral received from the tier
+    pub collateral_received: Vec<Asset>,
+}
+
+/// Redemption from a Position
+#[cw_serde]
+pub struct RedeemedPosition {
+    /// Position
+    pub position_info: UserInfo,
+    /// Credit redeemed
+    pub credit_redeemed: Uint128,
+    /// Collateral received
+    pub collateral_received: Vec<Asset>,
+}
+
 /// Response for GetUserPositions
 #[cw_serde]
 pub struct PositionResponse {
@@ -544,6 +888,8 @@ pub struct InterestResponse {
 pub struct CollateralInterestResponse {
     /// Collateral interest rates in the order of the collateral types
     pub rates: Vec<Decimal>,
+    /// Model that produced each rate
+    pub rate_models: Vec<RateModel>,
 }
 
 #[cw_serde]
@@ -551,8 +897,94 @@ pub struct RedeemabilityResponse {
     /// State for each premium 
     pub premium_infos: Vec<PremiumInfo>,
 }
+/// Position state after an action.
+/// Stored once per block, the latest action in the block overwrites.
+#[cw_serde]
+pub struct PositionSnapshot {
+    /// Basket ID
+    pub basket_id: Uint128,
+    /// Block time in seconds of the snapshotted action
+    pub time: u64,
+    /// Position debt
+    pub credit_amount: Uint128,
+    /// Position collateral
+    pub collateral_assets: Vec<Asset>,
+    /// Collateral value, None if the collateral couldn't be priced
+    pub collateral_value: Option<Decimal>,
+    /// Debt value, None if it couldn't be valued
+    pub debt_value: Option<Decimal>,
+    /// Debt value / collateral value, None if either is missing
+    pub LTV: Option<Decimal>,
+}
+
+/// Response for GetDebtCapDashboard
+#[cw_serde]
+pub struct DebtCapDashboardResponse {
+    /// Metrics for each collateral type
+    pub collateral_caps: Vec<CollateralCapInfo>,
+    /// Utilization of each multi-asset supply cap
+    pub multi_asset_caps: Vec<MultiAssetCapInfo>,
+}
+
+/// Supply & debt cap metrics for a collateral type
+#[cw_serde]
+pub struct CollateralCapInfo {
+    /// Collateral asset info
+    pub collateral: AssetInfo,
+    /// Debt collateralized by the asset
+    pub debt_total: Uint128,
+    /// Debt ceiling
+    pub debt_cap: Uint128,
+    /// CDT that can still be minted against the asset
+    pub remaining_mintable: Uint128,
+    /// Asset's current ratio of the Basket's TVL
+    pub supply_ratio: Decimal,
+    /// Governance set supply cap ratio
+    pub supply_cap_ratio: Decimal,
+    /// Supply cap ratio after the volatility adjustment
+    pub volatility_adjusted_cap_ratio: Decimal,
+    /// Volatility index, None if the asset's volatility isn't tracked yet
+    pub volatility_index: Option<Decimal>,
+    /// Ratio of Stability Pool liquidity the debt cap is based on
+    pub stability_pool_ratio: Option<Decimal>,
+    /// Debt cap based on Stability Pool liquidity
+    pub stability_pool_cap: Option<Uint128>,
+}
+
+/// Utilization of a multi-asset supply cap
+#[cw_serde]
+pub struct MultiAssetCapInfo {
+    /// Assets in the cap
+    pub assets: Vec<AssetInfo>,
+    /// The assets' combined ratio of the Basket's TVL
+    pub supply_ratio: Decimal,
+    /// Supply cap ratio
+    pub supply_cap_ratio: Decimal,
+    /// supply_ratio / supply_cap_ratio, None if the cap is 0
+    pub utilization: Option<Decimal>,
+}
+
+/// Volatility tracker of a collateral type
+#[cw_serde]
+pub struct CollateralVolatilityResponse {
+    /// Collateral asset info
+    pub asset: AssetInfo,
+    /// Tracker state w/ its effective config
+    pub volatility: CollateralVolatility,
+}
+
+#[cw_serde]
+pub struct OperatorResponse {
+    /// Operator address
+    pub operator: String,
+    /// Operator allowance
+    pub allowance: OperatorAllowance,
+}
+
 #[cw_serde]
 pub struct InsolvencyResponse {
-    /// List of insolvent Positions
+    /// List of Position insolvency statuses
     pub insolvent_positions: Vec<InsolventPosition>,
+    /// Last Position owner checked, use as start_after for the next page
+    pub last_position_owner: Option<String>,
 }
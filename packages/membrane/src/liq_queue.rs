@@ -4,7 +4,7 @@ use cosmwasm_std::{Addr, Uint128};
 
 use crate::math::{Decimal256, Uint256};
 use crate::types::{AssetInfo, Bid, BidInput, Asset};
-use crate::oracle::PriceResponse;
+use crate::oracle::{PricePolicy, PriceResponse};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -67,6 +67,9 @@ pub enum ExecuteMsg {
         max_premium: Uint128, 
         /// Minimum bid amount. Unlocks waiting bids if total_bids is less than.
         bid_threshold: Uint256,
+        /// Osmosis pool ID if bid_for is an LP share.
+        /// Bids are settled in the pool's underlying assets if set.
+        lp_pool_id: Option<u64>,
     },
     /// Update a queue
     UpdateQueue {
@@ -76,6 +79,8 @@ pub enum ExecuteMsg {
         max_premium: Option<Uint128>,
         /// Minimum bid amount. Unlocks waiting bids if total_bids is less than.
         bid_threshold: Option<Uint256>,
+        /// Osmosis pool ID to settle LP share bids in the pool's underlying assets
+        lp_pool_id: Option<u64>,
     },
     /// Update contract config
     UpdateConfig {
@@ -91,6 +96,8 @@ pub enum ExecuteMsg {
         minimum_bid: Option<Uint128>,
         /// Maximum waiting bids
         maximum_waiting_bids: Option<u64>,
+        /// Collateral price staleness & confidence policy
+        price_policy: Option<PricePolicy>,
     },
 }
 
@@ -185,6 +192,8 @@ pub struct Config {
     pub minimum_bid: Uint128,
     /// Maximum total bids
     pub maximum_waiting_bids: u64,
+    /// Max staleness & confidence interval of collateral prices sent for liquidation
+    pub price_policy: Option<PricePolicy>,
 }
 
 #[cw_serde]
@@ -261,6 +270,8 @@ pub struct QueueResponse {
     pub current_bid_id: Uint128,
     /// Minimum bid amount
     pub bid_threshold: Uint256,
+    /// LP pool ID, claims are settled in the pool's underlying assets if set
+    pub lp_pool_id: Option<u64>,
 }
 
 #[cw_serde]
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Decimal, Uint128, Addr, StdResult};
+use cosmwasm_std::{Decimal, Uint128, Addr, StdError, StdResult};
 
 use pyth_sdk_cw::PriceIdentifier;
 
@@ -36,6 +36,8 @@ pub enum ExecuteMsg {
         /// Osmosis pools for OSMO/USD-par TWAP.
         /// Replaces saved state.
         pools_for_usd_par_twap: Option<Vec<TWAPPoolInfo>>,
+        /// Price history settings
+        price_history: Option<PriceHistoryConfig>,
     },
     /// Add a new asset
     AddAsset {
@@ -53,6 +55,20 @@ pub enum ExecuteMsg {
         /// Toggle to remove
         remove: bool,
     },
+    /// Save the current price of each asset to its price history.
+    /// Permissionless crank, priced with the Positions contract's TWAP timeframe & oracle time limit.
+    UpdatePriceHistory {
+        /// Asset infos
+        asset_infos: Vec<AssetInfo>,
+        /// None defaults to 1, which is assumed the USD basket.
+        basket_id: Option<Uint128>,
+    },
+    /// Save prices queried by the Positions contract to their price history.
+    /// Positions contract only.
+    RecordPrices {
+        /// Final prices
+        prices: Vec<PriceRecord>,
+    },
 }
 
 #[cw_serde]
@@ -79,12 +95,42 @@ pub enum QueryMsg {
         twap_timeframe: u64,
         /// Pyth Oracle time limit in seconds
         oracle_time_limit: u64,
+        /// To switch on oracle sources.
+        /// None defaults to 1, which is assumed the USD basket.
+        basket_id: Option<Uint128>,
     },
     /// Return list of asset oracle info
     Assets {
         /// List of asset infos
         asset_infos: Vec<AssetInfo> 
     },
+    /// Returns an asset's saved prices, oldest first
+    PriceHistory {
+        /// Asset info
+        asset_info: AssetInfo,
+        /// None defaults to 1, which is assumed the USD basket.
+        basket_id: Option<Uint128>,
+        /// Number of the latest prices to return
+        limit: Option<u32>,
+    },
+    /// Returns the time-weighted average of an asset's saved prices
+    OracleTWAP {
+        /// Asset info
+        asset_info: AssetInfo,
+        /// Timeframe in seconds
+        window: u64,
+        /// None defaults to 1, which is assumed the USD basket.
+        basket_id: Option<Uint128>,
+    },
+    /// Returns the price at each hop of an asset's Osmosis TWAP path
+    SimulatePricePath {
+        /// Asset info
+        asset_info: AssetInfo,
+        /// Timeframe in minutes
+        twap_timeframe: u64,
+        /// None defaults to 1, which is assumed the USD basket.
+        basket_id: Option<Uint128>,
+    },
 }
 
 
@@ -105,6 +151,40 @@ pub struct Config {
     /// Osmosis pools for OSMO/USD-par TWAP.
     /// This list of pools will be used separately and medianized.
     pub pools_for_usd_par_twap: Vec<TWAPPoolInfo>,
+    /// Price history settings
+    #[serde(default)]
+    pub price_history: PriceHistoryConfig,
+}
+
+/// Price history settings
+#[cw_serde]
+pub struct PriceHistoryConfig {
+    /// Number of prices kept per asset, the oldest is dropped once full
+    pub length: u32,
+    /// Minimum seconds between saved prices
+    pub sampling_interval: u64,
+    /// Max age in seconds of the latest saved price for it to be used as a backup.
+    /// Backups are used when every source fails. 0 disables them.
+    pub max_backup_age: u64,
+}
+
+impl Default for PriceHistoryConfig {
+    fn default() -> Self {
+        PriceHistoryConfig {
+            length: 144,
+            sampling_interval: 600,
+            max_backup_age: 3600,
+        }
+    }
+}
+
+impl PriceHistoryConfig {
+    pub fn validate(&self) -> StdResult<()> {
+        if self.length == 0 || self.sampling_interval == 0 {
+            return Err(StdError::GenericErr { msg: String::from("Price history length & sampling interval must be greater than 0") });
+        }
+        Ok(())
+    }
 }
 
 #[cw_serde]
@@ -115,6 +195,14 @@ pub struct PriceResponse {
     pub price: Decimal,
     /// Asset decimals
     pub decimals: u64,
+    /// Price sources deviate by more than the asset's max_source_deviation.
+    /// Positions can't take new debt against degraded collateral.
+    #[serde(default)]
+    pub degraded: bool,
+    /// Freshness & quality of the final price.
+    /// None if the source doesn't report it.
+    #[serde(default)]
+    pub metadata: Option<PriceMetadata>,
 }
 
 impl PriceResponse {
@@ -152,6 +240,94 @@ impl PriceResponse {
     }
 }
 
+#[cw_serde]
+pub struct PriceMetadata {
+    /// Unix time (seconds) the oldest source of the price was published
+    pub publish_time: u64,
+    /// Source used for the final price
+    pub source: String,
+    /// Pyth confidence interval as a % of the price
+    pub confidence: Option<Decimal>,
+    /// Seconds of TWAP history covered by the price
+    pub twap_window: Option<u64>,
+}
+
+impl PriceMetadata {
+    /// Combine the metadata of prices used to derive another price.
+    /// Keeps the oldest publish time, widest confidence & shortest TWAP window.
+    pub fn merge(self, other: PriceMetadata) -> PriceMetadata {
+        let source = if self.source == other.source {
+            self.source
+        } else {
+            format!("{}, {}", self.source, other.source)
+        };
+
+        PriceMetadata {
+            publish_time: self.publish_time.min(other.publish_time),
+            source,
+            confidence: match (self.confidence, other.confidence) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            },
+            twap_window: match (self.twap_window, other.twap_window) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+}
+
+/// Merge optional metadata, keeping whichever is reported
+pub fn merge_price_metadata(a: Option<PriceMetadata>, b: Option<PriceMetadata>) -> Option<PriceMetadata> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.merge(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Caller limits on how stale or uncertain a price can be
+#[cw_serde]
+pub struct PricePolicy {
+    /// Max seconds since the price was published
+    pub max_staleness: Option<u64>,
+    /// Max Pyth confidence interval as a % of the price
+    pub max_confidence: Option<Decimal>,
+}
+
+impl PricePolicy {
+    pub fn validate(&self) -> StdResult<()> {
+        if let Some(max_confidence) = self.max_confidence {
+            if max_confidence.is_zero() {
+                return Err(StdError::GenericErr { msg: String::from("Max price confidence must be greater than 0") });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Error if the price is too stale or too uncertain.
+    /// Prices without metadata are accepted.
+    pub fn check(&self, asset: &str, price: &PriceResponse, current_time: u64) -> StdResult<()> {
+        let metadata = match &price.metadata {
+            Some(metadata) => metadata,
+            None => return Ok(()),
+        };
+
+        if let Some(max_staleness) = self.max_staleness {
+            if current_time.saturating_sub(metadata.publish_time) > max_staleness {
+                return Err(StdError::GenericErr { msg: format!("{} price is stale, published at {}", asset, metadata.publish_time) });
+            }
+        }
+        if let (Some(max_confidence), Some(confidence)) = (self.max_confidence, metadata.confidence) {
+            if confidence > max_confidence {
+                return Err(StdError::GenericErr { msg: format!("{} price confidence interval is too wide: {}", asset, confidence) });
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[cw_serde]
 pub struct PriceResponse256 {
     /// List of PriceInfo from different sources
@@ -188,6 +364,74 @@ impl PriceResponse256 {
     }
 }
 
+/// Final price of an asset for a basket
+#[cw_serde]
+pub struct PriceRecord {
+    /// Asset info
+    pub asset_info: AssetInfo,
+    /// Basket the price was queried for
+    pub basket_id: Uint128,
+    /// Final price
+    pub price: PriceResponse,
+    /// Time the price was queried in seconds
+    pub time: u64,
+}
+
+/// Saved price
+#[cw_serde]
+pub struct PriceSample {
+    /// Final price
+    pub price: Decimal,
+    /// Time of the price in seconds
+    pub time: u64,
+}
+
+#[cw_serde]
+pub struct PriceHistoryResponse {
+    /// Asset info
+    pub asset_info: AssetInfo,
+    /// Saved prices, oldest first
+    pub prices: Vec<PriceSample>,
+}
+
+#[cw_serde]
+pub struct OracleTWAPResponse {
+    /// Time-weighted average price
+    pub price: Decimal,
+    /// Seconds of the window covered by saved prices
+    pub window_covered: u64,
+    /// Number of saved prices in the window
+    pub samples: u32,
+}
+
+#[cw_serde]
+pub struct PricePathHop {
+    /// Pool ID
+    pub pool_id: u64,
+    /// Base asset denom
+    pub base_asset_denom: String,
+    /// Base asset decimals
+    pub base_decimals: u64,
+    /// Quote asset denom
+    pub quote_asset_denom: String,
+    /// Quote asset decimals
+    pub quote_decimals: u64,
+    /// Pool TWAP in the assets' smallest units
+    pub twap: Decimal,
+    /// Whole base token price in whole quote tokens
+    pub price: Decimal,
+    /// Asset price in this hop's quote asset
+    pub cumulative_price: Decimal,
+}
+
+#[cw_serde]
+pub struct PricePathResponse {
+    /// Each hop of the TWAP path
+    pub hops: Vec<PricePathHop>,
+    /// Asset price in the path's last quote asset
+    pub price: Decimal,
+}
+
 #[cw_serde]
 pub struct AssetResponse {
     /// Asset info
@@ -197,4 +441,24 @@ pub struct AssetResponse {
 }
 
 #[cw_serde]
-pub struct MigrateMsg {}
\ No newline at end of file
+pub struct MigrateMsg {}
+
+/// Query interface for CosmWasm price feed sources
+#[cw_serde]
+pub enum PriceFeedQueryMsg {
+    /// Returns FeedPriceResponse
+    Price {
+        /// Feed ID for the asset
+        feed_id: String,
+    },
+}
+
+#[cw_serde]
+pub struct FeedPriceResponse {
+    /// USD price per whole token
+    pub price: Decimal,
+    /// Unix time (seconds) the price was published
+    pub publish_time: u64,
+    /// Confidence interval as a % of the price
+    pub confidence: Option<Decimal>,
+}
\ No newline at end of file
@@ -10,6 +10,9 @@ use cw_coins::Coins;
 
 use osmosis_std::types::cosmos::base::v1beta1::Coin;
 
+/// Max % deviation between price sources for assets w/o a max_source_deviation
+pub const DEFAULT_MAX_SOURCE_DEVIATION_PERCENTAGE: u64 = 10;
+
 /// Stability Pool
 #[cw_serde]
 pub struct PositionUserInfo {
@@ -105,6 +108,9 @@ pub struct Queue {
     pub current_bid_id: Uint128,
     /// Minimum bid amount in the queue before waiting period is set to 0. Threshold should be larger than the largest single liquidation amount.
     pub bid_threshold: Uint256,
+    /// Osmosis pool ID of the LP share being bid for.
+    /// If set, claims are exited from the pool & settled in its underlying assets.
+    pub lp_pool_id: Option<u64>,
 }
 
 impl Queue {
@@ -114,6 +120,7 @@ impl Queue {
             max_premium: self.max_premium,
             current_bid_id: self.current_bid_id,
             bid_threshold: self.bid_threshold,
+            lp_pool_id: self.lp_pool_id,
         }
     }
 }
@@ -354,11 +361,124 @@ pub struct AssetOracleInfo {
     pub vault_info: Option<VaultTokenInfo>,
     /// Asset decimals
     pub decimals: u64,
+    /// Max % deviation between the price sources, ex: Pyth & Osmosis TWAP.
+    /// Prices past it are flagged as degraded.
+    /// None defaults to 10% for assets w/ more than one source, single source assets skip the check.
+    pub max_source_deviation: Option<Decimal>,
+    /// Ordered list of price sources.
+    /// If empty, sources are built from the pyth_price_feed_id, pools_for_osmo_twap & vault_info.
+    #[serde(default)]
+    pub sources: Vec<OracleSource>,
+}
+
+impl AssetOracleInfo {
+    /// Return the asset's price sources.
+    /// Legacy fields use Pyth with the Osmosis TWAP as its backup, or the vault token's underlying price.
+    pub fn get_sources(&self) -> Vec<OracleSource> {
+        if !self.sources.is_empty() {
+            return self.sources.clone();
+        }
+
+        if let Some(vault_info) = self.vault_info.clone() {
+            return vec![OracleSource {
+                source_type: OracleSourceType::VaultToken { vault_info },
+                weight: Decimal::one(),
+                max_staleness: None,
+            }];
+        }
+
+        match self.pyth_price_feed_id.clone() {
+            Some(price_feed_id) => vec![
+                OracleSource {
+                    source_type: OracleSourceType::Pyth { price_feed_id },
+                    weight: Decimal::one(),
+                    max_staleness: None,
+                },
+                OracleSource {
+                    source_type: OracleSourceType::OsmosisTwap {},
+                    weight: Decimal::zero(),
+                    max_staleness: None,
+                },
+            ],
+            None => vec![OracleSource {
+                source_type: OracleSourceType::OsmosisTwap {},
+                weight: Decimal::one(),
+                max_staleness: None,
+            }],
+        }
+    }
+}
+
+impl AssetOracleInfo {
+    /// Return the max deviation between the asset's price sources.
+    /// Assets w/ more than one source, incl. the legacy Pyth & Osmosis TWAP setup,
+    /// are checked against the default if none is set.
+    pub fn get_max_source_deviation(&self) -> Option<Decimal> {
+        match self.max_source_deviation {
+            Some(max_deviation) => Some(max_deviation),
+            None if self.get_sources().len() > 1 => Some(Decimal::percent(DEFAULT_MAX_SOURCE_DEVIATION_PERCENTAGE)),
+            None => None,
+        }
+    }
 }
 
 impl fmt::Display for AssetOracleInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "osmo_pools: {:?}, is_usd_par: {:?}", self.pools_for_osmo_twap, self.is_usd_par)
+        write!(f, "osmo_pools: {:?}, is_usd_par: {:?}, sources: {:?}", self.pools_for_osmo_twap, self.is_usd_par, self.sources)
+    }
+}
+
+/// Oracle price source
+#[cw_serde]
+pub struct OracleSource {
+    /// Source type & its parameters
+    pub source_type: OracleSourceType,
+    /// Weight of the source's price in the asset price.
+    /// 0 weight sources are backups, used if no weighted source returns a price.
+    pub weight: Decimal,
+    /// Max seconds since the source's price was published
+    pub max_staleness: Option<u64>,
+}
+
+#[cw_serde]
+pub enum OracleSourceType {
+    /// Pyth USD price feed, queried from the oracle's pyth_osmosis_address
+    Pyth {
+        /// Pyth price feed ID
+        price_feed_id: String,
+    },
+    /// Osmosis TWAP through the oracle info's pools_for_osmo_twap, quoted in USD through OSMO or the path's last quote asset
+    OsmosisTwap {},
+    /// Vault token valued using its underlying token's price
+    VaultToken {
+        /// Vault Info
+        vault_info: VaultTokenInfo,
+    },
+    /// CosmWasm contract that implements the oracle's PriceFeedQueryMsg
+    PriceFeed {
+        /// Price feed contract address
+        contract: String,
+        /// Feed ID for the asset
+        feed_id: String,
+    },
+    /// Governance set USD price
+    Manual {
+        /// Price per whole token
+        price: Decimal,
+        /// Unix time (seconds) the price expires
+        expiry: u64,
+    },
+}
+
+impl fmt::Display for OracleSourceType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OracleSourceType::Pyth { .. } => write!(f, "pyth"),
+            OracleSourceType::OsmosisTwap {} => write!(f, "osmosis"),
+            OracleSourceType::VaultToken { .. } => write!(f, "vault_contract"),
+            OracleSourceType::PriceFeed { .. } => write!(f, "price_feed"),
+            OracleSourceType::Manual { .. } => write!(f, "manual"),
+        }
     }
 }
 
@@ -415,6 +535,174 @@ pub struct cAsset {
     pub pool_info: Option<PoolInfo>,
     /// Is this subject to rate hikes?
     pub hike_rates: Option<bool>,
+    /// Debt ceiling for isolated collateral.
+    /// If set, positions holding this asset can't hold other collateral.
+    pub isolated_debt_ceiling: Option<Uint128>,
+    /// Interest rate model.
+    /// None defaults to RateModel::TwoSlope.
+    pub rate_model: Option<RateModel>,
+}
+
+/// cAsset interest rate models.
+/// Utilization is the cAsset's debt cap utilization.
+#[cw_serde]
+pub enum RateModel {
+    /// base_interest_rate / max_LTV, pro-rata to utilization.
+    /// Multiplied by the rate_slope_multiplier above the debt or supply cap.
+    TwoSlope {},
+    /// Kinked utilization curve.
+    /// Rises by slope_1 up to the kink & by slope_2 from the kink to full utilization.
+    Kinked {
+        base_rate: Decimal,
+        kink: Decimal,
+        slope_1: Decimal,
+        slope_2: Decimal,
+    },
+    /// Fixed rate
+    Fixed { rate: Decimal },
+    /// PID controlled rate that targets a utilization.
+    /// The integral accrues per day of error.
+    PID {
+        target_utilization: Decimal,
+        kp: Decimal,
+        ki: Decimal,
+        kd: Decimal,
+        min_rate: Decimal,
+        max_rate: Decimal,
+    },
+    /// base_rate +/- multiplier * the credit TWAP's % deviation from the redemption price.
+    /// Rates rise while credit trades below the redemption price.
+    CreditDeviation {
+        base_rate: Decimal,
+        multiplier: Decimal,
+        max_rate: Decimal,
+    },
+}
+
+impl fmt::Display for RateModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RateModel::TwoSlope {} => write!(f, "two_slope"),
+            RateModel::Kinked { .. } => write!(f, "kinked"),
+            RateModel::Fixed { .. } => write!(f, "fixed"),
+            RateModel::PID { .. } => write!(f, "pid"),
+            RateModel::CreditDeviation { .. } => write!(f, "credit_deviation"),
+        }
+    }
+}
+
+/// Interest discount source & its cap
+#[cw_serde]
+pub struct DiscountSource {
+    /// Source type
+    pub source_type: DiscountSourceType,
+    /// Contract queried for the user's standing
+    pub contract: String,
+    /// Max discount from this source
+    pub cap: Decimal,
+}
+
+/// Interest discount source types.
+/// Tiered sources apply the highest tier the user qualifies for.
+#[cw_serde]
+pub enum DiscountSourceType {
+    /// Tiers of staked MBRN
+    Staking { tiers: Vec<DiscountTier> },
+    /// Tiers of total points
+    Points { tiers: Vec<DiscountTier> },
+    /// Governance approved contract that returns a UserDiscountResponse
+    Partner {},
+    /// Tiers of days the user's oldest discount vault LP has been deposited
+    VaultTenure { tiers: Vec<DiscountTier> },
+}
+
+impl fmt::Display for DiscountSourceType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiscountSourceType::Staking { .. } => write!(f, "staking"),
+            DiscountSourceType::Points { .. } => write!(f, "points"),
+            DiscountSourceType::Partner {} => write!(f, "partner"),
+            DiscountSourceType::VaultTenure { .. } => write!(f, "vault_tenure"),
+        }
+    }
+}
+
+/// Discount tier
+#[cw_serde]
+pub struct DiscountTier {
+    /// Minimum staked MBRN, points or days to qualify
+    pub threshold: Uint128,
+    /// Discount for the tier
+    pub discount: Decimal,
+}
+
+/// Collateral volatility tracker.
+/// Once the volatility list is full, the index multiplies the asset's supply cap ratio.
+#[cw_serde]
+pub struct CollateralVolatility {
+    /// Supply cap multiplier
+    pub index: Decimal,
+    /// Speed of price change (% change / seconds) for each sample in the window
+    pub volatility_list: Vec<Decimal>,
+    /// Time of the last sample in seconds
+    pub last_sample_time: Option<u64>,
+    /// Tracker settings.
+    /// None uses VolatilityConfig::default().
+    pub config: Option<VolatilityConfig>,
+}
+
+impl Default for CollateralVolatility {
+    fn default() -> Self {
+        CollateralVolatility {
+            index: Decimal::one(),
+            volatility_list: vec![],
+            last_sample_time: None,
+            config: None,
+        }
+    }
+}
+
+impl CollateralVolatility {
+    /// Returns the tracker's config or the default
+    pub fn get_config(&self) -> VolatilityConfig {
+        self.config.clone().unwrap_or_default()
+    }
+}
+
+/// Volatility tracker settings
+#[cw_serde]
+pub struct VolatilityConfig {
+    /// Number of samples in the volatility list
+    pub window: u32,
+    /// Minimum seconds between samples
+    pub sampling_interval: u64,
+    /// Averaging method for the volatility list
+    pub smoothing: VolatilitySmoothing,
+    /// Minimum supply cap multiplier
+    pub multiplier_floor: Decimal,
+    /// Maximum supply cap multiplier, at most 1
+    pub multiplier_ceiling: Decimal,
+}
+
+impl Default for VolatilityConfig {
+    fn default() -> Self {
+        VolatilityConfig {
+            window: 48,
+            sampling_interval: 0,
+            smoothing: VolatilitySmoothing::Simple {},
+            multiplier_floor: Decimal::zero(),
+            multiplier_ceiling: Decimal::one(),
+        }
+    }
+}
+
+/// Averaging method for the volatility list
+#[cw_serde]
+pub enum VolatilitySmoothing {
+    /// Simple average
+    Simple {},
+    /// Exponential moving average w/ alpha = 2 / (window + 1)
+    EMA {},
 }
 
 /// Osmosis PoolInfo
@@ -466,6 +754,25 @@ pub struct PositionRedemption {
     pub restricted_collateral_assets: Vec<String>,
 }
 
+/// Collateral a redeemer accepts
+#[cw_serde]
+pub enum RedemptionCollateralFilter {
+    /// Only accept these denoms
+    Whitelist { denoms: Vec<String> },
+    /// Accept all but these denoms
+    Blacklist { denoms: Vec<String> },
+}
+
+impl RedemptionCollateralFilter {
+    /// Returns whether the redeemer accepts the asset
+    pub fn accepts(&self, asset_info: &AssetInfo) -> bool {
+        match self {
+            RedemptionCollateralFilter::Whitelist { denoms } => denoms.contains(&asset_info.to_string()),
+            RedemptionCollateralFilter::Blacklist { denoms } => !denoms.contains(&asset_info.to_string()),
+        }
+    }
+}
+
 #[cw_serde]
 pub struct PremiumInfo {
     /// Premium
@@ -522,6 +829,83 @@ pub struct Basket {
     pub cpc_margin_of_error: Decimal,
     /// Liquidation queue contract address
     pub liq_queue: Option<Addr>,
+    /// Max % of a Position's debt repaid per liquidation.
+    /// If None, liquidations repay down to the avg_borrow_LTV.
+    pub close_factor: Option<Decimal>,
+    /// Soft liquidation settings for Positions between their borrow & max LTVs.
+    /// If None, only Positions above their max LTV are liquidatable.
+    pub soft_liquidation: Option<SoftLiquidation>,
+}
+
+#[cw_serde]
+pub struct SoftLiquidation {
+    /// % of the Position's debt repaid per soft liquidation
+    pub repay_ratio: Decimal,
+    /// Caller fee as a % of the repaid collateral.
+    /// Replaces the dynamic caller fee used for insolvent Positions.
+    pub caller_fee: Decimal,
+}
+
+/// Linear ramp from a start to an end value
+#[cw_serde]
+pub struct ParameterRamp {
+    /// Value at the start of the ramp
+    pub start_value: Decimal,
+    /// Value at the end of the ramp
+    pub end_value: Decimal,
+    /// Ramp start time in seconds
+    pub start_time: u64,
+    /// Ramp end time in seconds
+    pub end_time: u64,
+}
+
+impl ParameterRamp {
+    /// Returns the ramped value at the given time
+    pub fn get_value(&self, time: u64) -> Decimal {
+        if time >= self.end_time || self.end_time <= self.start_time {
+            return self.end_value;
+        } else if time <= self.start_time {
+            return self.start_value;
+        }
+
+        let progress = Decimal::from_ratio(time - self.start_time, self.end_time - self.start_time);
+        if self.end_value >= self.start_value {
+            self.start_value + (self.end_value - self.start_value) * progress
+        } else {
+            self.start_value - (self.start_value - self.end_value) * progress
+        }
+    }
+}
+
+/// Pending LTV changes for a cAsset
+#[cw_serde]
+pub struct LTVRamp {
+    /// cAsset being ramped
+    pub asset_info: AssetInfo,
+    /// Borrow LTV ramp
+    pub max_borrow_LTV: ParameterRamp,
+    /// Liquidation LTV ramp
+    pub max_LTV: ParameterRamp,
+}
+
+/// Wind-down schedule for a deprecated cAsset
+#[cw_serde]
+pub struct CollateralDeprecation {
+    /// Deprecated asset
+    pub asset_info: AssetInfo,
+    /// Start of the max_LTV ramp
+    pub start_time: u64,
+    /// End of the max_LTV ramp
+    pub end_time: u64,
+    /// max_LTV when deprecated
+    pub initial_max_LTV: Decimal,
+    /// max_LTV at the end of the ramp
+    pub final_max_LTV: Decimal,
+    /// Added to the liquidation caller fee,
+    /// weighted by the asset's ratio in the Position
+    pub liquidation_incentive: Decimal,
+    /// Removed from the Basket
+    pub removed: bool,
 }
 
 #[cw_serde]
@@ -578,6 +962,66 @@ impl fmt::Display for UserInfo {
     }
 }
 
+#[cw_serde]
+pub struct OperatorAllowance {
+    /// Position IDs the operator can manage.
+    /// All of the owner's Positions if None.
+    pub position_ids: Option<Vec<Uint128>>,
+    /// Allow the operator to withdraw collateral.
+    /// Withdrawals are sent to the Position owner.
+    pub withdraw: bool,
+    /// Allow the operator to increase debt
+    pub increase_debt: bool,
+    /// Remaining debt the operator can mint.
+    /// Decreases as debt is minted, unlimited if None.
+    pub debt_limit: Option<Uint128>,
+    /// Allow the operator to edit redeemability
+    pub edit_redeemability: bool,
+    /// Block time in seconds the allowance expires at.
+    /// Never expires if None.
+    pub expiration: Option<u64>,
+}
+
+/// Condition that makes a PositionOrder executable
+#[cw_serde]
+pub enum OrderTrigger {
+    /// Position LTV is at or above this LTV
+    LTVAbove { LTV: Decimal },
+    /// Collateral price is at or below this price
+    PriceBelow { asset: AssetInfo, price: Decimal },
+    /// Collateral price is at or above this price
+    PriceAbove { asset: AssetInfo, price: Decimal },
+}
+
+/// Action a PositionOrder takes on the Position
+#[cw_serde]
+pub enum OrderAction {
+    /// Sell collateral to repay the full debt & withdraw the rest to the owner
+    Close { max_spread: Decimal },
+    /// Sell collateral to repay a % of the debt
+    Deleverage { repay_ratio: Decimal, max_spread: Decimal },
+    /// Withdraw collateral to the owner
+    Withdraw { assets: Vec<Asset> },
+}
+
+/// Conditional order on a Position, executable by anyone once triggered
+#[cw_serde]
+pub struct PositionOrder {
+    /// Order ID
+    pub order_id: u64,
+    /// Position owner
+    pub position_owner: Addr,
+    /// Position ID
+    pub position_id: Uint128,
+    /// Condition to execute at
+    pub trigger: OrderTrigger,
+    /// Action taken on execution
+    pub action: OrderAction,
+    /// Tip paid to the executor.
+    /// Escrowed when the order is set & refunded if cancelled.
+    pub tip: Option<Asset>,
+}
+
 #[cw_serde]
 pub struct InsolventPosition {
     /// Is insolvent?
@@ -586,6 +1030,11 @@ pub struct InsolventPosition {
     pub position_info: UserInfo,
     /// Current LTV
     pub current_LTV: Decimal,
+    /// Average max LTV of the Position's collateral
+    pub avg_max_LTV: Decimal,
+    /// Distance from the current LTV to the avg_max_LTV.
+    /// 0 if insolvent.
+    pub LTV_buffer: Decimal,
     /// Available liquidation fee
     pub available_fee: Uint128,
 }